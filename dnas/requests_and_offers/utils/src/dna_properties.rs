@@ -1,3 +1,5 @@
+use std::collections::BTreeMap;
+
 use hdk::prelude::*;
 
 use crate::errors::CommonError;
@@ -5,6 +7,12 @@ use crate::errors::CommonError;
 #[derive(Serialize, Deserialize, SerializedBytes, Debug, Clone)]
 pub struct DnaProperties {
   pub progenitor_pubkey: Option<String>,
+  /// How far below zero a ledger balance can go, by medium of exchange code
+  #[serde(default)]
+  pub credit_limits: BTreeMap<String, f64>,
+  /// The credit limit of the mediums of exchange not listed in `credit_limits`
+  #[serde(default)]
+  pub default_credit_limit: Option<f64>,
 }
 
 impl DnaProperties {
@@ -0,0 +1,290 @@
+use hdk::prelude::*;
+
+/// Mean radius of the Earth, in kilometres
+const EARTH_RADIUS_KM: f64 = 6371.0;
+
+/// Kilometres per degree of latitude
+const KM_PER_DEGREE: f64 = 111.32;
+
+const GEOHASH_ALPHABET: &[u8; 32] = b"0123456789bcdefghjkmnpqrstuvwxyz";
+
+/// Longest geohash a location is indexed under (cells of about 5 km)
+pub const MAX_GEOHASH_PRECISION: usize = 5;
+
+/// Largest privacy radius a listing can use
+pub const MAX_PRIVACY_RADIUS_KM: f64 = 50.0;
+
+/// Largest radius a proximity search can cover
+pub const MAX_SEARCH_RADIUS_KM: f64 = 1000.0;
+
+/// A point on the globe, in decimal degrees
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq)]
+pub struct GeoPoint {
+  pub latitude: f64,
+  pub longitude: f64,
+}
+
+/// The approximate location of a listing.
+///
+/// The point is snapped to a grid of cells about `privacy_radius_km` wide before it is
+/// published (see [`GeoLocation::snapped`]): the actual place is anywhere within
+/// `privacy_radius_km` of it.
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq)]
+pub struct GeoLocation {
+  pub point: GeoPoint,
+  pub privacy_radius_km: f64,
+}
+
+impl GeoPoint {
+  /// Checks that the coordinates are on the globe
+  pub fn validate(&self) -> Result<(), String> {
+    if !self.latitude.is_finite() || !(-90.0..=90.0).contains(&self.latitude) {
+      return Err("Latitude must be between -90 and 90".to_string());
+    }
+    if !self.longitude.is_finite() || !(-180.0..=180.0).contains(&self.longitude) {
+      return Err("Longitude must be between -180 and 180".to_string());
+    }
+    Ok(())
+  }
+
+  /// Great-circle distance to another point, in kilometres
+  pub fn distance_km(&self, other: &GeoPoint) -> f64 {
+    let (lat1, lat2) = (self.latitude.to_radians(), other.latitude.to_radians());
+    let delta_lat = lat2 - lat1;
+    let delta_lon = (other.longitude - self.longitude).to_radians();
+
+    let a =
+      (delta_lat / 2.0).sin().powi(2) + lat1.cos() * lat2.cos() * (delta_lon / 2.0).sin().powi(2);
+    2.0 * EARTH_RADIUS_KM * a.sqrt().asin()
+  }
+
+  /// Encodes the point as a geohash of `precision` characters
+  pub fn geohash(&self, precision: usize) -> String {
+    let mut latitude_range = (-90.0, 90.0);
+    let mut longitude_range = (-180.0, 180.0);
+    let mut hash = String::with_capacity(precision);
+    let mut bits = 0;
+    let mut bit_count = 0;
+    let mut is_longitude_bit = true;
+
+    while hash.len() < precision {
+      let (range, value) = if is_longitude_bit {
+        (&mut longitude_range, self.longitude)
+      } else {
+        (&mut latitude_range, self.latitude)
+      };
+      let middle = (range.0 + range.1) / 2.0;
+      bits <<= 1;
+      if value >= middle {
+        bits |= 1;
+        range.0 = middle;
+      } else {
+        range.1 = middle;
+      }
+
+      is_longitude_bit = !is_longitude_bit;
+      bit_count += 1;
+      if bit_count == 5 {
+        hash.push(GEOHASH_ALPHABET[bits] as char);
+        bits = 0;
+        bit_count = 0;
+      }
+    }
+
+    hash
+  }
+}
+
+/// Rounds `value` to the nearest multiple of `step` within `-limit..=limit`
+fn snap_to_step(value: f64, step: f64, limit: f64) -> f64 {
+  let steps = (value / step)
+    .round()
+    .clamp((-limit / step).ceil(), (limit / step).floor());
+  (steps * step).clamp(-limit, limit)
+}
+
+impl GeoLocation {
+  /// Checks that the location is on the globe, with a privacy radius in range, and that
+  /// its point is snapped to the grid of its privacy radius
+  pub fn validate(&self) -> Result<(), String> {
+    self.point.validate()?;
+    if !self.privacy_radius_km.is_finite()
+      || !(0.0..=MAX_PRIVACY_RADIUS_KM).contains(&self.privacy_radius_km)
+    {
+      return Err(format!(
+        "The privacy radius must be between 0 and {} km",
+        MAX_PRIVACY_RADIUS_KM
+      ));
+    }
+    if self.snapped() != *self {
+      return Err("The point must be snapped to the grid of its privacy radius".to_string());
+    }
+    Ok(())
+  }
+
+  /// The location with its point moved to the nearest corner of a grid whose cells are
+  /// `privacy_radius_km` high and wide, so that the published point does not reveal the
+  /// actual place. Snapping a snapped location leaves it unchanged.
+  pub fn snapped(&self) -> GeoLocation {
+    if self.privacy_radius_km <= 0.0 {
+      return *self;
+    }
+
+    let latitude_step = self.privacy_radius_km / KM_PER_DEGREE;
+    let latitude = snap_to_step(self.point.latitude, latitude_step, 90.0);
+    // Meridians get closer towards the poles, where a single cell spans every longitude
+    let longitude_step = latitude_step / latitude.to_radians().cos();
+    let longitude = if longitude_step.is_finite() && longitude_step < 360.0 {
+      snap_to_step(self.point.longitude, longitude_step, 180.0)
+    } else {
+      0.0
+    };
+
+    GeoLocation {
+      point: GeoPoint {
+        latitude,
+        longitude,
+      },
+      privacy_radius_km: self.privacy_radius_km,
+    }
+  }
+
+  /// Serializes the location into a link tag, so index links can be filtered by distance
+  /// without fetching the listing
+  pub fn to_link_tag(&self) -> Result<LinkTag, SerializedBytesError> {
+    Ok(LinkTag::new(holochain_serialized_bytes::encode(self)?))
+  }
+
+  pub fn from_link_tag(tag: &LinkTag) -> Result<Self, SerializedBytesError> {
+    holochain_serialized_bytes::decode(&tag.0)
+  }
+
+  /// The geohashes of every precision the location is indexed under, coarsest first
+  pub fn geohashes(&self) -> Vec<String> {
+    let finest = self.point.geohash(MAX_GEOHASH_PRECISION);
+    (1..=MAX_GEOHASH_PRECISION)
+      .map(|precision| finest[..precision].to_string())
+      .collect()
+  }
+}
+
+/// Height and width of a geohash cell of `precision` characters, in degrees
+fn geohash_cell_degrees(precision: usize) -> (f64, f64) {
+  let bits = 5 * precision as i32;
+  let latitude_bits = bits / 2;
+  let longitude_bits = bits - latitude_bits;
+  (
+    180.0 / 2f64.powi(latitude_bits),
+    360.0 / 2f64.powi(longitude_bits),
+  )
+}
+
+/// The geohashes of the cell containing `center` and its neighbours, at the finest
+/// precision whose cells are at least `radius_km` wide, so that together they cover
+/// the whole circle. Near the poles the coarsest precision is used.
+pub fn covering_geohashes(center: &GeoPoint, radius_km: f64) -> Vec<String> {
+  // Cells are narrowest at the edge of the circle closest to a pole
+  let furthest_latitude = (center.latitude.abs() + radius_km / KM_PER_DEGREE).min(90.0);
+  let fits = |precision: &usize| {
+    let (height, width) = geohash_cell_degrees(*precision);
+    height * KM_PER_DEGREE >= radius_km
+      && width * KM_PER_DEGREE * furthest_latitude.to_radians().cos() >= radius_km
+  };
+  let precision = (1..=MAX_GEOHASH_PRECISION).rev().find(fits).unwrap_or(1);
+
+  let (height, width) = geohash_cell_degrees(precision);
+  let mut hashes: Vec<String> = Vec::new();
+  for latitude_step in [-1.0, 0.0, 1.0] {
+    for longitude_step in [-1.0, 0.0, 1.0] {
+      let mut longitude = center.longitude + longitude_step * width;
+      if longitude < -180.0 {
+        longitude += 360.0;
+      } else if longitude > 180.0 {
+        longitude -= 360.0;
+      }
+      let neighbour = GeoPoint {
+        latitude: (center.latitude + latitude_step * height).clamp(-90.0, 90.0),
+        longitude,
+      };
+      let hash = neighbour.geohash(precision);
+      if !hashes.contains(&hash) {
+        hashes.push(hash);
+      }
+    }
+  }
+
+  hashes
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  fn point(latitude: f64, longitude: f64) -> GeoPoint {
+    GeoPoint {
+      latitude,
+      longitude,
+    }
+  }
+
+  #[test]
+  fn test_geohash_matches_reference_values() {
+    assert_eq!(point(57.64911, 10.40744).geohash(5), "u4pru");
+    assert_eq!(point(-25.382708, -49.265506).geohash(5), "6gkzw");
+  }
+
+  #[test]
+  fn test_distance_between_cities() {
+    let paris = point(48.8566, 2.3522);
+    let london = point(51.5074, -0.1278);
+    let distance = paris.distance_km(&london);
+    assert!((distance - 344.0).abs() < 5.0, "got {}", distance);
+  }
+
+  #[test]
+  fn test_covering_geohashes_include_nearby_points() {
+    let center = point(48.8566, 2.3522);
+    let nearby = point(48.95, 2.45);
+    let hashes = covering_geohashes(&center, 20.0);
+
+    assert!(hashes.len() <= 9);
+    let precision = hashes[0].len();
+    assert!(hashes.contains(&nearby.geohash(precision)));
+  }
+
+  #[test]
+  fn test_snapped_locations_stay_within_the_privacy_radius() {
+    for (latitude, longitude) in [(48.8566, 2.3522), (-33.8688, 151.2093), (89.99, -179.99)] {
+      let location = GeoLocation {
+        point: point(latitude, longitude),
+        privacy_radius_km: 2.0,
+      };
+      let snapped = location.snapped();
+
+      assert!(snapped.validate().is_ok());
+      assert_eq!(snapped.snapped(), snapped);
+      assert!(location.point.distance_km(&snapped.point) <= 2.0);
+    }
+
+    let unsnapped = GeoLocation {
+      point: point(48.8566, 2.3522),
+      privacy_radius_km: 2.0,
+    };
+    assert!(unsnapped.validate().is_err());
+  }
+
+  #[test]
+  fn test_invalid_locations_are_rejected() {
+    let location = GeoLocation {
+      point: point(91.0, 0.0),
+      privacy_radius_km: 1.0,
+    };
+    assert!(location.validate().is_err());
+
+    let location = GeoLocation {
+      point: point(45.0, 0.0),
+      privacy_radius_km: MAX_PRIVACY_RADIUS_KM + 1.0,
+    };
+    assert!(location.validate().is_err());
+  }
+}
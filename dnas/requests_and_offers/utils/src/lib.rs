@@ -1,10 +1,24 @@
 pub mod dna_properties;
 pub mod errors;
+pub mod geo;
+pub mod listing_links;
+pub mod listing_status;
+pub mod pagination;
+pub mod role_links;
+pub mod session_approvals;
 pub mod types;
+pub mod update_authorization;
+pub mod valuation;
 
 pub use dna_properties::DnaProperties;
 use errors::CommonError;
+pub use geo::{GeoLocation, GeoPoint};
+pub use listing_links::organization_membership_tag;
+pub use listing_status::ListingStatus;
+pub use pagination::*;
 pub use types::*;
+pub use update_authorization::UpdateAuthorization;
+pub use valuation::{Valuation, ValuationFilter, ValuationUnit};
 
 use std::io::Cursor;
 
@@ -20,7 +34,9 @@ pub fn check_if_progenitor() -> ExternResult<bool> {
   }
 }
 
-pub fn get_original_record(original_action_hash: OriginalActionHash) -> ExternResult<Option<Record>> {
+pub fn get_original_record(
+  original_action_hash: OriginalActionHash,
+) -> ExternResult<Option<Record>> {
   let Some(details) = get_details(original_action_hash.0, GetOptions::default())? else {
     return Ok(None);
   };
@@ -119,6 +135,38 @@ where
   }
 }
 
+/// Calls a zome function on another agent's cell of this DNA.
+/// The function must be covered by a capability grant on the remote cell.
+pub fn external_remote_call<I, T>(
+  agent: AgentPubKey,
+  fn_name: &str,
+  zome_name: &str,
+  payload: I,
+) -> ExternResult<T>
+where
+  I: Clone + Serialize + Debug,
+  T: Debug + DeserializeOwned,
+{
+  let zome_call_response = call_remote(
+    agent,
+    ZomeName(zome_name.to_owned().into()),
+    FunctionName(fn_name.into()),
+    None,
+    payload,
+  )?;
+
+  match zome_call_response {
+    ZomeCallResponse::Ok(response) => Ok(response.decode().map_err(CommonError::Serialize)?),
+    _ => Err(
+      CommonError::External(format!(
+        "Error while calling the {} function of the {} zome of a remote agent",
+        fn_name, zome_name
+      ))
+      .into(),
+    ),
+  }
+}
+
 pub fn timetamp_now() -> Timestamp {
   Timestamp::from_micros(chrono::UTC::now().timestamp_subsec_micros() as i64)
 }
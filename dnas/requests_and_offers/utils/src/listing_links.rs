@@ -0,0 +1,217 @@
+use hdk::prelude::*;
+
+use crate::pagination::index_link_created_at;
+use crate::role_links::{
+  make_role_proof, must_get_create_of, must_get_live_role_link, role_proof_from_tag,
+  role_proof_tag, ORGANIZATION_ENTRY, ORGANIZATION_MEMBERS, USER_ENTRY,
+};
+
+/// The tag of a link between a listing and an organization: the proof of the
+/// `OrganizationMembers` link that makes the listing's author a member
+pub fn organization_membership_tag(member_link: &Link) -> ExternResult<LinkTag> {
+  role_proof_tag(&make_role_proof(member_link)?)
+}
+
+/// Fetches the action creating a version of a listing, or returns why it cannot be used
+fn must_get_listing_action(
+  hash: &AnyLinkableHash,
+  listing_entry_type: &EntryType,
+) -> ExternResult<Result<Action, String>> {
+  let Some(action_hash) = hash.clone().into_action_hash() else {
+    return Ok(Err("A listing link must point to an action".to_string()));
+  };
+  let action = must_get_valid_record(action_hash)?.action().clone();
+  if action.entry_type() != Some(listing_entry_type) {
+    return Ok(Err("A listing link must point to a listing".to_string()));
+  }
+
+  Ok(Ok(action))
+}
+
+/// Walks back from a version of a listing to its original `Create`, returning its hash
+/// and action
+fn must_get_listing_create(listing_action_hash: ActionHash) -> ExternResult<(ActionHash, Action)> {
+  let mut action_hash = listing_action_hash;
+  let mut action = must_get_action(action_hash.clone())?.action().clone();
+  while let Action::Update(update) = action {
+    action_hash = update.original_action_address;
+    action = must_get_action(action_hash.clone())?.action().clone();
+  }
+  Ok((action_hash, action))
+}
+
+/// The timestamp of the original `Create` of a listing
+fn must_get_listing_created_at(listing_hash: &AnyLinkableHash) -> ExternResult<Option<Timestamp>> {
+  let Some(listing_action_hash) = listing_hash.clone().into_action_hash() else {
+    return Ok(None);
+  };
+  Ok(Some(
+    must_get_listing_create(listing_action_hash)?.1.timestamp(),
+  ))
+}
+
+/// Checks a link from a status index path to a version of a listing. Only the author of
+/// that version can index it: the listing's author, or the administrator or coordinator
+/// who updated it. The tag must hold the listing's creation timestamp, which orders the
+/// pages of the index.
+pub fn validate_listing_index_link(
+  action: &CreateLink,
+  path: &str,
+  listing_entry_type: &EntryType,
+) -> ExternResult<ValidateCallbackResult> {
+  if action.base_address != Path::from(path).path_entry_hash()?.into() {
+    return Ok(ValidateCallbackResult::Invalid(format!(
+      "The link must start from the \"{}\" path",
+      path
+    )));
+  }
+
+  let listing_action = match must_get_listing_action(&action.target_address, listing_entry_type)? {
+    Ok(listing_action) => listing_action,
+    Err(error) => return Ok(ValidateCallbackResult::Invalid(error)),
+  };
+  if *listing_action.author() != action.author {
+    return Ok(ValidateCallbackResult::Invalid(
+      "Only the author of a listing version can index it".to_string(),
+    ));
+  }
+  if index_link_created_at(&action.tag) != must_get_listing_created_at(&action.target_address)? {
+    return Ok(ValidateCallbackResult::Invalid(
+      "A status index link must be tagged with the listing's creation timestamp".to_string(),
+    ));
+  }
+
+  Ok(ValidateCallbackResult::Valid)
+}
+
+/// Checks a link between a listing and the user profile of its author, in either
+/// direction
+pub fn validate_listing_user_link(
+  action: &CreateLink,
+  listing_hash: &AnyLinkableHash,
+  user_hash: &AnyLinkableHash,
+  listing_entry_type: &EntryType,
+) -> ExternResult<ValidateCallbackResult> {
+  let listing_action = match must_get_listing_action(listing_hash, listing_entry_type)? {
+    Ok(listing_action) => listing_action,
+    Err(error) => return Ok(ValidateCallbackResult::Invalid(error)),
+  };
+  if !matches!(listing_action, Action::Create(_)) || *listing_action.author() != action.author {
+    return Ok(ValidateCallbackResult::Invalid(
+      "Only the author of a listing can link it to their user profile".to_string(),
+    ));
+  }
+
+  let user = match must_get_create_of(user_hash, USER_ENTRY, &dna_info()?.zome_names)? {
+    Ok(user) => user,
+    Err(error) => return Ok(ValidateCallbackResult::Invalid(error)),
+  };
+  if user.author != action.author {
+    return Ok(ValidateCallbackResult::Invalid(
+      "A listing can only be linked to its author's user profile".to_string(),
+    ));
+  }
+
+  Ok(ValidateCallbackResult::Valid)
+}
+
+/// Checks a link between a listing and an organization, in either direction. The
+/// listing's author must be a member of the organization, which the tag proves with
+/// the [`RoleProof`](crate::role_links::RoleProof) of their `OrganizationMembers` link.
+pub fn validate_listing_organization_link(
+  action: &CreateLink,
+  listing_hash: &AnyLinkableHash,
+  organization_hash: &AnyLinkableHash,
+  listing_entry_type: &EntryType,
+) -> ExternResult<ValidateCallbackResult> {
+  let listing_action = match must_get_listing_action(listing_hash, listing_entry_type)? {
+    Ok(listing_action) => listing_action,
+    Err(error) => return Ok(ValidateCallbackResult::Invalid(error)),
+  };
+  if !matches!(listing_action, Action::Create(_)) || *listing_action.author() != action.author {
+    return Ok(ValidateCallbackResult::Invalid(
+      "Only the author of a listing can link it to an organization".to_string(),
+    ));
+  }
+
+  let zome_names = dna_info()?.zome_names;
+  if let Err(error) = must_get_create_of(organization_hash, ORGANIZATION_ENTRY, &zome_names)? {
+    return Ok(ValidateCallbackResult::Invalid(error));
+  }
+
+  let Some(proof) = role_proof_from_tag(&action.tag) else {
+    return Ok(ValidateCallbackResult::Invalid(
+      "An organization link must be tagged with the author's membership proof".to_string(),
+    ));
+  };
+  let member_link = match must_get_live_role_link(
+    &proof,
+    ORGANIZATION_MEMBERS,
+    &zome_names,
+    &action.author,
+    &action.prev_action,
+    action.timestamp,
+  )? {
+    Ok(create_link) => create_link,
+    Err(error) => return Ok(ValidateCallbackResult::Invalid(error)),
+  };
+  if member_link.base_address != *organization_hash {
+    return Ok(ValidateCallbackResult::Invalid(
+      "The membership link does not belong to the organization".to_string(),
+    ));
+  }
+  let Some(user_hash) = member_link.target_address.into_action_hash() else {
+    return Ok(ValidateCallbackResult::Invalid(
+      "The membership link must point to a user".to_string(),
+    ));
+  };
+  if *must_get_action(user_hash)?.action().author() != action.author {
+    return Ok(ValidateCallbackResult::Invalid(
+      "Only members of an organization can link listings to it".to_string(),
+    ));
+  }
+
+  Ok(ValidateCallbackResult::Valid)
+}
+
+/// Checks that a link to or from a listing is deleted by the listing's author, or by an
+/// agent who updated the listing since the link was created.
+///
+/// A `DeleteLink` cannot carry the proof of the deleting agent's role, but an update of
+/// someone else's listing must carry an [`UpdateAuthorization`](crate::UpdateAuthorization).
+/// Administrators and coordinators thus update a listing before removing its links.
+pub fn validate_delete_listing_link(
+  action: &DeleteLink,
+  original_action: &CreateLink,
+  listing_hash: &AnyLinkableHash,
+  listing_entry_type: &EntryType,
+) -> ExternResult<ValidateCallbackResult> {
+  let Some(listing_action_hash) = listing_hash.clone().into_action_hash() else {
+    return Ok(ValidateCallbackResult::Invalid(
+      "A listing link must point to an action".to_string(),
+    ));
+  };
+  let (create_hash, create) = must_get_listing_create(listing_action_hash)?;
+  if *create.author() == action.author {
+    return Ok(ValidateCallbackResult::Valid);
+  }
+
+  let activity = must_get_agent_activity(
+    action.author.clone(),
+    ChainFilter::new(action.prev_action.clone()).until_timestamp(original_action.timestamp),
+  )?;
+  for activity in activity {
+    let Action::Update(update) = activity.action.hashed.content else {
+      continue;
+    };
+    if update.entry_type == *listing_entry_type
+      && must_get_listing_create(update.original_action_address)?.0 == create_hash
+    {
+      return Ok(ValidateCallbackResult::Valid);
+    }
+  }
+
+  Ok(ValidateCallbackResult::Invalid(
+    "Only the author of a listing, or an agent who updated it, can remove its links".to_string(),
+  ))
+}
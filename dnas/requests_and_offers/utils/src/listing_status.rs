@@ -0,0 +1,75 @@
+use hdk::prelude::*;
+
+/// Lifecycle status of a listing (request or offer)
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Default)]
+pub enum ListingStatus {
+  #[default]
+  Active,
+  Archived,
+  Deleted, // Soft delete
+  /// The end of the listing's date range has passed (requests only)
+  Expired,
+  /// Someone is working on the listing; it is still listed as active
+  InProgress,
+  /// The listing was carried out
+  Fulfilled,
+}
+
+impl ListingStatus {
+  /// Whether a listing in this status may be updated to `next`.
+  ///
+  /// Active and in-progress listings can be edited without changing status. Fulfilled
+  /// listings can only be archived, archived listings can be reactivated, and nothing
+  /// comes back from a soft delete.
+  pub fn can_transition_to(&self, next: &ListingStatus) -> bool {
+    use ListingStatus::*;
+
+    match self {
+      Active => true,
+      InProgress => !matches!(next, Expired),
+      Expired => matches!(next, Active | Archived | Deleted),
+      Fulfilled => matches!(next, Archived | Deleted),
+      Archived => matches!(next, Active | Deleted),
+      Deleted => false,
+    }
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::ListingStatus::*;
+
+  #[test]
+  fn test_fulfilment_lifecycle() {
+    assert!(Active.can_transition_to(&InProgress));
+    assert!(InProgress.can_transition_to(&Fulfilled));
+    assert!(Fulfilled.can_transition_to(&Archived));
+  }
+
+  #[test]
+  fn test_in_progress_can_be_reopened() {
+    assert!(InProgress.can_transition_to(&Active));
+  }
+
+  #[test]
+  fn test_fulfilled_and_deleted_are_final() {
+    assert!(!Fulfilled.can_transition_to(&Active));
+    assert!(!Fulfilled.can_transition_to(&InProgress));
+    assert!(!Fulfilled.can_transition_to(&Fulfilled));
+    assert!(!Deleted.can_transition_to(&Active));
+  }
+
+  #[test]
+  fn test_archived_can_be_reactivated() {
+    assert!(Archived.can_transition_to(&Active));
+    assert!(!Archived.can_transition_to(&InProgress));
+    assert!(!Archived.can_transition_to(&Fulfilled));
+  }
+
+  #[test]
+  fn test_only_active_requests_expire() {
+    assert!(Active.can_transition_to(&Expired));
+    assert!(!InProgress.can_transition_to(&Expired));
+    assert!(!Archived.can_transition_to(&Expired));
+  }
+}
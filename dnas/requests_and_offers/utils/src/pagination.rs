@@ -0,0 +1,206 @@
+use hdk::prelude::*;
+
+use crate::errors::CommonError;
+
+/// Largest page size accepted by the paginated externs
+pub const MAX_PAGE_SIZE: usize = 100;
+
+/// Position of the last item of a page.
+///
+/// Links are ordered by the creation timestamp of what they index, with the `CreateLink`
+/// action hash as a tie-breaker, so a cursor identifies a unique position in the index.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct PaginationCursor {
+  pub timestamp: Timestamp,
+  pub create_link_hash: ActionHash,
+}
+
+impl From<&Link> for PaginationCursor {
+  fn from(link: &Link) -> Self {
+    Self {
+      timestamp: created_at(link),
+      create_link_hash: link.create_link_hash.clone(),
+    }
+  }
+}
+
+/// The tag of a status index link: the timestamp of the listing's original `Create`.
+/// Status changes and edits recreate index links, and the tag keeps the listing in its
+/// place in the pages.
+pub fn index_link_tag(created_at: Timestamp) -> LinkTag {
+  LinkTag::new(created_at.as_micros().to_be_bytes().to_vec())
+}
+
+/// The tag of a status index link to a version of the listing created by
+/// `original_action_hash`
+pub fn listing_index_tag(original_action_hash: &ActionHash) -> ExternResult<LinkTag> {
+  let original_action = must_get_action(original_action_hash.clone())?;
+  Ok(index_link_tag(original_action.action().timestamp()))
+}
+
+/// The creation timestamp in the tag of a status index link, if any
+pub fn index_link_created_at(tag: &LinkTag) -> Option<Timestamp> {
+  let micros: [u8; 8] = tag.0.as_slice().try_into().ok()?;
+  Some(Timestamp::from_micros(i64::from_be_bytes(micros)))
+}
+
+/// When what a link indexes was created: the timestamp in its tag, or else the link's own
+fn created_at(link: &Link) -> Timestamp {
+  index_link_created_at(&link.tag).unwrap_or(link.timestamp)
+}
+
+/// Input for the paginated "get all" externs.
+///
+/// Pass `cursor: None` for the first page, then the `next_cursor` of the previous page.
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct PaginationInput {
+  pub cursor: Option<PaginationCursor>,
+  pub limit: usize,
+}
+
+/// A page of results. `next_cursor` is `None` on the last page.
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct Page<T> {
+  pub items: Vec<T>,
+  pub next_cursor: Option<PaginationCursor>,
+}
+
+/// Sorts links from oldest to newest and returns the page following `input.cursor`,
+/// along with the cursor of the next page.
+pub fn paginate_links(
+  mut links: Vec<Link>,
+  input: &PaginationInput,
+) -> ExternResult<(Vec<Link>, Option<PaginationCursor>)> {
+  if input.limit == 0 || input.limit > MAX_PAGE_SIZE {
+    return Err(
+      CommonError::InvalidData(format!("Page size must be between 1 and {}", MAX_PAGE_SIZE)).into(),
+    );
+  }
+
+  links.sort_by(|a, b| {
+    created_at(a)
+      .cmp(&created_at(b))
+      .then_with(|| a.create_link_hash.cmp(&b.create_link_hash))
+  });
+
+  let start = match &input.cursor {
+    Some(cursor) => links
+      .iter()
+      .position(|link| {
+        (created_at(link), &link.create_link_hash) > (cursor.timestamp, &cursor.create_link_hash)
+      })
+      .unwrap_or(links.len()),
+    None => 0,
+  };
+
+  let page: Vec<Link> = links.into_iter().skip(start).collect();
+  let has_more = page.len() > input.limit;
+  let page: Vec<Link> = page.into_iter().take(input.limit).collect();
+
+  let next_cursor = if has_more {
+    page.last().map(PaginationCursor::from)
+  } else {
+    None
+  };
+
+  Ok((page, next_cursor))
+}
+
+/// Paginates index links and resolves their targets to records.
+///
+/// Targets that can no longer be fetched are skipped, so a page may hold fewer
+/// items than requested while still having a `next_cursor`.
+pub fn paginate_records(links: Vec<Link>, input: &PaginationInput) -> ExternResult<Page<Record>> {
+  let (links, next_cursor) = paginate_links(links, input)?;
+
+  let get_input: Vec<GetInput> = links
+    .into_iter()
+    .filter_map(|link| link.target.into_any_dht_hash())
+    .map(|hash| GetInput::new(hash, GetOptions::default()))
+    .collect();
+  let items: Vec<Record> = HDK
+    .with(|hdk| hdk.borrow().get(get_input))?
+    .into_iter()
+    .flatten()
+    .collect();
+
+  Ok(Page { items, next_cursor })
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  fn link(micros: i64, byte: u8) -> Link {
+    Link {
+      author: AgentPubKey::from_raw_36(vec![0; 36]),
+      base: ActionHash::from_raw_36(vec![0; 36]).into(),
+      target: ActionHash::from_raw_36(vec![byte; 36]).into(),
+      timestamp: Timestamp::from_micros(micros),
+      zome_index: 0.into(),
+      link_type: 0.into(),
+      tag: LinkTag::new(vec![]),
+      create_link_hash: ActionHash::from_raw_36(vec![byte; 36]),
+    }
+  }
+
+  fn input(cursor: Option<PaginationCursor>, limit: usize) -> PaginationInput {
+    PaginationInput { cursor, limit }
+  }
+
+  #[test]
+  fn test_pages_are_ordered_by_timestamp() {
+    let links = vec![link(30, 3), link(10, 1), link(20, 2)];
+
+    let (page, next_cursor) = paginate_links(links.clone(), &input(None, 2)).unwrap();
+    assert_eq!(page, vec![link(10, 1), link(20, 2)]);
+    let next_cursor = next_cursor.unwrap();
+    assert_eq!(next_cursor, PaginationCursor::from(&link(20, 2)));
+
+    let (page, next_cursor) = paginate_links(links, &input(Some(next_cursor), 2)).unwrap();
+    assert_eq!(page, vec![link(30, 3)]);
+    assert!(next_cursor.is_none());
+  }
+
+  #[test]
+  fn test_equal_timestamps_use_hash_tie_breaker() {
+    let links = vec![link(10, 2), link(10, 1), link(10, 3)];
+
+    let (page, next_cursor) = paginate_links(links.clone(), &input(None, 1)).unwrap();
+    assert_eq!(page, vec![link(10, 1)]);
+
+    let (page, _) = paginate_links(links, &input(next_cursor, 5)).unwrap();
+    assert_eq!(page, vec![link(10, 2), link(10, 3)]);
+  }
+
+  #[test]
+  fn test_tagged_links_keep_their_creation_order() {
+    // The listing created first was re-indexed last
+    let mut reindexed = link(40, 1);
+    reindexed.tag = index_link_tag(Timestamp::from_micros(5));
+    let links = vec![link(10, 2), reindexed.clone(), link(20, 3)];
+
+    let (page, next_cursor) = paginate_links(links.clone(), &input(None, 1)).unwrap();
+    assert_eq!(page, vec![reindexed.clone()]);
+    assert_eq!(next_cursor.unwrap().timestamp, Timestamp::from_micros(5));
+
+    let (page, _) =
+      paginate_links(links, &input(Some(PaginationCursor::from(&reindexed)), 5)).unwrap();
+    assert_eq!(page, vec![link(10, 2), link(20, 3)]);
+  }
+
+  #[test]
+  fn test_exact_page_has_no_next_cursor() {
+    let links = vec![link(10, 1), link(20, 2)];
+
+    let (page, next_cursor) = paginate_links(links, &input(None, 2)).unwrap();
+    assert_eq!(page.len(), 2);
+    assert!(next_cursor.is_none());
+  }
+
+  #[test]
+  fn test_invalid_page_size_is_rejected() {
+    assert!(paginate_links(vec![], &input(None, 0)).is_err());
+    assert!(paginate_links(vec![], &input(None, MAX_PAGE_SIZE + 1)).is_err());
+  }
+}
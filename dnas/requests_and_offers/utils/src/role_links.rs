@@ -0,0 +1,357 @@
+//! Links that give an agent a role: administrator of the network, or coordinator or
+//! member of an organization.
+//!
+//! Validation cannot query links, so an agent acting on the strength of a role carries a
+//! [`RoleProof`]: in the tag of the links they create, or in the entries they update. A
+//! role link created by anyone other than the one trusted bootstrap author (the
+//! progenitor, or the organization's creator) carries the proof of its author's own
+//! role, so each role traces back to the bootstrap author.
+//!
+//! Validation cannot see the `DeleteLink` that revokes a role either. A proof is checked
+//! against the chains of the agent who gave the role and of the agent holding it: the
+//! holder's up to the action being validated, the giver's up to the chain top recorded
+//! in the proof, which must be at most [`ROLE_PROOF_MAX_AGE`] older than that action.
+//! Roles removed by anyone else are enforced by the coordinators, which only build
+//! proofs from live links.
+//!
+//! The types of other zomes are referenced by zome name and index. The integrity zomes
+//! defining them test that the indices below still match their enums.
+
+use std::time::Duration;
+
+use hdk::prelude::*;
+
+use crate::DnaProperties;
+
+/// `EntryTypes::User` of `users_organizations_integrity`
+pub const USER_ENTRY: (&str, u8) = ("users_organizations_integrity", 0);
+/// `EntryTypes::Organization` of `users_organizations_integrity`
+pub const ORGANIZATION_ENTRY: (&str, u8) = ("users_organizations_integrity", 1);
+/// `EntryTypes::Request` of `requests_integrity`
+pub const REQUEST_ENTRY: (&str, u8) = ("requests_integrity", 0);
+/// `EntryTypes::Offer` of `offers_integrity`
+pub const OFFER_ENTRY: (&str, u8) = ("offers_integrity", 0);
+/// `EntryTypes::ServiceType` of `service_types_integrity`
+pub const SERVICE_TYPE_ENTRY: (&str, u8) = ("service_types_integrity", 0);
+/// `EntryTypes::MediumOfExchange` of `mediums_of_exchange_integrity`
+pub const MEDIUM_OF_EXCHANGE_ENTRY: (&str, u8) = ("mediums_of_exchange_integrity", 0);
+/// `EntryTypes::ExchangeAgreement` of `exchanges_integrity`
+pub const EXCHANGE_AGREEMENT_ENTRY: (&str, u8) = ("exchanges_integrity", 0);
+/// `EntryTypes::Response` of `responses_integrity`
+pub const RESPONSE_ENTRY: (&str, u8) = ("responses_integrity", 0);
+/// `LinkTypes::AgentAdministrators` of `administration_integrity`: agent -> "{entity}.administrators"
+pub const AGENT_ADMINISTRATORS: (&str, u8) = ("administration_integrity", 1);
+/// `LinkTypes::OrganizationCoordinators` of `users_organizations_integrity`: organization -> user
+pub const ORGANIZATION_COORDINATORS: (&str, u8) = ("users_organizations_integrity", 8);
+/// `LinkTypes::OrganizationMembers` of `users_organizations_integrity`: organization -> user
+pub const ORGANIZATION_MEMBERS: (&str, u8) = ("users_organizations_integrity", 9);
+
+/// How much older than the action using it the chain top recorded in a [`RoleProof`]
+/// can be
+pub const ROLE_PROOF_MAX_AGE: Duration = Duration::from_secs(10 * 60);
+
+/// Proof that an agent holds a role
+#[derive(Serialize, Deserialize, SerializedBytes, Debug, Clone, PartialEq)]
+pub struct RoleProof {
+  /// The `CreateLink` giving the role
+  pub role_link: ActionHash,
+  /// The head of the chain of the agent who gave the role when the proof was made. Their
+  /// chain up to it must not delete the role link, and it must be at most
+  /// [`ROLE_PROOF_MAX_AGE`] older than the action using the proof.
+  pub granter_chain_top: ActionHash,
+}
+
+/// Builds the proof of the role given by `role_link`, from the current chain head of the
+/// agent who created it
+pub fn make_role_proof(role_link: &Link) -> ExternResult<RoleProof> {
+  let agent_info = agent_info()?;
+  let granter_chain_top = if role_link.author == agent_info.agent_initial_pubkey {
+    agent_info.chain_head.0
+  } else {
+    match get_agent_activity(
+      role_link.author.clone(),
+      ChainQueryFilter::new(),
+      ActivityRequest::Status,
+    )?
+    .status
+    {
+      ChainStatus::Valid(chain_head) => chain_head.hash,
+      _ => {
+        return Err(wasm_error!(WasmErrorInner::Guest(
+          "The chain of the agent who gave this role is not available".to_string()
+        )))
+      }
+    }
+  };
+
+  Ok(RoleProof {
+    role_link: role_link.create_link_hash.clone(),
+    granter_chain_top,
+  })
+}
+
+/// The tag of a role link created on the strength of another role
+pub fn role_proof_tag(proof: &RoleProof) -> ExternResult<LinkTag> {
+  let bytes = SerializedBytes::try_from(proof.clone()).map_err(|e| wasm_error!(e))?;
+  Ok(LinkTag::new(bytes.bytes().clone()))
+}
+
+/// The proof carried by a tag made by [`role_proof_tag`]
+pub fn role_proof_from_tag(tag: &LinkTag) -> Option<RoleProof> {
+  RoleProof::try_from(SerializedBytes::from(UnsafeBytes::from(tag.0.clone()))).ok()
+}
+
+/// Fetches a `CreateLink` of the given link type, or returns why it cannot be used
+pub fn must_get_create_link(
+  create_link_hash: &ActionHash,
+  (zome_name, link_type): (&str, u8),
+  zome_names: &[ZomeName],
+) -> ExternResult<Result<CreateLink, String>> {
+  let Action::CreateLink(create_link) = must_get_valid_record(create_link_hash.clone())?
+    .action()
+    .clone()
+  else {
+    return Ok(Err(
+      "The referenced action must be a CreateLink".to_string(),
+    ));
+  };
+
+  let is_zome = zome_names
+    .get(create_link.zome_index.0 as usize)
+    .is_some_and(|name| name.0 == zome_name);
+  if !is_zome || create_link.link_type.0 != link_type {
+    return Ok(Err(format!(
+      "The referenced link is of the wrong type in {}",
+      zome_name
+    )));
+  }
+
+  Ok(Ok(create_link))
+}
+
+/// Whether the activity of a chain deletes the link `create_link_hash`
+fn deletes_link(activity: &[RegisterAgentActivity], create_link_hash: &ActionHash) -> bool {
+  activity
+    .iter()
+    .any(|activity| match &activity.action.hashed.content {
+      Action::DeleteLink(delete_link) => delete_link.link_add_address == *create_link_hash,
+      _ => false,
+    })
+}
+
+/// Fetches the role link of a proof, or returns why it cannot be used. `holder` is the
+/// agent using the proof, `holder_chain_top` the previous action on their chain and
+/// `timestamp` the time of the action using it.
+pub fn must_get_live_role_link(
+  proof: &RoleProof,
+  link_type: (&str, u8),
+  zome_names: &[ZomeName],
+  holder: &AgentPubKey,
+  holder_chain_top: &ActionHash,
+  timestamp: Timestamp,
+) -> ExternResult<Result<CreateLink, String>> {
+  let role_link = match must_get_create_link(&proof.role_link, link_type, zome_names)? {
+    Ok(role_link) => role_link,
+    Err(error) => return Ok(Err(error)),
+  };
+
+  let granter_activity = must_get_agent_activity(
+    role_link.author.clone(),
+    ChainFilter::new(proof.granter_chain_top.clone()).until_hash(proof.role_link.clone()),
+  )?;
+  let oldest_chain_top = timestamp.as_micros() - ROLE_PROOF_MAX_AGE.as_micros() as i64;
+  let is_recent = granter_activity.first().is_some_and(|activity| {
+    activity.action.hashed.hash == proof.granter_chain_top
+      && activity.action.hashed.content.timestamp().as_micros() >= oldest_chain_top
+  });
+  if !is_recent {
+    return Ok(Err("The role proof is too old for this action".to_string()));
+  }
+
+  let holder_activity = must_get_agent_activity(
+    holder.clone(),
+    ChainFilter::new(holder_chain_top.clone()).until_timestamp(role_link.timestamp),
+  )?;
+  if deletes_link(&granter_activity, &proof.role_link)
+    || deletes_link(&holder_activity, &proof.role_link)
+  {
+    return Ok(Err("The role link has been deleted".to_string()));
+  }
+
+  Ok(Ok(role_link))
+}
+
+/// Checks that `holder` administers the entity whose `"{entity}.administrators"` path is
+/// `administrators_path`. Administrators can only be trusted when they trace back to a
+/// progenitor, so without one no administrator proof is accepted.
+pub fn check_administrator_proof(
+  proof: &RoleProof,
+  administrators_path: &AnyLinkableHash,
+  holder: &AgentPubKey,
+  holder_chain_top: &ActionHash,
+  timestamp: Timestamp,
+  zome_names: &[ZomeName],
+) -> ExternResult<Result<(), String>> {
+  if DnaProperties::get_progenitor_pubkey()?.is_none() {
+    return Ok(Err(
+      "Administrator roles cannot be proven without a progenitor".to_string(),
+    ));
+  }
+
+  let administrator_link = match must_get_live_role_link(
+    proof,
+    AGENT_ADMINISTRATORS,
+    zome_names,
+    holder,
+    holder_chain_top,
+    timestamp,
+  )? {
+    Ok(create_link) => create_link,
+    Err(error) => return Ok(Err(error)),
+  };
+  if administrator_link.base_address != holder.clone().into()
+    || administrator_link.target_address != *administrators_path
+  {
+    return Ok(Err(
+      "The administrator link does not belong to the agent for this entity".to_string(),
+    ));
+  }
+
+  Ok(Ok(()))
+}
+
+/// Checks that the author of a link is a network administrator, with the proof of their
+/// role in the tag
+pub fn validate_network_administrator_link(
+  action: &CreateLink,
+) -> ExternResult<ValidateCallbackResult> {
+  let Some(proof) = role_proof_from_tag(&action.tag) else {
+    return Ok(ValidateCallbackResult::Invalid(
+      "Only network administrators can create this link".to_string(),
+    ));
+  };
+  if let Err(error) = check_administrator_proof(
+    &proof,
+    &Path::from("network.administrators")
+      .path_entry_hash()?
+      .into(),
+    &action.author,
+    &action.prev_action,
+    action.timestamp,
+    &dna_info()?.zome_names,
+  )? {
+    return Ok(ValidateCallbackResult::Invalid(error));
+  }
+
+  Ok(ValidateCallbackResult::Valid)
+}
+
+/// Whether an entry type is the given entry type of another zome
+pub fn is_entry_type_of(
+  entry_type: &EntryType,
+  (zome_name, entry_index): (&str, u8),
+  zome_names: &[ZomeName],
+) -> bool {
+  match entry_type {
+    EntryType::App(app_entry_def) => {
+      app_entry_def.entry_index.0 == entry_index
+        && zome_names
+          .get(app_entry_def.zome_index.0 as usize)
+          .is_some_and(|name| name.0 == zome_name)
+    }
+    _ => false,
+  }
+}
+
+/// Fetches the `Create` action of an entry of the given type in another zome, or returns
+/// why it cannot be used
+pub fn must_get_create_of(
+  hash: &AnyLinkableHash,
+  (zome_name, entry_index): (&str, u8),
+  zome_names: &[ZomeName],
+) -> ExternResult<Result<Create, String>> {
+  let Some(action_hash) = hash.clone().into_action_hash() else {
+    return Ok(Err("A link must point to an action".to_string()));
+  };
+  let Action::Create(create) = must_get_valid_record(action_hash)?.action().clone() else {
+    return Ok(Err(
+      "A link must point to the original action of an entry".to_string(),
+    ));
+  };
+
+  if !is_entry_type_of(&create.entry_type, (zome_name, entry_index), zome_names) {
+    return Ok(Err(format!(
+      "A link points to an entry of the wrong type in {}",
+      zome_name
+    )));
+  }
+
+  Ok(Ok(create))
+}
+
+/// Checks that the author of a role link in an organization is the organization's
+/// creator, or a coordinator of it whose proof is in the tag
+pub fn validate_organization_role_author(
+  action: &CreateLink,
+  organization_author: &AgentPubKey,
+  zome_names: &[ZomeName],
+) -> ExternResult<ValidateCallbackResult> {
+  if action.author == *organization_author {
+    return Ok(ValidateCallbackResult::Valid);
+  }
+
+  let Some(proof) = role_proof_from_tag(&action.tag) else {
+    return Ok(ValidateCallbackResult::Invalid(
+      "Only the coordinators of an organization can give roles in it".to_string(),
+    ));
+  };
+  let coordinator_link = match must_get_live_role_link(
+    &proof,
+    ORGANIZATION_COORDINATORS,
+    zome_names,
+    &action.author,
+    &action.prev_action,
+    action.timestamp,
+  )? {
+    Ok(create_link) => create_link,
+    Err(error) => return Ok(ValidateCallbackResult::Invalid(error)),
+  };
+  if coordinator_link.base_address != action.base_address {
+    return Ok(ValidateCallbackResult::Invalid(
+      "The coordinator link does not belong to the organization".to_string(),
+    ));
+  }
+  let Some(user_hash) = coordinator_link.target_address.into_action_hash() else {
+    return Ok(ValidateCallbackResult::Invalid(
+      "The coordinator link must point to a user".to_string(),
+    ));
+  };
+  if *must_get_action(user_hash)?.action().author() != action.author {
+    return Ok(ValidateCallbackResult::Invalid(
+      "The coordinator link does not belong to the author".to_string(),
+    ));
+  }
+
+  Ok(ValidateCallbackResult::Valid)
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  #[test]
+  fn test_role_proof_tag_round_trip() {
+    let proof = RoleProof {
+      role_link: ActionHash::from_raw_36(vec![1; 36]),
+      granter_chain_top: ActionHash::from_raw_36(vec![2; 36]),
+    };
+
+    let tag = role_proof_tag(&proof).unwrap();
+    assert_eq!(role_proof_from_tag(&tag), Some(proof));
+  }
+
+  #[test]
+  fn test_empty_tag_carries_no_proof() {
+    assert_eq!(role_proof_from_tag(&LinkTag::new(Vec::new())), None);
+  }
+}
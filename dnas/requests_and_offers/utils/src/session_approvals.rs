@@ -0,0 +1,122 @@
+//! Approvals of countersigning sessions.
+//!
+//! A countersigning session is started by one party, who brings the other party's cell
+//! into it with remote calls. The other party allows these calls for one entry only, by
+//! creating a cap grant tagged with the hash of the entry to countersign. An approval is
+//! honoured for [`APPROVAL_DURATION`], and revoked once the entry is committed or the
+//! approval has expired.
+
+use std::collections::HashSet;
+use std::time::Duration;
+
+use hdk::prelude::*;
+
+/// How long an approval is honoured after it is given
+pub const APPROVAL_DURATION: Duration = Duration::from_secs(5 * 60);
+
+/// The tag of the approval of the session countersigning `entry_hash`
+pub fn approval_tag(prefix: &str, entry_hash: &EntryHash) -> String {
+  format!("{}.{}", prefix, entry_hash)
+}
+
+/// A cap grant of the calling agent approving a session
+struct Approval {
+  action_hash: ActionHash,
+  tag: String,
+  expires_at: i64,
+}
+
+/// The calling agent's approvals with a tag starting with `prefix` that are not deleted
+fn live_approvals(prefix: &str) -> ExternResult<Vec<Approval>> {
+  let tag_prefix = format!("{}.", prefix);
+  let deleted: HashSet<ActionHash> =
+    query(ChainQueryFilter::new().action_type(ActionType::Delete))?
+      .into_iter()
+      .filter_map(|record| match record.action() {
+        Action::Delete(delete) => Some(delete.deletes_address.clone()),
+        _ => None,
+      })
+      .collect();
+
+  Ok(
+    query(
+      ChainQueryFilter::new()
+        .entry_type(EntryType::CapGrant)
+        .include_entries(true),
+    )?
+    .into_iter()
+    .filter(|record| !deleted.contains(record.action_address()))
+    .filter_map(|record| match record.entry().as_option() {
+      Some(Entry::CapGrant(grant)) if grant.tag.starts_with(&tag_prefix) => Some(Approval {
+        action_hash: record.action_address().clone(),
+        tag: grant.tag.clone(),
+        expires_at: record.action().timestamp().as_micros() + APPROVAL_DURATION.as_micros() as i64,
+      }),
+      _ => None,
+    })
+    .collect(),
+  )
+}
+
+/// The calling agent's approval of the session countersigning `entry_hash`, unless it
+/// has expired or been revoked
+pub fn get_approval(prefix: &str, entry_hash: &EntryHash) -> ExternResult<Option<ActionHash>> {
+  let tag = approval_tag(prefix, entry_hash);
+  let now = sys_time()?.as_micros();
+  Ok(
+    live_approvals(prefix)?
+      .into_iter()
+      .find(|approval| approval.tag == tag && approval.expires_at > now)
+      .map(|approval| approval.action_hash),
+  )
+}
+
+/// Approves the session countersigning `entry_hash`: the counterpart can call
+/// `functions` of the calling zome until the approval expires or is revoked
+pub fn approve_session(
+  prefix: &str,
+  entry_hash: &EntryHash,
+  functions: &[&str],
+) -> ExternResult<()> {
+  if get_approval(prefix, entry_hash)?.is_some() {
+    return Ok(());
+  }
+
+  let zome_name = zome_info()?.name;
+  let functions = functions
+    .iter()
+    .map(|function| (zome_name.clone(), (*function).into()))
+    .collect();
+  create_cap_grant(CapGrantEntry {
+    tag: approval_tag(prefix, entry_hash),
+    access: CapAccess::Unrestricted,
+    functions: GrantedFunctions::Listed(functions),
+  })?;
+
+  Ok(())
+}
+
+/// Revokes the calling agent's approvals of sessions whose entry is in `committed`, and
+/// those that have expired. Returns how long until the next remaining approval expires.
+pub fn revoke_approvals(
+  prefix: &str,
+  committed: &HashSet<EntryHash>,
+) -> ExternResult<Option<Duration>> {
+  let committed_tags: HashSet<String> = committed
+    .iter()
+    .map(|entry_hash| approval_tag(prefix, entry_hash))
+    .collect();
+  let now = sys_time()?.as_micros();
+
+  let mut next_expiry: Option<i64> = None;
+  for approval in live_approvals(prefix)? {
+    if committed_tags.contains(&approval.tag) || approval.expires_at <= now {
+      delete_cap_grant(approval.action_hash)?;
+    } else {
+      next_expiry =
+        Some(next_expiry.map_or(approval.expires_at, |next| next.min(approval.expires_at)));
+    }
+  }
+
+  Ok(next_expiry.map(|expires_at| Duration::from_micros((expires_at - now) as u64)))
+}
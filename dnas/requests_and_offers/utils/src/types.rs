@@ -1,5 +1,7 @@
 use hdk::prelude::*;
 
+use crate::GeoLocation;
+
 /// The ActionHash of the original Create action — immutable entity identifier.
 ///
 /// Wraps `ActionHash` to prevent accidental swapping with [`PreviousActionHash`].
@@ -77,7 +79,7 @@ impl From<PreviousActionHash> for AnyLinkableHash {
 /// Input for a contact preference
 #[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
 pub enum ContactPreference {
-  // AppChat,
+  AppChat,
   Email,
   Phone,
   Other(String),
@@ -107,6 +109,26 @@ pub struct DateRange {
   pub end: Option<Timestamp>,
 }
 
+impl DateRange {
+  /// Whether the range has an end and it is before `now`
+  pub fn has_ended(&self, now: Timestamp) -> bool {
+    self.end.is_some_and(|end| end < now)
+  }
+
+  /// Whether the two ranges share at least one instant. A missing bound is open-ended.
+  pub fn overlaps(&self, other: &DateRange) -> bool {
+    let starts_before_other_ends = match (self.start, other.end) {
+      (Some(start), Some(end)) => start <= end,
+      _ => true,
+    };
+    let other_starts_before_end = match (other.start, self.end) {
+      (Some(start), Some(end)) => start <= end,
+      _ => true,
+    };
+    starts_before_other_ends && other_starts_before_end
+  }
+}
+
 // Type alias for TimeZone
 pub type TimeZone = String;
 
@@ -192,3 +214,57 @@ pub struct UpdateMediumOfExchangeLinksInput {
   pub entity: String,
   pub new_medium_of_exchange_hashes: Vec<ActionHash>,
 }
+
+/// Input for converting an amount between two mediums of exchange
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct ConvertInput {
+  pub amount: f64,
+  pub from_medium_hash: ActionHash,
+  pub to_medium_hash: ActionHash,
+  /// The time of the rate to use, now if None
+  pub at: Option<Timestamp>,
+}
+
+/// Input for marking a request or offer as fulfilled
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct FulfilListingInput {
+  pub original_action_hash: OriginalActionHash,
+  pub fulfilment_notes: Option<String>,
+}
+
+/// Input for indexing the text fields of a request, offer, user or organization
+/// into the search zome
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct SearchIndexInput {
+  pub original_action_hash: OriginalActionHash,
+  pub entity: String,
+  pub fields: Vec<String>,
+}
+
+/// Input for indexing the location of a request or offer into the search zome.
+/// A listing without a location is removed from the geo index.
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct GeoIndexInput {
+  pub original_action_hash: OriginalActionHash,
+  pub entity: String,
+  pub location: Option<GeoLocation>,
+}
+
+/// A new request or offer, sent to the agents subscribed to its service types or
+/// mediums of exchange
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct ListingNotification {
+  pub original_action_hash: OriginalActionHash,
+  pub entity: String,
+  pub title: String,
+  pub service_type_hashes: Vec<ActionHash>,
+  pub medium_of_exchange_hashes: Vec<ActionHash>,
+}
+
+/// The outcome of claiming a slot of the offer proposed by an accepted response
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+#[serde(tag = "type")]
+pub enum OfferSlotClaim {
+  Taken,
+  Waitlisted { position: u32 },
+}
@@ -0,0 +1,193 @@
+use hdk::prelude::*;
+
+use crate::role_links::{
+  check_administrator_proof, must_get_live_role_link, RoleProof, ORGANIZATION_COORDINATORS,
+};
+
+/// Proof that the author of an update to someone else's listing is allowed to make it.
+///
+/// Validation cannot query links, so each variant carries the proof of the updating
+/// agent's role (see `crate::role_links`), checked against the updating agent and the
+/// listing.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub enum UpdateAuthorization {
+  /// The updating agent is a network administrator
+  Administrator {
+    /// Proof of the `AgentAdministrators` link from the updating agent to
+    /// "network.administrators"
+    administrator_proof: RoleProof,
+  },
+  /// The updating agent is a coordinator of the organization the listing belongs to
+  OrganizationCoordinator {
+    /// The link from the listing's original action hash to its organization, created by
+    /// the listing's author
+    organization_link: ActionHash,
+    /// Proof of the `OrganizationCoordinators` link from the organization to the user
+    /// profile created by the updating agent
+    coordinator_proof: RoleProof,
+  },
+}
+
+/// Follows an update chain back to its `Create`, returning its hash and author
+fn get_original_create(
+  original_action: &EntryCreationAction,
+  previous_action_hash: ActionHash,
+) -> ExternResult<(ActionHash, AgentPubKey)> {
+  let mut action_hash = previous_action_hash;
+  let mut action = original_action.clone();
+
+  loop {
+    match action {
+      EntryCreationAction::Create(create) => return Ok((action_hash, create.author)),
+      EntryCreationAction::Update(update) => {
+        action_hash = update.original_action_address;
+        action = match must_get_action(action_hash.clone())?.action().clone() {
+          Action::Create(create) => EntryCreationAction::Create(create),
+          Action::Update(update) => EntryCreationAction::Update(update),
+          _ => {
+            return Err(wasm_error!(WasmErrorInner::Guest(
+              "An update chain must start with a Create action".to_string()
+            )))
+          }
+        };
+      }
+    }
+  }
+}
+
+/// Checks that an update to a listing is made by the listing's original author, by an
+/// administrator, or by a coordinator of the listing's organization.
+///
+/// `organization_link_type` is the listing zome's link type from a listing to its
+/// organization.
+pub fn validate_update_authorization(
+  action: &Update,
+  original_action: &EntryCreationAction,
+  organization_link_type: ScopedLinkType,
+  authorization: Option<&UpdateAuthorization>,
+) -> ExternResult<ValidateCallbackResult> {
+  let (original_action_hash, original_author) =
+    get_original_create(original_action, action.original_action_address.clone())?;
+  if action.author == original_author {
+    return Ok(ValidateCallbackResult::Valid);
+  }
+
+  let Some(authorization) = authorization else {
+    return Ok(ValidateCallbackResult::Invalid(
+      "Only the author, an administrator or a coordinator of the organization can update a listing"
+        .to_string(),
+    ));
+  };
+
+  let zome_names = dna_info()?.zome_names;
+  match authorization {
+    UpdateAuthorization::Administrator {
+      administrator_proof,
+    } => {
+      if let Err(error) = check_administrator_proof(
+        administrator_proof,
+        &Path::from("network.administrators")
+          .path_entry_hash()?
+          .into(),
+        &action.author,
+        &action.prev_action,
+        action.timestamp,
+        &zome_names,
+      )? {
+        return Ok(ValidateCallbackResult::Invalid(error));
+      }
+    }
+    UpdateAuthorization::OrganizationCoordinator {
+      organization_link,
+      coordinator_proof,
+    } => {
+      let Action::CreateLink(organization_link) = must_get_valid_record(organization_link.clone())?
+        .action()
+        .clone()
+      else {
+        return Ok(ValidateCallbackResult::Invalid(
+          "An update authorization must reference CreateLink actions".to_string(),
+        ));
+      };
+      if organization_link.zome_index != organization_link_type.zome_index
+        || organization_link.link_type != organization_link_type.zome_type
+        || organization_link.base_address != original_action_hash.into()
+        || organization_link.author != original_author
+      {
+        return Ok(ValidateCallbackResult::Invalid(
+          "The organization link does not belong to the listing".to_string(),
+        ));
+      }
+
+      let coordinator_link = match must_get_live_role_link(
+        coordinator_proof,
+        ORGANIZATION_COORDINATORS,
+        &zome_names,
+        &action.author,
+        &action.prev_action,
+        action.timestamp,
+      )? {
+        Ok(create_link) => create_link,
+        Err(error) => return Ok(ValidateCallbackResult::Invalid(error)),
+      };
+
+      if coordinator_link.base_address != organization_link.target_address {
+        return Ok(ValidateCallbackResult::Invalid(
+          "The coordinator link does not belong to the listing's organization".to_string(),
+        ));
+      }
+      let Some(user_hash) = coordinator_link.target_address.into_action_hash() else {
+        return Ok(ValidateCallbackResult::Invalid(
+          "The coordinator link must point to a user".to_string(),
+        ));
+      };
+      if *must_get_action(user_hash)?.action().author() != action.author {
+        return Ok(ValidateCallbackResult::Invalid(
+          "The coordinator link does not belong to the updating agent".to_string(),
+        ));
+      }
+    }
+  }
+
+  Ok(ValidateCallbackResult::Valid)
+}
+
+/// Checks a link from the original action of a listing to one of its updates. Readers
+/// follow the newest of these links to the latest version, so the target must be a valid
+/// update of that listing, linked by its author: the update itself passed
+/// [`validate_update_authorization`].
+pub fn validate_listing_update_link(
+  action: &CreateLink,
+  listing_entry_type: &EntryType,
+) -> ExternResult<ValidateCallbackResult> {
+  let Some(update_hash) = action.target_address.clone().into_action_hash() else {
+    return Ok(ValidateCallbackResult::Invalid(
+      "An update link must point to an action".to_string(),
+    ));
+  };
+  let Action::Update(update) = must_get_valid_record(update_hash.clone())?.action().clone() else {
+    return Ok(ValidateCallbackResult::Invalid(
+      "An update link must point to an Update action".to_string(),
+    ));
+  };
+  if update.entry_type != *listing_entry_type {
+    return Ok(ValidateCallbackResult::Invalid(
+      "An update link must point to an update of a listing".to_string(),
+    ));
+  }
+  if update.author != action.author {
+    return Ok(ValidateCallbackResult::Invalid(
+      "Only the author of an update can link it to its listing".to_string(),
+    ));
+  }
+
+  let (original_action_hash, _) =
+    get_original_create(&EntryCreationAction::Update(update), update_hash)?;
+  if action.base_address != original_action_hash.into() {
+    return Ok(ValidateCallbackResult::Invalid(
+      "An update link must start from the original action of the updated listing".to_string(),
+    ));
+  }
+
+  Ok(ValidateCallbackResult::Valid)
+}
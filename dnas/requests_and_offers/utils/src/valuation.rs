@@ -0,0 +1,176 @@
+use hdk::prelude::*;
+
+use crate::errors::CommonError;
+use crate::external_local_call;
+use crate::role_links::{is_entry_type_of, MEDIUM_OF_EXCHANGE_ENTRY};
+
+/// Valuation and ledger amounts are integers in hundredths of a unit of their medium of
+/// exchange, so that they are exact
+pub const MINOR_UNITS_PER_UNIT: i64 = 100;
+
+/// What the amount of a valuation is counted per
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq)]
+pub enum ValuationUnit {
+  PerHour,
+  Fixed,
+  PerItem,
+}
+
+/// How much a listing asks or offers, in a medium of exchange
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct Valuation {
+  /// In minor units of the medium of exchange, see [`MINOR_UNITS_PER_UNIT`]
+  pub amount: i64,
+  pub unit: ValuationUnit,
+  /// Original action hash of the medium of exchange the amount is in
+  pub medium_of_exchange_hash: ActionHash,
+}
+
+/// Range filter on the valuations of listings
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct ValuationFilter {
+  /// The medium of exchange the bounds are in. Listings valued in another medium are
+  /// compared with the current exchange rate, and left out when there is none.
+  pub medium_of_exchange_hash: ActionHash,
+  /// In minor units of the filter's medium of exchange
+  pub min_amount: Option<i64>,
+  /// In minor units of the filter's medium of exchange
+  pub max_amount: Option<i64>,
+  /// Only keeps listings valued per this unit, when set
+  #[serde(default)]
+  pub unit: Option<ValuationUnit>,
+}
+
+impl ValuationFilter {
+  /// Whether an amount in minor units of the filter's medium of exchange, counted per
+  /// `unit`, is within the filter
+  pub fn includes(&self, amount: i64, unit: ValuationUnit) -> bool {
+    self.unit.is_none_or(|filter_unit| filter_unit == unit)
+      && self
+        .min_amount
+        .is_none_or(|min_amount| amount >= min_amount)
+      && self
+        .max_amount
+        .is_none_or(|max_amount| amount <= max_amount)
+  }
+}
+
+/// Checks that a valuation has a non-negative amount in the original action of a medium
+/// of exchange. Whether the medium is approved can only be checked by the coordinators.
+pub fn validate_valuation(valuation: &Valuation) -> ExternResult<ValidateCallbackResult> {
+  if valuation.amount < 0 {
+    return Ok(ValidateCallbackResult::Invalid(
+      "The valuation amount cannot be negative".to_string(),
+    ));
+  }
+
+  let is_medium_of_exchange =
+    match must_get_action(valuation.medium_of_exchange_hash.clone())?.action() {
+      Action::Create(create) => is_entry_type_of(
+        &create.entry_type,
+        MEDIUM_OF_EXCHANGE_ENTRY,
+        &dna_info()?.zome_names,
+      ),
+      _ => false,
+    };
+  if !is_medium_of_exchange {
+    return Ok(ValidateCallbackResult::Invalid(
+      "A valuation must be in the original action of a medium of exchange".to_string(),
+    ));
+  }
+
+  Ok(ValidateCallbackResult::Valid)
+}
+
+/// Checks that a listing is valued in an approved medium of exchange
+pub fn check_valuation_medium_of_exchange(valuation: &Valuation) -> ExternResult<()> {
+  let is_approved: bool = external_local_call(
+    "is_medium_of_exchange_approved",
+    "mediums_of_exchange",
+    valuation.medium_of_exchange_hash.clone(),
+  )?;
+  if !is_approved {
+    return Err(
+      CommonError::InvalidData(
+        "Listings can only be valued in an approved medium of exchange".to_string(),
+      )
+      .into(),
+    );
+  }
+  Ok(())
+}
+
+/// Keeps the listings whose valuation is within a filter. `rate_to_filter_medium` gives
+/// the current exchange rate from another medium of exchange to the filter's, if any.
+pub fn filter_by_valuation(
+  listings: Vec<(Record, Option<Valuation>)>,
+  filter: &ValuationFilter,
+  rate_to_filter_medium: impl Fn(ActionHash) -> ExternResult<Option<f64>>,
+) -> ExternResult<Vec<Record>> {
+  let mut rates: Vec<(ActionHash, Option<f64>)> = Vec::new();
+  let mut filtered = Vec::new();
+  for (record, valuation) in listings {
+    let Some(valuation) = valuation else {
+      continue;
+    };
+
+    let rate = if valuation.medium_of_exchange_hash == filter.medium_of_exchange_hash {
+      Some(1.0)
+    } else if let Some((_, rate)) = rates
+      .iter()
+      .find(|(medium_hash, _)| *medium_hash == valuation.medium_of_exchange_hash)
+    {
+      *rate
+    } else {
+      let rate = rate_to_filter_medium(valuation.medium_of_exchange_hash.clone())?;
+      rates.push((valuation.medium_of_exchange_hash, rate));
+      rate
+    };
+
+    let amount = rate.map(|rate| (valuation.amount as f64 * rate).round() as i64);
+    if amount.is_some_and(|amount| filter.includes(amount, valuation.unit)) {
+      filtered.push(record);
+    }
+  }
+  Ok(filtered)
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  fn filter(min_amount: Option<i64>, max_amount: Option<i64>) -> ValuationFilter {
+    ValuationFilter {
+      medium_of_exchange_hash: ActionHash::from_raw_36(vec![1; 36]),
+      min_amount,
+      max_amount,
+      unit: None,
+    }
+  }
+
+  #[test]
+  fn test_bounds_are_inclusive() {
+    let filter = filter(Some(1000), Some(2000));
+    assert!(filter.includes(1000, ValuationUnit::Fixed));
+    assert!(filter.includes(2000, ValuationUnit::Fixed));
+    assert!(!filter.includes(999, ValuationUnit::Fixed));
+    assert!(!filter.includes(2001, ValuationUnit::Fixed));
+  }
+
+  #[test]
+  fn test_missing_bounds_are_open() {
+    assert!(filter(None, None).includes(100_000_000, ValuationUnit::PerItem));
+    assert!(filter(Some(500), None).includes(100_000_000, ValuationUnit::PerItem));
+    assert!(filter(None, Some(500)).includes(0, ValuationUnit::PerItem));
+  }
+
+  #[test]
+  fn test_unit_must_match_when_set() {
+    let filter = ValuationFilter {
+      unit: Some(ValuationUnit::PerHour),
+      ..filter(None, None)
+    };
+    assert!(filter.includes(1500, ValuationUnit::PerHour));
+    assert!(!filter.includes(1500, ValuationUnit::Fixed));
+  }
+}
@@ -2,6 +2,7 @@ use administration_integrity::*;
 use hdk::prelude::*;
 use utils::{
   errors::{AdministrationError, CommonError},
+  role_links::{make_role_proof, role_proof_tag},
   DnaProperties, EntityActionHash, EntityActionHashAgents, EntityAgent,
 };
 
@@ -14,6 +15,19 @@ pub fn is_progenitor(_: ()) -> ExternResult<bool> {
   utils::check_if_progenitor()
 }
 
+/// The tag of the administrator links created by the calling agent: the proof of their own
+/// `AgentAdministrators` link to the administrators path, which integrity requires from
+/// anyone but the progenitor. Empty when the caller has none (progenitor or bootstrap).
+fn administrator_proof_tag(administrators_path_hash: EntryHash) -> ExternResult<LinkTag> {
+  let proof_link = get_agent_administrator_links(agent_info()?.agent_initial_pubkey)?
+    .into_iter()
+    .find(|link| link.target == administrators_path_hash.clone().into());
+  Ok(match proof_link {
+    Some(link) => role_proof_tag(&make_role_proof(&link)?)?,
+    None => LinkTag::new(Vec::new()),
+  })
+}
+
 /// Internal helper — not exposed as an extern. Called only by `add_administrator`
 /// (which enforces the progenitor-or-existing-admin gate) and from within this
 /// coordinator via cross-zome call through `add_administrator`.
@@ -31,11 +45,12 @@ fn register_administrator(input: EntityActionHashAgents) -> ExternResult<bool> {
   }
 
   let path = Path::from(format!("{}.administrators", input.entity));
+  let tag = administrator_proof_tag(path.path_entry_hash()?)?;
   create_link(
     path.path_entry_hash()?,
     input.entity_original_action_hash.clone(),
     LinkTypes::AllAdministrators,
-    (),
+    tag.clone(),
   )?;
 
   for agent_pubkey in input.agent_pubkeys.clone() {
@@ -43,7 +58,7 @@ fn register_administrator(input: EntityActionHashAgents) -> ExternResult<bool> {
       agent_pubkey.clone(),
       path.path_entry_hash()?,
       LinkTypes::AgentAdministrators,
-      (),
+      tag.clone(),
     )?;
   }
 
@@ -118,13 +133,7 @@ pub fn check_if_entity_is_administrator(input: EntityActionHash) -> ExternResult
 /// loading the full admin list used by [`check_if_entity_is_administrator`].
 #[hdk_extern]
 pub fn check_if_agent_is_administrator(input: EntityAgent) -> ExternResult<bool> {
-  let link_type_filter = LinkTypes::AgentAdministrators
-    .try_into_filter()
-    .map_err(|e| wasm_error!(WasmErrorInner::Guest(e.to_string())))?;
-  let agent_administrator_links = get_links(
-    LinkQuery::new(input.agent_pubkey, link_type_filter),
-    GetStrategy::Network,
-  )?;
+  let agent_administrator_links = get_agent_administrator_links(input.agent_pubkey)?;
   if !agent_administrator_links.is_empty() {
     return Ok(true);
   }
@@ -132,14 +141,30 @@ pub fn check_if_agent_is_administrator(input: EntityAgent) -> ExternResult<bool>
   Ok(false)
 }
 
+/// Returns the `AgentAdministrators` links from `agent_pubkey` to the
+/// `"{entity}.administrators"` paths the agent administers.
+///
+/// Listings zomes reference these links to prove an administrator's right to update
+/// someone else's listing.
+#[hdk_extern]
+pub fn get_agent_administrator_links(agent_pubkey: AgentPubKey) -> ExternResult<Vec<Link>> {
+  let link_type_filter = LinkTypes::AgentAdministrators
+    .try_into_filter()
+    .map_err(|e| wasm_error!(WasmErrorInner::Guest(e.to_string())))?;
+  get_links(
+    LinkQuery::new(agent_pubkey, link_type_filter),
+    GetStrategy::Network,
+  )
+}
+
 /// Removes an administrator from the entity specified in `input`.
 ///
 /// The caller must be an existing administrator. Returns `Err(AdministrationError::Unauthorized)`
 /// otherwise. Returns `Err(AdministrationError::LastAdmin)` if removing this administrator
 /// would leave the entity with no administrators (preventing lock-out).
 ///
-/// Deletes the `AllAdministrators` link for the entity and all `AgentAdministrators` links
-/// for each agent public key in `input.agent_pubkeys`.
+/// Deletes the `AllAdministrators` link for the entity and the `AgentAdministrators` link
+/// of each agent public key in `input.agent_pubkeys`.
 #[hdk_extern]
 pub fn remove_administrator(input: EntityActionHashAgents) -> ExternResult<bool> {
   if !check_if_agent_is_administrator(EntityAgent {
@@ -159,25 +184,26 @@ pub fn remove_administrator(input: EntityActionHashAgents) -> ExternResult<bool>
     .find(|link| link.target == input.entity_original_action_hash.clone().into())
     .ok_or(CommonError::LinkNotFound("administrator".to_string()))?;
 
+  let administrators_path_hash: AnyLinkableHash =
+    Path::from(format!("{}.administrators", input.entity))
+      .path_entry_hash()?
+      .into();
+  let mut agent_administrator_links = Vec::new();
+  for administrator_pubkey in input.agent_pubkeys.clone() {
+    let link = get_agent_administrator_links(administrator_pubkey)?
+      .into_iter()
+      .find(|link| link.target == administrators_path_hash)
+      .ok_or(CommonError::LinkNotFound("administrator".to_string()))?;
+    agent_administrator_links.push(link);
+  }
+
   delete_link(
     administrator_link.create_link_hash.clone(),
     GetOptions::default(),
   )?;
 
-  for agent_pubkey in input.agent_pubkeys.clone() {
-    let link_type_filter = LinkTypes::AgentAdministrators
-      .try_into_filter()
-      .map_err(|e| wasm_error!(WasmErrorInner::Guest(e.to_string())))?;
-    let links = get_links(
-      LinkQuery::new(agent_pubkey, link_type_filter),
-      GetStrategy::Network,
-    )?;
-
-    let link = links
-      .first()
-      .ok_or(CommonError::LinkNotFound("administrator".to_string()))?;
-
-    delete_link(link.create_link_hash.clone(), GetOptions::default())?;
+  for link in agent_administrator_links {
+    delete_link(link.create_link_hash, GetOptions::default())?;
   }
 
   Ok(true)
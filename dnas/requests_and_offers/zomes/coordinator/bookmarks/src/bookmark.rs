@@ -0,0 +1,155 @@
+use bookmarks_integrity::*;
+use hdk::prelude::*;
+use utils::errors::CommonError;
+
+use crate::external_calls::{
+  get_latest_offer_record, get_latest_organization_record, get_latest_request_record,
+  get_latest_user_record,
+};
+
+#[derive(Serialize, Deserialize, Debug)]
+pub struct UpdateBookmarkInput {
+  pub previous_bookmark_hash: ActionHash,
+  pub updated_bookmark: Bookmark,
+}
+
+/// A bookmark together with the latest version of what it points to
+#[derive(Serialize, Deserialize, Debug)]
+pub struct ResolvedBookmark {
+  /// The action hash of the current version of the bookmark
+  pub bookmark_hash: ActionHash,
+  pub bookmark: Bookmark,
+  /// The latest record of the target, or None if it was deleted
+  pub target_record: Option<Record>,
+}
+
+/// Returns the current versions of the calling agent's bookmarks, leaving out the ones
+/// that were updated or deleted
+fn get_current_bookmarks() -> ExternResult<Vec<(ActionHash, Bookmark)>> {
+  let replaced_hashes: Vec<ActionHash> = query(
+    ChainQueryFilter::new()
+      .action_type(ActionType::Delete)
+      .action_type(ActionType::Update),
+  )?
+  .into_iter()
+  .filter_map(|record| match record.action() {
+    Action::Delete(delete) => Some(delete.deletes_address.clone()),
+    Action::Update(update) => Some(update.original_action_address.clone()),
+    _ => None,
+  })
+  .collect();
+
+  let mut bookmarks = Vec::new();
+  for record in query(
+    ChainQueryFilter::new()
+      .entry_type(UnitEntryTypes::Bookmark.try_into()?)
+      .include_entries(true),
+  )? {
+    if replaced_hashes.contains(record.action_address()) {
+      continue;
+    }
+    let bookmark: Bookmark = record
+      .entry()
+      .to_app_option()
+      .map_err(CommonError::Serialize)?
+      .ok_or(CommonError::EntryNotFound(
+        "Could not deserialize bookmark entry".to_string(),
+      ))?;
+    bookmarks.push((record.action_address().clone(), bookmark));
+  }
+
+  Ok(bookmarks)
+}
+
+fn get_latest_target_record(target: &BookmarkTarget) -> ExternResult<Option<Record>> {
+  match target {
+    BookmarkTarget::Request(hash) => get_latest_request_record(hash.clone()),
+    BookmarkTarget::Offer(hash) => get_latest_offer_record(hash.clone()),
+    BookmarkTarget::User(hash) => get_latest_user_record(hash.clone()),
+    BookmarkTarget::Organization(hash) => get_latest_organization_record(hash.clone()),
+  }
+}
+
+fn resolve_bookmarks(
+  bookmarks: Vec<(ActionHash, Bookmark)>,
+) -> ExternResult<Vec<ResolvedBookmark>> {
+  bookmarks
+    .into_iter()
+    .map(|(bookmark_hash, bookmark)| {
+      Ok(ResolvedBookmark {
+        target_record: get_latest_target_record(&bookmark.target)?,
+        bookmark_hash,
+        bookmark,
+      })
+    })
+    .collect()
+}
+
+#[hdk_extern]
+pub fn create_bookmark(bookmark: Bookmark) -> ExternResult<Record> {
+  if get_current_bookmarks()?
+    .iter()
+    .any(|(_, existing)| existing.target == bookmark.target)
+  {
+    return Err(CommonError::InvalidData("This is already bookmarked".to_string()).into());
+  }
+
+  let bookmark_hash = create_entry(&EntryTypes::Bookmark(bookmark))?;
+  get(bookmark_hash, GetOptions::default())?.ok_or(
+    CommonError::EntryNotFound("Could not find the newly created bookmark".to_string()).into(),
+  )
+}
+
+/// Changes the notes or folder of a bookmark. The target cannot be changed.
+#[hdk_extern]
+pub fn update_bookmark(input: UpdateBookmarkInput) -> ExternResult<Record> {
+  let (_, previous_bookmark) = get_current_bookmarks()?
+    .into_iter()
+    .find(|(hash, _)| *hash == input.previous_bookmark_hash)
+    .ok_or(CommonError::EntryNotFound(
+      "Could not find the bookmark".to_string(),
+    ))?;
+  if previous_bookmark.target != input.updated_bookmark.target {
+    return Err(
+      CommonError::InvalidData("The target of a bookmark cannot be changed".to_string()).into(),
+    );
+  }
+
+  let updated_bookmark_hash = update_entry(input.previous_bookmark_hash, &input.updated_bookmark)?;
+  get(updated_bookmark_hash, GetOptions::default())?
+    .ok_or(CommonError::EntryNotFound("Could not find the updated bookmark".to_string()).into())
+}
+
+#[hdk_extern]
+pub fn delete_bookmark(bookmark_hash: ActionHash) -> ExternResult<ActionHash> {
+  delete_entry(bookmark_hash)
+}
+
+/// Get the calling agent's bookmarks with the latest record of each target
+#[hdk_extern]
+pub fn get_my_bookmarks(_: ()) -> ExternResult<Vec<ResolvedBookmark>> {
+  resolve_bookmarks(get_current_bookmarks()?)
+}
+
+/// Get the calling agent's bookmarks filed under a folder
+#[hdk_extern]
+pub fn get_my_bookmarks_in_folder(folder: String) -> ExternResult<Vec<ResolvedBookmark>> {
+  resolve_bookmarks(
+    get_current_bookmarks()?
+      .into_iter()
+      .filter(|(_, bookmark)| bookmark.folder.as_ref() == Some(&folder))
+      .collect(),
+  )
+}
+
+/// Get the names of the folders the calling agent files bookmarks under
+#[hdk_extern]
+pub fn get_my_bookmark_folders(_: ()) -> ExternResult<Vec<String>> {
+  let mut folders: Vec<String> = get_current_bookmarks()?
+    .into_iter()
+    .filter_map(|(_, bookmark)| bookmark.folder)
+    .collect();
+  folders.sort();
+  folders.dedup();
+  Ok(folders)
+}
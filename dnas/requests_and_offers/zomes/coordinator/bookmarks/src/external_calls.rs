@@ -0,0 +1,32 @@
+use hdk::prelude::*;
+use utils::external_local_call;
+
+pub fn get_latest_request_record(original_action_hash: ActionHash) -> ExternResult<Option<Record>> {
+  external_local_call(
+    "get_latest_request_record",
+    "requests",
+    original_action_hash,
+  )
+}
+
+pub fn get_latest_offer_record(original_action_hash: ActionHash) -> ExternResult<Option<Record>> {
+  external_local_call("get_latest_offer_record", "offers", original_action_hash)
+}
+
+pub fn get_latest_user_record(original_action_hash: ActionHash) -> ExternResult<Option<Record>> {
+  external_local_call(
+    "get_latest_user_record",
+    "users_organizations",
+    original_action_hash,
+  )
+}
+
+pub fn get_latest_organization_record(
+  original_action_hash: ActionHash,
+) -> ExternResult<Option<Record>> {
+  external_local_call(
+    "get_latest_organization_record",
+    "users_organizations",
+    original_action_hash,
+  )
+}
@@ -0,0 +1,434 @@
+use exchanges_integrity::*;
+use hdk::prelude::*;
+use utils::{
+  errors::{CommonError, UsersError},
+  external_remote_call,
+  session_approvals::{approve_session, get_approval, revoke_approvals},
+  GetServiceTypeForEntityInput, OriginalActionHash,
+};
+
+use crate::external_calls::{
+  get_medium_of_exchange, get_offer, get_offer_creator, get_offer_organization, get_request,
+  get_request_creator, get_request_organization, get_service_types_for_entity,
+};
+
+/// How long the counterpart has to sign an agreement once the session starts
+const SESSION_DURATION_MS: u64 = 30_000;
+
+/// How long revoking approvals waits after a session is joined: the chain stays locked
+/// until the countersigning session completes or expires
+const REVOKE_DELAY: std::time::Duration = std::time::Duration::from_millis(SESSION_DURATION_MS);
+
+/// The prefix of the tags of the cap grants approving agreements
+const APPROVAL_PREFIX: &str = "exchange_agreement";
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct ExchangeAgreementInput {
+  pub request_hash: ActionHash,
+  pub offer_hash: ActionHash,
+  pub service_type_hash: ActionHash,
+  pub medium_of_exchange_hash: ActionHash,
+  pub amount: AgreedAmount,
+}
+
+/// An agreement sent to the counterpart, along with the countersigning session to join
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct AgreementProposal {
+  pub input: ExchangeAgreementInput,
+  pub preflight_request: PreflightRequest,
+}
+
+/// The signed preflight responses of both parties, from which each party commits the agreement
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct CommitAgreementInput {
+  pub agreement: ExchangeAgreement,
+  pub responses: Vec<PreflightResponse>,
+}
+
+/// Builds an agreement from the request and offer it pairs, checking that both listings
+/// offer the service type and that the medium of exchange exists
+fn build_agreement(input: &ExchangeAgreementInput) -> ExternResult<ExchangeAgreement> {
+  let request_record = get_request(input.request_hash.clone())?.ok_or(
+    CommonError::EntryNotFound("Could not find the request".to_string()),
+  )?;
+  let offer_record = get_offer(input.offer_hash.clone())?.ok_or(CommonError::EntryNotFound(
+    "Could not find the offer".to_string(),
+  ))?;
+
+  let requester = ExchangeParty {
+    agent: request_record.action().author().clone(),
+    user_hash: get_request_creator(input.request_hash.clone())?
+      .ok_or(UsersError::UserProfileRequired)?,
+    organization_hash: get_request_organization(input.request_hash.clone())?,
+  };
+  let provider = ExchangeParty {
+    agent: offer_record.action().author().clone(),
+    user_hash: get_offer_creator(input.offer_hash.clone())?
+      .ok_or(UsersError::UserProfileRequired)?,
+    organization_hash: get_offer_organization(input.offer_hash.clone())?,
+  };
+
+  for (hash, entity) in [
+    (&input.request_hash, "request"),
+    (&input.offer_hash, "offer"),
+  ] {
+    let service_types = get_service_types_for_entity(GetServiceTypeForEntityInput {
+      original_action_hash: OriginalActionHash(hash.clone()),
+      entity: entity.to_string(),
+    })?;
+    if !service_types.contains(&input.service_type_hash) {
+      return Err(
+        CommonError::InvalidData(format!("The {} does not list this service type", entity)).into(),
+      );
+    }
+  }
+
+  if get_medium_of_exchange(input.medium_of_exchange_hash.clone())?.is_none() {
+    return Err(
+      CommonError::EntryNotFound("Could not find the medium of exchange".to_string()).into(),
+    );
+  }
+
+  Ok(ExchangeAgreement {
+    request_hash: input.request_hash.clone(),
+    offer_hash: input.offer_hash.clone(),
+    requester,
+    provider,
+    service_type_hash: input.service_type_hash.clone(),
+    medium_of_exchange_hash: input.medium_of_exchange_hash.clone(),
+    amount: input.amount.clone(),
+  })
+}
+
+/// The agent on the other side of the agreement from the calling agent
+pub(crate) fn counterpart(
+  agreement: &ExchangeAgreement,
+  agent: &AgentPubKey,
+) -> ExternResult<AgentPubKey> {
+  if *agent == agreement.requester.agent {
+    Ok(agreement.provider.agent.clone())
+  } else if *agent == agreement.provider.agent {
+    Ok(agreement.requester.agent.clone())
+  } else {
+    Err(UsersError::NotAuthor.into())
+  }
+}
+
+fn accept_preflight_request(
+  preflight_request: PreflightRequest,
+) -> ExternResult<PreflightResponse> {
+  match accept_countersigning_preflight_request(preflight_request)? {
+    PreflightRequestAcceptance::Accepted(response) => Ok(response),
+    refusal => Err(
+      CommonError::InvalidData(format!(
+        "Could not join the countersigning session: {:?}",
+        refusal
+      ))
+      .into(),
+    ),
+  }
+}
+
+/// Checks that the calling agent approved an agreement
+fn check_approval(agreement: &ExchangeAgreement) -> ExternResult<()> {
+  match get_approval(APPROVAL_PREFIX, &hash_entry(agreement)?)? {
+    Some(_) => Ok(()),
+    None => Err(
+      CommonError::InvalidData("The counterpart has not approved this agreement".to_string())
+        .into(),
+    ),
+  }
+}
+
+/// Commits the countersigned agreement to the calling agent's chain
+fn commit_agreement(
+  agreement: ExchangeAgreement,
+  responses: Vec<PreflightResponse>,
+) -> ExternResult<ActionHash> {
+  let session_data = CounterSigningSessionData::try_from_responses(responses, vec![])
+    .map_err(|e| wasm_error!(WasmErrorInner::Guest(e.to_string())))?;
+  if session_data.preflight_request().app_entry_hash != hash_entry(&agreement)? {
+    return Err(
+      CommonError::InvalidData("The session was not started for this agreement".to_string()).into(),
+    );
+  }
+
+  let entry_def_index = ScopedEntryDefIndex::try_from(UnitEntryTypes::ExchangeAgreement)?;
+  let entry = Entry::CounterSign(
+    Box::new(session_data),
+    AppEntryBytes(SerializedBytes::try_from(agreement).map_err(CommonError::Serialize)?),
+  );
+
+  create(CreateInput::new(
+    EntryDefLocation::App(entry_def_index.into()),
+    EntryVisibility::Public,
+    entry,
+    // Countersigned entries must be committed with strict ordering
+    ChainTopOrdering::Strict,
+  ))
+}
+
+/// Approves an agreement between the author of a request and the author of an offer.
+/// Must be called by the counterpart of the party who will start the agreement, before
+/// they start it: it lets them bring the calling agent's cell into the countersigning
+/// session.
+#[hdk_extern]
+pub fn approve_exchange_agreement(input: ExchangeAgreementInput) -> ExternResult<()> {
+  let agreement = build_agreement(&input)?;
+  counterpart(&agreement, &agent_info()?.agent_initial_pubkey)?;
+
+  approve_session(
+    APPROVAL_PREFIX,
+    &hash_entry(&agreement)?,
+    &["accept_exchange_agreement", "commit_exchange_agreement"],
+  )
+}
+
+/// Starts an exchange agreement between the author of a request and the author of an
+/// offer. Must be called by one of them once the other has approved it and while they
+/// are online: both chains are locked until the counterpart signs, or the session times
+/// out.
+///
+/// Once the session completes, call `index_exchange_agreement` to make the agreement
+/// discoverable from the listings and the parties' profiles.
+#[hdk_extern]
+pub fn create_exchange_agreement(input: ExchangeAgreementInput) -> ExternResult<ActionHash> {
+  let agreement = build_agreement(&input)?;
+  let counterpart_agent = counterpart(&agreement, &agent_info()?.agent_initial_pubkey)?;
+
+  let preflight_request = PreflightRequest::try_new(
+    hash_entry(&agreement)?,
+    vec![
+      (agreement.requester.agent.clone(), vec![]),
+      (agreement.provider.agent.clone(), vec![]),
+    ],
+    vec![],
+    0,
+    false,
+    session_times_from_millis(SESSION_DURATION_MS)?,
+    ActionBase::Create(CreateBase::new(
+      UnitEntryTypes::ExchangeAgreement.try_into()?,
+    )),
+    PreflightBytes(vec![]),
+  )
+  .map_err(|e| wasm_error!(WasmErrorInner::Guest(e.to_string())))?;
+
+  let own_response = accept_preflight_request(preflight_request.clone())?;
+  let counterpart_response: PreflightResponse = external_remote_call(
+    counterpart_agent.clone(),
+    "accept_exchange_agreement",
+    zome_info()?.name.0.as_ref(),
+    AgreementProposal {
+      input,
+      preflight_request,
+    },
+  )?;
+
+  // Responses follow the order of the signing agents: requester first
+  let responses = if own_response.agent_state.agent_index() == &0 {
+    vec![own_response, counterpart_response]
+  } else {
+    vec![counterpart_response, own_response]
+  };
+
+  let action_hash = commit_agreement(agreement.clone(), responses.clone())?;
+  let _: ActionHash = external_remote_call(
+    counterpart_agent,
+    "commit_exchange_agreement",
+    zome_info()?.name.0.as_ref(),
+    CommitAgreementInput {
+      agreement,
+      responses,
+    },
+  )?;
+
+  Ok(action_hash)
+}
+
+/// Joins the countersigning session of an agreement proposed by its counterpart.
+///
+/// The agreement is rebuilt from the listings it pairs, so the counterpart cannot
+/// misstate the parties, and it must have been approved. The approval is revoked once
+/// the session is over.
+#[hdk_extern]
+pub fn accept_exchange_agreement(proposal: AgreementProposal) -> ExternResult<PreflightResponse> {
+  let agreement = build_agreement(&proposal.input)?;
+  let counterpart_agent = counterpart(&agreement, &agent_info()?.agent_initial_pubkey)?;
+
+  if call_info()?.provenance != counterpart_agent {
+    return Err(UsersError::NotAuthor.into());
+  }
+  check_approval(&agreement)?;
+  if proposal.preflight_request.app_entry_hash != hash_entry(&agreement)? {
+    return Err(
+      CommonError::InvalidData("The session was not started for this agreement".to_string()).into(),
+    );
+  }
+
+  let response = accept_preflight_request(proposal.preflight_request)?;
+  schedule("revoke_exchange_agreement_approvals")?;
+
+  Ok(response)
+}
+
+/// Commits an approved agreement whose session both parties have joined
+#[hdk_extern]
+pub fn commit_exchange_agreement(input: CommitAgreementInput) -> ExternResult<ActionHash> {
+  let counterpart_agent = counterpart(&input.agreement, &agent_info()?.agent_initial_pubkey)?;
+  if call_info()?.provenance != counterpart_agent {
+    return Err(UsersError::NotAuthor.into());
+  }
+  check_approval(&input.agreement)?;
+  commit_agreement(input.agreement, input.responses)
+}
+
+/// Revokes the calling agent's approvals of agreements on its chain, and the expired ones
+fn revoke_my_approvals() -> ExternResult<Option<std::time::Duration>> {
+  let committed = get_my_exchange_agreements(())?
+    .iter()
+    .filter_map(|record| record.action().entry_hash().cloned())
+    .collect();
+  revoke_approvals(APPROVAL_PREFIX, &committed)
+}
+
+/// Scheduled by `accept_exchange_agreement`: waits for the countersigning session to be
+/// over, then revokes the approvals that are used up, until the remaining ones expire
+#[hdk_extern(infallible)]
+pub fn revoke_exchange_agreement_approvals(schedule: Option<Schedule>) -> Option<Schedule> {
+  if schedule.is_none() {
+    return Some(Schedule::Ephemeral(REVOKE_DELAY));
+  }
+  match revoke_my_approvals() {
+    Ok(next_expiry) => next_expiry.map(Schedule::Ephemeral),
+    Err(err) => {
+      error!("Error revoking exchange agreement approvals: {:?}", err);
+      Some(Schedule::Ephemeral(REVOKE_DELAY))
+    }
+  }
+}
+
+/// Reads the agreement out of a countersigned record
+pub(crate) fn agreement_from_record(record: &Record) -> ExternResult<ExchangeAgreement> {
+  match record.entry().as_option() {
+    Some(Entry::CounterSign(_, app_entry_bytes)) | Some(Entry::App(app_entry_bytes)) => Ok(
+      ExchangeAgreement::try_from(app_entry_bytes.clone().into_sb())
+        .map_err(CommonError::Serialize)?,
+    ),
+    _ => Err(
+      CommonError::EntryNotFound("Could not deserialize exchange agreement entry".to_string())
+        .into(),
+    ),
+  }
+}
+
+/// The action committing an exchange agreement on the calling agent's chain
+pub(crate) fn get_my_agreement_record(exchange_hash: &EntryHash) -> ExternResult<Record> {
+  get_my_exchange_agreements(())?
+    .into_iter()
+    .find(|record| record.action().entry_hash() == Some(exchange_hash))
+    .ok_or(CommonError::EntryNotFound("You are not a party to this exchange".to_string()).into())
+}
+
+#[hdk_extern]
+pub fn get_exchange_agreement(entry_hash: EntryHash) -> ExternResult<Option<Record>> {
+  get(entry_hash, GetOptions::default())
+}
+
+fn link_agreement(
+  base: ActionHash,
+  entry_hash: &EntryHash,
+  link_type: LinkTypes,
+) -> ExternResult<()> {
+  create_link(base, entry_hash.clone(), link_type, ())?;
+  Ok(())
+}
+
+/// Links a committed agreement from its listings and from the parties' user and
+/// organization profiles. Either party can call this; it does nothing if the
+/// agreement is already indexed.
+#[hdk_extern]
+pub fn index_exchange_agreement(entry_hash: EntryHash) -> ExternResult<()> {
+  let record = get_exchange_agreement(entry_hash.clone())?.ok_or(CommonError::EntryNotFound(
+    "Could not find the exchange agreement".to_string(),
+  ))?;
+  let agreement = agreement_from_record(&record)?;
+  counterpart(&agreement, &agent_info()?.agent_initial_pubkey)?;
+
+  let already_indexed =
+    get_agreements(agreement.request_hash.clone(), LinkTypes::RequestAgreements)?
+      .iter()
+      .any(|link| link.target.clone().into_entry_hash().as_ref() == Some(&entry_hash));
+  if already_indexed {
+    return Ok(());
+  }
+
+  link_agreement(
+    agreement.request_hash,
+    &entry_hash,
+    LinkTypes::RequestAgreements,
+  )?;
+  link_agreement(
+    agreement.offer_hash,
+    &entry_hash,
+    LinkTypes::OfferAgreements,
+  )?;
+  for party in [agreement.requester, agreement.provider] {
+    link_agreement(party.user_hash, &entry_hash, LinkTypes::UserAgreements)?;
+    if let Some(organization_hash) = party.organization_hash {
+      link_agreement(
+        organization_hash,
+        &entry_hash,
+        LinkTypes::OrganizationAgreements,
+      )?;
+    }
+  }
+
+  Ok(())
+}
+
+fn get_agreements(base: ActionHash, link_type: LinkTypes) -> ExternResult<Vec<Link>> {
+  let link_type_filter = link_type
+    .try_into_filter()
+    .map_err(|e| wasm_error!(WasmErrorInner::Guest(e.to_string())))?;
+  get_links(LinkQuery::new(base, link_type_filter), GetStrategy::Network)
+}
+
+fn get_agreement_records(base: ActionHash, link_type: LinkTypes) -> ExternResult<Vec<Record>> {
+  let get_input: Vec<GetInput> = get_agreements(base, link_type)?
+    .into_iter()
+    .filter_map(|link| link.target.into_any_dht_hash())
+    .map(|hash| GetInput::new(hash, GetOptions::default()))
+    .collect();
+  let records = HDK.with(|hdk| hdk.borrow().get(get_input))?;
+  Ok(records.into_iter().flatten().collect())
+}
+
+#[hdk_extern]
+pub fn get_agreements_for_request(request_hash: ActionHash) -> ExternResult<Vec<Record>> {
+  get_agreement_records(request_hash, LinkTypes::RequestAgreements)
+}
+
+#[hdk_extern]
+pub fn get_agreements_for_offer(offer_hash: ActionHash) -> ExternResult<Vec<Record>> {
+  get_agreement_records(offer_hash, LinkTypes::OfferAgreements)
+}
+
+#[hdk_extern]
+pub fn get_user_agreements(user_hash: ActionHash) -> ExternResult<Vec<Record>> {
+  get_agreement_records(user_hash, LinkTypes::UserAgreements)
+}
+
+#[hdk_extern]
+pub fn get_organization_agreements(organization_hash: ActionHash) -> ExternResult<Vec<Record>> {
+  get_agreement_records(organization_hash, LinkTypes::OrganizationAgreements)
+}
+
+/// Get the agreements on the calling agent's own chain, including ones not indexed yet
+#[hdk_extern]
+pub fn get_my_exchange_agreements(_: ()) -> ExternResult<Vec<Record>> {
+  query(
+    ChainQueryFilter::new()
+      .entry_type(UnitEntryTypes::ExchangeAgreement.try_into()?)
+      .include_entries(true),
+  )
+}
@@ -0,0 +1,72 @@
+use exchanges_integrity::*;
+use hdk::prelude::*;
+use utils::errors::CommonError;
+
+use crate::exchange_agreement::{agreement_from_record, counterpart, get_my_agreement_record};
+
+fn get_completion_links(exchange_hash: &EntryHash) -> ExternResult<Vec<Link>> {
+  let link_type_filter = LinkTypes::AgreementCompletions
+    .try_into_filter()
+    .map_err(|e| wasm_error!(WasmErrorInner::Guest(e.to_string())))?;
+  get_links(
+    LinkQuery::new(exchange_hash.clone(), link_type_filter),
+    GetStrategy::Network,
+  )
+}
+
+/// The completion of an exchange confirmed by `agent`, if any
+pub(crate) fn get_completion_by(
+  exchange_hash: &EntryHash,
+  agent: &AgentPubKey,
+) -> ExternResult<Option<ActionHash>> {
+  Ok(
+    get_completion_links(exchange_hash)?
+      .into_iter()
+      .find(|link| link.author == *agent)
+      .and_then(|link| link.target.into_action_hash()),
+  )
+}
+
+/// Confirms that an agreed exchange took place. The parties can review each other once
+/// both have confirmed it, and each party can confirm an exchange once.
+#[hdk_extern]
+pub fn confirm_exchange_completion(exchange_hash: EntryHash) -> ExternResult<Record> {
+  let agent = agent_info()?.agent_initial_pubkey;
+  let agreement_record = get_my_agreement_record(&exchange_hash)?;
+  counterpart(&agreement_from_record(&agreement_record)?, &agent)?;
+  if get_completion_by(&exchange_hash, &agent)?.is_some() {
+    return Err(
+      CommonError::InvalidData("You have already confirmed this exchange".to_string()).into(),
+    );
+  }
+
+  let completion = ExchangeCompletion {
+    exchange_hash: exchange_hash.clone(),
+    agreement_action_hash: agreement_record.action_address().clone(),
+  };
+  let completion_hash = create_entry(&EntryTypes::ExchangeCompletion(completion))?;
+  let record = get(completion_hash.clone(), GetOptions::default())?.ok_or(
+    CommonError::EntryNotFound("Could not find the newly created exchange completion".to_string()),
+  )?;
+
+  create_link(
+    exchange_hash,
+    completion_hash,
+    LinkTypes::AgreementCompletions,
+    (),
+  )?;
+
+  Ok(record)
+}
+
+/// Get the confirmations that an exchange is complete
+#[hdk_extern]
+pub fn get_exchange_completions(exchange_hash: EntryHash) -> ExternResult<Vec<Record>> {
+  let get_input: Vec<GetInput> = get_completion_links(&exchange_hash)?
+    .into_iter()
+    .filter_map(|link| link.target.into_any_dht_hash())
+    .map(|hash| GetInput::new(hash, GetOptions::default()))
+    .collect();
+  let records = HDK.with(|hdk| hdk.borrow().get(get_input))?;
+  Ok(records.into_iter().flatten().collect())
+}
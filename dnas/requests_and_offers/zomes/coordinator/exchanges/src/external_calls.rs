@@ -0,0 +1,40 @@
+use hdk::prelude::*;
+use utils::{external_local_call, GetServiceTypeForEntityInput};
+
+pub fn get_request(action_hash: ActionHash) -> ExternResult<Option<Record>> {
+  external_local_call("get_request", "requests", action_hash)
+}
+
+pub fn get_request_creator(request_hash: ActionHash) -> ExternResult<Option<ActionHash>> {
+  external_local_call("get_request_creator", "requests", request_hash)
+}
+
+pub fn get_request_organization(request_hash: ActionHash) -> ExternResult<Option<ActionHash>> {
+  external_local_call("get_request_organization", "requests", request_hash)
+}
+
+pub fn get_offer(action_hash: ActionHash) -> ExternResult<Option<Record>> {
+  external_local_call("get_offer", "offers", action_hash)
+}
+
+pub fn get_offer_creator(offer_hash: ActionHash) -> ExternResult<Option<ActionHash>> {
+  external_local_call("get_offer_creator", "offers", offer_hash)
+}
+
+pub fn get_offer_organization(offer_hash: ActionHash) -> ExternResult<Option<ActionHash>> {
+  external_local_call("get_offer_organization", "offers", offer_hash)
+}
+
+pub fn get_service_types_for_entity(
+  input: GetServiceTypeForEntityInput,
+) -> ExternResult<Vec<ActionHash>> {
+  external_local_call("get_service_types_for_entity", "service_types", input)
+}
+
+pub fn get_medium_of_exchange(medium_of_exchange_hash: ActionHash) -> ExternResult<Option<Record>> {
+  external_local_call(
+    "get_medium_of_exchange",
+    "mediums_of_exchange",
+    medium_of_exchange_hash,
+  )
+}
@@ -0,0 +1,144 @@
+use exchanges_integrity::*;
+use hdk::prelude::*;
+use utils::errors::CommonError;
+
+use crate::{
+  exchange_agreement::{agreement_from_record, counterpart, get_my_agreement_record},
+  exchange_completion::get_completion_by,
+};
+
+/// Aggregate of the reviews a user received
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct ReputationSummary {
+  pub review_count: u32,
+  /// Mean rating, or None when the user has no reviews yet
+  pub average_rating: Option<f64>,
+  /// Number of reviews for each rating, from 1 to 5
+  pub rating_counts: Vec<u32>,
+}
+
+fn get_review_links(base: AnyLinkableHash, link_type: LinkTypes) -> ExternResult<Vec<Link>> {
+  let link_type_filter = link_type
+    .try_into_filter()
+    .map_err(|e| wasm_error!(WasmErrorInner::Guest(e.to_string())))?;
+  get_links(LinkQuery::new(base, link_type_filter), GetStrategy::Network)
+}
+
+fn get_review_records(base: AnyLinkableHash, link_type: LinkTypes) -> ExternResult<Vec<Record>> {
+  let get_input: Vec<GetInput> = get_review_links(base, link_type)?
+    .into_iter()
+    .filter_map(|link| link.target.into_any_dht_hash())
+    .map(|hash| GetInput::new(hash, GetOptions::default()))
+    .collect();
+  let records = HDK.with(|hdk| hdk.borrow().get(get_input))?;
+  Ok(records.into_iter().flatten().collect())
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct ReviewInput {
+  pub exchange_hash: EntryHash,
+  pub reviewee_hash: ActionHash,
+  pub rating: u8,
+  pub comment: String,
+}
+
+/// Reviews the other party of an exchange agreement, once both parties have confirmed
+/// the exchange is complete. Each party can review an exchange once.
+#[hdk_extern]
+pub fn create_review(input: ReviewInput) -> ExternResult<Record> {
+  let agent = agent_info()?.agent_initial_pubkey;
+  let agreement_record = get_my_agreement_record(&input.exchange_hash)?;
+  let counterpart_agent = counterpart(&agreement_from_record(&agreement_record)?, &agent)?;
+  if get_completion_by(&input.exchange_hash, &agent)?.is_none() {
+    return Err(
+      CommonError::InvalidData("Confirm the exchange is complete before reviewing it".to_string())
+        .into(),
+    );
+  }
+  let counterpart_completion_hash = get_completion_by(&input.exchange_hash, &counterpart_agent)?
+    .ok_or(CommonError::InvalidData(
+      "The other party has not confirmed the exchange is complete yet".to_string(),
+    ))?;
+
+  let already_reviewed = get_review_links(
+    input.exchange_hash.clone().into(),
+    LinkTypes::AgreementReviews,
+  )?
+  .iter()
+  .any(|link| link.author == agent);
+  if already_reviewed {
+    return Err(
+      CommonError::InvalidData("You have already reviewed this exchange".to_string()).into(),
+    );
+  }
+
+  let review = Review {
+    agreement_action_hash: agreement_record.action_address().clone(),
+    exchange_hash: input.exchange_hash,
+    reviewee_hash: input.reviewee_hash,
+    counterpart_completion_hash,
+    rating: input.rating,
+    comment: input.comment,
+  };
+  let review_hash = create_entry(&EntryTypes::Review(review.clone()))?;
+  let record = get(review_hash.clone(), GetOptions::default())?.ok_or(
+    CommonError::EntryNotFound("Could not find the newly created review".to_string()),
+  )?;
+
+  create_link(
+    review.exchange_hash,
+    review_hash.clone(),
+    LinkTypes::AgreementReviews,
+    (),
+  )?;
+  create_link(
+    review.reviewee_hash,
+    review_hash,
+    LinkTypes::UserReviews,
+    (),
+  )?;
+
+  Ok(record)
+}
+
+/// Get the reviews left on an exchange agreement
+#[hdk_extern]
+pub fn get_reviews_for_agreement(exchange_hash: EntryHash) -> ExternResult<Vec<Record>> {
+  get_review_records(exchange_hash.into(), LinkTypes::AgreementReviews)
+}
+
+/// Get the reviews a user received
+#[hdk_extern]
+pub fn get_user_reviews(user_hash: ActionHash) -> ExternResult<Vec<Record>> {
+  get_review_records(user_hash.into(), LinkTypes::UserReviews)
+}
+
+/// Aggregates the reviews a user received
+#[hdk_extern]
+pub fn get_user_reputation(user_hash: ActionHash) -> ExternResult<ReputationSummary> {
+  let mut rating_counts = vec![0; (MAX_RATING - MIN_RATING + 1) as usize];
+  let mut rating_sum = 0u32;
+  let mut review_count = 0u32;
+
+  for record in get_user_reviews(user_hash.clone())? {
+    let review: Review = record
+      .entry()
+      .to_app_option()
+      .map_err(CommonError::Serialize)?
+      .ok_or(CommonError::EntryNotFound(
+        "Could not deserialize review entry".to_string(),
+      ))?;
+    if review.reviewee_hash != user_hash {
+      continue;
+    }
+    rating_counts[(review.rating - MIN_RATING) as usize] += 1;
+    rating_sum += review.rating as u32;
+    review_count += 1;
+  }
+
+  Ok(ReputationSummary {
+    review_count,
+    average_rating: (review_count > 0).then(|| rating_sum as f64 / review_count as f64),
+    rating_counts,
+  })
+}
@@ -0,0 +1,118 @@
+use hdk::prelude::*;
+use ledger_integrity::*;
+use utils::errors::CommonError;
+
+use crate::ledger_transaction::{get_user_ledger_transactions, transaction_from_record};
+
+/// A user's balance in one medium of exchange
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct LedgerBalance {
+  pub medium_of_exchange_hash: ActionHash,
+  /// Credits minus debits in minor units: negative when the user owes the community
+  pub balance: i64,
+  /// How far below zero the balance can go, in minor units
+  pub credit_limit: i64,
+}
+
+/// Input for getting a user's statement in a medium of exchange
+#[derive(Serialize, Deserialize, Debug)]
+pub struct LedgerStatementInput {
+  pub user_hash: ActionHash,
+  pub medium_of_exchange_hash: ActionHash,
+}
+
+/// A transaction as it appears on a user's statement
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct LedgerStatementLine {
+  /// The entry hash of the transaction
+  pub transaction_hash: EntryHash,
+  /// The entry hash of the exchange agreement settled
+  pub agreement_hash: EntryHash,
+  /// The original action hash of the other party's user profile
+  pub counterpart_user_hash: ActionHash,
+  /// In minor units: positive for a credit, negative for a debit
+  pub amount: i64,
+  /// The user's balance after the transaction, in minor units
+  pub balance: i64,
+  pub timestamp: Timestamp,
+}
+
+/// How a transaction changes a user's balance, and the other party
+fn user_side(transaction: &LedgerTransaction, user_hash: &ActionHash) -> Option<(i64, ActionHash)> {
+  if transaction.payee.user_hash == *user_hash {
+    Some((transaction.amount, transaction.payer.user_hash.clone()))
+  } else if transaction.payer.user_hash == *user_hash {
+    Some((-transaction.amount, transaction.payee.user_hash.clone()))
+  } else {
+    None
+  }
+}
+
+/// The indexed transactions of a user with their records, oldest first
+fn get_user_transactions(user_hash: ActionHash) -> ExternResult<Vec<(Record, LedgerTransaction)>> {
+  get_user_ledger_transactions(user_hash)?
+    .into_iter()
+    .map(|record| {
+      let transaction = transaction_from_record(&record)?;
+      Ok((record, transaction))
+    })
+    .collect()
+}
+
+/// Get a user's balance in each medium of exchange they have transactions in
+#[hdk_extern]
+pub fn get_user_balances(user_hash: ActionHash) -> ExternResult<Vec<LedgerBalance>> {
+  let mut balances: Vec<LedgerBalance> = Vec::new();
+  for (_, transaction) in get_user_transactions(user_hash.clone())? {
+    let Some((amount, _)) = user_side(&transaction, &user_hash) else {
+      continue;
+    };
+    match balances
+      .iter_mut()
+      .find(|balance| balance.medium_of_exchange_hash == transaction.medium_of_exchange_hash)
+    {
+      Some(balance) => balance.balance += amount,
+      None => {
+        let medium = must_get_ledger_medium(&transaction.medium_of_exchange_hash)?.ok_or(
+          CommonError::EntryNotFound("Could not find the medium of exchange".to_string()),
+        )?;
+        balances.push(LedgerBalance {
+          medium_of_exchange_hash: transaction.medium_of_exchange_hash,
+          balance: amount,
+          credit_limit: credit_limit(&medium.code)?,
+        });
+      }
+    }
+  }
+  Ok(balances)
+}
+
+/// Get a user's statement in a medium of exchange: their transactions in it, oldest
+/// first, with the running balance
+#[hdk_extern]
+pub fn get_user_statement(input: LedgerStatementInput) -> ExternResult<Vec<LedgerStatementLine>> {
+  let mut balance = 0;
+  let mut lines = Vec::new();
+  for (record, transaction) in get_user_transactions(input.user_hash.clone())? {
+    if transaction.medium_of_exchange_hash != input.medium_of_exchange_hash {
+      continue;
+    }
+    let Some((amount, counterpart_user_hash)) = user_side(&transaction, &input.user_hash) else {
+      continue;
+    };
+    let Some(transaction_hash) = record.action().entry_hash().cloned() else {
+      continue;
+    };
+
+    balance += amount;
+    lines.push(LedgerStatementLine {
+      transaction_hash,
+      agreement_hash: transaction.agreement_hash,
+      counterpart_user_hash,
+      amount,
+      balance,
+      timestamp: record.action().timestamp(),
+    });
+  }
+  Ok(lines)
+}
@@ -0,0 +1,400 @@
+use hdk::prelude::*;
+use ledger_integrity::*;
+use utils::{
+  errors::{CommonError, UsersError},
+  external_remote_call,
+  session_approvals::{approve_session, get_approval, revoke_approvals},
+};
+
+/// How long the payer has to sign a transaction once the session starts
+const SESSION_DURATION_MS: u64 = 30_000;
+
+/// How long indexing waits after a transaction is recorded: the chain stays locked
+/// until the countersigning session completes or expires
+const INDEX_DELAY: std::time::Duration = std::time::Duration::from_millis(SESSION_DURATION_MS);
+
+/// How long revoking approvals waits after the payer joins a session, for the same reason
+const REVOKE_DELAY: std::time::Duration = std::time::Duration::from_millis(SESSION_DURATION_MS);
+
+/// The prefix of the tags of the cap grants by which the payer approves transactions
+const APPROVAL_PREFIX: &str = "ledger_transaction";
+
+/// A transaction sent to the payer, along with the countersigning session to join
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct TransactionProposal {
+  pub agreement_action_hash: ActionHash,
+  pub preflight_request: PreflightRequest,
+}
+
+/// The signed preflight responses of both parties, from which each party commits the
+/// transaction
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct CommitTransactionInput {
+  pub transaction: LedgerTransaction,
+  pub responses: Vec<PreflightResponse>,
+}
+
+/// Builds the transaction settling an exchange agreement, from a party's record of it:
+/// the requester pays the provider the agreed amount
+fn build_transaction(agreement_action_hash: &ActionHash) -> ExternResult<LedgerTransaction> {
+  let (agreement, terms) = must_get_agreement_terms(agreement_action_hash)?.ok_or(
+    CommonError::EntryNotFound("Could not find the exchange agreement".to_string()),
+  )?;
+  let amount = terms.amount.minor_units().ok_or(CommonError::InvalidData(
+    "The agreed amount cannot be recorded in the ledger".to_string(),
+  ))?;
+
+  Ok(LedgerTransaction {
+    agreement_hash: agreement.entry_hash,
+    agreement_action_hash: agreement_action_hash.clone(),
+    medium_of_exchange_hash: terms.medium_of_exchange_hash,
+    payer: LedgerParty {
+      agent: terms.requester.agent,
+      user_hash: terms.requester.user_hash,
+    },
+    payee: LedgerParty {
+      agent: terms.provider.agent,
+      user_hash: terms.provider.user_hash,
+    },
+    amount,
+  })
+}
+
+/// The transactions on the calling agent's chain
+fn my_transactions() -> ExternResult<Vec<LedgerTransaction>> {
+  let mut transactions = Vec::new();
+  for record in get_my_ledger_transactions(())? {
+    if let Some(entry) = record.entry().as_option() {
+      if let Some(transaction) = ledger_transaction_from_entry(entry)? {
+        transactions.push(transaction);
+      }
+    }
+  }
+  Ok(transactions)
+}
+
+/// Checks the transaction the way validation will, so that an invalid one fails before
+/// both chains are locked in a session. The payer's credit limit can only be checked on
+/// the payer's own cell.
+fn check_transaction(transaction: &LedgerTransaction) -> ExternResult<()> {
+  let mut result = validate_ledger_transaction(transaction)?;
+  if result == ValidateCallbackResult::Valid
+    && transaction.payer.agent == agent_info()?.agent_initial_pubkey
+  {
+    result = check_payer_history(transaction, &my_transactions()?)?;
+  }
+
+  match result {
+    ValidateCallbackResult::Invalid(reason) => Err(CommonError::InvalidData(reason).into()),
+    _ => Ok(()),
+  }
+}
+
+fn accept_preflight_request(
+  preflight_request: PreflightRequest,
+) -> ExternResult<PreflightResponse> {
+  match accept_countersigning_preflight_request(preflight_request)? {
+    PreflightRequestAcceptance::Accepted(response) => Ok(response),
+    refusal => Err(
+      CommonError::InvalidData(format!(
+        "Could not join the countersigning session: {:?}",
+        refusal
+      ))
+      .into(),
+    ),
+  }
+}
+
+/// Checks that the payer approved the settlement of an agreement
+fn check_approval(agreement_hash: &EntryHash) -> ExternResult<()> {
+  match get_approval(APPROVAL_PREFIX, agreement_hash)? {
+    Some(_) => Ok(()),
+    None => Err(
+      CommonError::InvalidData("The payer has not approved this transaction".to_string()).into(),
+    ),
+  }
+}
+
+/// Commits the countersigned transaction to the calling agent's chain
+fn commit_transaction(
+  transaction: LedgerTransaction,
+  responses: Vec<PreflightResponse>,
+) -> ExternResult<ActionHash> {
+  let session_data = CounterSigningSessionData::try_from_responses(responses, vec![])
+    .map_err(|e| wasm_error!(WasmErrorInner::Guest(e.to_string())))?;
+  if session_data.preflight_request().app_entry_hash != hash_entry(&transaction)? {
+    return Err(
+      CommonError::InvalidData("The session was not started for this transaction".to_string())
+        .into(),
+    );
+  }
+
+  let entry_def_index = ScopedEntryDefIndex::try_from(UnitEntryTypes::LedgerTransaction)?;
+  let entry = Entry::CounterSign(
+    Box::new(session_data),
+    AppEntryBytes(SerializedBytes::try_from(transaction).map_err(CommonError::Serialize)?),
+  );
+
+  create(CreateInput::new(
+    EntryDefLocation::App(entry_def_index.into()),
+    EntryVisibility::Public,
+    entry,
+    // Countersigned entries must be committed with strict ordering
+    ChainTopOrdering::Strict,
+  ))
+}
+
+/// Approves the settlement of an exchange agreement, given a party's record of it. Must
+/// be called by the payer, the requester, before the payee can record the transaction:
+/// it lets the payee bring the payer's cell into the countersigning session.
+#[hdk_extern]
+pub fn approve_ledger_transaction(agreement_action_hash: ActionHash) -> ExternResult<()> {
+  let transaction = build_transaction(&agreement_action_hash)?;
+  if transaction.payer.agent != agent_info()?.agent_initial_pubkey {
+    return Err(UsersError::NotAuthor.into());
+  }
+  check_transaction(&transaction)?;
+
+  approve_session(
+    APPROVAL_PREFIX,
+    &transaction.agreement_hash,
+    &["accept_ledger_transaction", "commit_ledger_transaction"],
+  )
+}
+
+/// Settles an exchange agreement in the ledger, given a party's record of it: the
+/// requester is debited and the provider credited the agreed amount of its medium of
+/// exchange, which must be a base medium such as TIME. Must be called by the payee,
+/// the provider, once the payer has approved the transaction and while they are online:
+/// both chains are locked until the payer signs, or the session times out.
+///
+/// The transaction is added to the parties' balances and statements once the session
+/// is over.
+#[hdk_extern]
+pub fn record_exchange_transaction(agreement_action_hash: ActionHash) -> ExternResult<ActionHash> {
+  let transaction = build_transaction(&agreement_action_hash)?;
+  if transaction.payee.agent != agent_info()?.agent_initial_pubkey {
+    return Err(UsersError::NotAuthor.into());
+  }
+  check_transaction(&transaction)?;
+
+  let preflight_request = PreflightRequest::try_new(
+    hash_entry(&transaction)?,
+    vec![
+      (transaction.payer.agent.clone(), vec![]),
+      (transaction.payee.agent.clone(), vec![]),
+    ],
+    vec![],
+    0,
+    false,
+    session_times_from_millis(SESSION_DURATION_MS)?,
+    ActionBase::Create(CreateBase::new(
+      UnitEntryTypes::LedgerTransaction.try_into()?,
+    )),
+    PreflightBytes(vec![]),
+  )
+  .map_err(|e| wasm_error!(WasmErrorInner::Guest(e.to_string())))?;
+
+  let own_response = accept_preflight_request(preflight_request.clone())?;
+  let payer_response: PreflightResponse = external_remote_call(
+    transaction.payer.agent.clone(),
+    "accept_ledger_transaction",
+    zome_info()?.name.0.as_ref(),
+    TransactionProposal {
+      agreement_action_hash,
+      preflight_request,
+    },
+  )?;
+
+  // Responses follow the order of the signing agents: payer first
+  let responses = vec![payer_response, own_response];
+
+  let action_hash = commit_transaction(transaction.clone(), responses.clone())?;
+  let _: ActionHash = external_remote_call(
+    transaction.payer.agent.clone(),
+    "commit_ledger_transaction",
+    zome_info()?.name.0.as_ref(),
+    CommitTransactionInput {
+      transaction,
+      responses,
+    },
+  )?;
+
+  // Nothing else can be written in the call that commits a countersigned entry
+  schedule("index_ledger_transactions")?;
+
+  Ok(action_hash)
+}
+
+/// Joins, as the payer, the countersigning session of a transaction the payee records.
+///
+/// The transaction is rebuilt from the agreement it settles, so the payee cannot
+/// misstate the amount or the parties, and it must have been approved. The approval is
+/// revoked once the session is over.
+#[hdk_extern]
+pub fn accept_ledger_transaction(proposal: TransactionProposal) -> ExternResult<PreflightResponse> {
+  let transaction = build_transaction(&proposal.agreement_action_hash)?;
+  if transaction.payer.agent != agent_info()?.agent_initial_pubkey
+    || call_info()?.provenance != transaction.payee.agent
+  {
+    return Err(UsersError::NotAuthor.into());
+  }
+  check_approval(&transaction.agreement_hash)?;
+  if proposal.preflight_request.app_entry_hash != hash_entry(&transaction)? {
+    return Err(
+      CommonError::InvalidData("The session was not started for this transaction".to_string())
+        .into(),
+    );
+  }
+  check_transaction(&transaction)?;
+
+  let response = accept_preflight_request(proposal.preflight_request)?;
+  schedule("revoke_ledger_transaction_approvals")?;
+
+  Ok(response)
+}
+
+/// Commits, as the payer, an approved transaction whose session both parties have joined
+#[hdk_extern]
+pub fn commit_ledger_transaction(input: CommitTransactionInput) -> ExternResult<ActionHash> {
+  if input.transaction.payer.agent != agent_info()?.agent_initial_pubkey
+    || call_info()?.provenance != input.transaction.payee.agent
+  {
+    return Err(UsersError::NotAuthor.into());
+  }
+  check_approval(&input.transaction.agreement_hash)?;
+  commit_transaction(input.transaction, input.responses)
+}
+
+/// Revokes the payer's approvals of agreements settled on its chain, and the expired ones
+fn revoke_my_approvals() -> ExternResult<Option<std::time::Duration>> {
+  let settled = my_transactions()?
+    .into_iter()
+    .map(|transaction| transaction.agreement_hash)
+    .collect();
+  revoke_approvals(APPROVAL_PREFIX, &settled)
+}
+
+/// Scheduled by `accept_ledger_transaction`: waits for the countersigning session to be
+/// over, then revokes the approvals that are used up, until the remaining ones expire
+#[hdk_extern(infallible)]
+pub fn revoke_ledger_transaction_approvals(schedule: Option<Schedule>) -> Option<Schedule> {
+  if schedule.is_none() {
+    return Some(Schedule::Ephemeral(REVOKE_DELAY));
+  }
+  match revoke_my_approvals() {
+    Ok(next_expiry) => next_expiry.map(Schedule::Ephemeral),
+    Err(err) => {
+      error!("Error revoking ledger transaction approvals: {:?}", err);
+      Some(Schedule::Ephemeral(REVOKE_DELAY))
+    }
+  }
+}
+
+/// Reads the transaction out of a countersigned record
+pub(crate) fn transaction_from_record(record: &Record) -> ExternResult<LedgerTransaction> {
+  record
+    .entry()
+    .as_option()
+    .map(ledger_transaction_from_entry)
+    .transpose()?
+    .flatten()
+    .ok_or(
+      CommonError::EntryNotFound("Could not deserialize ledger transaction entry".to_string())
+        .into(),
+    )
+}
+
+#[hdk_extern]
+pub fn get_ledger_transaction(entry_hash: EntryHash) -> ExternResult<Option<Record>> {
+  get(entry_hash, GetOptions::default())
+}
+
+fn get_user_transaction_links(user_hash: ActionHash) -> ExternResult<Vec<Link>> {
+  let link_type_filter = LinkTypes::UserLedgerTransactions
+    .try_into_filter()
+    .map_err(|e| wasm_error!(WasmErrorInner::Guest(e.to_string())))?;
+  get_links(
+    LinkQuery::new(user_hash, link_type_filter),
+    GetStrategy::Network,
+  )
+}
+
+/// Links a committed transaction from the parties' user profiles, unless it is already
+/// indexed
+fn index_ledger_transaction(
+  entry_hash: EntryHash,
+  transaction: LedgerTransaction,
+) -> ExternResult<()> {
+  let already_indexed = get_user_transaction_links(transaction.payer.user_hash.clone())?
+    .iter()
+    .any(|link| link.target.clone().into_entry_hash().as_ref() == Some(&entry_hash));
+  if already_indexed {
+    return Ok(());
+  }
+
+  for party in [transaction.payer, transaction.payee] {
+    create_link(
+      party.user_hash,
+      entry_hash.clone(),
+      LinkTypes::UserLedgerTransactions,
+      (),
+    )?;
+  }
+
+  Ok(())
+}
+
+/// Indexes the transactions the calling agent recorded as payee
+fn index_my_ledger_transactions() -> ExternResult<()> {
+  let agent = agent_info()?.agent_initial_pubkey;
+  for record in get_my_ledger_transactions(())? {
+    let transaction = transaction_from_record(&record)?;
+    if transaction.payee.agent != agent {
+      continue;
+    }
+    if let Some(entry_hash) = record.action().entry_hash() {
+      index_ledger_transaction(entry_hash.clone(), transaction)?;
+    }
+  }
+  Ok(())
+}
+
+/// Scheduled by `record_exchange_transaction`: waits for the countersigning session to
+/// be over, then indexes the recorded transactions
+#[hdk_extern(infallible)]
+pub fn index_ledger_transactions(schedule: Option<Schedule>) -> Option<Schedule> {
+  if schedule.is_none() {
+    return Some(Schedule::Ephemeral(INDEX_DELAY));
+  }
+  if let Err(err) = index_my_ledger_transactions() {
+    error!("Error indexing ledger transactions: {:?}", err);
+  }
+  None
+}
+
+/// Get the transactions of a user, oldest first
+#[hdk_extern]
+pub fn get_user_ledger_transactions(user_hash: ActionHash) -> ExternResult<Vec<Record>> {
+  let mut seen = HashSet::new();
+  let get_input: Vec<GetInput> = get_user_transaction_links(user_hash)?
+    .into_iter()
+    .filter_map(|link| link.target.into_any_dht_hash())
+    .filter(|hash| seen.insert(hash.clone()))
+    .map(|hash| GetInput::new(hash, GetOptions::default()))
+    .collect();
+  let records = HDK.with(|hdk| hdk.borrow().get(get_input))?;
+  let mut records: Vec<Record> = records.into_iter().flatten().collect();
+  records.sort_by_key(|record| record.action().timestamp());
+  Ok(records)
+}
+
+/// Get the transactions on the calling agent's own chain, including ones not indexed yet
+#[hdk_extern]
+pub fn get_my_ledger_transactions(_: ()) -> ExternResult<Vec<Record>> {
+  query(
+    ChainQueryFilter::new()
+      .entry_type(UnitEntryTypes::LedgerTransaction.try_into()?)
+      .include_entries(true),
+  )
+}
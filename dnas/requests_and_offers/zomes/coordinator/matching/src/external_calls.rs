@@ -0,0 +1,74 @@
+use hdk::prelude::*;
+use utils::{external_local_call, GetMediumOfExchangeForEntityInput, GetServiceTypeForEntityInput};
+
+use crate::scoring::ListingAttributes;
+
+pub fn get_latest_request(original_action_hash: ActionHash) -> ExternResult<ListingAttributes> {
+  external_local_call("get_latest_request", "requests", original_action_hash)
+}
+
+pub fn get_latest_offer(original_action_hash: ActionHash) -> ExternResult<ListingAttributes> {
+  external_local_call("get_latest_offer", "offers", original_action_hash)
+}
+
+pub fn get_service_types_for_entity(
+  input: GetServiceTypeForEntityInput,
+) -> ExternResult<Vec<ActionHash>> {
+  external_local_call("get_service_types_for_entity", "service_types", input)
+}
+
+pub fn get_mediums_of_exchange_for_entity(
+  input: GetMediumOfExchangeForEntityInput,
+) -> ExternResult<Vec<ActionHash>> {
+  external_local_call(
+    "get_mediums_of_exchange_for_entity",
+    "mediums_of_exchange",
+    input,
+  )
+}
+
+pub fn get_convertible_mediums_of_exchange(
+  medium_of_exchange_hash: ActionHash,
+) -> ExternResult<Vec<ActionHash>> {
+  external_local_call(
+    "get_convertible_mediums_of_exchange",
+    "mediums_of_exchange",
+    medium_of_exchange_hash,
+  )
+}
+
+pub fn get_requests_for_service_type(service_type_hash: ActionHash) -> ExternResult<Vec<Record>> {
+  external_local_call(
+    "get_requests_for_service_type",
+    "service_types",
+    service_type_hash,
+  )
+}
+
+pub fn get_offers_for_service_type(service_type_hash: ActionHash) -> ExternResult<Vec<Record>> {
+  external_local_call(
+    "get_offers_for_service_type",
+    "service_types",
+    service_type_hash,
+  )
+}
+
+pub fn get_requests_for_medium_of_exchange(
+  medium_of_exchange_hash: ActionHash,
+) -> ExternResult<Vec<Record>> {
+  external_local_call(
+    "get_requests_for_medium_of_exchange",
+    "mediums_of_exchange",
+    medium_of_exchange_hash,
+  )
+}
+
+pub fn get_offers_for_medium_of_exchange(
+  medium_of_exchange_hash: ActionHash,
+) -> ExternResult<Vec<Record>> {
+  external_local_call(
+    "get_offers_for_medium_of_exchange",
+    "mediums_of_exchange",
+    medium_of_exchange_hash,
+  )
+}
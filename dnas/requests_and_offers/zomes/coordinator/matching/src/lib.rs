@@ -0,0 +1,3 @@
+mod external_calls;
+pub mod matching;
+pub mod scoring;
@@ -0,0 +1,140 @@
+use std::collections::HashMap;
+
+use hdk::prelude::*;
+use utils::{
+  errors::CommonError, GetMediumOfExchangeForEntityInput, GetServiceTypeForEntityInput,
+  OriginalActionHash,
+};
+
+use crate::external_calls::{
+  get_convertible_mediums_of_exchange, get_latest_offer, get_latest_request,
+  get_mediums_of_exchange_for_entity, get_offers_for_medium_of_exchange,
+  get_offers_for_service_type, get_requests_for_medium_of_exchange, get_requests_for_service_type,
+  get_service_types_for_entity,
+};
+use crate::scoring::{rank_matches, score_candidate, ListingMatch, MatchCandidate};
+
+/// Gathers the attributes and links of a listing. The mediums of exchange its mediums
+/// can be converted to are only looked up `with_exchange_rates`.
+fn get_match_candidate(
+  original_action_hash: ActionHash,
+  entity: &str,
+  with_exchange_rates: bool,
+) -> ExternResult<MatchCandidate> {
+  let attributes = match entity {
+    "request" => get_latest_request(original_action_hash.clone())?,
+    "offer" => get_latest_offer(original_action_hash.clone())?,
+    _ => return Err(CommonError::InvalidData("Must be request or offer".to_string()).into()),
+  };
+
+  let service_type_hashes = get_service_types_for_entity(GetServiceTypeForEntityInput {
+    original_action_hash: OriginalActionHash(original_action_hash.clone()),
+    entity: entity.to_string(),
+  })?;
+
+  let medium_of_exchange_hashes =
+    get_mediums_of_exchange_for_entity(GetMediumOfExchangeForEntityInput {
+      original_action_hash: OriginalActionHash(original_action_hash),
+      entity: entity.to_string(),
+    })?;
+
+  let mut convertible_mediums_of_exchange = HashMap::new();
+  if with_exchange_rates {
+    for medium_of_exchange_hash in &medium_of_exchange_hashes {
+      convertible_mediums_of_exchange.insert(
+        medium_of_exchange_hash.clone(),
+        get_convertible_mediums_of_exchange(medium_of_exchange_hash.clone())?,
+      );
+    }
+  }
+
+  Ok(MatchCandidate {
+    attributes,
+    service_type_hashes,
+    medium_of_exchange_hashes,
+    convertible_mediums_of_exchange,
+  })
+}
+
+/// Collects the original action hashes of every `target_entity` listing that shares
+/// at least one service type or medium of exchange with the source listing, counting
+/// the mediums its mediums have an exchange rate with
+fn find_candidate_hashes(
+  source: &MatchCandidate,
+  target_entity: &str,
+) -> ExternResult<Vec<ActionHash>> {
+  let mut records: Vec<Record> = Vec::new();
+
+  for service_type_hash in &source.service_type_hashes {
+    records.extend(match target_entity {
+      "request" => get_requests_for_service_type(service_type_hash.clone())?,
+      _ => get_offers_for_service_type(service_type_hash.clone())?,
+    });
+  }
+
+  let mut medium_of_exchange_hashes = source.medium_of_exchange_hashes.clone();
+  for convertible in source.convertible_mediums_of_exchange.values() {
+    for medium_of_exchange_hash in convertible {
+      if !medium_of_exchange_hashes.contains(medium_of_exchange_hash) {
+        medium_of_exchange_hashes.push(medium_of_exchange_hash.clone());
+      }
+    }
+  }
+
+  for medium_of_exchange_hash in &medium_of_exchange_hashes {
+    records.extend(match target_entity {
+      "request" => get_requests_for_medium_of_exchange(medium_of_exchange_hash.clone())?,
+      _ => get_offers_for_medium_of_exchange(medium_of_exchange_hash.clone())?,
+    });
+  }
+
+  let mut candidate_hashes: Vec<ActionHash> = Vec::new();
+  for record in records {
+    let hash = record.action_address().clone();
+    if !candidate_hashes.contains(&hash) {
+      candidate_hashes.push(hash);
+    }
+  }
+
+  Ok(candidate_hashes)
+}
+
+fn find_matches(
+  original_action_hash: ActionHash,
+  source_entity: &str,
+  target_entity: &str,
+) -> ExternResult<Vec<ListingMatch>> {
+  let source = get_match_candidate(original_action_hash, source_entity, true)?;
+
+  let mut matches: Vec<ListingMatch> = Vec::new();
+  for candidate_hash in find_candidate_hashes(&source, target_entity)? {
+    let candidate = get_match_candidate(candidate_hash.clone(), target_entity, false)?;
+
+    // Only active listings can be answered
+    if !candidate.attributes.is_active() {
+      continue;
+    }
+
+    if let Some(listing_match) = score_candidate(&source, &candidate, candidate_hash) {
+      matches.push(listing_match);
+    }
+  }
+
+  Ok(rank_matches(matches))
+}
+
+/// Get the active offers matching a request, ranked from best to worst
+#[hdk_extern]
+pub fn get_matching_offers_for_request(
+  request_original_action_hash: ActionHash,
+) -> ExternResult<Vec<ListingMatch>> {
+  find_matches(request_original_action_hash, "request", "offer")
+}
+
+/// Get the active requests matching an offer, ranked from best to worst
+#[hdk_extern]
+pub fn get_matching_requests_for_offer(
+  offer_original_action_hash: ActionHash,
+) -> ExternResult<Vec<ListingMatch>> {
+  find_matches(offer_original_action_hash, "offer", "request")
+}
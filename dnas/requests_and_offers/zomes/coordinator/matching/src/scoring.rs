@@ -0,0 +1,351 @@
+use std::collections::HashMap;
+
+use hdk::prelude::*;
+use utils::{InteractionType, ListingStatus, TimePreference, TimeZone};
+
+/// Maximum points awarded for overlapping service types
+pub const SERVICE_TYPE_WEIGHT: u32 = 50;
+/// Maximum points awarded for overlapping mediums of exchange
+pub const MEDIUM_OF_EXCHANGE_WEIGHT: u32 = 20;
+/// Points awarded when both listings use the same interaction type
+pub const INTERACTION_TYPE_WEIGHT: u32 = 15;
+/// Points awarded when the time preferences are compatible
+pub const TIME_PREFERENCE_WEIGHT: u32 = 10;
+/// Points awarded when both listings share a time zone
+pub const TIME_ZONE_WEIGHT: u32 = 5;
+
+/// The fields of a `Request` or `Offer` entry that are relevant for matching.
+///
+/// Requests and offers are defined in separate integrity zomes, which cannot be
+/// linked into the same coordinator, so only the shared fields are mirrored here.
+/// Unknown fields are ignored when deserializing.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct ListingAttributes {
+  pub time_preference: TimePreference,
+  #[serde(default)]
+  pub time_zone: Option<TimeZone>,
+  pub interaction_type: InteractionType,
+  #[serde(default)]
+  pub status: ListingStatus,
+}
+
+impl ListingAttributes {
+  /// Whether the listing is still listed: active, or in progress with someone working on it
+  pub fn is_active(&self) -> bool {
+    matches!(
+      self.status,
+      ListingStatus::Active | ListingStatus::InProgress
+    )
+  }
+}
+
+/// A listing together with the links used to match it
+#[derive(Debug, Clone)]
+pub struct MatchCandidate {
+  pub attributes: ListingAttributes,
+  pub service_type_hashes: Vec<ActionHash>,
+  pub medium_of_exchange_hashes: Vec<ActionHash>,
+  /// For each medium of exchange of the listing, the mediums it has an exchange rate with.
+  /// Only needed for the listing being matched.
+  pub convertible_mediums_of_exchange: HashMap<ActionHash, Vec<ActionHash>>,
+}
+
+/// A ranked match returned by the matching externs
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct ListingMatch {
+  pub original_action_hash: ActionHash,
+  /// Score between 0 and 100, higher is better
+  pub score: u32,
+  pub shared_service_types: Vec<ActionHash>,
+  /// The mediums of exchange of the source listing that the candidate uses, directly or
+  /// through an exchange rate
+  pub shared_mediums_of_exchange: Vec<ActionHash>,
+}
+
+fn shared_hashes(source: &[ActionHash], candidate: &[ActionHash]) -> Vec<ActionHash> {
+  let mut shared: Vec<ActionHash> = source
+    .iter()
+    .filter(|hash| candidate.contains(hash))
+    .cloned()
+    .collect();
+  shared.dedup();
+  shared
+}
+
+fn shared_mediums_of_exchange(
+  source: &MatchCandidate,
+  candidate: &MatchCandidate,
+) -> Vec<ActionHash> {
+  let mut shared: Vec<ActionHash> = Vec::new();
+  for medium_hash in &source.medium_of_exchange_hashes {
+    let convertible = source.convertible_mediums_of_exchange.get(medium_hash);
+    let is_accepted = candidate
+      .medium_of_exchange_hashes
+      .iter()
+      .any(|candidate_medium_hash| {
+        candidate_medium_hash == medium_hash
+          || convertible.is_some_and(|convertible| convertible.contains(candidate_medium_hash))
+      });
+    if is_accepted && !shared.contains(medium_hash) {
+      shared.push(medium_hash.clone());
+    }
+  }
+  shared
+}
+
+fn proportional_points(weight: u32, shared: usize, total: usize) -> u32 {
+  if total == 0 {
+    return 0;
+  }
+  weight * shared.min(total) as u32 / total as u32
+}
+
+fn time_preferences_compatible(source: &TimePreference, candidate: &TimePreference) -> bool {
+  matches!(source, TimePreference::NoPreference)
+    || matches!(candidate, TimePreference::NoPreference)
+    || source == candidate
+}
+
+fn time_zones_match(source: &Option<TimeZone>, candidate: &Option<TimeZone>) -> bool {
+  match (source, candidate) {
+    (Some(source), Some(candidate)) => source.trim().eq_ignore_ascii_case(candidate.trim()),
+    _ => false,
+  }
+}
+
+/// Scores how well `candidate` answers `source`.
+///
+/// Service types and mediums of exchange are scored in proportion to how many of
+/// the source listing's links the candidate shares, a medium of exchange being shared
+/// when the candidate uses it or one it has an exchange rate with; the remaining
+/// attributes add fixed bonuses. Returns `None` when the listings share neither a service type
+/// nor a medium of exchange.
+pub fn score_candidate(
+  source: &MatchCandidate,
+  candidate: &MatchCandidate,
+  candidate_hash: ActionHash,
+) -> Option<ListingMatch> {
+  let shared_service_types =
+    shared_hashes(&source.service_type_hashes, &candidate.service_type_hashes);
+  let shared_mediums_of_exchange = shared_mediums_of_exchange(source, candidate);
+
+  if shared_service_types.is_empty() && shared_mediums_of_exchange.is_empty() {
+    return None;
+  }
+
+  let mut score = proportional_points(
+    SERVICE_TYPE_WEIGHT,
+    shared_service_types.len(),
+    source.service_type_hashes.len(),
+  ) + proportional_points(
+    MEDIUM_OF_EXCHANGE_WEIGHT,
+    shared_mediums_of_exchange.len(),
+    source.medium_of_exchange_hashes.len(),
+  );
+
+  if source.attributes.interaction_type == candidate.attributes.interaction_type {
+    score += INTERACTION_TYPE_WEIGHT;
+  }
+  if time_preferences_compatible(
+    &source.attributes.time_preference,
+    &candidate.attributes.time_preference,
+  ) {
+    score += TIME_PREFERENCE_WEIGHT;
+  }
+  if time_zones_match(
+    &source.attributes.time_zone,
+    &candidate.attributes.time_zone,
+  ) {
+    score += TIME_ZONE_WEIGHT;
+  }
+
+  Some(ListingMatch {
+    original_action_hash: candidate_hash,
+    score,
+    shared_service_types,
+    shared_mediums_of_exchange,
+  })
+}
+
+/// Sorts matches from best to worst, keeping the discovery order for ties
+pub fn rank_matches(mut matches: Vec<ListingMatch>) -> Vec<ListingMatch> {
+  matches.sort_by_key(|listing_match| std::cmp::Reverse(listing_match.score));
+  matches
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  fn hash(byte: u8) -> ActionHash {
+    ActionHash::from_raw_36(vec![byte; 36])
+  }
+
+  fn candidate(
+    service_types: Vec<ActionHash>,
+    mediums: Vec<ActionHash>,
+    interaction_type: InteractionType,
+    time_preference: TimePreference,
+    time_zone: Option<&str>,
+  ) -> MatchCandidate {
+    MatchCandidate {
+      attributes: ListingAttributes {
+        time_preference,
+        time_zone: time_zone.map(|tz| tz.to_string()),
+        interaction_type,
+        status: ListingStatus::Active,
+      },
+      service_type_hashes: service_types,
+      medium_of_exchange_hashes: mediums,
+      convertible_mediums_of_exchange: HashMap::new(),
+    }
+  }
+
+  #[test]
+  fn test_full_match_scores_100() {
+    let source = candidate(
+      vec![hash(1)],
+      vec![hash(2)],
+      InteractionType::Virtual,
+      TimePreference::Morning,
+      Some("UTC-5"),
+    );
+    let other = candidate(
+      vec![hash(1), hash(3)],
+      vec![hash(2)],
+      InteractionType::Virtual,
+      TimePreference::Morning,
+      Some("utc-5"),
+    );
+
+    let result = score_candidate(&source, &other, hash(9)).unwrap();
+    assert_eq!(result.score, 100);
+    assert_eq!(result.shared_service_types, vec![hash(1)]);
+    assert_eq!(result.shared_mediums_of_exchange, vec![hash(2)]);
+  }
+
+  #[test]
+  fn test_no_shared_links_is_not_a_match() {
+    let source = candidate(
+      vec![hash(1)],
+      vec![hash(2)],
+      InteractionType::Virtual,
+      TimePreference::Morning,
+      None,
+    );
+    let other = candidate(
+      vec![hash(3)],
+      vec![hash(4)],
+      InteractionType::Virtual,
+      TimePreference::Morning,
+      None,
+    );
+
+    assert!(score_candidate(&source, &other, hash(9)).is_none());
+  }
+
+  #[test]
+  fn test_mediums_with_an_exchange_rate_are_shared() {
+    let mut source = candidate(
+      vec![hash(1)],
+      vec![hash(2)],
+      InteractionType::Virtual,
+      TimePreference::Morning,
+      None,
+    );
+    let other = candidate(
+      vec![hash(3)],
+      vec![hash(4)],
+      InteractionType::Virtual,
+      TimePreference::Morning,
+      None,
+    );
+    source
+      .convertible_mediums_of_exchange
+      .insert(hash(2), vec![hash(4)]);
+
+    let result = score_candidate(&source, &other, hash(9)).unwrap();
+    assert_eq!(result.shared_mediums_of_exchange, vec![hash(2)]);
+    assert_eq!(
+      result.score,
+      MEDIUM_OF_EXCHANGE_WEIGHT + INTERACTION_TYPE_WEIGHT + TIME_PREFERENCE_WEIGHT
+    );
+  }
+
+  #[test]
+  fn test_partial_service_type_overlap_is_proportional() {
+    let source = candidate(
+      vec![hash(1), hash(2)],
+      vec![],
+      InteractionType::Virtual,
+      TimePreference::Evening,
+      None,
+    );
+    let other = candidate(
+      vec![hash(2)],
+      vec![],
+      InteractionType::InPerson,
+      TimePreference::Morning,
+      None,
+    );
+
+    let result = score_candidate(&source, &other, hash(9)).unwrap();
+    assert_eq!(result.score, SERVICE_TYPE_WEIGHT / 2);
+  }
+
+  #[test]
+  fn test_no_preference_is_compatible_with_any_time() {
+    assert!(time_preferences_compatible(
+      &TimePreference::NoPreference,
+      &TimePreference::Evening
+    ));
+    assert!(time_preferences_compatible(
+      &TimePreference::Other("Weekends".to_string()),
+      &TimePreference::Other("Weekends".to_string())
+    ));
+    assert!(!time_preferences_compatible(
+      &TimePreference::Morning,
+      &TimePreference::Evening
+    ));
+  }
+
+  #[test]
+  fn test_rank_matches_orders_by_score() {
+    let matches = vec![
+      ListingMatch {
+        original_action_hash: hash(1),
+        score: 20,
+        shared_service_types: vec![],
+        shared_mediums_of_exchange: vec![],
+      },
+      ListingMatch {
+        original_action_hash: hash(2),
+        score: 80,
+        shared_service_types: vec![],
+        shared_mediums_of_exchange: vec![],
+      },
+    ];
+
+    let ranked = rank_matches(matches);
+    assert_eq!(ranked[0].original_action_hash, hash(2));
+    assert_eq!(ranked[1].original_action_hash, hash(1));
+  }
+
+  #[test]
+  fn test_in_progress_listings_are_still_matched() {
+    let mut listing = candidate(
+      vec![hash(1)],
+      vec![],
+      InteractionType::Virtual,
+      TimePreference::Morning,
+      None,
+    );
+    assert!(listing.attributes.is_active());
+
+    listing.attributes.status = ListingStatus::InProgress;
+    assert!(listing.attributes.is_active());
+
+    listing.attributes.status = ListingStatus::Fulfilled;
+    assert!(!listing.attributes.is_active());
+  }
+}
@@ -0,0 +1,250 @@
+use hdk::prelude::*;
+use mediums_of_exchange_integrity::{EntryTypes, ExchangeRate, LinkTypes};
+use utils::{
+  errors::{AdministrationError, CommonError},
+  role_links::{make_role_proof, role_proof_tag, RoleProof},
+  ConvertInput,
+};
+
+use crate::external_calls::{check_if_agent_is_administrator, get_agent_administrator_links};
+use crate::medium_of_exchange::is_medium_of_exchange_approved;
+
+/// Input for creating an exchange rate: `rate_numerator` units of the `to` medium are
+/// worth `rate_denominator` units of the `from` medium
+#[derive(Serialize, Deserialize, Debug)]
+pub struct ExchangeRateInput {
+  pub from_medium_hash: ActionHash,
+  pub to_medium_hash: ActionHash,
+  pub rate_numerator: u64,
+  pub rate_denominator: u64,
+  pub effective_at: Timestamp,
+}
+
+/// Two mediums of exchange, by their original action hashes
+#[derive(Serialize, Deserialize, Debug)]
+pub struct MediumOfExchangePairInput {
+  pub from_medium_hash: ActionHash,
+  pub to_medium_hash: ActionHash,
+}
+
+fn medium_tag(medium_hash: &ActionHash) -> LinkTag {
+  LinkTag::new(medium_hash.get_raw_39())
+}
+
+/// Returns the links from a medium of exchange to its exchange rates with `other_medium_hash`,
+/// or to all of its exchange rates
+fn get_exchange_rate_links(
+  medium_hash: ActionHash,
+  other_medium_hash: Option<&ActionHash>,
+) -> ExternResult<Vec<Link>> {
+  let link_type_filter = LinkTypes::MediumOfExchangeToExchangeRate
+    .try_into_filter()
+    .map_err(|e| wasm_error!(WasmErrorInner::Guest(e.to_string())))?;
+  let mut query = LinkQuery::new(medium_hash, link_type_filter);
+  if let Some(other_medium_hash) = other_medium_hash {
+    query = query.tag_prefix(medium_tag(other_medium_hash));
+  }
+  get_links(query, GetStrategy::Network)
+}
+
+/// The exchange rates between two mediums of exchange, in both directions, oldest first
+fn get_exchange_rates_between(
+  from_medium_hash: &ActionHash,
+  to_medium_hash: &ActionHash,
+) -> ExternResult<Vec<(Record, ExchangeRate)>> {
+  let mut rates: Vec<(Record, ExchangeRate)> = Vec::new();
+  for link in get_exchange_rate_links(from_medium_hash.clone(), Some(to_medium_hash))? {
+    let Some(rate_hash) = link.target.into_action_hash() else {
+      continue;
+    };
+    if rates
+      .iter()
+      .any(|(record, _)| *record.action_address() == rate_hash)
+    {
+      continue;
+    }
+    let Some(record) = get(rate_hash, GetOptions::default())? else {
+      continue;
+    };
+    if let Some(exchange_rate) = record
+      .entry()
+      .to_app_option::<ExchangeRate>()
+      .map_err(CommonError::Serialize)?
+    {
+      rates.push((record, exchange_rate));
+    }
+  }
+  rates.sort_by_key(|(_, exchange_rate)| exchange_rate.effective_at);
+  Ok(rates)
+}
+
+/// The proof of the calling agent's `AgentAdministrators` link to "network.administrators"
+fn administrator_proof() -> ExternResult<RoleProof> {
+  let administrators_path_hash: AnyLinkableHash = Path::from("network.administrators")
+    .path_entry_hash()?
+    .into();
+  let administrator_link = get_agent_administrator_links(agent_info()?.agent_initial_pubkey)?
+    .into_iter()
+    .find(|link| link.target == administrators_path_hash)
+    .ok_or(AdministrationError::Unauthorized)?;
+  make_role_proof(&administrator_link)
+}
+
+/// Create an exchange rate between two approved mediums of exchange (admin only)
+#[hdk_extern]
+pub fn create_exchange_rate(input: ExchangeRateInput) -> ExternResult<Record> {
+  let is_admin = check_if_agent_is_administrator(agent_info()?.agent_initial_pubkey)?;
+  if !is_admin {
+    return Err(AdministrationError::Unauthorized.into());
+  }
+
+  let exchange_rate = ExchangeRate {
+    from_medium_hash: input.from_medium_hash,
+    to_medium_hash: input.to_medium_hash,
+    rate_numerator: input.rate_numerator,
+    rate_denominator: input.rate_denominator,
+    effective_at: input.effective_at,
+    administrator_proof: administrator_proof()?,
+  };
+  for medium_hash in [
+    &exchange_rate.from_medium_hash,
+    &exchange_rate.to_medium_hash,
+  ] {
+    if !is_medium_of_exchange_approved(medium_hash.clone())? {
+      return Err(
+        CommonError::InvalidData(
+          "Exchange rates can only be set between approved mediums of exchange".to_string(),
+        )
+        .into(),
+      );
+    }
+  }
+
+  let exchange_rate_hash = create_entry(EntryTypes::ExchangeRate(exchange_rate.clone()))?;
+  let record = get(exchange_rate_hash.clone(), GetOptions::default())?.ok_or(
+    CommonError::EntryNotFound("Could not find the newly created exchange rate".to_string()),
+  )?;
+
+  create_link(
+    exchange_rate.from_medium_hash.clone(),
+    exchange_rate_hash.clone(),
+    LinkTypes::MediumOfExchangeToExchangeRate,
+    medium_tag(&exchange_rate.to_medium_hash),
+  )?;
+  create_link(
+    exchange_rate.to_medium_hash,
+    exchange_rate_hash,
+    LinkTypes::MediumOfExchangeToExchangeRate,
+    medium_tag(&exchange_rate.from_medium_hash),
+  )?;
+
+  Ok(record)
+}
+
+/// Delete a mistaken exchange rate (admin only)
+#[hdk_extern]
+pub fn delete_exchange_rate(exchange_rate_hash: ActionHash) -> ExternResult<()> {
+  let is_admin = check_if_agent_is_administrator(agent_info()?.agent_initial_pubkey)?;
+  if !is_admin {
+    return Err(AdministrationError::Unauthorized.into());
+  }
+
+  let exchange_rate: ExchangeRate = get(exchange_rate_hash.clone(), GetOptions::default())?
+    .ok_or(CommonError::EntryNotFound(
+      "Could not find the exchange rate to delete".to_string(),
+    ))?
+    .entry()
+    .to_app_option()
+    .map_err(CommonError::Serialize)?
+    .ok_or(CommonError::EntryNotFound(
+      "Could not deserialize exchange rate entry".to_string(),
+    ))?;
+
+  // The deletions below cannot carry the proof of the administrator role
+  create_link(
+    exchange_rate_hash.clone(),
+    exchange_rate_hash.clone(),
+    LinkTypes::ExchangeRateDeletions,
+    role_proof_tag(&administrator_proof()?)?,
+  )?;
+
+  for (medium_hash, other_medium_hash) in [
+    (
+      &exchange_rate.from_medium_hash,
+      &exchange_rate.to_medium_hash,
+    ),
+    (
+      &exchange_rate.to_medium_hash,
+      &exchange_rate.from_medium_hash,
+    ),
+  ] {
+    for link in get_exchange_rate_links(medium_hash.clone(), Some(other_medium_hash))? {
+      if link.target.clone().into_action_hash().as_ref() == Some(&exchange_rate_hash) {
+        delete_link(link.create_link_hash, GetOptions::default())?;
+      }
+    }
+  }
+
+  delete_entry(exchange_rate_hash)?;
+
+  Ok(())
+}
+
+/// Get every exchange rate set between two mediums of exchange, in either direction,
+/// oldest first
+#[hdk_extern]
+pub fn get_exchange_rate_history(input: MediumOfExchangePairInput) -> ExternResult<Vec<Record>> {
+  Ok(
+    get_exchange_rates_between(&input.from_medium_hash, &input.to_medium_hash)?
+      .into_iter()
+      .map(|(record, _)| record)
+      .collect(),
+  )
+}
+
+/// Converts an amount from one medium of exchange to another with the latest rate between
+/// them that took effect at or before `at`. Rates set in the other direction are used
+/// inverted; mediums without a direct rate between them cannot be converted.
+#[hdk_extern]
+pub fn convert(input: ConvertInput) -> ExternResult<f64> {
+  if input.from_medium_hash == input.to_medium_hash {
+    return Ok(input.amount);
+  }
+
+  let at = match input.at {
+    Some(at) => at,
+    None => sys_time()?,
+  };
+  let exchange_rate = get_exchange_rates_between(&input.from_medium_hash, &input.to_medium_hash)?
+    .into_iter()
+    .map(|(_, exchange_rate)| exchange_rate)
+    .rfind(|exchange_rate| exchange_rate.effective_at <= at)
+    .ok_or(CommonError::EntryNotFound(
+      "No exchange rate between these mediums of exchange at that time".to_string(),
+    ))?;
+
+  let numerator = exchange_rate.rate_numerator as f64;
+  let denominator = exchange_rate.rate_denominator as f64;
+  if exchange_rate.from_medium_hash == input.from_medium_hash {
+    Ok(input.amount * numerator / denominator)
+  } else {
+    Ok(input.amount * denominator / numerator)
+  }
+}
+
+/// Get the original action hashes of the mediums of exchange that a medium of exchange has
+/// an exchange rate with
+#[hdk_extern]
+pub fn get_convertible_mediums_of_exchange(
+  medium_hash: ActionHash,
+) -> ExternResult<Vec<ActionHash>> {
+  let mut medium_hashes: Vec<ActionHash> = Vec::new();
+  for link in get_exchange_rate_links(medium_hash, None)? {
+    if let Ok(other_medium_hash) = ActionHash::try_from_raw_39(link.tag.0) {
+      if !medium_hashes.contains(&other_medium_hash) {
+        medium_hashes.push(other_medium_hash);
+      }
+    }
+  }
+  Ok(medium_hashes)
+}
@@ -11,6 +11,14 @@ pub fn check_if_agent_is_administrator(agent_pubkey: AgentPubKey) -> ExternResul
   external_local_call("check_if_agent_is_administrator", "administration", input)
 }
 
+pub fn get_agent_administrator_links(agent_pubkey: AgentPubKey) -> ExternResult<Vec<Link>> {
+  external_local_call(
+    "get_agent_administrator_links",
+    "administration",
+    agent_pubkey,
+  )
+}
+
 pub fn get_agent_user(agent_pubkey: AgentPubKey) -> ExternResult<Vec<Link>> {
   // The 'users_organizations' zome's 'get_agent_user' extern expects AgentPubKey directly.
   // Passing it directly resolves a deserialization error caused by type mismatch.
@@ -1,3 +1,4 @@
+pub mod exchange_rate;
 mod external_calls;
 pub mod medium_of_exchange;
 
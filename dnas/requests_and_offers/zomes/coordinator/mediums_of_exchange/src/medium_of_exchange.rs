@@ -2,8 +2,8 @@ use hdk::prelude::*;
 use mediums_of_exchange_integrity::{EntryTypes, LinkTypes, MediumOfExchange};
 use utils::errors::{AdministrationError, CommonError};
 use utils::{
-  GetMediumOfExchangeForEntityInput, MediumOfExchangeLinkInput, OriginalActionHash,
-  PreviousActionHash, UpdateMediumOfExchangeLinksInput,
+  paginate_records, GetMediumOfExchangeForEntityInput, MediumOfExchangeLinkInput,
+  OriginalActionHash, Page, PaginationInput, PreviousActionHash, UpdateMediumOfExchangeLinksInput,
 };
 
 use crate::external_calls::{
@@ -276,6 +276,20 @@ pub fn get_all_mediums_of_exchange(_: ()) -> ExternResult<Vec<Record>> {
   Ok(records)
 }
 
+/// Get a page of mediums of exchange, ordered by creation time
+#[hdk_extern]
+pub fn get_all_mediums_of_exchange_paginated(input: PaginationInput) -> ExternResult<Page<Record>> {
+  let path = Path::from("mediums_of_exchange");
+  let link_type_filter = LinkTypes::AllMediumsOfExchange
+    .try_into_filter()
+    .map_err(|e| wasm_error!(WasmErrorInner::Guest(e.to_string())))?;
+  let links = get_links(
+    LinkQuery::new(path.path_entry_hash()?, link_type_filter),
+    GetStrategy::Network,
+  )?;
+  paginate_records(links, &input)
+}
+
 /// Get pending mediums of exchange (admin only)
 #[hdk_extern]
 pub fn get_pending_mediums_of_exchange(_: ()) -> ExternResult<Vec<Record>> {
@@ -0,0 +1,14 @@
+use hdk::prelude::*;
+use utils::external_local_call;
+
+pub fn get_agent_user(agent_pubkey: AgentPubKey) -> ExternResult<Vec<Link>> {
+  external_local_call("get_agent_user", "users_organizations", agent_pubkey)
+}
+
+pub fn get_user_agents(user_original_action_hash: ActionHash) -> ExternResult<Vec<AgentPubKey>> {
+  external_local_call(
+    "get_user_agents",
+    "users_organizations",
+    user_original_action_hash,
+  )
+}
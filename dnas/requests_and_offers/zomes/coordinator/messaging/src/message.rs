@@ -0,0 +1,237 @@
+use hdk::prelude::*;
+use messaging_integrity::*;
+use utils::errors::{CommonError, UsersError};
+
+use crate::external_calls::{get_agent_user, get_user_agents};
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct SendMessageInput {
+  /// The original action hash of the recipient's user profile
+  pub recipient_hash: ActionHash,
+  pub content: String,
+}
+
+/// A mailbox message pushed to an online recipient as a remote signal
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct MessageDelivery {
+  pub mailbox_hash: ActionHash,
+  pub mailbox_message: MailboxMessage,
+}
+
+/// Returns the user profile of an agent
+fn get_user_hash(agent: AgentPubKey) -> ExternResult<Option<ActionHash>> {
+  Ok(
+    get_agent_user(agent)?
+      .first()
+      .and_then(|link| link.target.clone().into_action_hash()),
+  )
+}
+
+fn message_from_record(record: &Record) -> ExternResult<Message> {
+  Ok(
+    record
+      .entry()
+      .to_app_option()
+      .map_err(CommonError::Serialize)?
+      .ok_or(CommonError::EntryNotFound(
+        "Could not deserialize message entry".to_string(),
+      ))?,
+  )
+}
+
+/// Sends a private message to every agent of a user.
+///
+/// The message is encrypted for each agent and left in their mailbox; agents that are
+/// online also receive it straight away as a remote signal.
+#[hdk_extern]
+pub fn send_message(input: SendMessageInput) -> ExternResult<Record> {
+  let my_agent = agent_info()?.agent_initial_pubkey;
+  let sender_hash = get_user_hash(my_agent.clone())?.ok_or(UsersError::UserProfileRequired)?;
+
+  let recipient_agents = get_user_agents(input.recipient_hash.clone())?;
+  if recipient_agents.is_empty() {
+    return Err(
+      CommonError::EntryNotFound("Could not find the recipient's agents".to_string()).into(),
+    );
+  }
+
+  let message = Message {
+    sender_hash,
+    recipient_hash: input.recipient_hash,
+    content: input.content,
+    sent_at: sys_time()?,
+    mailbox_hash: None,
+  };
+  let message_hash = create_entry(&EntryTypes::Message(message.clone()))?;
+  let message_bytes = SerializedBytes::try_from(message)
+    .map_err(CommonError::Serialize)?
+    .bytes()
+    .to_vec();
+
+  for agent in recipient_agents {
+    let encrypted_message = ed_25519_x_salsa20_poly1305_encrypt(
+      my_agent.clone(),
+      agent.clone(),
+      message_bytes.clone().into(),
+    )?;
+    let mailbox_message = MailboxMessage {
+      recipient: agent.clone(),
+      encrypted_message,
+    };
+    let mailbox_hash = create_entry(&EntryTypes::MailboxMessage(mailbox_message.clone()))?;
+    create_link(
+      agent.clone(),
+      mailbox_hash.clone(),
+      LinkTypes::AgentMailbox,
+      (),
+    )?;
+
+    send_remote_signal(
+      MessageDelivery {
+        mailbox_hash,
+        mailbox_message,
+      },
+      vec![agent],
+    )?;
+  }
+
+  get(message_hash, GetOptions::default())?.ok_or(
+    CommonError::EntryNotFound("Could not find the newly created message".to_string()).into(),
+  )
+}
+
+/// Decrypts a mailbox message sent by `sender` and commits a private copy of it.
+/// Returns None if the message is not for this agent or was already received.
+fn receive_message(sender: AgentPubKey, delivery: MessageDelivery) -> ExternResult<Option<Record>> {
+  let my_agent = agent_info()?.agent_initial_pubkey;
+  if delivery.mailbox_message.recipient != my_agent {
+    return Ok(None);
+  }
+
+  let already_received = get_my_messages(())?
+    .iter()
+    .map(message_from_record)
+    .collect::<ExternResult<Vec<Message>>>()?
+    .iter()
+    .any(|message| message.mailbox_hash.as_ref() == Some(&delivery.mailbox_hash));
+  if already_received {
+    return Ok(None);
+  }
+
+  let decrypted = ed_25519_x_salsa20_poly1305_decrypt(
+    my_agent,
+    sender.clone(),
+    delivery.mailbox_message.encrypted_message,
+  )?;
+  let mut message = Message::try_from(SerializedBytes::from(UnsafeBytes::from(
+    decrypted.as_ref().to_vec(),
+  )))
+  .map_err(CommonError::Serialize)?;
+
+  // The sender must be one of the agents of the user profile the message claims to come from
+  if !get_user_agents(message.sender_hash.clone())?.contains(&sender) {
+    return Err(
+      CommonError::InvalidData("The message sender does not match its user profile".to_string())
+        .into(),
+    );
+  }
+
+  message.mailbox_hash = Some(delivery.mailbox_hash);
+  let message_hash = create_entry(&EntryTypes::Message(message))?;
+  get(message_hash, GetOptions::default())
+}
+
+/// Live delivery: stores a message pushed by an online sender, who must be an agent of
+/// a user. The commit is signalled to the UI by `post_commit`.
+#[hdk_extern]
+pub fn recv_remote_signal(delivery: MessageDelivery) -> ExternResult<()> {
+  let sender = call_info()?.provenance;
+  let is_user_agent = match get_user_hash(sender.clone())? {
+    Some(user_hash) => get_user_agents(user_hash)?.contains(&sender),
+    None => false,
+  };
+  if !is_user_agent {
+    return Err(UsersError::UserProfileRequired.into());
+  }
+
+  receive_message(sender, delivery)?;
+  Ok(())
+}
+
+/// Offline delivery: stores the messages left in this agent's mailbox since it was last
+/// collected, and returns them. Collected messages are removed from the mailbox; messages
+/// that cannot be received are logged and skipped.
+#[hdk_extern]
+pub fn collect_messages(_: ()) -> ExternResult<Vec<Record>> {
+  let link_type_filter = LinkTypes::AgentMailbox
+    .try_into_filter()
+    .map_err(|e| wasm_error!(WasmErrorInner::Guest(e.to_string())))?;
+  let links = get_links(
+    LinkQuery::new(agent_info()?.agent_initial_pubkey, link_type_filter),
+    GetStrategy::Network,
+  )?;
+
+  let mut received = Vec::new();
+  for link in links {
+    let Some(mailbox_hash) = link.target.into_action_hash() else {
+      continue;
+    };
+    let Some(mailbox_record) = get(mailbox_hash.clone(), GetOptions::default())? else {
+      continue;
+    };
+    let Ok(Some(mailbox_message)) = mailbox_record.entry().to_app_option::<MailboxMessage>() else {
+      error!(
+        "Skipping mailbox entry {} that is not a message",
+        mailbox_hash
+      );
+      continue;
+    };
+    let delivery = MessageDelivery {
+      mailbox_hash: mailbox_hash.clone(),
+      mailbox_message,
+    };
+    // One message that cannot be received must not hold back the rest of the mailbox
+    match receive_message(mailbox_record.action().author().clone(), delivery) {
+      Ok(Some(record)) => received.push(record),
+      Ok(None) => (),
+      Err(err) => {
+        error!(
+          "Could not receive mailbox message {}: {:?}",
+          mailbox_hash, err
+        );
+        continue;
+      }
+    }
+
+    // The private copy is on this agent's chain: the mailbox no longer needs the message
+    delete_link(link.create_link_hash, GetOptions::default())?;
+    delete_entry(mailbox_hash)?;
+  }
+
+  Ok(received)
+}
+
+/// Get every message this agent sent or received
+#[hdk_extern]
+pub fn get_my_messages(_: ()) -> ExternResult<Vec<Record>> {
+  query(
+    ChainQueryFilter::new()
+      .entry_type(UnitEntryTypes::Message.try_into()?)
+      .include_entries(true),
+  )
+}
+
+/// Get the messages exchanged with a user, oldest first
+#[hdk_extern]
+pub fn get_conversation(user_hash: ActionHash) -> ExternResult<Vec<Record>> {
+  let mut conversation = Vec::new();
+  for record in get_my_messages(())? {
+    let message = message_from_record(&record)?;
+    if message.sender_hash == user_hash || message.recipient_hash == user_hash {
+      conversation.push((message.sent_at, record));
+    }
+  }
+  conversation.sort_by_key(|(sent_at, _)| *sent_at);
+
+  Ok(conversation.into_iter().map(|(_, record)| record).collect())
+}
@@ -0,0 +1,177 @@
+use hdk::prelude::*;
+use notifications_integrity::*;
+use utils::{errors::CommonError, ListingNotification};
+
+use crate::Signal;
+
+fn get_subscription_links(base: AnyLinkableHash, link_type: LinkTypes) -> ExternResult<Vec<Link>> {
+  let link_type_filter = link_type
+    .try_into_filter()
+    .map_err(|e| wasm_error!(WasmErrorInner::Guest(e.to_string())))?;
+  get_links(LinkQuery::new(base, link_type_filter), GetStrategy::Network)
+}
+
+fn subscription_from_record(record: &Record) -> ExternResult<Subscription> {
+  Ok(
+    record
+      .entry()
+      .to_app_option()
+      .map_err(CommonError::Serialize)?
+      .ok_or(CommonError::EntryNotFound(
+        "Could not deserialize subscription entry".to_string(),
+      ))?,
+  )
+}
+
+/// Get the subscriptions of the calling agent
+#[hdk_extern]
+pub fn get_my_subscriptions(_: ()) -> ExternResult<Vec<Record>> {
+  let get_input: Vec<GetInput> = get_subscription_links(
+    agent_info()?.agent_initial_pubkey.into(),
+    LinkTypes::AgentSubscriptions,
+  )?
+  .into_iter()
+  .filter_map(|link| link.target.into_any_dht_hash())
+  .map(|hash| GetInput::new(hash, GetOptions::default()))
+  .collect();
+  let records = HDK.with(|hdk| hdk.borrow().get(get_input))?;
+  Ok(records.into_iter().flatten().collect())
+}
+
+/// Get the targets the calling agent is subscribed to
+fn get_my_subscription_targets() -> ExternResult<Vec<SubscriptionTarget>> {
+  get_my_subscriptions(())?
+    .iter()
+    .map(|record| Ok(subscription_from_record(record)?.target))
+    .collect()
+}
+
+/// Subscribes the calling agent to new listings for a service type or medium of exchange
+#[hdk_extern]
+pub fn subscribe(target: SubscriptionTarget) -> ExternResult<Record> {
+  if get_my_subscription_targets()?.contains(&target) {
+    return Err(CommonError::InvalidData("Already subscribed".to_string()).into());
+  }
+
+  let subscription_hash = create_entry(&EntryTypes::Subscription(Subscription {
+    target: target.clone(),
+  }))?;
+  let record = get(subscription_hash.clone(), GetOptions::default())?.ok_or(
+    CommonError::EntryNotFound("Could not find the newly created subscription".to_string()),
+  )?;
+
+  let agent = agent_info()?.agent_initial_pubkey;
+  create_link(
+    target.hash().clone(),
+    agent.clone(),
+    LinkTypes::TargetSubscribers,
+    (),
+  )?;
+  create_link(agent, subscription_hash, LinkTypes::AgentSubscriptions, ())?;
+
+  Ok(record)
+}
+
+/// Removes the calling agent's subscription to a service type or medium of exchange
+#[hdk_extern]
+pub fn unsubscribe(target: SubscriptionTarget) -> ExternResult<bool> {
+  let agent = agent_info()?.agent_initial_pubkey;
+
+  for link in get_subscription_links(target.hash().clone().into(), LinkTypes::TargetSubscribers)? {
+    if link.author == agent {
+      delete_link(link.create_link_hash, GetOptions::default())?;
+    }
+  }
+
+  let mut unsubscribed = false;
+  for link in get_subscription_links(agent.into(), LinkTypes::AgentSubscriptions)? {
+    let Some(subscription_hash) = link.target.clone().into_action_hash() else {
+      continue;
+    };
+    let Some(record) = get(subscription_hash.clone(), GetOptions::default())? else {
+      continue;
+    };
+    if subscription_from_record(&record)?.target == target {
+      delete_link(link.create_link_hash, GetOptions::default())?;
+      delete_entry(subscription_hash)?;
+      unsubscribed = true;
+    }
+  }
+
+  Ok(unsubscribed)
+}
+
+/// Get the agents subscribed to a service type or medium of exchange
+#[hdk_extern]
+pub fn get_subscribers(target: SubscriptionTarget) -> ExternResult<Vec<AgentPubKey>> {
+  Ok(
+    get_subscription_links(target.hash().clone().into(), LinkTypes::TargetSubscribers)?
+      .into_iter()
+      .filter_map(|link| link.target.into_agent_pub_key())
+      .collect(),
+  )
+}
+
+/// The subscription targets a listing matches
+fn listing_targets(listing: &ListingNotification) -> Vec<SubscriptionTarget> {
+  listing
+    .service_type_hashes
+    .iter()
+    .cloned()
+    .map(SubscriptionTarget::ServiceType)
+    .chain(
+      listing
+        .medium_of_exchange_hashes
+        .iter()
+        .cloned()
+        .map(SubscriptionTarget::MediumOfExchange),
+    )
+    .collect()
+}
+
+/// Sends a new listing to every agent subscribed to one of its service types or mediums
+/// of exchange. Called by the requests and offers zomes when a listing is created.
+#[hdk_extern]
+pub fn notify_subscribers(listing: ListingNotification) -> ExternResult<()> {
+  let my_agent = agent_info()?.agent_initial_pubkey;
+
+  let mut subscribers = HashSet::new();
+  for target in listing_targets(&listing) {
+    subscribers.extend(get_subscribers(target)?);
+  }
+  subscribers.remove(&my_agent);
+
+  if !subscribers.is_empty() {
+    send_remote_signal(listing, subscribers.into_iter().collect())?;
+  }
+
+  Ok(())
+}
+
+/// Forwards a listing notification to the UI if the listing exists, was written by the
+/// agent who sent the notification, and still matches one of this agent's subscriptions
+#[hdk_extern]
+pub fn recv_remote_signal(listing: ListingNotification) -> ExternResult<()> {
+  let author = call_info()?.provenance;
+  let Some(listing_record) = get(
+    listing.original_action_hash.0.clone(),
+    GetOptions::default(),
+  )?
+  else {
+    return Ok(());
+  };
+  if *listing_record.action().author() != author {
+    return Ok(());
+  }
+
+  let my_targets = get_my_subscription_targets()?;
+  let is_subscribed = listing_targets(&listing)
+    .iter()
+    .any(|target| my_targets.contains(target));
+
+  if is_subscribed {
+    emit_signal(Signal::ListingPublished { author, listing })?;
+  }
+
+  Ok(())
+}
@@ -0,0 +1,234 @@
+use hdk::prelude::*;
+use offers_integrity::*;
+use utils::{
+  errors::{CommonError, UsersError},
+  ListingStatus, OfferSlotClaim,
+};
+
+use crate::external_calls::{get_latest_response, get_latest_response_record};
+use crate::offer::{archive_offer, get_latest_offer, get_offer};
+
+/// Cron schedule of the sweep archiving full offers: at the start of every hour
+const ARCHIVE_SCHEDULE: &str = "0 0 * * * * *";
+
+/// How much of an offer's capacity has been taken up
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct OfferCapacity {
+  /// The declared capacity, or None if the offer can be taken up any number of times
+  pub capacity: Option<u32>,
+  /// The original action hashes of the accepted responses holding a slot, oldest first
+  pub taken: Vec<ActionHash>,
+  /// The number of slots left, or None if the offer is unlimited
+  pub remaining: Option<u32>,
+  /// The original action hashes of the accepted responses waiting for a slot, oldest first
+  pub waitlist: Vec<ActionHash>,
+}
+
+/// Returns the links of the given type from an offer, oldest first
+fn get_slot_links(
+  original_action_hash: ActionHash,
+  link_type: LinkTypes,
+) -> ExternResult<Vec<Link>> {
+  let link_type_filter = link_type
+    .try_into_filter()
+    .map_err(|e| wasm_error!(WasmErrorInner::Guest(e.to_string())))?;
+  let mut links = get_links(
+    LinkQuery::new(original_action_hash, link_type_filter),
+    GetStrategy::Network,
+  )?;
+  links.sort_by_key(|link| link.timestamp);
+  Ok(links)
+}
+
+/// Whether a response is still accepted, and so can hold a slot of the offer it proposes
+fn is_accepted_response(response_hash: ActionHash) -> ExternResult<bool> {
+  let Some(record) = get_latest_response_record(response_hash)? else {
+    return Ok(false);
+  };
+  let response: Option<ResponseClaim> = record
+    .entry()
+    .to_app_option()
+    .map_err(CommonError::Serialize)?;
+  Ok(response.is_some_and(|response| response.status == "Accepted"))
+}
+
+/// Returns the accepted responses claiming a slot of an offer, or a place on its
+/// waitlist, in the order of their claims and each only once
+fn get_claiming_responses(original_action_hash: ActionHash) -> ExternResult<Vec<ActionHash>> {
+  let mut links = get_slot_links(original_action_hash.clone(), LinkTypes::OfferTakers)?;
+  links.extend(get_slot_links(
+    original_action_hash,
+    LinkTypes::OfferWaitlist,
+  )?);
+  links.sort_by(|a, b| {
+    a.timestamp
+      .cmp(&b.timestamp)
+      .then_with(|| a.create_link_hash.cmp(&b.create_link_hash))
+  });
+
+  let mut responses: Vec<ActionHash> = Vec::new();
+  for link in links {
+    if let Some(response_hash) = link.target.into_action_hash() {
+      if !responses.contains(&response_hash) && is_accepted_response(response_hash.clone())? {
+        responses.push(response_hash);
+      }
+    }
+  }
+  Ok(responses)
+}
+
+/// The oldest claims hold the offer's slots and the others wait for one, whether they
+/// were written as takers or on the waitlist. Two claims made concurrently on the last
+/// slot thus end up with one on the waitlist, and slots freed by released, declined or
+/// deleted responses go to the oldest responses on the waitlist.
+#[hdk_extern]
+pub fn get_offer_capacity(original_action_hash: ActionHash) -> ExternResult<OfferCapacity> {
+  let offer = get_latest_offer(original_action_hash.clone())?;
+  let mut taken = get_claiming_responses(original_action_hash)?;
+  let waitlist = match offer.capacity {
+    Some(capacity) if taken.len() > capacity as usize => taken.split_off(capacity as usize),
+    _ => Vec::new(),
+  };
+  let remaining = offer
+    .capacity
+    .map(|capacity| capacity.saturating_sub(taken.len() as u32));
+
+  Ok(OfferCapacity {
+    capacity: offer.capacity,
+    taken,
+    remaining,
+    waitlist,
+  })
+}
+
+/// Takes up a slot of the offer proposed by an accepted response, or puts the
+/// response on the offer's waitlist if every slot is taken. Claiming the same
+/// response twice returns its current place. A slot taken concurrently by an older
+/// claim moves this one to the waitlist, see `get_offer_capacity`.
+///
+/// An offer whose last slot is taken is archived by its author's cell, the next time
+/// `archive_full_offers` runs there.
+#[hdk_extern]
+pub fn claim_offer_slot(response_hash: ActionHash) -> ExternResult<OfferSlotClaim> {
+  let response_record = get_latest_response_record(response_hash.clone())?.ok_or(
+    CommonError::EntryNotFound("Could not find the response".to_string()),
+  )?;
+
+  // Only the request owner who accepted the response can claim the slot
+  if *response_record.action().author() != agent_info()?.agent_initial_pubkey {
+    return Err(UsersError::NotAuthor.into());
+  }
+
+  let response = get_latest_response(response_hash.clone())?;
+  if response.status != "Accepted" {
+    return Err(
+      CommonError::InvalidData("Only accepted responses can take up an offer".to_string()).into(),
+    );
+  }
+  let offer_hash = response.offer_hash.ok_or(CommonError::InvalidData(
+    "The response does not propose an offer".to_string(),
+  ))?;
+
+  let capacity = get_offer_capacity(offer_hash.clone())?;
+  if capacity.taken.contains(&response_hash) {
+    return Ok(OfferSlotClaim::Taken);
+  }
+  if let Some(index) = capacity
+    .waitlist
+    .iter()
+    .position(|hash| *hash == response_hash)
+  {
+    return Ok(OfferSlotClaim::Waitlisted {
+      position: index as u32 + 1,
+    });
+  }
+
+  let tag = slot_claim_tag(response_record.action_address());
+  if capacity.remaining == Some(0) {
+    create_link(offer_hash, response_hash, LinkTypes::OfferWaitlist, tag)?;
+    return Ok(OfferSlotClaim::Waitlisted {
+      position: capacity.waitlist.len() as u32 + 1,
+    });
+  }
+
+  create_link(offer_hash, response_hash, LinkTypes::OfferTakers, tag)?;
+  Ok(OfferSlotClaim::Taken)
+}
+
+/// Gives up the slot, or the place on the waitlist, claimed for an accepted response,
+/// for instance when the exchange falls through or the response is withdrawn. Can be
+/// called by the agent who claimed it or the author of the offer. The oldest response
+/// on the waitlist takes the freed slot. Returns whether anything was released.
+#[hdk_extern]
+pub fn release_offer_slot(response_hash: ActionHash) -> ExternResult<bool> {
+  let offer_hash = get_latest_response(response_hash.clone())?
+    .offer_hash
+    .ok_or(CommonError::InvalidData(
+      "The response does not propose an offer".to_string(),
+    ))?;
+  let agent = agent_info()?.agent_initial_pubkey;
+  let is_offer_author =
+    get_offer(offer_hash.clone())?.is_some_and(|record| *record.action().author() == agent);
+
+  let mut released = false;
+  for link_type in [LinkTypes::OfferTakers, LinkTypes::OfferWaitlist] {
+    for link in get_slot_links(offer_hash.clone(), link_type)? {
+      if (is_offer_author || link.author == agent)
+        && link.target.into_action_hash().as_ref() == Some(&response_hash)
+      {
+        delete_link(link.create_link_hash, GetOptions::default())?;
+        released = true;
+      }
+    }
+  }
+
+  Ok(released)
+}
+
+/// Archives one of the calling agent's offers once all of its slots are taken
+#[hdk_extern]
+pub fn archive_offer_if_full(original_action_hash: ActionHash) -> ExternResult<bool> {
+  let offer_record = get_offer(original_action_hash.clone())?.ok_or(CommonError::EntryNotFound(
+    "Could not find the offer".to_string(),
+  ))?;
+  if *offer_record.action().author() != agent_info()?.agent_initial_pubkey {
+    return Err(UsersError::NotAuthor.into());
+  }
+
+  let offer = get_latest_offer(original_action_hash.clone())?;
+  if !matches!(
+    offer.status,
+    ListingStatus::Active | ListingStatus::InProgress
+  ) {
+    return Ok(false);
+  }
+  if get_offer_capacity(original_action_hash.clone())?.remaining != Some(0) {
+    return Ok(false);
+  }
+
+  archive_offer(original_action_hash)
+}
+
+fn archive_own_full_offers() -> ExternResult<()> {
+  let created_offers = query(
+    ChainQueryFilter::new()
+      .entry_type(UnitEntryTypes::Offer.try_into()?)
+      .action_type(ActionType::Create),
+  )?;
+  for created_offer in created_offers {
+    if let Err(err) = archive_offer_if_full(created_offer.action_address().clone()) {
+      error!("Could not archive the full offer: {:?}", err);
+    }
+  }
+  Ok(())
+}
+
+/// Archives the calling agent's offers whose slots have all been taken by other
+/// agents. Scheduled hourly from `init`.
+#[hdk_extern(infallible)]
+pub fn archive_full_offers(_: Option<Schedule>) -> Option<Schedule> {
+  if let Err(err) = archive_own_full_offers() {
+    error!("Error archiving full offers: {:?}", err);
+  }
+  Some(Schedule::Persisted(ARCHIVE_SCHEDULE.to_string()))
+}
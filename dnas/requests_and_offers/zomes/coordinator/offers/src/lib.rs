@@ -1,3 +1,4 @@
+pub mod capacity;
 mod external_calls;
 pub mod offer;
 
@@ -7,6 +8,7 @@ use utils::errors::CommonError;
 
 #[hdk_extern]
 pub fn init(_: ()) -> ExternResult<InitCallbackResult> {
+  schedule("archive_full_offers")?;
   Ok(InitCallbackResult::Pass)
 }
 
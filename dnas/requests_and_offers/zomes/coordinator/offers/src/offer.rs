@@ -1,16 +1,23 @@
 use hdk::prelude::*;
 use offers_integrity::*;
 use utils::{
-  errors::{AdministrationError, CommonError, UsersError},
-  EntityActionHash, GetMediumOfExchangeForEntityInput, GetServiceTypeForEntityInput,
-  MediumOfExchangeLinkInput, OriginalActionHash, PreviousActionHash, ServiceTypeLinkInput,
-  UpdateMediumOfExchangeLinksInput, UpdateServiceTypeLinksInput,
+  errors::{AdministrationError, CommonError, OrganizationsError, UsersError},
+  listing_index_tag, organization_membership_tag, paginate_records,
+  role_links::make_role_proof,
+  valuation::{check_valuation_medium_of_exchange, filter_by_valuation},
+  ConvertInput, EntityActionHash, FulfilListingInput, GeoIndexInput,
+  GetMediumOfExchangeForEntityInput, GetServiceTypeForEntityInput, ListingNotification,
+  MediumOfExchangeLinkInput, OriginalActionHash, Page, PaginationInput, PreviousActionHash,
+  SearchIndexInput, ServiceTypeLinkInput, UpdateAuthorization, UpdateMediumOfExchangeLinksInput,
+  UpdateServiceTypeLinksInput, ValuationFilter,
 };
 
 use crate::external_calls::{
-  check_if_agent_is_administrator, check_if_entity_is_accepted,
+  check_if_agent_is_administrator, check_if_entity_is_accepted, convert,
   delete_all_medium_of_exchange_links_for_entity, delete_all_service_type_links_for_entity,
-  get_agent_user, link_to_medium_of_exchange, link_to_service_type,
+  get_agent_administrator_links, get_agent_user, get_organization_coordinators_links,
+  get_organization_members_links, index_entity, index_entity_location, link_to_medium_of_exchange,
+  link_to_service_type, notify_subscribers, remove_entity_from_index,
   update_medium_of_exchange_links, update_service_type_links,
 };
 
@@ -22,8 +29,77 @@ pub struct OfferInput {
   medium_of_exchange_hashes: Vec<ActionHash>,
 }
 
+/// Indexes the title and description of an offer for full-text search, and its
+/// location for proximity search
+fn index_offer(original_action_hash: ActionHash, offer: &Offer) -> ExternResult<()> {
+  index_entity(SearchIndexInput {
+    original_action_hash: OriginalActionHash(original_action_hash.clone()),
+    entity: "offer".to_string(),
+    fields: vec![offer.title.clone(), offer.description.clone()],
+  })?;
+  index_entity_location(GeoIndexInput {
+    original_action_hash: OriginalActionHash(original_action_hash),
+    entity: "offer".to_string(),
+    location: offer.location,
+  })
+}
+
+/// Checks that the calling agent can update an offer. Returns the proof of their role
+/// when they are not its author: as an administrator, or as a coordinator of the
+/// offer's organization.
+fn get_update_authorization(original_record: &Record) -> ExternResult<Option<UpdateAuthorization>> {
+  let agent_pubkey = agent_info()?.agent_initial_pubkey;
+  let author = original_record.action().author();
+  if *author == agent_pubkey {
+    return Ok(None);
+  }
+
+  let administrators_path_hash: AnyLinkableHash = Path::from("network.administrators")
+    .path_entry_hash()?
+    .into();
+  if let Some(administrator_link) = get_agent_administrator_links(agent_pubkey.clone())?
+    .into_iter()
+    .find(|link| link.target == administrators_path_hash)
+  {
+    return Ok(Some(UpdateAuthorization::Administrator {
+      administrator_proof: make_role_proof(&administrator_link)?,
+    }));
+  }
+
+  let user_hash = get_agent_user(agent_pubkey)?
+    .first()
+    .and_then(|link| link.target.clone().into_action_hash())
+    .ok_or(UsersError::NotAuthor)?;
+  let link_type_filter = LinkTypes::OfferOrganization
+    .try_into_filter()
+    .map_err(|e| wasm_error!(WasmErrorInner::Guest(e.to_string())))?;
+  let organization_links = get_links(
+    LinkQuery::new(original_record.action_address().clone(), link_type_filter),
+    GetStrategy::Network,
+  )?;
+  for organization_link in organization_links
+    .into_iter()
+    .filter(|link| link.author == *author)
+  {
+    let Some(organization_hash) = organization_link.target.clone().into_action_hash() else {
+      continue;
+    };
+    if let Some(coordinator_link) = get_organization_coordinators_links(organization_hash)?
+      .into_iter()
+      .find(|link| link.target.clone().into_action_hash().as_ref() == Some(&user_hash))
+    {
+      return Ok(Some(UpdateAuthorization::OrganizationCoordinator {
+        organization_link: organization_link.create_link_hash,
+        coordinator_proof: make_role_proof(&coordinator_link)?,
+      }));
+    }
+  }
+
+  Err(UsersError::NotAuthor.into())
+}
+
 #[hdk_extern]
-pub fn create_offer(input: OfferInput) -> ExternResult<Record> {
+pub fn create_offer(mut input: OfferInput) -> ExternResult<Record> {
   let user_links = get_agent_user(agent_info()?.agent_initial_pubkey)?;
   if user_links.is_empty() {
     return Err(UsersError::UserProfileRequired.into());
@@ -36,14 +112,19 @@ pub fn create_offer(input: OfferInput) -> ExternResult<Record> {
     .into_action_hash()
     .ok_or(CommonError::ActionHashNotFound("user".to_string()))?;
   let is_accepted = check_if_entity_is_accepted(EntityActionHash {
-    entity_original_action_hash: OriginalActionHash(user_hash),
+    entity_original_action_hash: OriginalActionHash(user_hash.clone()),
     entity: "users".to_string(),
   })?;
   if !is_accepted {
     return Err(AdministrationError::EntityNotAccepted("users".to_string()).into());
   }
 
-  let offer_hash = create_entry(&EntryTypes::Offer(input.offer))?;
+  if let Some(valuation) = &input.offer.valuation {
+    check_valuation_medium_of_exchange(valuation)?;
+  }
+  input.offer.location = input.offer.location.map(|location| location.snapped());
+
+  let offer_hash = create_entry(&EntryTypes::Offer(input.offer.clone()))?;
 
   let record = get(offer_hash.clone(), GetOptions::default())?.ok_or(
     CommonError::EntryNotFound("Could not find the newly created offer".to_string()),
@@ -56,7 +137,7 @@ pub fn create_offer(input: OfferInput) -> ExternResult<Record> {
     path_hash.clone(),
     offer_hash.clone(),
     LinkTypes::ActiveOffers,
-    (),
+    listing_index_tag(&offer_hash)?,
   )?;
 
   // Get user profile links to link the offer
@@ -79,21 +160,35 @@ pub fn create_offer(input: OfferInput) -> ExternResult<Record> {
   }
 
   // Bidirectional link to the organization if their is one
-  if input.organization.is_some() {
+  if let Some(organization_hash) = input.organization.clone() {
+    // Organization links carry the creator's membership link as proof for validation
+    let member_link = get_organization_members_links(organization_hash.clone())?
+      .into_iter()
+      .find(|link| link.target.clone().into_action_hash().as_ref() == Some(&user_hash))
+      .ok_or(OrganizationsError::NotMember)?;
+    let tag = organization_membership_tag(&member_link)?;
     create_link(
-      input.organization.clone().unwrap(),
+      organization_hash.clone(),
       offer_hash.clone(),
       LinkTypes::OrganizationOffers,
-      (),
+      tag.clone(),
     )?;
     create_link(
       offer_hash.clone(),
-      input.organization.clone().unwrap(),
+      organization_hash,
       LinkTypes::OfferOrganization,
-      (),
+      tag,
     )?;
   }
 
+  let notification = ListingNotification {
+    original_action_hash: OriginalActionHash(offer_hash.clone()),
+    entity: "offer".to_string(),
+    title: input.offer.title.clone(),
+    service_type_hashes: input.service_type_hashes.clone(),
+    medium_of_exchange_hashes: input.medium_of_exchange_hashes.clone(),
+  };
+
   // Create bidirectional links to service types
   for service_type_hash in input.service_type_hashes {
     link_to_service_type(ServiceTypeLinkInput {
@@ -112,6 +207,13 @@ pub fn create_offer(input: OfferInput) -> ExternResult<Record> {
     })?;
   }
 
+  index_offer(offer_hash, &input.offer)?;
+
+  // Notifications are best effort: a subscriber being unreachable must not fail the listing
+  if let Err(err) = notify_subscribers(notification) {
+    error!("Error notifying subscribers: {:?}", err);
+  }
+
   Ok(record)
 }
 
@@ -185,6 +287,34 @@ pub fn get_active_offers(_: ()) -> ExternResult<Vec<Record>> {
   Ok(records)
 }
 
+/// Get the active offers whose valuation is within a range. Offers valued in another medium
+/// of exchange are compared with the current exchange rate to the filter's medium, and
+/// offers without a valuation are left out.
+#[hdk_extern]
+pub fn get_active_offers_by_valuation(filter: ValuationFilter) -> ExternResult<Vec<Record>> {
+  let mut listings = Vec::new();
+  for record in get_active_offers(())? {
+    let valuation = record
+      .entry()
+      .to_app_option::<Offer>()
+      .map_err(CommonError::Serialize)?
+      .and_then(|offer| offer.valuation);
+    listings.push((record, valuation));
+  }
+
+  filter_by_valuation(listings, &filter, |medium_of_exchange_hash| {
+    Ok(
+      convert(ConvertInput {
+        amount: 1.0,
+        from_medium_hash: medium_of_exchange_hash,
+        to_medium_hash: filter.medium_of_exchange_hash.clone(),
+        at: None,
+      })
+      .ok(),
+    )
+  })
+}
+
 #[hdk_extern]
 pub fn get_archived_offers(_: ()) -> ExternResult<Vec<Record>> {
   let path = Path::from("offers.archived");
@@ -213,6 +343,74 @@ pub fn get_archived_offers(_: ()) -> ExternResult<Vec<Record>> {
   Ok(records)
 }
 
+#[hdk_extern]
+pub fn get_fulfilled_offers(_: ()) -> ExternResult<Vec<Record>> {
+  let path = Path::from("offers.fulfilled");
+  let path_hash = path.path_entry_hash()?;
+  let link_type_filter = LinkTypes::FulfilledOffers
+    .try_into_filter()
+    .map_err(|e| wasm_error!(WasmErrorInner::Guest(e.to_string())))?;
+  let links = get_links(
+    LinkQuery::new(path_hash.clone(), link_type_filter),
+    GetStrategy::Network,
+  )?;
+  let get_input: Vec<GetInput> = links
+    .into_iter()
+    .filter_map(|link| {
+      link
+        .target
+        .clone()
+        .into_any_dht_hash()
+        .map(|hash| GetInput::new(hash, GetOptions::default()))
+    })
+    .collect();
+  let records = HDK.with(|hdk| hdk.borrow().get(get_input))?;
+  let records: Vec<Record> = records.into_iter().flatten().collect();
+  Ok(records)
+}
+
+/// Get a page of active offers, ordered by the time they entered the index
+#[hdk_extern]
+pub fn get_active_offers_paginated(input: PaginationInput) -> ExternResult<Page<Record>> {
+  let path_hash = Path::from("offers.active").path_entry_hash()?;
+  let link_type_filter = LinkTypes::ActiveOffers
+    .try_into_filter()
+    .map_err(|e| wasm_error!(WasmErrorInner::Guest(e.to_string())))?;
+  let links = get_links(
+    LinkQuery::new(path_hash, link_type_filter),
+    GetStrategy::Network,
+  )?;
+  paginate_records(links, &input)
+}
+
+/// Get a page of archived offers, ordered by the time they entered the index
+#[hdk_extern]
+pub fn get_archived_offers_paginated(input: PaginationInput) -> ExternResult<Page<Record>> {
+  let path_hash = Path::from("offers.archived").path_entry_hash()?;
+  let link_type_filter = LinkTypes::ArchivedOffers
+    .try_into_filter()
+    .map_err(|e| wasm_error!(WasmErrorInner::Guest(e.to_string())))?;
+  let links = get_links(
+    LinkQuery::new(path_hash, link_type_filter),
+    GetStrategy::Network,
+  )?;
+  paginate_records(links, &input)
+}
+
+/// Get a page of fulfilled offers, ordered by the time they entered the index
+#[hdk_extern]
+pub fn get_fulfilled_offers_paginated(input: PaginationInput) -> ExternResult<Page<Record>> {
+  let path_hash = Path::from("offers.fulfilled").path_entry_hash()?;
+  let link_type_filter = LinkTypes::FulfilledOffers
+    .try_into_filter()
+    .map_err(|e| wasm_error!(WasmErrorInner::Guest(e.to_string())))?;
+  let links = get_links(
+    LinkQuery::new(path_hash, link_type_filter),
+    GetStrategy::Network,
+  )?;
+  paginate_records(links, &input)
+}
+
 #[hdk_extern]
 pub fn get_user_offers(user_hash: ActionHash) -> ExternResult<Vec<Record>> {
   let link_type_filter = LinkTypes::UserOffers
@@ -358,16 +556,7 @@ pub fn update_offer(input: UpdateOfferInput) -> ExternResult<Record> {
   let original_record = get(input.original_action_hash.0.clone(), GetOptions::default())?.ok_or(
     CommonError::EntryNotFound("Could not find the original offer".to_string()),
   )?;
-  let agent_pubkey = agent_info()?.agent_initial_pubkey;
-
-  // Check if the agent is the author or an administrator
-  let author = original_record.action().author().clone();
-  let is_author = author == agent_pubkey;
-  let is_admin = check_if_agent_is_administrator(agent_pubkey.clone())?;
-
-  if !is_author && !is_admin {
-    return Err(UsersError::NotAuthor.into());
-  }
+  let update_authorization = get_update_authorization(&original_record)?;
 
   // Check if offer is archived (archived offers cannot be edited)
   let current_offer: Offer = original_record
@@ -382,8 +571,20 @@ pub fn update_offer(input: UpdateOfferInput) -> ExternResult<Record> {
     return Err(CommonError::CannotUpdateArchived("offer".to_string()).into());
   }
 
+  let original_hash = input.original_action_hash.0.clone();
   let previous_hash = input.previous_action_hash.0.clone();
-  let updated_offer_hash = update_entry(input.previous_action_hash.into(), &input.updated_offer)?;
+  let mut updated_offer = input.updated_offer.clone();
+  updated_offer.update_authorization = update_authorization;
+  updated_offer.location = updated_offer.location.map(|location| location.snapped());
+  if let Some(valuation) = &updated_offer.valuation {
+    let previous_medium_of_exchange_hash = get_latest_offer(original_hash.clone())?
+      .valuation
+      .map(|previous| previous.medium_of_exchange_hash);
+    if previous_medium_of_exchange_hash.as_ref() != Some(&valuation.medium_of_exchange_hash) {
+      check_valuation_medium_of_exchange(valuation)?;
+    }
+  }
+  let updated_offer_hash = update_entry(input.previous_action_hash.into(), &updated_offer)?;
 
   // Update the ActiveOffers link in "offers.active" to point to the new record.
   // create_offer uses Path::from("offers.active") + LinkTypes::ActiveOffers, so
@@ -414,7 +615,7 @@ pub fn update_offer(input: UpdateOfferInput) -> ExternResult<Record> {
     active_path_hash.clone(),
     updated_offer_hash.clone(),
     LinkTypes::ActiveOffers,
-    (),
+    listing_index_tag(&original_hash)?,
   )?;
 
   // Create the update tracking link (original → updated, for get_latest_offer_record)
@@ -439,6 +640,8 @@ pub fn update_offer(input: UpdateOfferInput) -> ExternResult<Record> {
     new_medium_of_exchange_hashes: input.medium_of_exchange_hashes,
   })?;
 
+  index_offer(original_hash, &updated_offer)?;
+
   let record = get(updated_offer_hash.clone(), GetOptions::default())?.ok_or(
     CommonError::EntryNotFound("Could not find the newly updated Offer".to_string()),
   )?;
@@ -462,6 +665,12 @@ pub fn delete_offer(original_action_hash: ActionHash) -> ExternResult<bool> {
     return Err(UsersError::NotAuthor.into());
   }
 
+  // An administrator first marks the offer as deleted: integrity only lets the author, or an
+  // agent who updated the offer with the proof of their role, remove its links
+  if !is_author {
+    change_offer_status(original_action_hash.clone(), ListingStatus::Deleted, None)?;
+  }
+
   // Delete all offers links
   let path = Path::from("offers");
   let path_hash = path.path_entry_hash()?;
@@ -577,31 +786,71 @@ pub fn delete_offer(original_action_hash: ActionHash) -> ExternResult<bool> {
     delete_link(link.create_link_hash, GetOptions::default())?;
   }
 
+  // Remove the listing from the search index
+  remove_entity_from_index(original_action_hash.clone())?;
+
   // Finally delete the offer entry
   delete_entry(original_action_hash.clone())?;
 
   Ok(true)
 }
 
+/// Archives an offer, moving it from its status index to "offers.archived". Fulfilment
+/// notes are cleared, as only fulfilled offers keep them.
 #[hdk_extern]
 pub fn archive_offer(original_action_hash: ActionHash) -> ExternResult<bool> {
-  // Get the latest offer record (follows update chain)
-  let latest_record = get_latest_offer_record(original_action_hash.clone())?.ok_or(
-    CommonError::EntryNotFound("Could not find the offer".to_string()),
+  change_offer_status(original_action_hash, ListingStatus::Archived, None)?;
+  Ok(true)
+}
+
+/// Deletes the links from the `path` index to `target`
+fn unlink_from_path(path: &str, link_type: LinkTypes, target: &ActionHash) -> ExternResult<()> {
+  let path_hash = Path::from(path).path_entry_hash()?;
+  let link_type_filter = link_type
+    .try_into_filter()
+    .map_err(|e| wasm_error!(WasmErrorInner::Guest(e.to_string())))?;
+  let links = get_links(
+    LinkQuery::new(path_hash, link_type_filter),
+    GetStrategy::Network,
   )?;
-  let agent_pubkey = agent_info()?.agent_initial_pubkey;
 
-  // Check if the agent is the author or an administrator
-  let author = latest_record.action().author().clone();
-  let is_author = author == agent_pubkey;
-  let is_admin = check_if_agent_is_administrator(agent_pubkey.clone())?;
+  for link in links {
+    if link.target.into_action_hash().as_ref() == Some(target) {
+      delete_link(link.create_link_hash, GetOptions::default())?;
+    }
+  }
 
-  if !is_author && !is_admin {
-    return Err(UsersError::NotAuthor.into());
+  Ok(())
+}
+
+/// The index path and link type listing offers in `status`, if any.
+/// In-progress offers stay listed as active.
+fn status_index(status: &ListingStatus) -> Option<(&'static str, LinkTypes)> {
+  match status {
+    ListingStatus::Active | ListingStatus::InProgress => {
+      Some(("offers.active", LinkTypes::ActiveOffers))
+    }
+    ListingStatus::Archived => Some(("offers.archived", LinkTypes::ArchivedOffers)),
+    ListingStatus::Fulfilled => Some(("offers.fulfilled", LinkTypes::FulfilledOffers)),
+    ListingStatus::Expired | ListingStatus::Deleted => None,
   }
+}
+
+/// Moves the latest version of an offer to `status`, keeping the status indexes in sync
+fn change_offer_status(
+  original_action_hash: ActionHash,
+  status: ListingStatus,
+  fulfilment_notes: Option<String>,
+) -> ExternResult<Record> {
+  let original_record = get(original_action_hash.clone(), GetOptions::default())?.ok_or(
+    CommonError::EntryNotFound("Could not find the original offer".to_string()),
+  )?;
+  let update_authorization = get_update_authorization(&original_record)?;
 
-  // Get the current offer
-  let current_offer: Offer = latest_record
+  let latest_record = get_latest_offer_record(original_action_hash.clone())?.ok_or(
+    CommonError::EntryNotFound("Could not find the offer".to_string()),
+  )?;
+  let mut offer: Offer = latest_record
     .entry()
     .to_app_option()
     .map_err(CommonError::Serialize)?
@@ -609,55 +858,71 @@ pub fn archive_offer(original_action_hash: ActionHash) -> ExternResult<bool> {
       "Could not deserialize offer entry".to_string(),
     ))?;
 
-  // Update the offer with archived status
-  let mut updated_offer = current_offer.clone();
-  updated_offer.status = ListingStatus::Archived;
-
-  // Create update entry - use the LATEST action hash as previous
-  let latest_action_hash = latest_record.signed_action.hashed.hash.clone();
-  let updated_offer_hash = update_entry(latest_action_hash.clone(), &updated_offer)?;
-
-  // Move the link from "offers.active" to "offers.archived" path
-  // First, remove the link from the active path
-  let active_path = Path::from("offers.active");
-  let active_path_hash = active_path.path_entry_hash()?;
-  let active_link_filter = LinkTypes::ActiveOffers
-    .try_into_filter()
-    .map_err(|e| wasm_error!(WasmErrorInner::Guest(e.to_string())))?;
-  let active_links = get_links(
-    LinkQuery::new(active_path_hash.clone(), active_link_filter),
-    GetStrategy::Local,
-  )?;
-
-  // Find and remove the link from active path
-  for link in active_links {
-    if let Some(hash) = link.target.clone().into_action_hash() {
-      if hash == latest_action_hash {
-        delete_link(link.create_link_hash, GetOptions::default())?;
-        break;
-      }
-    }
+  if !offer.status.can_transition_to(&status) {
+    return Err(
+      CommonError::InvalidData(format!(
+        "Offer status cannot change from {:?} to {:?}",
+        offer.status, status
+      ))
+      .into(),
+    );
   }
 
-  // Create new link in the archived path
-  let archived_path = Path::from("offers.archived");
-  let archived_path_hash = archived_path.path_entry_hash()?;
-  create_link(
-    archived_path_hash.clone(),
-    updated_offer_hash.clone(),
-    LinkTypes::ArchivedOffers,
-    (),
-  )?;
+  let latest_action_hash = latest_record.action_address().clone();
+  let previous_index = status_index(&offer.status);
+  offer.status = status;
+  offer.fulfilment_notes = fulfilment_notes;
+  offer.update_authorization = update_authorization;
+  let updated_offer_hash = update_entry(latest_action_hash.clone(), &offer)?;
 
-  // Create link to track the update (from original to new)
+  if let Some((path, link_type)) = previous_index {
+    unlink_from_path(path, link_type, &latest_action_hash)?;
+  }
+  if let Some((path, link_type)) = status_index(&offer.status) {
+    create_link(
+      Path::from(path).path_entry_hash()?,
+      updated_offer_hash.clone(),
+      link_type,
+      listing_index_tag(&original_action_hash)?,
+    )?;
+  }
   create_link(
-    original_action_hash.clone(),
+    original_action_hash,
     updated_offer_hash.clone(),
     LinkTypes::OfferUpdates,
     (),
   )?;
 
-  Ok(true)
+  get(updated_offer_hash, GetOptions::default())?
+    .ok_or(CommonError::EntryNotFound("Could not find the updated offer".to_string()).into())
+}
+
+/// Marks an offer as being worked on
+#[hdk_extern]
+pub fn mark_offer_in_progress(original_action_hash: ActionHash) -> ExternResult<Record> {
+  change_offer_status(original_action_hash, ListingStatus::InProgress, None)
+}
+
+/// Reactivates an archived offer, moving it back to "offers.active". The original
+/// action hash and its service type and medium of exchange links are kept.
+#[hdk_extern]
+pub fn reactivate_offer(original_action_hash: ActionHash) -> ExternResult<Record> {
+  if get_latest_offer(original_action_hash.clone())?.status != ListingStatus::Archived {
+    return Err(
+      CommonError::InvalidData("Only archived offers can be reactivated".to_string()).into(),
+    );
+  }
+  change_offer_status(original_action_hash, ListingStatus::Active, None)
+}
+
+/// Marks an offer as fulfilled, moving it to "offers.fulfilled"
+#[hdk_extern]
+pub fn fulfil_offer(input: FulfilListingInput) -> ExternResult<Record> {
+  change_offer_status(
+    input.original_action_hash.0,
+    ListingStatus::Fulfilled,
+    input.fulfilment_notes,
+  )
 }
 
 #[hdk_extern]
@@ -669,17 +934,16 @@ pub fn get_my_listings(user_hash: ActionHash) -> ExternResult<Vec<Record>> {
 pub fn get_offers_by_tag(tag: String) -> ExternResult<Vec<Record>> {
   use utils::external_local_call;
 
-  // Call service_types zome to get service types with this tag
-  let service_type_records: Vec<Record> =
-    external_local_call("get_service_types_by_tag", "service_types", tag)?;
+  // Call service_types zome to get the original hashes of the service types with this tag,
+  // which listings are linked from
+  let service_type_hashes: Vec<ActionHash> =
+    external_local_call("get_service_type_hashes_by_tag", "service_types", tag)?;
 
   // For each service type, get all offers that use it
   let mut all_offers = Vec::new();
   let mut seen_hashes = std::collections::HashSet::new();
 
-  for service_type_record in service_type_records {
-    let service_type_hash = service_type_record.signed_action.hashed.hash;
-
+  for service_type_hash in service_type_hashes {
     // Call service_types zome to get offers for this service type
     let offers: Vec<Record> = external_local_call(
       "get_offers_for_service_type",
@@ -1,7 +1,8 @@
 use hdk::prelude::*;
 use utils::{
-  external_local_call, EntityActionHash, EntityAgent, GetMediumOfExchangeForEntityInput,
-  GetServiceTypeForEntityInput, MediumOfExchangeLinkInput, ServiceTypeLinkInput,
+  external_local_call, ConvertInput, EntityActionHash, EntityAgent, GeoIndexInput,
+  GetMediumOfExchangeForEntityInput, GetServiceTypeForEntityInput, ListingNotification,
+  MediumOfExchangeLinkInput, SearchIndexInput, ServiceTypeLinkInput,
   UpdateMediumOfExchangeLinksInput, UpdateServiceTypeLinksInput,
 };
 
@@ -18,6 +19,34 @@ pub fn check_if_agent_is_administrator(agent_pubkey: AgentPubKey) -> ExternResul
   external_local_call("check_if_agent_is_administrator", "administration", input)
 }
 
+pub fn get_agent_administrator_links(agent_pubkey: AgentPubKey) -> ExternResult<Vec<Link>> {
+  external_local_call(
+    "get_agent_administrator_links",
+    "administration",
+    agent_pubkey,
+  )
+}
+
+pub fn get_organization_coordinators_links(
+  organization_original_action_hash: ActionHash,
+) -> ExternResult<Vec<Link>> {
+  external_local_call(
+    "get_organization_coordinators_links",
+    "users_organizations",
+    organization_original_action_hash,
+  )
+}
+
+pub fn get_organization_members_links(
+  organization_original_action_hash: ActionHash,
+) -> ExternResult<Vec<Link>> {
+  external_local_call(
+    "get_organization_members_links",
+    "users_organizations",
+    organization_original_action_hash,
+  )
+}
+
 pub fn link_to_service_type(input: ServiceTypeLinkInput) -> ExternResult<()> {
   external_local_call("link_to_service_type", "service_types", input)
 }
@@ -60,6 +89,10 @@ pub fn delete_all_medium_of_exchange_links_for_entity(
   )
 }
 
+pub fn convert(input: ConvertInput) -> ExternResult<f64> {
+  external_local_call("convert", "mediums_of_exchange", input)
+}
+
 pub fn check_if_entity_is_accepted(original_action_hash: EntityActionHash) -> ExternResult<bool> {
   external_local_call(
     "check_if_entity_is_accepted",
@@ -67,3 +100,19 @@ pub fn check_if_entity_is_accepted(original_action_hash: EntityActionHash) -> Ex
     original_action_hash,
   )
 }
+
+pub fn index_entity(input: SearchIndexInput) -> ExternResult<()> {
+  external_local_call("index_entity", "search", input)
+}
+
+pub fn index_entity_location(input: GeoIndexInput) -> ExternResult<()> {
+  external_local_call("index_entity_location", "search", input)
+}
+
+pub fn remove_entity_from_index(original_action_hash: ActionHash) -> ExternResult<()> {
+  external_local_call("remove_entity_from_index", "search", original_action_hash)
+}
+
+pub fn notify_subscribers(listing: ListingNotification) -> ExternResult<()> {
+  external_local_call("notify_subscribers", "notifications", listing)
+}
@@ -7,6 +7,7 @@ use utils::errors::CommonError;
 
 #[hdk_extern]
 pub fn init(_: ()) -> ExternResult<InitCallbackResult> {
+  schedule("expire_requests")?;
   Ok(InitCallbackResult::Pass)
 }
 
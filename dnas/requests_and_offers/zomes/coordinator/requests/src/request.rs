@@ -1,16 +1,23 @@
 use hdk::prelude::*;
 use requests_integrity::*;
 use utils::{
-  errors::{AdministrationError, CommonError, UsersError},
-  EntityActionHash, GetMediumOfExchangeForEntityInput, GetServiceTypeForEntityInput,
-  MediumOfExchangeLinkInput, OriginalActionHash, PreviousActionHash, ServiceTypeLinkInput,
-  UpdateMediumOfExchangeLinksInput, UpdateServiceTypeLinksInput,
+  errors::{AdministrationError, CommonError, OrganizationsError, UsersError},
+  listing_index_tag, organization_membership_tag, paginate_records,
+  role_links::make_role_proof,
+  valuation::{check_valuation_medium_of_exchange, filter_by_valuation},
+  ConvertInput, DateRange, EntityActionHash, FulfilListingInput, GeoIndexInput,
+  GetMediumOfExchangeForEntityInput, GetServiceTypeForEntityInput, ListingNotification,
+  MediumOfExchangeLinkInput, OriginalActionHash, Page, PaginationInput, PreviousActionHash,
+  SearchIndexInput, ServiceTypeLinkInput, UpdateAuthorization, UpdateMediumOfExchangeLinksInput,
+  UpdateServiceTypeLinksInput, ValuationFilter,
 };
 
 use crate::external_calls::{
-  check_if_agent_is_administrator, check_if_entity_is_accepted,
+  check_if_agent_is_administrator, check_if_entity_is_accepted, convert,
   delete_all_medium_of_exchange_links_for_entity, delete_all_service_type_links_for_entity,
-  get_agent_user, link_to_medium_of_exchange, link_to_service_type,
+  get_agent_administrator_links, get_agent_user, get_organization_coordinators_links,
+  get_organization_members_links, index_entity, index_entity_location, link_to_medium_of_exchange,
+  link_to_service_type, notify_subscribers, remove_entity_from_index,
   update_medium_of_exchange_links, update_service_type_links,
 };
 
@@ -22,8 +29,80 @@ pub struct RequestInput {
   medium_of_exchange_hashes: Vec<ActionHash>,
 }
 
+/// Cron schedule of the expiry sweep: at the start of every hour
+const EXPIRY_SCHEDULE: &str = "0 0 * * * * *";
+
+/// Indexes the title and description of a request for full-text search, and its
+/// location for proximity search
+fn index_request(original_action_hash: ActionHash, request: &Request) -> ExternResult<()> {
+  index_entity(SearchIndexInput {
+    original_action_hash: OriginalActionHash(original_action_hash.clone()),
+    entity: "request".to_string(),
+    fields: vec![request.title.clone(), request.description.clone()],
+  })?;
+  index_entity_location(GeoIndexInput {
+    original_action_hash: OriginalActionHash(original_action_hash),
+    entity: "request".to_string(),
+    location: request.location,
+  })
+}
+
+/// Checks that the calling agent can update a request. Returns the proof of their role
+/// when they are not its author: as an administrator, or as a coordinator of the
+/// request's organization.
+fn get_update_authorization(original_record: &Record) -> ExternResult<Option<UpdateAuthorization>> {
+  let agent_pubkey = agent_info()?.agent_initial_pubkey;
+  let author = original_record.action().author();
+  if *author == agent_pubkey {
+    return Ok(None);
+  }
+
+  let administrators_path_hash: AnyLinkableHash = Path::from("network.administrators")
+    .path_entry_hash()?
+    .into();
+  if let Some(administrator_link) = get_agent_administrator_links(agent_pubkey.clone())?
+    .into_iter()
+    .find(|link| link.target == administrators_path_hash)
+  {
+    return Ok(Some(UpdateAuthorization::Administrator {
+      administrator_proof: make_role_proof(&administrator_link)?,
+    }));
+  }
+
+  let user_hash = get_agent_user(agent_pubkey)?
+    .first()
+    .and_then(|link| link.target.clone().into_action_hash())
+    .ok_or(UsersError::NotAuthor)?;
+  let link_type_filter = LinkTypes::RequestOrganization
+    .try_into_filter()
+    .map_err(|e| wasm_error!(WasmErrorInner::Guest(e.to_string())))?;
+  let organization_links = get_links(
+    LinkQuery::new(original_record.action_address().clone(), link_type_filter),
+    GetStrategy::Network,
+  )?;
+  for organization_link in organization_links
+    .into_iter()
+    .filter(|link| link.author == *author)
+  {
+    let Some(organization_hash) = organization_link.target.clone().into_action_hash() else {
+      continue;
+    };
+    if let Some(coordinator_link) = get_organization_coordinators_links(organization_hash)?
+      .into_iter()
+      .find(|link| link.target.clone().into_action_hash().as_ref() == Some(&user_hash))
+    {
+      return Ok(Some(UpdateAuthorization::OrganizationCoordinator {
+        organization_link: organization_link.create_link_hash,
+        coordinator_proof: make_role_proof(&coordinator_link)?,
+      }));
+    }
+  }
+
+  Err(UsersError::NotAuthor.into())
+}
+
 #[hdk_extern]
-pub fn create_request(input: RequestInput) -> ExternResult<Record> {
+pub fn create_request(mut input: RequestInput) -> ExternResult<Record> {
   let user_links = get_agent_user(agent_info()?.agent_initial_pubkey)?;
   if user_links.is_empty() {
     return Err(UsersError::UserProfileRequired.into());
@@ -36,14 +115,19 @@ pub fn create_request(input: RequestInput) -> ExternResult<Record> {
     .into_action_hash()
     .ok_or(CommonError::ActionHashNotFound("user".to_string()))?;
   let is_accepted = check_if_entity_is_accepted(EntityActionHash {
-    entity_original_action_hash: OriginalActionHash(user_hash),
+    entity_original_action_hash: OriginalActionHash(user_hash.clone()),
     entity: "users".to_string(),
   })?;
   if !is_accepted {
     return Err(AdministrationError::EntityNotAccepted("users".to_string()).into());
   }
 
-  let request_hash = create_entry(&EntryTypes::Request(input.request))?;
+  if let Some(valuation) = &input.request.valuation {
+    check_valuation_medium_of_exchange(valuation)?;
+  }
+  input.request.location = input.request.location.map(|location| location.snapped());
+
+  let request_hash = create_entry(&EntryTypes::Request(input.request.clone()))?;
 
   let record = get(request_hash.clone(), GetOptions::default())?.ok_or(
     CommonError::EntryNotFound("Could not find the newly created request".to_string()),
@@ -56,7 +140,7 @@ pub fn create_request(input: RequestInput) -> ExternResult<Record> {
     path_hash.clone(),
     request_hash.clone(),
     LinkTypes::ActiveRequests,
-    (),
+    listing_index_tag(&request_hash)?,
   )?;
 
   // Get user profile links to link the request
@@ -78,21 +162,35 @@ pub fn create_request(input: RequestInput) -> ExternResult<Record> {
     )?;
   }
 
-  if input.organization.is_some() {
+  if let Some(organization_hash) = input.organization.clone() {
+    // Organization links carry the creator's membership link as proof for validation
+    let member_link = get_organization_members_links(organization_hash.clone())?
+      .into_iter()
+      .find(|link| link.target.clone().into_action_hash().as_ref() == Some(&user_hash))
+      .ok_or(OrganizationsError::NotMember)?;
+    let tag = organization_membership_tag(&member_link)?;
     create_link(
-      input.organization.clone().unwrap(),
+      organization_hash.clone(),
       request_hash.clone(),
       LinkTypes::OrganizationRequests,
-      (),
+      tag.clone(),
     )?;
     create_link(
       request_hash.clone(),
-      input.organization.clone().unwrap(),
+      organization_hash,
       LinkTypes::RequestOrganization,
-      (),
+      tag,
     )?;
   }
 
+  let notification = ListingNotification {
+    original_action_hash: OriginalActionHash(request_hash.clone()),
+    entity: "request".to_string(),
+    title: input.request.title.clone(),
+    service_type_hashes: input.service_type_hashes.clone(),
+    medium_of_exchange_hashes: input.medium_of_exchange_hashes.clone(),
+  };
+
   // Create bidirectional links to service types
   for service_type_hash in input.service_type_hashes {
     link_to_service_type(ServiceTypeLinkInput {
@@ -111,6 +209,13 @@ pub fn create_request(input: RequestInput) -> ExternResult<Record> {
     })?;
   }
 
+  index_request(request_hash, &input.request)?;
+
+  // Notifications are best effort: a subscriber being unreachable must not fail the listing
+  if let Err(err) = notify_subscribers(notification) {
+    error!("Error notifying subscribers: {:?}", err);
+  }
+
   Ok(record)
 }
 
@@ -186,6 +291,34 @@ pub fn get_active_requests(_: ()) -> ExternResult<Vec<Record>> {
   Ok(records)
 }
 
+/// Get the active requests whose valuation is within a range. Requests valued in another medium
+/// of exchange are compared with the current exchange rate to the filter's medium, and
+/// requests without a valuation are left out.
+#[hdk_extern]
+pub fn get_active_requests_by_valuation(filter: ValuationFilter) -> ExternResult<Vec<Record>> {
+  let mut listings = Vec::new();
+  for record in get_active_requests(())? {
+    let valuation = record
+      .entry()
+      .to_app_option::<Request>()
+      .map_err(CommonError::Serialize)?
+      .and_then(|request| request.valuation);
+    listings.push((record, valuation));
+  }
+
+  filter_by_valuation(listings, &filter, |medium_of_exchange_hash| {
+    Ok(
+      convert(ConvertInput {
+        amount: 1.0,
+        from_medium_hash: medium_of_exchange_hash,
+        to_medium_hash: filter.medium_of_exchange_hash.clone(),
+        at: None,
+      })
+      .ok(),
+    )
+  })
+}
+
 #[hdk_extern]
 pub fn get_archived_requests(_: ()) -> ExternResult<Vec<Record>> {
   let path = Path::from("requests.archived");
@@ -214,6 +347,114 @@ pub fn get_archived_requests(_: ()) -> ExternResult<Vec<Record>> {
   Ok(records)
 }
 
+#[hdk_extern]
+pub fn get_expired_requests(_: ()) -> ExternResult<Vec<Record>> {
+  let path = Path::from("requests.expired");
+  let path_hash = path.path_entry_hash()?;
+  let link_type_filter = LinkTypes::ExpiredRequests
+    .try_into_filter()
+    .map_err(|e| wasm_error!(WasmErrorInner::Guest(e.to_string())))?;
+  let links = get_links(
+    LinkQuery::new(path_hash.clone(), link_type_filter),
+    GetStrategy::Network,
+  )?;
+  let get_input: Vec<GetInput> = links
+    .into_iter()
+    .filter_map(|link| {
+      link
+        .target
+        .clone()
+        .into_any_dht_hash()
+        .map(|hash| GetInput::new(hash, GetOptions::default()))
+    })
+    .collect();
+  let records = HDK.with(|hdk| hdk.borrow().get(get_input))?;
+  let records: Vec<Record> = records.into_iter().flatten().collect();
+  Ok(records)
+}
+
+#[hdk_extern]
+pub fn get_fulfilled_requests(_: ()) -> ExternResult<Vec<Record>> {
+  let path = Path::from("requests.fulfilled");
+  let path_hash = path.path_entry_hash()?;
+  let link_type_filter = LinkTypes::FulfilledRequests
+    .try_into_filter()
+    .map_err(|e| wasm_error!(WasmErrorInner::Guest(e.to_string())))?;
+  let links = get_links(
+    LinkQuery::new(path_hash.clone(), link_type_filter),
+    GetStrategy::Network,
+  )?;
+  let get_input: Vec<GetInput> = links
+    .into_iter()
+    .filter_map(|link| {
+      link
+        .target
+        .clone()
+        .into_any_dht_hash()
+        .map(|hash| GetInput::new(hash, GetOptions::default()))
+    })
+    .collect();
+  let records = HDK.with(|hdk| hdk.borrow().get(get_input))?;
+  let records: Vec<Record> = records.into_iter().flatten().collect();
+  Ok(records)
+}
+
+/// Get a page of active requests, ordered by the time they entered the index
+#[hdk_extern]
+pub fn get_active_requests_paginated(input: PaginationInput) -> ExternResult<Page<Record>> {
+  let path_hash = Path::from("requests.active").path_entry_hash()?;
+  let link_type_filter = LinkTypes::ActiveRequests
+    .try_into_filter()
+    .map_err(|e| wasm_error!(WasmErrorInner::Guest(e.to_string())))?;
+  let links = get_links(
+    LinkQuery::new(path_hash, link_type_filter),
+    GetStrategy::Network,
+  )?;
+  paginate_records(links, &input)
+}
+
+/// Get a page of archived requests, ordered by the time they entered the index
+#[hdk_extern]
+pub fn get_archived_requests_paginated(input: PaginationInput) -> ExternResult<Page<Record>> {
+  let path_hash = Path::from("requests.archived").path_entry_hash()?;
+  let link_type_filter = LinkTypes::ArchivedRequests
+    .try_into_filter()
+    .map_err(|e| wasm_error!(WasmErrorInner::Guest(e.to_string())))?;
+  let links = get_links(
+    LinkQuery::new(path_hash, link_type_filter),
+    GetStrategy::Network,
+  )?;
+  paginate_records(links, &input)
+}
+
+/// Get a page of expired requests, ordered by the time they entered the index
+#[hdk_extern]
+pub fn get_expired_requests_paginated(input: PaginationInput) -> ExternResult<Page<Record>> {
+  let path_hash = Path::from("requests.expired").path_entry_hash()?;
+  let link_type_filter = LinkTypes::ExpiredRequests
+    .try_into_filter()
+    .map_err(|e| wasm_error!(WasmErrorInner::Guest(e.to_string())))?;
+  let links = get_links(
+    LinkQuery::new(path_hash, link_type_filter),
+    GetStrategy::Network,
+  )?;
+  paginate_records(links, &input)
+}
+
+/// Get a page of fulfilled requests, ordered by the time they entered the index
+#[hdk_extern]
+pub fn get_fulfilled_requests_paginated(input: PaginationInput) -> ExternResult<Page<Record>> {
+  let path_hash = Path::from("requests.fulfilled").path_entry_hash()?;
+  let link_type_filter = LinkTypes::FulfilledRequests
+    .try_into_filter()
+    .map_err(|e| wasm_error!(WasmErrorInner::Guest(e.to_string())))?;
+  let links = get_links(
+    LinkQuery::new(path_hash, link_type_filter),
+    GetStrategy::Network,
+  )?;
+  paginate_records(links, &input)
+}
+
 #[hdk_extern]
 pub fn get_user_requests(user_hash: ActionHash) -> ExternResult<Vec<Record>> {
   let link_type_filter = LinkTypes::UserRequests
@@ -361,16 +602,7 @@ pub fn update_request(input: UpdateRequestInput) -> ExternResult<Record> {
   let original_record = get(input.original_action_hash.0.clone(), GetOptions::default())?.ok_or(
     CommonError::EntryNotFound("Could not find the original request".to_string()),
   )?;
-  let agent_pubkey = agent_info()?.agent_initial_pubkey;
-
-  // Check if the agent is the author or an administrator
-  let author = original_record.action().author().clone();
-  let is_author = author == agent_pubkey;
-  let is_admin = check_if_agent_is_administrator(agent_pubkey.clone())?;
-
-  if !is_author && !is_admin {
-    return Err(UsersError::NotAuthor.into());
-  }
+  let update_authorization = get_update_authorization(&original_record)?;
 
   // Check if request is archived (archived requests cannot be edited)
   let current_request: Request = original_record
@@ -385,9 +617,30 @@ pub fn update_request(input: UpdateRequestInput) -> ExternResult<Record> {
     return Err(CommonError::CannotUpdateArchived("request".to_string()).into());
   }
 
+  // Expired requests are moved back to "requests.active" by renew_request only
+  let latest_request = get_latest_request(input.original_action_hash.0.clone())?;
+  if latest_request.status == ListingStatus::Expired {
+    return Err(
+      CommonError::InvalidData("Expired requests must be renewed before editing".to_string())
+        .into(),
+    );
+  }
+
+  let original_hash = input.original_action_hash.0.clone();
   let previous_hash = input.previous_action_hash.0.clone();
-  let updated_request_hash =
-    update_entry(input.previous_action_hash.into(), &input.updated_request)?;
+  let mut updated_request = input.updated_request.clone();
+  updated_request.update_authorization = update_authorization;
+  updated_request.location = updated_request.location.map(|location| location.snapped());
+  if let Some(valuation) = &updated_request.valuation {
+    let previous_medium_of_exchange_hash = latest_request
+      .valuation
+      .as_ref()
+      .map(|previous| &previous.medium_of_exchange_hash);
+    if previous_medium_of_exchange_hash != Some(&valuation.medium_of_exchange_hash) {
+      check_valuation_medium_of_exchange(valuation)?;
+    }
+  }
+  let updated_request_hash = update_entry(input.previous_action_hash.into(), &updated_request)?;
 
   // Update the ActiveRequests link in "requests.active" to point to the new record.
   // create_request uses Path::from("requests.active") + LinkTypes::ActiveRequests, so
@@ -418,7 +671,7 @@ pub fn update_request(input: UpdateRequestInput) -> ExternResult<Record> {
     active_path_hash.clone(),
     updated_request_hash.clone(),
     LinkTypes::ActiveRequests,
-    (),
+    listing_index_tag(&input.original_action_hash.0)?,
   )?;
 
   // Create the update tracking link (original → updated, for get_latest_request_record)
@@ -443,6 +696,8 @@ pub fn update_request(input: UpdateRequestInput) -> ExternResult<Record> {
     new_medium_of_exchange_hashes: input.medium_of_exchange_hashes,
   })?;
 
+  index_request(original_hash, &updated_request)?;
+
   let record = get(updated_request_hash.clone(), GetOptions::default())?.ok_or(
     CommonError::EntryNotFound("Could not find the newly updated Request".to_string()),
   )?;
@@ -466,6 +721,12 @@ pub fn delete_request(original_action_hash: ActionHash) -> ExternResult<bool> {
     return Err(UsersError::NotAuthor.into());
   }
 
+  // An administrator first marks the request as deleted: integrity only lets the author, or an
+  // agent who updated the request with the proof of their role, remove its links
+  if !is_author {
+    change_request_status(original_action_hash.clone(), ListingStatus::Deleted, None)?;
+  }
+
   // Delete all requests links
   let path = Path::from("requests");
   let path_hash = path.path_entry_hash()?;
@@ -581,31 +842,162 @@ pub fn delete_request(original_action_hash: ActionHash) -> ExternResult<bool> {
     delete_link(link.create_link_hash, GetOptions::default())?;
   }
 
+  // Remove the listing from the search index
+  remove_entity_from_index(original_action_hash.clone())?;
+
   // Finally delete the request entry
   delete_entry(original_action_hash.clone())?;
 
   Ok(true)
 }
 
+/// Archives a request, moving it from its status index to "requests.archived". Fulfilment
+/// notes are cleared, as only fulfilled requests keep them.
 #[hdk_extern]
 pub fn archive_request(original_action_hash: ActionHash) -> ExternResult<bool> {
-  // Get the latest request record (follows update chain)
-  let latest_record = get_latest_request_record(original_action_hash.clone())?.ok_or(
-    CommonError::EntryNotFound("Could not find the request".to_string()),
+  change_request_status(original_action_hash, ListingStatus::Archived, None)?;
+  Ok(true)
+}
+
+/// Deletes the links from the `path` index to `target`
+fn unlink_from_path(path: &str, link_type: LinkTypes, target: &ActionHash) -> ExternResult<()> {
+  let path_hash = Path::from(path).path_entry_hash()?;
+  let link_type_filter = link_type
+    .try_into_filter()
+    .map_err(|e| wasm_error!(WasmErrorInner::Guest(e.to_string())))?;
+  let links = get_links(
+    LinkQuery::new(path_hash, link_type_filter),
+    GetStrategy::Network,
   )?;
-  let agent_pubkey = agent_info()?.agent_initial_pubkey;
 
-  // Check if the agent is the author or an administrator
-  let author = latest_record.action().author().clone();
-  let is_author = author == agent_pubkey;
-  let is_admin = check_if_agent_is_administrator(agent_pubkey.clone())?;
+  for link in links {
+    if link.target.into_action_hash().as_ref() == Some(target) {
+      delete_link(link.create_link_hash, GetOptions::default())?;
+    }
+  }
 
-  if !is_author && !is_admin {
-    return Err(UsersError::NotAuthor.into());
+  Ok(())
+}
+
+/// Marks the latest version of a request as expired and moves it from "requests.active"
+/// to "requests.expired"
+fn expire_request(
+  original_action_hash: ActionHash,
+  latest_action_hash: ActionHash,
+  mut request: Request,
+) -> ExternResult<ActionHash> {
+  request.status = ListingStatus::Expired;
+  request.update_authorization = None;
+  let expired_request_hash = update_entry(latest_action_hash.clone(), &request)?;
+
+  unlink_from_path(
+    "requests.active",
+    LinkTypes::ActiveRequests,
+    &latest_action_hash,
+  )?;
+  create_link(
+    Path::from("requests.expired").path_entry_hash()?,
+    expired_request_hash.clone(),
+    LinkTypes::ExpiredRequests,
+    listing_index_tag(&original_action_hash)?,
+  )?;
+  create_link(
+    original_action_hash,
+    expired_request_hash.clone(),
+    LinkTypes::RequestUpdates,
+    (),
+  )?;
+
+  Ok(expired_request_hash)
+}
+
+/// Expires the active requests created by this agent whose date range has ended,
+/// returning their original action hashes
+fn expire_own_requests() -> ExternResult<Vec<ActionHash>> {
+  let now = sys_time()?;
+  let active_hashes: Vec<ActionHash> = get_links(
+    LinkQuery::new(
+      Path::from("requests.active").path_entry_hash()?,
+      LinkTypes::ActiveRequests
+        .try_into_filter()
+        .map_err(|e| wasm_error!(WasmErrorInner::Guest(e.to_string())))?,
+    ),
+    GetStrategy::Network,
+  )?
+  .into_iter()
+  .filter_map(|link| link.target.into_action_hash())
+  .collect();
+
+  let created_requests = query(
+    ChainQueryFilter::new()
+      .entry_type(UnitEntryTypes::Request.try_into()?)
+      .action_type(ActionType::Create),
+  )?;
+
+  let mut expired = Vec::new();
+  for created_request in created_requests {
+    let original_action_hash = created_request.action_address().clone();
+    let Some(latest_record) = get_latest_request_record(original_action_hash.clone())? else {
+      continue;
+    };
+    let latest_action_hash = latest_record.action_address().clone();
+    if !active_hashes.contains(&latest_action_hash) {
+      continue;
+    }
+
+    let Some(request) = latest_record
+      .entry()
+      .to_app_option::<Request>()
+      .map_err(CommonError::Serialize)?
+    else {
+      continue;
+    };
+    let has_ended = request
+      .date_range
+      .as_ref()
+      .is_some_and(|date_range| date_range.has_ended(now));
+
+    if request.status == ListingStatus::Active && has_ended {
+      expire_request(original_action_hash.clone(), latest_action_hash, request)?;
+      expired.push(original_action_hash);
+    }
+  }
+
+  Ok(expired)
+}
+
+/// Scheduled sweep moving this agent's requests whose date range has ended from
+/// "requests.active" to "requests.expired". Scheduled hourly from `init`.
+///
+/// A request can only be expired from its author's cell, so it stays active until
+/// its author is online after its end date.
+#[hdk_extern(infallible)]
+pub fn expire_requests(_: Option<Schedule>) -> Option<Schedule> {
+  if let Err(err) = expire_own_requests() {
+    error!("Error expiring requests: {:?}", err);
   }
+  Some(Schedule::Persisted(EXPIRY_SCHEDULE.to_string()))
+}
 
-  // Get the current request
-  let current_request: Request = latest_record
+#[derive(Serialize, Deserialize, Debug)]
+pub struct RenewRequestInput {
+  pub original_action_hash: OriginalActionHash,
+  pub date_range: DateRange,
+}
+
+/// Renews an expired request with a new date range, moving it back to "requests.active"
+#[hdk_extern]
+pub fn renew_request(input: RenewRequestInput) -> ExternResult<Record> {
+  let original_action_hash = input.original_action_hash.0;
+  let original_record = get(original_action_hash.clone(), GetOptions::default())?.ok_or(
+    CommonError::EntryNotFound("Could not find the original request".to_string()),
+  )?;
+  let update_authorization = get_update_authorization(&original_record)?;
+
+  let latest_record = get_latest_request_record(original_action_hash.clone())?.ok_or(
+    CommonError::EntryNotFound("Could not find the request".to_string()),
+  )?;
+  let mut request: Request = latest_record
     .entry()
     .to_app_option()
     .map_err(CommonError::Serialize)?
@@ -613,55 +1005,146 @@ pub fn archive_request(original_action_hash: ActionHash) -> ExternResult<bool> {
       "Could not deserialize request entry".to_string(),
     ))?;
 
-  // Update the request with archived status
-  let mut updated_request = current_request.clone();
-  updated_request.status = ListingStatus::Archived;
+  if request.status != ListingStatus::Expired {
+    return Err(
+      CommonError::InvalidData("Only expired requests can be renewed".to_string()).into(),
+    );
+  }
+  if input.date_range.has_ended(sys_time()?) {
+    return Err(
+      CommonError::InvalidData("The new date range must end in the future".to_string()).into(),
+    );
+  }
 
-  // Create update entry - use the LATEST action hash as previous
-  let latest_action_hash = latest_record.signed_action.hashed.hash.clone();
-  let updated_request_hash = update_entry(latest_action_hash.clone(), &updated_request)?;
+  request.date_range = Some(input.date_range);
+  request.status = ListingStatus::Active;
+  request.update_authorization = update_authorization;
+  let latest_action_hash = latest_record.action_address().clone();
+  let renewed_request_hash = update_entry(latest_action_hash.clone(), &request)?;
 
-  // Move the link from "requests.active" to "requests.archived" path
-  // First, remove the link from the active path
-  let active_path = Path::from("requests.active");
-  let active_path_hash = active_path.path_entry_hash()?;
-  let active_link_filter = LinkTypes::ActiveRequests
-    .try_into_filter()
-    .map_err(|e| wasm_error!(WasmErrorInner::Guest(e.to_string())))?;
-  let active_links = get_links(
-    LinkQuery::new(active_path_hash.clone(), active_link_filter),
-    GetStrategy::Local,
+  unlink_from_path(
+    "requests.expired",
+    LinkTypes::ExpiredRequests,
+    &latest_action_hash,
+  )?;
+  create_link(
+    Path::from("requests.active").path_entry_hash()?,
+    renewed_request_hash.clone(),
+    LinkTypes::ActiveRequests,
+    listing_index_tag(&original_action_hash)?,
+  )?;
+  create_link(
+    original_action_hash,
+    renewed_request_hash.clone(),
+    LinkTypes::RequestUpdates,
+    (),
   )?;
 
-  // Find and remove the link from active path
-  for link in active_links {
-    if let Some(hash) = link.target.clone().into_action_hash() {
-      if hash == latest_action_hash {
-        delete_link(link.create_link_hash, GetOptions::default())?;
-        break;
-      }
+  get(renewed_request_hash, GetOptions::default())?
+    .ok_or(CommonError::EntryNotFound("Could not find the renewed request".to_string()).into())
+}
+
+/// The index path and link type listing requests in `status`, if any.
+/// In-progress requests stay listed as active.
+fn status_index(status: &ListingStatus) -> Option<(&'static str, LinkTypes)> {
+  match status {
+    ListingStatus::Active | ListingStatus::InProgress => {
+      Some(("requests.active", LinkTypes::ActiveRequests))
     }
+    ListingStatus::Archived => Some(("requests.archived", LinkTypes::ArchivedRequests)),
+    ListingStatus::Expired => Some(("requests.expired", LinkTypes::ExpiredRequests)),
+    ListingStatus::Fulfilled => Some(("requests.fulfilled", LinkTypes::FulfilledRequests)),
+    ListingStatus::Deleted => None,
   }
+}
 
-  // Create new link in the archived path
-  let archived_path = Path::from("requests.archived");
-  let archived_path_hash = archived_path.path_entry_hash()?;
-  create_link(
-    archived_path_hash.clone(),
-    updated_request_hash.clone(),
-    LinkTypes::ArchivedRequests,
-    (),
+/// Moves the latest version of a request to `status`, keeping the status indexes in sync
+fn change_request_status(
+  original_action_hash: ActionHash,
+  status: ListingStatus,
+  fulfilment_notes: Option<String>,
+) -> ExternResult<Record> {
+  let original_record = get(original_action_hash.clone(), GetOptions::default())?.ok_or(
+    CommonError::EntryNotFound("Could not find the original request".to_string()),
+  )?;
+  let update_authorization = get_update_authorization(&original_record)?;
+
+  let latest_record = get_latest_request_record(original_action_hash.clone())?.ok_or(
+    CommonError::EntryNotFound("Could not find the request".to_string()),
   )?;
+  let mut request: Request = latest_record
+    .entry()
+    .to_app_option()
+    .map_err(CommonError::Serialize)?
+    .ok_or(CommonError::EntryNotFound(
+      "Could not deserialize request entry".to_string(),
+    ))?;
 
-  // Create link to track the update (from original to new)
+  if !request.status.can_transition_to(&status) {
+    return Err(
+      CommonError::InvalidData(format!(
+        "Request status cannot change from {:?} to {:?}",
+        request.status, status
+      ))
+      .into(),
+    );
+  }
+
+  let latest_action_hash = latest_record.action_address().clone();
+  let previous_index = status_index(&request.status);
+  request.status = status;
+  request.fulfilment_notes = fulfilment_notes;
+  request.update_authorization = update_authorization;
+  let updated_request_hash = update_entry(latest_action_hash.clone(), &request)?;
+
+  if let Some((path, link_type)) = previous_index {
+    unlink_from_path(path, link_type, &latest_action_hash)?;
+  }
+  if let Some((path, link_type)) = status_index(&request.status) {
+    create_link(
+      Path::from(path).path_entry_hash()?,
+      updated_request_hash.clone(),
+      link_type,
+      listing_index_tag(&original_action_hash)?,
+    )?;
+  }
   create_link(
-    original_action_hash.clone(),
+    original_action_hash,
     updated_request_hash.clone(),
     LinkTypes::RequestUpdates,
     (),
   )?;
 
-  Ok(true)
+  get(updated_request_hash, GetOptions::default())?
+    .ok_or(CommonError::EntryNotFound("Could not find the updated request".to_string()).into())
+}
+
+/// Marks a request as being worked on
+#[hdk_extern]
+pub fn mark_request_in_progress(original_action_hash: ActionHash) -> ExternResult<Record> {
+  change_request_status(original_action_hash, ListingStatus::InProgress, None)
+}
+
+/// Reactivates an archived request, moving it back to "requests.active". The original
+/// action hash and its service type and medium of exchange links are kept.
+#[hdk_extern]
+pub fn reactivate_request(original_action_hash: ActionHash) -> ExternResult<Record> {
+  if get_latest_request(original_action_hash.clone())?.status != ListingStatus::Archived {
+    return Err(
+      CommonError::InvalidData("Only archived requests can be reactivated".to_string()).into(),
+    );
+  }
+  change_request_status(original_action_hash, ListingStatus::Active, None)
+}
+
+/// Marks a request as fulfilled, moving it to "requests.fulfilled"
+#[hdk_extern]
+pub fn fulfil_request(input: FulfilListingInput) -> ExternResult<Record> {
+  change_request_status(
+    input.original_action_hash.0,
+    ListingStatus::Fulfilled,
+    input.fulfilment_notes,
+  )
 }
 
 #[hdk_extern]
@@ -673,17 +1156,16 @@ pub fn get_my_listings(user_hash: ActionHash) -> ExternResult<Vec<Record>> {
 pub fn get_requests_by_tag(tag: String) -> ExternResult<Vec<Record>> {
   use utils::external_local_call;
 
-  // Call service_types zome to get service types with this tag
-  let service_type_records: Vec<Record> =
-    external_local_call("get_service_types_by_tag", "service_types", tag)?;
+  // Call service_types zome to get the original hashes of the service types with this tag,
+  // which listings are linked from
+  let service_type_hashes: Vec<ActionHash> =
+    external_local_call("get_service_type_hashes_by_tag", "service_types", tag)?;
 
   // For each service type, get all requests that use it
   let mut all_requests = Vec::new();
   let mut seen_hashes = std::collections::HashSet::new();
 
-  for service_type_record in service_type_records {
-    let service_type_hash = service_type_record.signed_action.hashed.hash;
-
+  for service_type_hash in service_type_hashes {
     // Call service_types zome to get requests for this service type
     let requests: Vec<Record> = external_local_call(
       "get_requests_for_service_type",
@@ -0,0 +1,40 @@
+use hdk::prelude::*;
+use utils::{external_local_call, EntityActionHash, OfferSlotClaim};
+
+use crate::response::ListingState;
+
+pub fn get_agent_user(agent_pubkey: AgentPubKey) -> ExternResult<Vec<Link>> {
+  external_local_call("get_agent_user", "users_organizations", agent_pubkey)
+}
+
+pub fn check_if_entity_is_accepted(original_action_hash: EntityActionHash) -> ExternResult<bool> {
+  external_local_call(
+    "check_if_entity_is_accepted",
+    "administration",
+    original_action_hash,
+  )
+}
+
+pub fn get_request(action_hash: ActionHash) -> ExternResult<Option<Record>> {
+  external_local_call("get_request", "requests", action_hash)
+}
+
+pub fn get_latest_request(original_action_hash: ActionHash) -> ExternResult<ListingState> {
+  external_local_call("get_latest_request", "requests", original_action_hash)
+}
+
+pub fn get_request_creator(request_hash: ActionHash) -> ExternResult<Option<ActionHash>> {
+  external_local_call("get_request_creator", "requests", request_hash)
+}
+
+pub fn get_offer_creator(offer_hash: ActionHash) -> ExternResult<Option<ActionHash>> {
+  external_local_call("get_offer_creator", "offers", offer_hash)
+}
+
+pub fn claim_offer_slot(response_hash: ActionHash) -> ExternResult<OfferSlotClaim> {
+  external_local_call("claim_offer_slot", "offers", response_hash)
+}
+
+pub fn release_offer_slot(response_hash: ActionHash) -> ExternResult<bool> {
+  external_local_call("release_offer_slot", "offers", response_hash)
+}
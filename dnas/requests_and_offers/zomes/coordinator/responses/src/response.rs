@@ -0,0 +1,330 @@
+use hdk::prelude::*;
+use responses_integrity::*;
+use utils::{
+  errors::{AdministrationError, CommonError, UsersError},
+  EntityActionHash, ListingStatus, OriginalActionHash,
+};
+
+use crate::external_calls::{
+  check_if_entity_is_accepted, claim_offer_slot, get_agent_user, get_latest_request,
+  get_offer_creator, get_request, get_request_creator, release_offer_slot,
+};
+
+/// The part of a request entry needed to check that it can still be answered.
+/// Other fields are ignored when deserializing.
+#[derive(Serialize, Deserialize, Debug)]
+pub struct ListingState {
+  #[serde(default)]
+  pub status: ListingStatus,
+}
+
+/// Returns the user profile of the calling agent, which must have been accepted
+fn get_accepted_user_hash() -> ExternResult<ActionHash> {
+  let user_links = get_agent_user(agent_info()?.agent_initial_pubkey)?;
+  let user_hash = user_links
+    .first()
+    .and_then(|link| link.target.clone().into_action_hash())
+    .ok_or(UsersError::UserProfileRequired)?;
+
+  let is_accepted = check_if_entity_is_accepted(EntityActionHash {
+    entity_original_action_hash: OriginalActionHash(user_hash.clone()),
+    entity: "users".to_string(),
+  })?;
+  if !is_accepted {
+    return Err(AdministrationError::EntityNotAccepted("users".to_string()).into());
+  }
+
+  Ok(user_hash)
+}
+
+/// Answers a request, optionally with one of the responder's offers
+#[hdk_extern]
+pub fn create_response(response: Response) -> ExternResult<Record> {
+  let user_hash = get_accepted_user_hash()?;
+
+  // Only open requests can be answered
+  let request = get_latest_request(response.request_hash.clone())?;
+  if !matches!(
+    request.status,
+    ListingStatus::Active | ListingStatus::InProgress
+  ) {
+    return Err(
+      CommonError::InvalidData("Only active requests can be answered".to_string()).into(),
+    );
+  }
+
+  if get_request_creator(response.request_hash.clone())? == Some(user_hash.clone()) {
+    return Err(CommonError::InvalidData("Cannot respond to your own request".to_string()).into());
+  }
+
+  if let Some(offer_hash) = response.offer_hash.clone() {
+    if get_offer_creator(offer_hash)? != Some(user_hash.clone()) {
+      return Err(
+        CommonError::InvalidData("A response can only reference your own offers".to_string())
+          .into(),
+      );
+    }
+  }
+
+  let response_hash = create_entry(&EntryTypes::Response(response.clone()))?;
+  let record = get(response_hash.clone(), GetOptions::default())?.ok_or(
+    CommonError::EntryNotFound("Could not find the newly created response".to_string()),
+  )?;
+
+  create_link(
+    response.request_hash,
+    response_hash.clone(),
+    LinkTypes::RequestResponses,
+    (),
+  )?;
+  create_link(
+    user_hash.clone(),
+    response_hash.clone(),
+    LinkTypes::UserResponses,
+    (),
+  )?;
+  create_link(response_hash, user_hash, LinkTypes::ResponseResponder, ())?;
+
+  Ok(record)
+}
+
+#[hdk_extern]
+pub fn get_latest_response_record(
+  original_action_hash: ActionHash,
+) -> ExternResult<Option<Record>> {
+  let link_type_filter = LinkTypes::ResponseUpdates
+    .try_into_filter()
+    .map_err(|e| wasm_error!(WasmErrorInner::Guest(e.to_string())))?;
+  let links = get_links(
+    LinkQuery::new(original_action_hash.clone(), link_type_filter),
+    GetStrategy::Network,
+  )?;
+  let latest_action_hash = match links.into_iter().max_by_key(|link| link.timestamp) {
+    Some(link) => link
+      .target
+      .into_action_hash()
+      .ok_or(CommonError::ActionHashNotFound("response".to_string()))?,
+    None => original_action_hash,
+  };
+  get(latest_action_hash, GetOptions::default())
+}
+
+#[hdk_extern]
+pub fn get_latest_response(original_action_hash: ActionHash) -> ExternResult<Response> {
+  let record = get_latest_response_record(original_action_hash.clone())?.ok_or(
+    CommonError::EntryNotFound(format!(
+      "Response not found for action hash: {}",
+      original_action_hash
+    )),
+  )?;
+
+  record
+    .entry()
+    .to_app_option()
+    .map_err(CommonError::Serialize)?
+    .ok_or(CommonError::EntryNotFound("Could not deserialize response entry".to_string()).into())
+}
+
+/// Resolves links to original response hashes into the latest version of each response
+fn get_latest_response_records(links: Vec<Link>) -> ExternResult<Vec<Record>> {
+  let mut records = Vec::new();
+  for original_action_hash in links
+    .into_iter()
+    .filter_map(|link| link.target.into_action_hash())
+  {
+    if let Some(record) = get_latest_response_record(original_action_hash)? {
+      records.push(record);
+    }
+  }
+  Ok(records)
+}
+
+/// Get every response to a request
+#[hdk_extern]
+pub fn get_responses_for_request(request_hash: ActionHash) -> ExternResult<Vec<Record>> {
+  let link_type_filter = LinkTypes::RequestResponses
+    .try_into_filter()
+    .map_err(|e| wasm_error!(WasmErrorInner::Guest(e.to_string())))?;
+  let links = get_links(
+    LinkQuery::new(request_hash, link_type_filter),
+    GetStrategy::Network,
+  )?;
+  get_latest_response_records(links)
+}
+
+/// Get every response written by a user
+#[hdk_extern]
+pub fn get_user_responses(user_hash: ActionHash) -> ExternResult<Vec<Record>> {
+  let link_type_filter = LinkTypes::UserResponses
+    .try_into_filter()
+    .map_err(|e| wasm_error!(WasmErrorInner::Guest(e.to_string())))?;
+  let links = get_links(
+    LinkQuery::new(user_hash, link_type_filter),
+    GetStrategy::Network,
+  )?;
+  get_latest_response_records(links)
+}
+
+#[hdk_extern]
+pub fn get_response_responder(response_hash: ActionHash) -> ExternResult<Option<ActionHash>> {
+  let link_type_filter = LinkTypes::ResponseResponder
+    .try_into_filter()
+    .map_err(|e| wasm_error!(WasmErrorInner::Guest(e.to_string())))?;
+  let links = get_links(
+    LinkQuery::new(response_hash, link_type_filter),
+    GetStrategy::Network,
+  )?;
+
+  Ok(
+    links
+      .first()
+      .and_then(|link| link.target.clone().into_action_hash()),
+  )
+}
+
+/// Records the decision of the request owner on a pending response
+fn decide_response(
+  original_action_hash: ActionHash,
+  status: ResponseStatus,
+) -> ExternResult<Record> {
+  let latest_record = get_latest_response_record(original_action_hash.clone())?.ok_or(
+    CommonError::EntryNotFound("Could not find the response".to_string()),
+  )?;
+  let mut response: Response = latest_record
+    .entry()
+    .to_app_option()
+    .map_err(CommonError::Serialize)?
+    .ok_or(CommonError::EntryNotFound(
+      "Could not deserialize response entry".to_string(),
+    ))?;
+
+  // Only the owner of the request can decide on its responses
+  let request_record = get_request(response.request_hash.clone())?.ok_or(
+    CommonError::EntryNotFound("Could not find the request".to_string()),
+  )?;
+  if *request_record.action().author() != agent_info()?.agent_initial_pubkey {
+    return Err(UsersError::NotAuthor.into());
+  }
+
+  if response.status != ResponseStatus::Pending {
+    return Err(
+      CommonError::InvalidData("The response was already accepted or declined".to_string()).into(),
+    );
+  }
+
+  response.status = status;
+  let updated_response_hash = update_entry(latest_record.action_address().clone(), &response)?;
+
+  create_link(
+    original_action_hash,
+    updated_response_hash.clone(),
+    LinkTypes::ResponseUpdates,
+    (),
+  )?;
+
+  get(updated_response_hash, GetOptions::default())?
+    .ok_or(CommonError::EntryNotFound("Could not find the updated response".to_string()).into())
+}
+
+/// Accepts a response. If it proposes an offer, this takes up one of the offer's
+/// slots, or puts the response on the offer's waitlist when none are left.
+#[hdk_extern]
+pub fn accept_response(original_action_hash: ActionHash) -> ExternResult<Record> {
+  let record = decide_response(original_action_hash.clone(), ResponseStatus::Accepted)?;
+
+  let response: Response = record
+    .entry()
+    .to_app_option()
+    .map_err(CommonError::Serialize)?
+    .ok_or(CommonError::EntryNotFound(
+      "Could not deserialize response entry".to_string(),
+    ))?;
+  if response.offer_hash.is_some() {
+    claim_offer_slot(original_action_hash)?;
+  }
+
+  Ok(record)
+}
+
+#[hdk_extern]
+pub fn decline_response(original_action_hash: ActionHash) -> ExternResult<Record> {
+  decide_response(original_action_hash, ResponseStatus::Declined)
+}
+
+/// Deletes the links from `base` to `target`
+fn delete_links_to(
+  base: AnyLinkableHash,
+  link_type: LinkTypes,
+  target: &ActionHash,
+) -> ExternResult<()> {
+  let link_type_filter = link_type
+    .try_into_filter()
+    .map_err(|e| wasm_error!(WasmErrorInner::Guest(e.to_string())))?;
+  let links = get_links(LinkQuery::new(base, link_type_filter), GetStrategy::Network)?;
+
+  for link in links {
+    if link.target.into_action_hash().as_ref() == Some(target) {
+      delete_link(link.create_link_hash, GetOptions::default())?;
+    }
+  }
+
+  Ok(())
+}
+
+/// Withdraws a response. Only the responder can do this.
+#[hdk_extern]
+pub fn delete_response(original_action_hash: ActionHash) -> ExternResult<bool> {
+  let original_record = get(original_action_hash.clone(), GetOptions::default())?.ok_or(
+    CommonError::EntryNotFound("Could not find the original response".to_string()),
+  )?;
+  if *original_record.action().author() != agent_info()?.agent_initial_pubkey {
+    return Err(UsersError::NotAuthor.into());
+  }
+  let response: Response = original_record
+    .entry()
+    .to_app_option()
+    .map_err(CommonError::Serialize)?
+    .ok_or(CommonError::EntryNotFound(
+      "Could not deserialize response entry".to_string(),
+    ))?;
+
+  delete_links_to(
+    response.request_hash.into(),
+    LinkTypes::RequestResponses,
+    &original_action_hash,
+  )?;
+
+  if let Some(user_hash) = get_response_responder(original_action_hash.clone())? {
+    delete_links_to(
+      user_hash.clone().into(),
+      LinkTypes::UserResponses,
+      &original_action_hash,
+    )?;
+    delete_links_to(
+      original_action_hash.clone().into(),
+      LinkTypes::ResponseResponder,
+      &user_hash,
+    )?;
+  }
+
+  // Delete any update links
+  let link_type_filter = LinkTypes::ResponseUpdates
+    .try_into_filter()
+    .map_err(|e| wasm_error!(WasmErrorInner::Guest(e.to_string())))?;
+  let update_links = get_links(
+    LinkQuery::new(original_action_hash.clone(), link_type_filter),
+    GetStrategy::Network,
+  )?;
+  for link in update_links {
+    delete_link(link.create_link_hash, GetOptions::default())?;
+  }
+
+  // Free the slot the response took up on the offer it proposed
+  if response.offer_hash.is_some() {
+    release_offer_slot(original_action_hash.clone())?;
+  }
+
+  delete_entry(original_action_hash)?;
+
+  Ok(true)
+}
@@ -0,0 +1,48 @@
+use hdk::prelude::*;
+use utils::external_local_call;
+
+use crate::saved_search::ListingFields;
+
+pub fn get_latest_request(original_action_hash: ActionHash) -> ExternResult<ListingFields> {
+  external_local_call("get_latest_request", "requests", original_action_hash)
+}
+
+pub fn get_latest_offer(original_action_hash: ActionHash) -> ExternResult<ListingFields> {
+  external_local_call("get_latest_offer", "offers", original_action_hash)
+}
+
+pub fn get_requests_for_service_type(service_type_hash: ActionHash) -> ExternResult<Vec<Record>> {
+  external_local_call(
+    "get_requests_for_service_type",
+    "service_types",
+    service_type_hash,
+  )
+}
+
+pub fn get_offers_for_service_type(service_type_hash: ActionHash) -> ExternResult<Vec<Record>> {
+  external_local_call(
+    "get_offers_for_service_type",
+    "service_types",
+    service_type_hash,
+  )
+}
+
+pub fn get_requests_for_medium_of_exchange(
+  medium_of_exchange_hash: ActionHash,
+) -> ExternResult<Vec<Record>> {
+  external_local_call(
+    "get_requests_for_medium_of_exchange",
+    "mediums_of_exchange",
+    medium_of_exchange_hash,
+  )
+}
+
+pub fn get_offers_for_medium_of_exchange(
+  medium_of_exchange_hash: ActionHash,
+) -> ExternResult<Vec<Record>> {
+  external_local_call(
+    "get_offers_for_medium_of_exchange",
+    "mediums_of_exchange",
+    medium_of_exchange_hash,
+  )
+}
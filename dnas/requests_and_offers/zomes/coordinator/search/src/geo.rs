@@ -0,0 +1,207 @@
+use hdk::prelude::*;
+use search_integrity::*;
+use utils::{
+  errors::CommonError,
+  geo::{covering_geohashes, MAX_PRIVACY_RADIUS_KM, MAX_SEARCH_RADIUS_KM},
+  GeoIndexInput, GeoLocation, GeoPoint, ListingStatus,
+};
+
+use crate::external_calls::{get_latest_offer, get_latest_request};
+use crate::search::is_entity_author;
+
+/// Kinds of entities that can be located
+pub const LOCATABLE_ENTITIES: [&str; 2] = ["request", "offer"];
+
+fn check_locatable_entity(entity: &str) -> ExternResult<()> {
+  if LOCATABLE_ENTITIES.contains(&entity) {
+    Ok(())
+  } else {
+    Err(CommonError::InvalidData("Must be request or offer".to_string()).into())
+  }
+}
+
+fn geo_anchor_path(entity: &str, geohash: &str) -> String {
+  format!("geo.{}.{}", entity, geohash)
+}
+
+/// Removes every geo index link pointing to an entity, using its reverse anchor links
+pub fn remove_entity_from_geo_index(original_action_hash: ActionHash) -> ExternResult<()> {
+  let anchors_filter = LinkTypes::EntityGeoAnchors
+    .try_into_filter()
+    .map_err(|e| wasm_error!(WasmErrorInner::Guest(e.to_string())))?;
+  let anchor_links = get_links(
+    LinkQuery::new(original_action_hash.clone(), anchors_filter),
+    GetStrategy::Network,
+  )?;
+
+  for anchor_link in anchor_links {
+    let index_filter = LinkTypes::GeoIndex
+      .try_into_filter()
+      .map_err(|e| wasm_error!(WasmErrorInner::Guest(e.to_string())))?;
+    let index_links = get_links(
+      LinkQuery::new(anchor_link.target.clone(), index_filter),
+      GetStrategy::Network,
+    )?;
+
+    for index_link in index_links {
+      if index_link.target.clone().into_action_hash() == Some(original_action_hash.clone()) {
+        delete_link(index_link.create_link_hash, GetOptions::default())?;
+      }
+    }
+
+    delete_link(anchor_link.create_link_hash, GetOptions::default())?;
+  }
+
+  Ok(())
+}
+
+/// Indexes the location of a request or offer under the geohash of every precision,
+/// replacing any previous location. The location is snapped to the grid of its privacy
+/// radius first. Does nothing when called by someone other than the listing's author.
+#[hdk_extern]
+pub fn index_entity_location(input: GeoIndexInput) -> ExternResult<()> {
+  check_locatable_entity(&input.entity)?;
+
+  let original_action_hash = input.original_action_hash.0;
+  if !is_entity_author(&original_action_hash)? {
+    return Ok(());
+  }
+  remove_entity_from_geo_index(original_action_hash.clone())?;
+
+  let Some(location) = input.location else {
+    return Ok(());
+  };
+  let location = location.snapped();
+  location.validate().map_err(CommonError::InvalidData)?;
+  let tag = location.to_link_tag().map_err(CommonError::Serialize)?;
+
+  for geohash in location.geohashes() {
+    let anchor_hash = Path::from(geo_anchor_path(&input.entity, &geohash)).path_entry_hash()?;
+
+    create_link(
+      anchor_hash.clone(),
+      original_action_hash.clone(),
+      LinkTypes::GeoIndex,
+      tag.clone(),
+    )?;
+    create_link(
+      original_action_hash.clone(),
+      anchor_hash,
+      LinkTypes::EntityGeoAnchors,
+      tag.clone(),
+    )?;
+  }
+
+  Ok(())
+}
+
+#[derive(Serialize, Deserialize, Debug)]
+pub struct NearbyListingsInput {
+  pub point: GeoPoint,
+  pub radius_km: f64,
+  /// Entity kinds to search; requests and offers are searched when empty
+  #[serde(default)]
+  pub kinds: Vec<String>,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct NearbyListing {
+  pub original_action_hash: ActionHash,
+  pub entity: String,
+  pub location: GeoLocation,
+  /// Distance from the search point to the listing's published point
+  pub distance_km: f64,
+}
+
+/// Whether the latest version of a listing is active or in progress
+fn is_listed(entity: &str, original_action_hash: &ActionHash) -> ExternResult<bool> {
+  let listing = if entity == "request" {
+    get_latest_request(original_action_hash.clone())?
+  } else {
+    get_latest_offer(original_action_hash.clone())?
+  };
+  Ok(matches!(
+    listing.status,
+    ListingStatus::Active | ListingStatus::InProgress
+  ))
+}
+
+/// Get the active and in-progress listings that may be within `radius_km` of a point,
+/// closest first.
+///
+/// A listing is included when its privacy circle overlaps the search circle.
+#[hdk_extern]
+pub fn get_listings_near(input: NearbyListingsInput) -> ExternResult<Vec<NearbyListing>> {
+  input.point.validate().map_err(CommonError::InvalidData)?;
+  if !input.radius_km.is_finite()
+    || input.radius_km <= 0.0
+    || input.radius_km > MAX_SEARCH_RADIUS_KM
+  {
+    return Err(
+      CommonError::InvalidData(format!(
+        "The radius must be greater than 0 and at most {} km",
+        MAX_SEARCH_RADIUS_KM
+      ))
+      .into(),
+    );
+  }
+
+  let kinds: Vec<String> = if input.kinds.is_empty() {
+    LOCATABLE_ENTITIES
+      .iter()
+      .map(|kind| kind.to_string())
+      .collect()
+  } else {
+    input.kinds
+  };
+  for kind in &kinds {
+    check_locatable_entity(kind)?;
+  }
+
+  let mut listings: Vec<NearbyListing> = Vec::new();
+  for kind in &kinds {
+    // A listing is indexed at its published point, up to its privacy radius away from
+    // the search circle
+    for geohash in covering_geohashes(&input.point, input.radius_km + MAX_PRIVACY_RADIUS_KM) {
+      let anchor_hash = Path::from(geo_anchor_path(kind, &geohash)).path_entry_hash()?;
+      let link_type_filter = LinkTypes::GeoIndex
+        .try_into_filter()
+        .map_err(|e| wasm_error!(WasmErrorInner::Guest(e.to_string())))?;
+      let links = get_links(
+        LinkQuery::new(anchor_hash, link_type_filter),
+        GetStrategy::Network,
+      )?;
+
+      for link in links {
+        let (Some(original_action_hash), Ok(location)) = (
+          link.target.into_action_hash(),
+          GeoLocation::from_link_tag(&link.tag),
+        ) else {
+          continue;
+        };
+        if listings
+          .iter()
+          .any(|listing| listing.original_action_hash == original_action_hash)
+        {
+          continue;
+        }
+
+        let distance_km = input.point.distance_km(&location.point);
+        if distance_km - location.privacy_radius_km <= input.radius_km
+          && is_listed(kind, &original_action_hash)?
+        {
+          listings.push(NearbyListing {
+            original_action_hash,
+            entity: kind.clone(),
+            location,
+            distance_km,
+          });
+        }
+      }
+    }
+  }
+
+  listings.sort_by(|a, b| a.distance_km.total_cmp(&b.distance_km));
+
+  Ok(listings)
+}
@@ -0,0 +1,218 @@
+use hdk::prelude::*;
+use search_integrity::*;
+use utils::{
+  errors::CommonError, DateRange, InteractionType, ListingStatus, TimePreference, TimeZone,
+};
+
+use crate::external_calls::{
+  get_latest_offer, get_latest_request, get_offers_for_medium_of_exchange,
+  get_offers_for_service_type, get_requests_for_medium_of_exchange, get_requests_for_service_type,
+};
+
+/// The fields of a `Request` or `Offer` entry that saved searches filter on.
+/// Unknown fields are ignored when deserializing.
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct ListingFields {
+  pub time_preference: TimePreference,
+  #[serde(default)]
+  pub time_zone: Option<TimeZone>,
+  pub interaction_type: InteractionType,
+  #[serde(default)]
+  pub date_range: Option<DateRange>,
+  #[serde(default)]
+  pub status: ListingStatus,
+}
+
+/// The listings found by a saved search
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct SavedSearchResults {
+  /// The original action hashes of every listing found
+  pub result_hashes: Vec<ActionHash>,
+  /// The results the previous run of the search did not find
+  pub new_result_hashes: Vec<ActionHash>,
+}
+
+fn entry_from_record<T>(record: &Record, entry_name: &str) -> ExternResult<T>
+where
+  T: TryFrom<SerializedBytes, Error = SerializedBytesError>,
+{
+  Ok(
+    record
+      .entry()
+      .to_app_option()
+      .map_err(CommonError::Serialize)?
+      .ok_or(CommonError::EntryNotFound(format!(
+        "Could not deserialize {} entry",
+        entry_name
+      )))?,
+  )
+}
+
+#[hdk_extern]
+pub fn create_saved_search(saved_search: SavedSearch) -> ExternResult<Record> {
+  let saved_search_hash = create_entry(&EntryTypes::SavedSearch(saved_search))?;
+  get(saved_search_hash, GetOptions::default())?.ok_or(
+    CommonError::EntryNotFound("Could not find the newly created saved search".to_string()).into(),
+  )
+}
+
+/// Get the saved searches of the calling agent that were not deleted
+#[hdk_extern]
+pub fn get_my_saved_searches(_: ()) -> ExternResult<Vec<Record>> {
+  let deleted_hashes: Vec<ActionHash> =
+    query(ChainQueryFilter::new().action_type(ActionType::Delete))?
+      .into_iter()
+      .filter_map(|record| match record.action() {
+        Action::Delete(delete) => Some(delete.deletes_address.clone()),
+        _ => None,
+      })
+      .collect();
+
+  Ok(
+    query(
+      ChainQueryFilter::new()
+        .entry_type(UnitEntryTypes::SavedSearch.try_into()?)
+        .include_entries(true),
+    )?
+    .into_iter()
+    .filter(|record| !deleted_hashes.contains(record.action_address()))
+    .collect(),
+  )
+}
+
+#[hdk_extern]
+pub fn delete_saved_search(saved_search_hash: ActionHash) -> ExternResult<ActionHash> {
+  delete_entry(saved_search_hash)
+}
+
+/// Collects the original action hashes of the listings that have one of the search's
+/// service types and one of its mediums of exchange. An empty list of either does not
+/// restrict the results.
+fn find_candidate_hashes(saved_search: &SavedSearch) -> ExternResult<Vec<ActionHash>> {
+  let is_request = saved_search.entity == "request";
+
+  let mut by_service_type: Vec<ActionHash> = Vec::new();
+  for service_type_hash in &saved_search.service_type_hashes {
+    let records = if is_request {
+      get_requests_for_service_type(service_type_hash.clone())?
+    } else {
+      get_offers_for_service_type(service_type_hash.clone())?
+    };
+    by_service_type.extend(records.iter().map(|record| record.action_address().clone()));
+  }
+
+  let mut by_medium_of_exchange: Vec<ActionHash> = Vec::new();
+  for medium_of_exchange_hash in &saved_search.medium_of_exchange_hashes {
+    let records = if is_request {
+      get_requests_for_medium_of_exchange(medium_of_exchange_hash.clone())?
+    } else {
+      get_offers_for_medium_of_exchange(medium_of_exchange_hash.clone())?
+    };
+    by_medium_of_exchange.extend(records.iter().map(|record| record.action_address().clone()));
+  }
+
+  let mut candidate_hashes: Vec<ActionHash> = Vec::new();
+  let hashes = if saved_search.service_type_hashes.is_empty() {
+    &by_medium_of_exchange
+  } else {
+    &by_service_type
+  };
+  for hash in hashes {
+    let has_medium_of_exchange =
+      saved_search.medium_of_exchange_hashes.is_empty() || by_medium_of_exchange.contains(hash);
+    if has_medium_of_exchange && !candidate_hashes.contains(hash) {
+      candidate_hashes.push(hash.clone());
+    }
+  }
+
+  Ok(candidate_hashes)
+}
+
+/// Whether an active listing meets the remaining criteria of a saved search
+fn listing_matches(saved_search: &SavedSearch, listing: &ListingFields) -> bool {
+  if listing.status != ListingStatus::Active {
+    return false;
+  }
+
+  let interaction_type_matches = saved_search
+    .interaction_type
+    .as_ref()
+    .is_none_or(|interaction_type| *interaction_type == listing.interaction_type);
+
+  let time_preference_matches = match &saved_search.time_preference {
+    None | Some(TimePreference::NoPreference) => true,
+    Some(time_preference) => {
+      listing.time_preference == TimePreference::NoPreference
+        || *time_preference == listing.time_preference
+    }
+  };
+
+  let time_zone_matches = saved_search
+    .time_zone
+    .as_ref()
+    .is_none_or(|time_zone| listing.time_zone.as_ref() == Some(time_zone));
+
+  let date_range_matches = match (&saved_search.date_range, &listing.date_range) {
+    (Some(window), Some(date_range)) => window.overlaps(date_range),
+    _ => true,
+  };
+
+  interaction_type_matches && time_preference_matches && time_zone_matches && date_range_matches
+}
+
+/// Runs a saved search against the service type and medium of exchange indexes, and
+/// records its results so that the next run can tell which listings are new
+#[hdk_extern]
+pub fn run_saved_search(saved_search_hash: ActionHash) -> ExternResult<SavedSearchResults> {
+  let saved_search_record = get_my_saved_searches(())?
+    .into_iter()
+    .find(|record| *record.action_address() == saved_search_hash)
+    .ok_or(CommonError::EntryNotFound(
+      "Could not find the saved search".to_string(),
+    ))?;
+  let saved_search: SavedSearch = entry_from_record(&saved_search_record, "saved search")?;
+
+  let mut result_hashes = Vec::new();
+  for candidate_hash in find_candidate_hashes(&saved_search)? {
+    let listing = if saved_search.entity == "request" {
+      get_latest_request(candidate_hash.clone())?
+    } else {
+      get_latest_offer(candidate_hash.clone())?
+    };
+    if listing_matches(&saved_search, &listing) {
+      result_hashes.push(candidate_hash);
+    }
+  }
+
+  // Runs are committed in chain order, so the last one for this search is the previous run
+  let mut previous_result_hashes: Option<Vec<ActionHash>> = None;
+  for record in query(
+    ChainQueryFilter::new()
+      .entry_type(UnitEntryTypes::SavedSearchRun.try_into()?)
+      .include_entries(true),
+  )? {
+    let run: SavedSearchRun = entry_from_record(&record, "saved search run")?;
+    if run.saved_search_hash == saved_search_hash {
+      previous_result_hashes = Some(run.result_hashes);
+    }
+  }
+  let new_result_hashes = result_hashes
+    .iter()
+    .filter(|hash| {
+      previous_result_hashes
+        .as_ref()
+        .is_none_or(|previous| !previous.contains(hash))
+    })
+    .cloned()
+    .collect();
+
+  create_entry(&EntryTypes::SavedSearchRun(SavedSearchRun {
+    saved_search_hash,
+    result_hashes: result_hashes.clone(),
+  }))?;
+
+  Ok(SavedSearchResults {
+    result_hashes,
+    new_result_hashes,
+  })
+}
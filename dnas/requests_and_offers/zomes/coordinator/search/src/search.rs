@@ -0,0 +1,226 @@
+use std::collections::HashMap;
+
+use hdk::prelude::*;
+use search_integrity::*;
+use utils::{errors::CommonError, SearchIndexInput};
+
+use crate::geo::remove_entity_from_geo_index;
+use crate::tokens::{anchor_path, token_prefix, token_score, tokenize, tokenize_fields};
+
+/// Kinds of entities that can be indexed
+pub const SEARCHABLE_ENTITIES: [&str; 4] = ["request", "offer", "user", "organization"];
+
+const DEFAULT_SEARCH_LIMIT: usize = 20;
+const MAX_SEARCH_LIMIT: usize = 100;
+
+fn check_searchable_entity(entity: &str) -> ExternResult<()> {
+  if SEARCHABLE_ENTITIES.contains(&entity) {
+    Ok(())
+  } else {
+    Err(CommonError::InvalidData("Must be request, offer, user or organization".to_string()).into())
+  }
+}
+
+fn token_from_tag(tag: &LinkTag) -> Option<String> {
+  String::from_utf8(tag.0.clone()).ok()
+}
+
+/// Removes every text index link pointing to an entity, using its reverse anchor links
+fn remove_entity_from_text_index(original_action_hash: ActionHash) -> ExternResult<()> {
+  let anchors_filter = LinkTypes::EntitySearchAnchors
+    .try_into_filter()
+    .map_err(|e| wasm_error!(WasmErrorInner::Guest(e.to_string())))?;
+  let anchor_links = get_links(
+    LinkQuery::new(original_action_hash.clone(), anchors_filter),
+    GetStrategy::Network,
+  )?;
+
+  let mut cleared_anchors: Vec<AnyLinkableHash> = Vec::new();
+  for anchor_link in anchor_links {
+    let anchor = anchor_link.target.clone();
+
+    if !cleared_anchors.contains(&anchor) {
+      let index_filter = LinkTypes::SearchIndex
+        .try_into_filter()
+        .map_err(|e| wasm_error!(WasmErrorInner::Guest(e.to_string())))?;
+      let index_links = get_links(
+        LinkQuery::new(anchor.clone(), index_filter),
+        GetStrategy::Network,
+      )?;
+
+      for index_link in index_links {
+        if index_link.target.clone().into_action_hash() == Some(original_action_hash.clone()) {
+          delete_link(index_link.create_link_hash, GetOptions::default())?;
+        }
+      }
+      cleared_anchors.push(anchor);
+    }
+
+    delete_link(anchor_link.create_link_hash, GetOptions::default())?;
+  }
+
+  Ok(())
+}
+
+/// Whether the calling agent is the author of an entity. Only authors can index their
+/// entities, so a listing updated by an administrator or coordinator keeps the index
+/// entries of its author.
+pub fn is_entity_author(original_action_hash: &ActionHash) -> ExternResult<bool> {
+  let record = get(original_action_hash.clone(), GetOptions::default())?
+    .ok_or(CommonError::EntryNotFound("entity".to_string()))?;
+  Ok(*record.action().author() == agent_info()?.agent_initial_pubkey)
+}
+
+/// Removes an entity from the text and geo indexes. Does nothing when called by someone
+/// other than the entity's author.
+#[hdk_extern]
+pub fn remove_entity_from_index(original_action_hash: ActionHash) -> ExternResult<()> {
+  if !is_entity_author(&original_action_hash)? {
+    return Ok(());
+  }
+
+  remove_entity_from_text_index(original_action_hash.clone())?;
+  remove_entity_from_geo_index(original_action_hash)
+}
+
+/// Indexes the given text fields of an entity, replacing any previous index entries. Does
+/// nothing when called by someone other than the entity's author.
+#[hdk_extern]
+pub fn index_entity(input: SearchIndexInput) -> ExternResult<()> {
+  check_searchable_entity(&input.entity)?;
+
+  let original_action_hash = input.original_action_hash.0;
+  if !is_entity_author(&original_action_hash)? {
+    return Ok(());
+  }
+  remove_entity_from_text_index(original_action_hash.clone())?;
+
+  for token in tokenize_fields(&input.fields) {
+    let anchor_hash =
+      Path::from(anchor_path(&input.entity, &token_prefix(&token))).path_entry_hash()?;
+
+    create_link(
+      anchor_hash.clone(),
+      original_action_hash.clone(),
+      LinkTypes::SearchIndex,
+      LinkTag::new(token.as_bytes().to_vec()),
+    )?;
+    create_link(
+      original_action_hash.clone(),
+      anchor_hash,
+      LinkTypes::EntitySearchAnchors,
+      LinkTag::new(token.into_bytes()),
+    )?;
+  }
+
+  Ok(())
+}
+
+#[derive(Serialize, Deserialize, Debug)]
+pub struct SearchInput {
+  pub query: String,
+  /// Entity kinds to search; all kinds are searched when empty
+  #[serde(default)]
+  pub kinds: Vec<String>,
+  pub limit: Option<usize>,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct SearchResult {
+  pub original_action_hash: ActionHash,
+  pub entity: String,
+  pub score: u32,
+}
+
+/// Scores every `entity` matching all of the query tokens
+fn search_entity(entity: &str, query_tokens: &[String]) -> ExternResult<Vec<SearchResult>> {
+  let mut scores: HashMap<ActionHash, (u32, usize)> = HashMap::new();
+
+  for query_token in query_tokens {
+    let anchor_hash =
+      Path::from(anchor_path(entity, &token_prefix(query_token))).path_entry_hash()?;
+    let link_type_filter = LinkTypes::SearchIndex
+      .try_into_filter()
+      .map_err(|e| wasm_error!(WasmErrorInner::Guest(e.to_string())))?;
+    let links = get_links(
+      LinkQuery::new(anchor_hash, link_type_filter),
+      GetStrategy::Network,
+    )?;
+
+    // Best score of this query token for each entity
+    let mut token_scores: HashMap<ActionHash, u32> = HashMap::new();
+    for link in links {
+      let (Some(hash), Some(indexed_token)) =
+        (link.target.into_action_hash(), token_from_tag(&link.tag))
+      else {
+        continue;
+      };
+      let score = token_score(&indexed_token, query_token);
+      if score > 0 {
+        let best = token_scores.entry(hash).or_insert(0);
+        *best = (*best).max(score);
+      }
+    }
+
+    for (hash, score) in token_scores {
+      let total = scores.entry(hash).or_insert((0, 0));
+      total.0 += score;
+      total.1 += 1;
+    }
+  }
+
+  Ok(
+    scores
+      .into_iter()
+      .filter(|(_, (_, matched_tokens))| *matched_tokens == query_tokens.len())
+      .map(|(original_action_hash, (score, _))| SearchResult {
+        original_action_hash,
+        entity: entity.to_string(),
+        score,
+      })
+      .collect(),
+  )
+}
+
+/// Searches the index for entities matching every word of the query.
+///
+/// Words are matched by prefix, exact matches ranking higher. Results are sorted
+/// from best to worst and truncated to `limit` (20 by default, at most 100).
+#[hdk_extern]
+pub fn search(input: SearchInput) -> ExternResult<Vec<SearchResult>> {
+  let kinds: Vec<String> = if input.kinds.is_empty() {
+    SEARCHABLE_ENTITIES
+      .iter()
+      .map(|kind| kind.to_string())
+      .collect()
+  } else {
+    input.kinds
+  };
+  for kind in &kinds {
+    check_searchable_entity(kind)?;
+  }
+
+  let query_tokens = tokenize(&input.query);
+  if query_tokens.is_empty() {
+    return Ok(vec![]);
+  }
+
+  let mut results: Vec<SearchResult> = Vec::new();
+  for kind in &kinds {
+    results.extend(search_entity(kind, &query_tokens)?);
+  }
+
+  results.sort_by(|a, b| {
+    b.score
+      .cmp(&a.score)
+      .then_with(|| a.original_action_hash.cmp(&b.original_action_hash))
+  });
+  results.truncate(
+    input
+      .limit
+      .unwrap_or(DEFAULT_SEARCH_LIMIT)
+      .min(MAX_SEARCH_LIMIT),
+  );
+
+  Ok(results)
+}
@@ -0,0 +1,123 @@
+use search_integrity::{MAX_SEARCH_TOKEN_LENGTH, SEARCH_PREFIX_LENGTH};
+
+/// Upper bound on the number of distinct tokens indexed for a single entity,
+/// which bounds the number of links written per create or update
+pub const MAX_TOKENS_PER_ENTITY: usize = 200;
+
+/// Splits free text into unique, lowercase alphanumeric tokens, in order of first
+/// appearance. Tokens shorter than [`SEARCH_PREFIX_LENGTH`] are dropped and longer
+/// ones are truncated to [`MAX_SEARCH_TOKEN_LENGTH`] characters.
+pub fn tokenize(text: &str) -> Vec<String> {
+  let mut tokens: Vec<String> = Vec::new();
+
+  for word in text.split(|c: char| !c.is_alphanumeric()) {
+    let token: String = word
+      .chars()
+      .flat_map(char::to_lowercase)
+      .filter(|c| c.is_alphanumeric())
+      .take(MAX_SEARCH_TOKEN_LENGTH)
+      .collect();
+
+    if token.chars().count() < SEARCH_PREFIX_LENGTH || tokens.contains(&token) {
+      continue;
+    }
+    tokens.push(token);
+
+    if tokens.len() == MAX_TOKENS_PER_ENTITY {
+      break;
+    }
+  }
+
+  tokens
+}
+
+/// Tokenizes every field of an entity into a single list of unique tokens
+pub fn tokenize_fields(fields: &[String]) -> Vec<String> {
+  let mut tokens: Vec<String> = Vec::new();
+  for field in fields {
+    for token in tokenize(field) {
+      if tokens.len() == MAX_TOKENS_PER_ENTITY {
+        return tokens;
+      }
+      if !tokens.contains(&token) {
+        tokens.push(token);
+      }
+    }
+  }
+  tokens
+}
+
+/// The anchor prefix a token is indexed under
+pub fn token_prefix(token: &str) -> String {
+  token.chars().take(SEARCH_PREFIX_LENGTH).collect()
+}
+
+/// Path of the anchor holding every `entity` token starting with `prefix`
+pub fn anchor_path(entity: &str, prefix: &str) -> String {
+  format!("search.{}.{}", entity, prefix)
+}
+
+/// Scores an indexed token against a query token: an exact match is worth more
+/// than a prefix match, anything else does not match.
+pub fn token_score(indexed_token: &str, query_token: &str) -> u32 {
+  if indexed_token == query_token {
+    2
+  } else if indexed_token.starts_with(query_token) {
+    1
+  } else {
+    0
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  #[test]
+  fn test_tokenize_normalizes_and_deduplicates() {
+    let tokens = tokenize("Help with Gardening, gardening & DIY-repairs!");
+    assert_eq!(tokens, vec!["help", "with", "gardening", "diy", "repairs"]);
+  }
+
+  #[test]
+  fn test_tokenize_drops_short_tokens() {
+    assert_eq!(tokenize("a to be or web"), vec!["web"]);
+  }
+
+  #[test]
+  fn test_tokenize_handles_unicode() {
+    assert_eq!(
+      tokenize("Éducation Français"),
+      vec!["éducation", "français"]
+    );
+  }
+
+  #[test]
+  fn test_tokenize_truncates_long_tokens() {
+    let long_word = "a".repeat(MAX_SEARCH_TOKEN_LENGTH + 10);
+    let tokens = tokenize(&long_word);
+    assert_eq!(tokens[0].chars().count(), MAX_SEARCH_TOKEN_LENGTH);
+  }
+
+  #[test]
+  fn test_tokenize_fields_merges_fields() {
+    let fields = vec!["Web design".to_string(), "Design and web apps".to_string()];
+    assert_eq!(
+      tokenize_fields(&fields),
+      vec!["web", "design", "and", "apps"]
+    );
+  }
+
+  #[test]
+  fn test_token_prefix_and_anchor() {
+    assert_eq!(token_prefix("gardening"), "gar");
+    assert_eq!(anchor_path("request", "gar"), "search.request.gar");
+  }
+
+  #[test]
+  fn test_token_score() {
+    assert_eq!(token_score("garden", "garden"), 2);
+    assert_eq!(token_score("gardening", "garden"), 1);
+    assert_eq!(token_score("gar", "garden"), 0);
+  }
+}
@@ -0,0 +1,313 @@
+use hdk::prelude::*;
+use service_types_integrity::{LinkTypes, ServiceType, MAX_SERVICE_TYPE_DEPTH};
+use utils::errors::CommonError;
+
+use crate::service_type::{
+  get_latest_service_type_record, get_offers_for_service_type, get_requests_for_service_type,
+};
+use crate::translation::administrator_proof_tag;
+
+/// A service type with the service types under it
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct ServiceTypeNode {
+  /// The original action hash of the service type
+  pub service_type_hash: ActionHash,
+  /// The latest record of the service type
+  pub record: Record,
+  pub children: Vec<ServiceTypeNode>,
+}
+
+fn get_latest_service_type(original_action_hash: ActionHash) -> ExternResult<ServiceType> {
+  get_latest_service_type_record(original_action_hash)?
+    .ok_or(CommonError::EntryNotFound(
+      "Could not find the service type".to_string(),
+    ))?
+    .entry()
+    .to_app_option()
+    .map_err(CommonError::Serialize)?
+    .ok_or(
+      CommonError::EntryNotFound("Could not deserialize service type entry".to_string()).into(),
+    )
+}
+
+fn get_child_links(original_action_hash: ActionHash) -> ExternResult<Vec<Link>> {
+  let link_type_filter = LinkTypes::ServiceTypeChildren
+    .try_into_filter()
+    .map_err(|e| wasm_error!(WasmErrorInner::Guest(e.to_string())))?;
+  get_links(
+    LinkQuery::new(original_action_hash, link_type_filter),
+    GetStrategy::Network,
+  )
+}
+
+/// Returns the original action hashes of the service types directly under a service type
+pub(crate) fn get_child_hashes(original_action_hash: ActionHash) -> ExternResult<Vec<ActionHash>> {
+  let mut child_hashes: Vec<ActionHash> = Vec::new();
+  for link in get_child_links(original_action_hash)? {
+    if let Some(child_hash) = link.target.into_action_hash() {
+      if !child_hashes.contains(&child_hash) {
+        child_hashes.push(child_hash);
+      }
+    }
+  }
+  Ok(child_hashes)
+}
+
+/// Returns the original action hashes of a service type and of every service type under it
+fn get_descendant_hashes(original_action_hash: ActionHash) -> ExternResult<Vec<ActionHash>> {
+  let mut hashes = vec![original_action_hash];
+  let mut index = 0;
+  while index < hashes.len() {
+    for child_hash in get_child_hashes(hashes[index].clone())? {
+      if !hashes.contains(&child_hash) {
+        hashes.push(child_hash);
+      }
+    }
+    index += 1;
+  }
+  Ok(hashes)
+}
+
+/// Returns the service type a service type is under. Parents cannot change, but a service
+/// type under a merged one is under the service type it was merged into.
+pub(crate) fn get_parent_hash(service_type: &ServiceType) -> ExternResult<Option<ActionHash>> {
+  let mut parent_hash = service_type.parent_hash.clone();
+  for _ in 0..MAX_SERVICE_TYPE_DEPTH {
+    let Some(hash) = parent_hash else {
+      return Ok(None);
+    };
+    match get_latest_service_type(hash.clone())?.merged_into {
+      Some(merged_into) => parent_hash = Some(merged_into),
+      None => return Ok(Some(hash)),
+    }
+  }
+
+  Err(CommonError::InvalidData("Too many merged service types to follow".to_string()).into())
+}
+
+/// Checks, against the latest versions of the service types, that `parent_hash` can
+/// become the parent of a service type, or of the children of one being merged
+pub fn check_service_type_parent(
+  original_action_hash: Option<&ActionHash>,
+  parent_hash: &ActionHash,
+) -> ExternResult<()> {
+  if get_latest_service_type(parent_hash.clone())?
+    .merged_into
+    .is_some()
+  {
+    return Err(
+      CommonError::InvalidData(
+        "A service type cannot be placed under a merged service type".to_string(),
+      )
+      .into(),
+    );
+  }
+
+  let mut ancestor_hash = Some(parent_hash.clone());
+  let mut depth = 1;
+  while let Some(hash) = ancestor_hash {
+    if Some(&hash) == original_action_hash {
+      return Err(
+        CommonError::InvalidData(
+          "A service type cannot be placed under itself or one of its descendants".to_string(),
+        )
+        .into(),
+      );
+    }
+    depth += 1;
+    if depth > MAX_SERVICE_TYPE_DEPTH {
+      return Err(
+        CommonError::InvalidData(format!(
+          "The service type hierarchy cannot be deeper than {} levels",
+          MAX_SERVICE_TYPE_DEPTH
+        ))
+        .into(),
+      );
+    }
+    ancestor_hash = get_parent_hash(&get_latest_service_type(hash)?)?;
+  }
+
+  Ok(())
+}
+
+/// Moves a service type from under `previous_parent_hash` to under `parent_hash`
+pub fn update_service_type_parent_links(
+  original_action_hash: &ActionHash,
+  previous_parent_hash: Option<&ActionHash>,
+  parent_hash: Option<&ActionHash>,
+) -> ExternResult<()> {
+  if previous_parent_hash == parent_hash {
+    return Ok(());
+  }
+
+  if let Some(previous_parent_hash) = previous_parent_hash {
+    for link in get_child_links(previous_parent_hash.clone())? {
+      if link.target.clone().into_action_hash().as_ref() == Some(original_action_hash) {
+        delete_link(link.create_link_hash, GetOptions::default())?;
+      }
+    }
+  }
+
+  if let Some(parent_hash) = parent_hash {
+    create_link(
+      parent_hash.clone(),
+      original_action_hash.clone(),
+      LinkTypes::ServiceTypeChildren,
+      (),
+    )?;
+  }
+
+  Ok(())
+}
+
+/// Moves the service types directly under a merged service type under the one it was
+/// merged into. Their parent stays the merged service type, so the new links carry the
+/// calling administrator's proof.
+pub fn move_service_type_children(
+  source_hash: &ActionHash,
+  target_hash: &ActionHash,
+) -> ExternResult<()> {
+  let child_links = get_child_links(source_hash.clone())?;
+  if child_links.is_empty() {
+    return Ok(());
+  }
+  check_service_type_parent(Some(source_hash), target_hash)?;
+
+  let tag = administrator_proof_tag()?;
+  for link in child_links {
+    let Some(child_hash) = link.target.into_action_hash() else {
+      continue;
+    };
+    delete_link(link.create_link_hash, GetOptions::default())?;
+    create_link(
+      target_hash.clone(),
+      child_hash,
+      LinkTypes::ServiceTypeChildren,
+      tag.clone(),
+    )?;
+  }
+
+  Ok(())
+}
+
+/// Checks that a service type can be deleted: service types with children cannot
+pub fn check_service_type_has_no_children(original_action_hash: &ActionHash) -> ExternResult<()> {
+  if !get_child_hashes(original_action_hash.clone())?.is_empty() {
+    return Err(
+      CommonError::InvalidData(
+        "A service type with service types under it cannot be deleted".to_string(),
+      )
+      .into(),
+    );
+  }
+  Ok(())
+}
+
+/// Get the latest records of the service types directly under a service type
+#[hdk_extern]
+pub fn get_service_type_children(original_action_hash: ActionHash) -> ExternResult<Vec<Record>> {
+  let mut records = Vec::new();
+  for child_hash in get_child_hashes(original_action_hash)? {
+    if let Some(record) = get_latest_service_type_record(child_hash)? {
+      records.push(record);
+    }
+  }
+  Ok(records)
+}
+
+fn build_service_type_node(
+  original_action_hash: ActionHash,
+  depth: usize,
+) -> ExternResult<Option<ServiceTypeNode>> {
+  let Some(record) = get_latest_service_type_record(original_action_hash.clone())? else {
+    return Ok(None);
+  };
+
+  let mut children = Vec::new();
+  if depth < MAX_SERVICE_TYPE_DEPTH {
+    for child_hash in get_child_hashes(original_action_hash.clone())? {
+      if let Some(child) = build_service_type_node(child_hash, depth + 1)? {
+        children.push(child);
+      }
+    }
+  }
+
+  Ok(Some(ServiceTypeNode {
+    service_type_hash: original_action_hash,
+    record,
+    children,
+  }))
+}
+
+/// Get a service type with every service type under it
+#[hdk_extern]
+pub fn get_service_type_subtree(
+  original_action_hash: ActionHash,
+) -> ExternResult<Option<ServiceTypeNode>> {
+  build_service_type_node(original_action_hash, 1)
+}
+
+/// Get the latest records of the service types above a service type, from its parent up
+/// to the root
+#[hdk_extern]
+pub fn get_service_type_ancestors(original_action_hash: ActionHash) -> ExternResult<Vec<Record>> {
+  let mut ancestors: Vec<Record> = Vec::new();
+  let mut visited = vec![original_action_hash.clone()];
+  let mut ancestor_hash = get_parent_hash(&get_latest_service_type(original_action_hash)?)?;
+
+  while let Some(hash) = ancestor_hash {
+    if visited.contains(&hash) || ancestors.len() >= MAX_SERVICE_TYPE_DEPTH {
+      break;
+    }
+    let Some(record) = get_latest_service_type_record(hash.clone())? else {
+      break;
+    };
+    ancestor_hash = match record
+      .entry()
+      .to_app_option::<ServiceType>()
+      .map_err(CommonError::Serialize)?
+    {
+      Some(service_type) => get_parent_hash(&service_type)?,
+      None => None,
+    };
+    visited.push(hash);
+    ancestors.push(record);
+  }
+
+  Ok(ancestors)
+}
+
+/// Collects the listings linked to a service type or to any service type under it
+fn get_listings_in_tree(
+  original_action_hash: ActionHash,
+  get_listings: fn(ActionHash) -> ExternResult<Vec<Record>>,
+) -> ExternResult<Vec<Record>> {
+  let mut listings: Vec<Record> = Vec::new();
+  for service_type_hash in get_descendant_hashes(original_action_hash)? {
+    for listing in get_listings(service_type_hash)? {
+      if !listings
+        .iter()
+        .any(|existing| existing.action_address() == listing.action_address())
+      {
+        listings.push(listing);
+      }
+    }
+  }
+  Ok(listings)
+}
+
+/// Get the requests linked to a service type or to any service type under it
+#[hdk_extern]
+pub fn get_requests_for_service_type_tree(
+  original_action_hash: ActionHash,
+) -> ExternResult<Vec<Record>> {
+  get_listings_in_tree(original_action_hash, get_requests_for_service_type)
+}
+
+/// Get the offers linked to a service type or to any service type under it
+#[hdk_extern]
+pub fn get_offers_for_service_type_tree(
+  original_action_hash: ActionHash,
+) -> ExternResult<Vec<Record>> {
+  get_listings_in_tree(original_action_hash, get_offers_for_service_type)
+}
@@ -1,5 +1,9 @@
 mod external_calls;
+pub mod hierarchy;
+pub mod merge;
 pub mod service_type;
+pub mod tag;
+pub mod translation;
 
 use hdk::prelude::*;
 use service_types_integrity::*;
@@ -0,0 +1,165 @@
+use hdk::prelude::*;
+use service_types_integrity::{LinkTypes, MergeAuthorization, ServiceType, MAX_SERVICE_TYPE_DEPTH};
+use utils::{
+  errors::{AdministrationError, CommonError},
+  OriginalActionHash, ServiceTypeLinkInput,
+};
+
+use crate::external_calls::check_if_agent_is_administrator;
+use crate::hierarchy::{
+  get_parent_hash, move_service_type_children, update_service_type_parent_links,
+};
+use crate::service_type::{
+  get_latest_service_type_record, is_service_type_approved, link_to_service_type,
+  remove_service_type_from_status_paths, unlink_from_service_type,
+};
+use crate::translation::administrator_proof;
+
+#[derive(Serialize, Deserialize, Debug)]
+pub struct MergeServiceTypesInput {
+  /// The original action hash of the duplicate service type
+  pub source_hash: ActionHash,
+  /// The original action hash of the service type to keep
+  pub target_hash: ActionHash,
+}
+
+/// The latest record of a service type with its entry
+fn get_latest_service_type_with_record(
+  original_action_hash: ActionHash,
+) -> ExternResult<(Record, ServiceType)> {
+  let record = get_latest_service_type_record(original_action_hash)?.ok_or(
+    CommonError::EntryNotFound("Could not find the service type".to_string()),
+  )?;
+  let service_type = record
+    .entry()
+    .to_app_option()
+    .map_err(CommonError::Serialize)?
+    .ok_or(CommonError::EntryNotFound(
+      "Could not deserialize service type entry".to_string(),
+    ))?;
+  Ok((record, service_type))
+}
+
+/// Returns the targets of the links of the given type from a service type
+fn get_linked_hashes(
+  service_type_hash: ActionHash,
+  link_type: LinkTypes,
+) -> ExternResult<Vec<ActionHash>> {
+  let link_type_filter = link_type
+    .try_into_filter()
+    .map_err(|e| wasm_error!(WasmErrorInner::Guest(e.to_string())))?;
+  Ok(
+    get_links(
+      LinkQuery::new(service_type_hash, link_type_filter),
+      GetStrategy::Network,
+    )?
+    .into_iter()
+    .filter_map(|link| link.target.into_action_hash())
+    .collect(),
+  )
+}
+
+/// Merges a duplicate service type into another one (admin only).
+///
+/// Every request, offer and user linked to the source is relinked to the target, and the
+/// service types under the source are moved under the target. The source is then taken
+/// out of the status lists and marked as merged into the target, see
+/// `resolve_service_type`.
+#[hdk_extern]
+pub fn merge_service_types(input: MergeServiceTypesInput) -> ExternResult<Record> {
+  let is_admin = check_if_agent_is_administrator(agent_info()?.agent_initial_pubkey)?;
+  if !is_admin {
+    return Err(AdministrationError::Unauthorized.into());
+  }
+
+  if input.source_hash == input.target_hash {
+    return Err(
+      CommonError::InvalidData("A service type cannot be merged into itself".to_string()).into(),
+    );
+  }
+  let (source_record, source) = get_latest_service_type_with_record(input.source_hash.clone())?;
+  let (target_record, target) = get_latest_service_type_with_record(input.target_hash.clone())?;
+  if source.merged_into.is_some() || target.merged_into.is_some() {
+    return Err(
+      CommonError::InvalidData("Service types cannot be merged twice".to_string()).into(),
+    );
+  }
+  if !is_service_type_approved(input.target_hash.clone())? {
+    return Err(
+      CommonError::InvalidData("Service types can only be merged into an approved one".to_string())
+        .into(),
+    );
+  }
+
+  // Relink the listings and users
+  for (entity, link_type) in [
+    ("request", LinkTypes::ServiceTypeToRequest),
+    ("offer", LinkTypes::ServiceTypeToOffer),
+    ("user", LinkTypes::ServiceTypeToUser),
+  ] {
+    let target_linked = get_linked_hashes(input.target_hash.clone(), link_type)?;
+    for entity_hash in get_linked_hashes(input.source_hash.clone(), link_type)? {
+      unlink_from_service_type(ServiceTypeLinkInput {
+        service_type_hash: OriginalActionHash(input.source_hash.clone()),
+        action_hash: OriginalActionHash(entity_hash.clone()),
+        entity: entity.to_string(),
+      })?;
+      if !target_linked.contains(&entity_hash) {
+        link_to_service_type(ServiceTypeLinkInput {
+          service_type_hash: OriginalActionHash(input.target_hash.clone()),
+          action_hash: OriginalActionHash(entity_hash),
+          entity: entity.to_string(),
+        })?;
+      }
+    }
+  }
+
+  // Move the service types under the source under the target. Their parent stays the
+  // source, which now resolves to the target.
+  move_service_type_children(&input.source_hash, &input.target_hash)?;
+
+  // Mark the source as merged, out of the hierarchy and the status lists
+  remove_service_type_from_status_paths(input.source_hash.clone())?;
+  update_service_type_parent_links(&input.source_hash, get_parent_hash(&source)?.as_ref(), None)?;
+  let merged_source = ServiceType {
+    merged_into: Some(input.target_hash),
+    merge_authorization: Some(MergeAuthorization {
+      administrator_proof: administrator_proof()?,
+      target_version: target_record.action_address().clone(),
+    }),
+    ..source
+  };
+  let merged_action_hash = update_entry(source_record.action_address().clone(), &merged_source)?;
+  create_link(
+    input.source_hash,
+    merged_action_hash.clone(),
+    LinkTypes::ServiceTypeUpdates,
+    (),
+  )?;
+
+  get(merged_action_hash, GetOptions::default())?
+    .ok_or(CommonError::EntryNotFound("Could not find the merged service type".to_string()).into())
+}
+
+/// Get the latest record of a service type, following merges to the service type it was
+/// merged into
+#[hdk_extern]
+pub fn resolve_service_type(original_action_hash: ActionHash) -> ExternResult<Option<Record>> {
+  let mut service_type_hash = original_action_hash;
+  for _ in 0..MAX_SERVICE_TYPE_DEPTH {
+    let Some(record) = get_latest_service_type_record(service_type_hash)? else {
+      return Ok(None);
+    };
+    let merged_into = record
+      .entry()
+      .to_app_option::<ServiceType>()
+      .map_err(CommonError::Serialize)?
+      .and_then(|service_type| service_type.merged_into);
+    match merged_into {
+      Some(merged_into) => service_type_hash = merged_into,
+      None => return Ok(Some(record)),
+    }
+  }
+
+  Err(CommonError::InvalidData("Too many merged service types to follow".to_string()).into())
+}
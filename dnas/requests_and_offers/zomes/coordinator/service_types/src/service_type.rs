@@ -9,6 +9,15 @@ use utils::{
 use crate::external_calls::{
   check_if_agent_is_administrator, check_if_entity_is_accepted, get_agent_user,
 };
+use crate::hierarchy::{
+  check_service_type_has_no_children, check_service_type_parent, get_parent_hash,
+  update_service_type_parent_links,
+};
+use crate::tag::{link_service_type_tags, normalize_tags, unlink_service_type_tags};
+use crate::translation::{
+  get_approved_translations, index_service_type_names, service_type_terms,
+  update_service_type_name_index,
+};
 
 // Path anchor constants for service type status
 const PENDING_SERVICE_TYPES_PATH: &str = "service_types.status.pending";
@@ -35,8 +44,21 @@ pub fn create_service_type(input: ServiceTypeInput) -> ExternResult<Record> {
     return Err(AdministrationError::Unauthorized.into());
   }
 
+  if let Some(parent_hash) = &input.service_type.parent_hash {
+    check_service_type_parent(None, parent_hash)?;
+  }
+
   // Create the service type entry
-  let service_type_hash = create_entry(EntryTypes::ServiceType(input.service_type.clone()))?;
+  let mut service_type = input.service_type;
+  service_type.tags = normalize_tags(&service_type.tags);
+  let service_type_hash = create_entry(EntryTypes::ServiceType(service_type.clone()))?;
+  link_service_type_tags(&service_type_hash, &service_type.tags)?;
+  update_service_type_parent_links(&service_type_hash, None, service_type.parent_hash.as_ref())?;
+  update_service_type_name_index(
+    &service_type_hash,
+    &[],
+    &service_type_terms(&service_type.name, &[]),
+  )?;
 
   // Get the created record
   let record = get(service_type_hash.clone(), GetOptions::default())?.ok_or(
@@ -68,9 +90,9 @@ pub fn create_service_type(input: ServiceTypeInput) -> ExternResult<Record> {
   Ok(record)
 }
 
-/// Suggest a new service type (accepted users OR administrators, pending approval)
-#[hdk_extern]
-pub fn suggest_service_type(input: ServiceTypeInput) -> ExternResult<Record> {
+/// Checks that the calling agent is an administrator or an accepted user, who can suggest
+/// service types and translations
+pub(crate) fn check_if_agent_can_suggest() -> ExternResult<()> {
   let agent_pubkey = agent_info()?.agent_initial_pubkey;
 
   // Check if the agent is an administrator first
@@ -92,8 +114,24 @@ pub fn suggest_service_type(input: ServiceTypeInput) -> ExternResult<Record> {
     }
   }
 
+  Ok(())
+}
+
+/// Suggest a new service type (accepted users OR administrators, pending approval)
+#[hdk_extern]
+pub fn suggest_service_type(input: ServiceTypeInput) -> ExternResult<Record> {
+  check_if_agent_can_suggest()?;
+
+  if let Some(parent_hash) = &input.service_type.parent_hash {
+    check_service_type_parent(None, parent_hash)?;
+  }
+
   // Create the service type entry
-  let service_type_hash = create_entry(EntryTypes::ServiceType(input.service_type.clone()))?;
+  let mut service_type = input.service_type;
+  service_type.tags = normalize_tags(&service_type.tags);
+  let service_type_hash = create_entry(EntryTypes::ServiceType(service_type.clone()))?;
+  link_service_type_tags(&service_type_hash, &service_type.tags)?;
+  update_service_type_parent_links(&service_type_hash, None, service_type.parent_hash.as_ref())?;
 
   // Get the created record
   let record = get(service_type_hash.clone(), GetOptions::default())?.ok_or(
@@ -174,11 +212,60 @@ pub fn update_service_type(input: UpdateServiceTypeInput) -> ExternResult<Action
     return Err(AdministrationError::Unauthorized.into());
   }
 
+  let previous_service_type: ServiceType =
+    get(input.previous_action_hash.0.clone(), GetOptions::default())?
+      .ok_or(CommonError::EntryNotFound(
+        "Could not find the previous service type".to_string(),
+      ))?
+      .entry()
+      .to_app_option()
+      .map_err(CommonError::Serialize)?
+      .ok_or(CommonError::EntryNotFound(
+        "Could not deserialize service type entry".to_string(),
+      ))?;
+  let mut updated_service_type = input.updated_service_type;
+  updated_service_type.tags = normalize_tags(&updated_service_type.tags);
+  updated_service_type.merge_authorization = previous_service_type.merge_authorization.clone();
+  if updated_service_type.merged_into != previous_service_type.merged_into {
+    return Err(
+      CommonError::InvalidData(
+        "Service types can only be merged with merge_service_types".to_string(),
+      )
+      .into(),
+    );
+  }
+  if updated_service_type.parent_hash != previous_service_type.parent_hash {
+    return Err(
+      CommonError::InvalidData("The parent of a service type cannot be changed".to_string()).into(),
+    );
+  }
+
   // Update the service type entry
-  let updated_action_hash = update_entry(
-    input.previous_action_hash.into(),
-    &input.updated_service_type,
-  )?;
+  let updated_action_hash = update_entry(input.previous_action_hash.into(), &updated_service_type)?;
+
+  // Keep the tag index in sync with the new tags
+  let removed_tags: Vec<String> = previous_service_type
+    .tags
+    .iter()
+    .filter(|tag| !updated_service_type.tags.contains(tag))
+    .cloned()
+    .collect();
+  let added_tags: Vec<String> = updated_service_type
+    .tags
+    .iter()
+    .filter(|tag| !previous_service_type.tags.contains(tag))
+    .cloned()
+    .collect();
+  unlink_service_type_tags(&input.original_action_hash.0, &removed_tags)?;
+  link_service_type_tags(&input.original_action_hash.0, &added_tags)?;
+  if updated_service_type.name != previous_service_type.name {
+    let translations = get_approved_translations(&input.original_action_hash.0)?;
+    update_service_type_name_index(
+      &input.original_action_hash.0,
+      &service_type_terms(&previous_service_type.name, &translations),
+      &service_type_terms(&updated_service_type.name, &translations),
+    )?;
+  }
 
   // Create a link from the original service type to the updated one
   create_link(
@@ -199,6 +286,7 @@ pub fn delete_service_type(service_type_hash: ActionHash) -> ExternResult<Action
   if !is_admin {
     return Err(AdministrationError::Unauthorized.into());
   }
+  check_service_type_has_no_children(&service_type_hash)?;
 
   // Remove the AllServiceTypes link
   let path = Path::from("service_types");
@@ -224,8 +312,35 @@ pub fn delete_service_type(service_type_hash: ActionHash) -> ExternResult<Action
   // Remove status links (pending, approved, or rejected)
   remove_service_type_from_status_paths(service_type_hash.clone())?;
 
-  // Delete the service type entry
-  delete_entry(service_type_hash)
+  let service_type = match get_latest_service_type_record(service_type_hash.clone())? {
+    Some(record) => record
+      .entry()
+      .to_app_option::<ServiceType>()
+      .map_err(CommonError::Serialize)?,
+    None => None,
+  };
+
+  // Delete the service type entry first: it allows removing the name index links created
+  // by other administrators
+  let delete_action_hash = delete_entry(service_type_hash.clone())?;
+
+  // Remove it from the paths of its tags, from under its parent and from the name index
+  if let Some(service_type) = service_type {
+    unlink_service_type_tags(&service_type_hash, &service_type.tags)?;
+    update_service_type_parent_links(
+      &service_type_hash,
+      get_parent_hash(&service_type)?.as_ref(),
+      None,
+    )?;
+    let translations = get_approved_translations(&service_type_hash)?;
+    update_service_type_name_index(
+      &service_type_hash,
+      &service_type_terms(&service_type.name, &translations),
+      &[],
+    )?;
+  }
+
+  Ok(delete_action_hash)
 }
 
 /// Get all pending service types (admin only)
@@ -304,12 +419,13 @@ pub fn approve_service_type(service_type_hash: ActionHash) -> ExternResult<()> {
   // Add to approved path
   create_link(
     approved_path_hash,
-    service_type_hash,
+    service_type_hash.clone(),
     LinkTypes::AllServiceTypes,
     (),
   )?;
 
-  Ok(())
+  // Suggested service types can only be found by name once approved
+  index_service_type_names(&service_type_hash)
 }
 
 /// Reject a pending service type (admin only)
@@ -447,7 +563,9 @@ pub fn reject_approved_service_type(service_type_hash: ActionHash) -> ExternResu
 }
 
 /// Helper function to remove a service type from all status paths
-fn remove_service_type_from_status_paths(service_type_hash: ActionHash) -> ExternResult<()> {
+pub(crate) fn remove_service_type_from_status_paths(
+  service_type_hash: ActionHash,
+) -> ExternResult<()> {
   // Get the path hashes
   let pending_path_hash = get_status_path_hash(PENDING_SERVICE_TYPES_PATH)?;
   let approved_path_hash = get_status_path_hash(APPROVED_SERVICE_TYPES_PATH)?;
@@ -526,9 +644,17 @@ pub fn is_service_type_approved(service_type_hash: ActionHash) -> ExternResult<b
   Ok(found_approved)
 }
 
-/// Get the status of a service type (pending, approved, or rejected)
+/// Get the status of a service type (pending, approved, rejected, or merged)
 #[hdk_extern]
 pub fn get_service_type_status(service_type_hash: ActionHash) -> ExternResult<String> {
+  // Merged service types are out of the status paths
+  let merged = get_latest_service_type_record(service_type_hash.clone())?
+    .and_then(|record| record.entry().to_app_option::<ServiceType>().ok().flatten())
+    .is_some_and(|service_type| service_type.merged_into.is_some());
+  if merged {
+    return Ok("merged".to_string());
+  }
+
   // Check each status path
   let pending_path_hash = get_status_path_hash(PENDING_SERVICE_TYPES_PATH)?;
   let approved_path_hash = get_status_path_hash(APPROVED_SERVICE_TYPES_PATH)?;
@@ -0,0 +1,210 @@
+use std::collections::HashMap;
+
+use hdk::prelude::*;
+use service_types_integrity::{normalize_tag, LinkTypes};
+
+use crate::service_type::{get_latest_service_type_record, is_service_type_approved};
+
+/// Path listing every tag in use
+const ALL_TAGS_PATH: &str = "service_types.tags";
+
+/// Maximum number of tags returned by `get_tag_suggestions`
+const MAX_TAG_SUGGESTIONS: usize = 10;
+
+/// How many approved service types carry a tag
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct TagStatistic {
+  pub tag: String,
+  pub service_type_count: u32,
+}
+
+/// The path of a tag. Built from components so that tags may contain dots.
+fn tag_path(tag: &str) -> Path {
+  Path::from(vec![
+    Component::from("service_types"),
+    Component::from("tags"),
+    Component::from(tag),
+  ])
+}
+
+fn tag_from_link_tag(tag: &LinkTag) -> Option<String> {
+  String::from_utf8(tag.0.clone()).ok()
+}
+
+/// Normalizes the tags given by a user, dropping empty and repeated ones
+pub fn normalize_tags(tags: &[String]) -> Vec<String> {
+  let mut normalized_tags: Vec<String> = Vec::new();
+  for tag in tags {
+    let tag = normalize_tag(tag);
+    if !tag.is_empty() && !normalized_tags.contains(&tag) {
+      normalized_tags.push(tag);
+    }
+  }
+  normalized_tags
+}
+
+/// Returns the links from the tag index to the tag paths whose tag starts with `prefix`
+fn get_tag_index_links(prefix: &str) -> ExternResult<Vec<Link>> {
+  let link_type_filter = LinkTypes::AllServiceTypeTags
+    .try_into_filter()
+    .map_err(|e| wasm_error!(WasmErrorInner::Guest(e.to_string())))?;
+  get_links(
+    LinkQuery::new(
+      Path::from(ALL_TAGS_PATH).path_entry_hash()?,
+      link_type_filter,
+    )
+    .tag_prefix(LinkTag::new(prefix.as_bytes().to_vec())),
+    GetStrategy::Network,
+  )
+}
+
+/// Returns the links from a tag path to the service types carrying the tag
+fn get_tag_links(tag: &str) -> ExternResult<Vec<Link>> {
+  let link_type_filter = LinkTypes::TagToServiceType
+    .try_into_filter()
+    .map_err(|e| wasm_error!(WasmErrorInner::Guest(e.to_string())))?;
+  get_links(
+    LinkQuery::new(tag_path(tag).path_entry_hash()?, link_type_filter),
+    GetStrategy::Network,
+  )
+}
+
+/// Links a service type to the paths of its tags, adding new tags to the tag index
+pub fn link_service_type_tags(
+  original_action_hash: &ActionHash,
+  tags: &[String],
+) -> ExternResult<()> {
+  for tag in tags {
+    let tag_path_hash = tag_path(tag).path_entry_hash()?;
+
+    let is_indexed = get_tag_index_links(tag)?
+      .iter()
+      .any(|link| tag_from_link_tag(&link.tag).as_ref() == Some(tag));
+    if !is_indexed {
+      create_link(
+        Path::from(ALL_TAGS_PATH).path_entry_hash()?,
+        tag_path_hash.clone(),
+        LinkTypes::AllServiceTypeTags,
+        LinkTag::new(tag.as_bytes().to_vec()),
+      )?;
+    }
+
+    create_link(
+      tag_path_hash,
+      original_action_hash.clone(),
+      LinkTypes::TagToServiceType,
+      (),
+    )?;
+  }
+
+  Ok(())
+}
+
+/// Unlinks a service type from the paths of the given tags, removing tags that are no
+/// longer used from the tag index
+pub fn unlink_service_type_tags(
+  original_action_hash: &ActionHash,
+  tags: &[String],
+) -> ExternResult<()> {
+  for tag in tags {
+    let mut remaining_links = 0;
+    for link in get_tag_links(tag)? {
+      if link.target.clone().into_action_hash().as_ref() == Some(original_action_hash) {
+        delete_link(link.create_link_hash, GetOptions::default())?;
+      } else {
+        remaining_links += 1;
+      }
+    }
+
+    if remaining_links == 0 {
+      for link in get_tag_index_links(tag)? {
+        if tag_from_link_tag(&link.tag).as_ref() == Some(tag) {
+          delete_link(link.create_link_hash, GetOptions::default())?;
+        }
+      }
+    }
+  }
+
+  Ok(())
+}
+
+/// Get the original action hashes of the approved service types carrying a tag
+#[hdk_extern]
+pub fn get_service_type_hashes_by_tag(tag: String) -> ExternResult<Vec<ActionHash>> {
+  let mut service_type_hashes: Vec<ActionHash> = Vec::new();
+  for link in get_tag_links(&normalize_tag(&tag))? {
+    let Some(service_type_hash) = link.target.into_action_hash() else {
+      continue;
+    };
+    if !service_type_hashes.contains(&service_type_hash)
+      && is_service_type_approved(service_type_hash.clone())?
+    {
+      service_type_hashes.push(service_type_hash);
+    }
+  }
+
+  Ok(service_type_hashes)
+}
+
+/// Get the latest records of the approved service types carrying a tag
+#[hdk_extern]
+pub fn get_service_types_by_tag(tag: String) -> ExternResult<Vec<Record>> {
+  let mut records = Vec::new();
+  for service_type_hash in get_service_type_hashes_by_tag(tag)? {
+    if let Some(record) = get_latest_service_type_record(service_type_hash)? {
+      records.push(record);
+    }
+  }
+
+  Ok(records)
+}
+
+/// Counts the approved service types of every tag starting with `prefix`, most used first.
+/// Tags only carried by pending or rejected service types are left out.
+fn get_tag_statistics_with_prefix(prefix: &str) -> ExternResult<Vec<TagStatistic>> {
+  let mut counts: HashMap<String, u32> = HashMap::new();
+  for link in get_tag_index_links(prefix)? {
+    let Some(tag) = tag_from_link_tag(&link.tag) else {
+      continue;
+    };
+    if counts.contains_key(&tag) {
+      continue;
+    }
+    let service_type_count = get_service_type_hashes_by_tag(tag.clone())?.len() as u32;
+    counts.insert(tag, service_type_count);
+  }
+
+  let mut statistics: Vec<TagStatistic> = counts
+    .into_iter()
+    .filter(|(_, service_type_count)| *service_type_count > 0)
+    .map(|(tag, service_type_count)| TagStatistic {
+      tag,
+      service_type_count,
+    })
+    .collect();
+  statistics.sort_by(|a, b| {
+    b.service_type_count
+      .cmp(&a.service_type_count)
+      .then_with(|| a.tag.cmp(&b.tag))
+  });
+
+  Ok(statistics)
+}
+
+/// Get how many approved service types carry each tag, most used first
+#[hdk_extern]
+pub fn get_tag_statistics(_: ()) -> ExternResult<Vec<TagStatistic>> {
+  get_tag_statistics_with_prefix("")
+}
+
+/// Autocompletes a tag: the most used tags starting with `prefix`
+#[hdk_extern]
+pub fn get_tag_suggestions(prefix: String) -> ExternResult<Vec<String>> {
+  Ok(
+    get_tag_statistics_with_prefix(&normalize_tag(&prefix))?
+      .into_iter()
+      .take(MAX_TAG_SUGGESTIONS)
+      .map(|statistic| statistic.tag)
+      .collect(),
+  )
+}
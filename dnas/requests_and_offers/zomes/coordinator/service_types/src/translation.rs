@@ -0,0 +1,557 @@
+use hdk::prelude::*;
+use service_types_integrity::{
+  normalize_locale, normalize_tag, service_type_name_from_tag, service_type_name_tag, EntryTypes,
+  LinkTypes, ServiceType, ServiceTypeTranslation, PENDING_TRANSLATIONS_PATH,
+  SERVICE_TYPE_NAMES_PATH,
+};
+use utils::{
+  errors::{AdministrationError, CommonError},
+  role_links::{make_role_proof, role_proof_tag, RoleProof},
+};
+
+use crate::external_calls::{check_if_agent_is_administrator, get_agent_administrator_links};
+use crate::service_type::{
+  check_if_agent_can_suggest, get_latest_service_type_record, is_service_type_approved,
+};
+
+// Path anchor constants for translation status
+const APPROVED_TRANSLATIONS_PATH: &str = "service_types.translations.approved";
+const REJECTED_TRANSLATIONS_PATH: &str = "service_types.translations.rejected";
+
+/// Maximum number of service types returned by `autocomplete_service_types`
+const MAX_AUTOCOMPLETE_RESULTS: usize = 10;
+
+/// Input for suggesting a translation
+#[derive(Serialize, Deserialize, Debug)]
+pub struct ServiceTypeTranslationInput {
+  pub translation: ServiceTypeTranslation,
+}
+
+/// Input for getting a service type in a locale
+#[derive(Serialize, Deserialize, Debug)]
+pub struct LocalizedServiceTypeInput {
+  pub service_type_hash: ActionHash,
+  pub locale: String,
+}
+
+/// Input for searching service types by name or alias
+#[derive(Serialize, Deserialize, Debug)]
+pub struct SearchServiceTypesInput {
+  pub query: String,
+  /// The locale to return the service types in
+  pub locale: String,
+}
+
+/// A service type as shown in a locale
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct LocalizedServiceType {
+  /// The original action hash of the service type
+  pub service_type_hash: ActionHash,
+  /// The latest record of the service type
+  pub record: Record,
+  /// The locale of the translation used, or None when no approved translation matches the
+  /// requested locale and the service type's own name is used
+  pub locale: Option<String>,
+  pub name: String,
+  pub description: String,
+  pub aliases: Vec<String>,
+}
+
+fn get_status_path_hash(status_path: &str) -> ExternResult<EntryHash> {
+  Path::from(status_path).path_entry_hash()
+}
+
+fn get_translation_status_links(status_path: &str) -> ExternResult<Vec<Link>> {
+  let link_type_filter = LinkTypes::ServiceTypeTranslationStatus
+    .try_into_filter()
+    .map_err(|e| wasm_error!(WasmErrorInner::Guest(e.to_string())))?;
+  get_links(
+    LinkQuery::new(get_status_path_hash(status_path)?, link_type_filter),
+    GetStrategy::Network,
+  )
+}
+
+fn get_translation_hashes_with_status(status_path: &str) -> ExternResult<Vec<ActionHash>> {
+  Ok(
+    get_translation_status_links(status_path)?
+      .into_iter()
+      .filter_map(|link| link.target.into_action_hash())
+      .collect(),
+  )
+}
+
+fn remove_translation_from_status_paths(translation_hash: &ActionHash) -> ExternResult<()> {
+  for status_path in [
+    PENDING_TRANSLATIONS_PATH,
+    APPROVED_TRANSLATIONS_PATH,
+    REJECTED_TRANSLATIONS_PATH,
+  ] {
+    for link in get_translation_status_links(status_path)? {
+      if link.target.clone().into_action_hash().as_ref() == Some(translation_hash) {
+        delete_link(link.create_link_hash, GetOptions::default())?;
+      }
+    }
+  }
+  Ok(())
+}
+
+fn get_translation(translation_hash: ActionHash) -> ExternResult<ServiceTypeTranslation> {
+  get(translation_hash, GetOptions::default())?
+    .ok_or(CommonError::EntryNotFound(
+      "Could not find the translation".to_string(),
+    ))?
+    .entry()
+    .to_app_option()
+    .map_err(CommonError::Serialize)?
+    .ok_or(CommonError::EntryNotFound("Could not deserialize translation entry".to_string()).into())
+}
+
+fn get_latest_service_type(
+  original_action_hash: ActionHash,
+) -> ExternResult<(Record, ServiceType)> {
+  let record = get_latest_service_type_record(original_action_hash)?.ok_or(
+    CommonError::EntryNotFound("Could not find the service type".to_string()),
+  )?;
+  let service_type = record
+    .entry()
+    .to_app_option()
+    .map_err(CommonError::Serialize)?
+    .ok_or(CommonError::EntryNotFound(
+      "Could not deserialize service type entry".to_string(),
+    ))?;
+  Ok((record, service_type))
+}
+
+/// The approved translations of a service type with their action hashes
+fn get_approved_translations_with_hashes(
+  service_type_hash: &ActionHash,
+) -> ExternResult<Vec<(ActionHash, ServiceTypeTranslation)>> {
+  let approved_hashes = get_translation_hashes_with_status(APPROVED_TRANSLATIONS_PATH)?;
+  let link_type_filter = LinkTypes::ServiceTypeToTranslation
+    .try_into_filter()
+    .map_err(|e| wasm_error!(WasmErrorInner::Guest(e.to_string())))?;
+  let links = get_links(
+    LinkQuery::new(service_type_hash.clone(), link_type_filter),
+    GetStrategy::Network,
+  )?;
+
+  let mut translations = Vec::new();
+  for link in links {
+    let Some(translation_hash) = link.target.into_action_hash() else {
+      continue;
+    };
+    if approved_hashes.contains(&translation_hash) {
+      let translation = get_translation(translation_hash.clone())?;
+      translations.push((translation_hash, translation));
+    }
+  }
+  Ok(translations)
+}
+
+/// The approved translations of a service type
+pub(crate) fn get_approved_translations(
+  service_type_hash: &ActionHash,
+) -> ExternResult<Vec<ServiceTypeTranslation>> {
+  Ok(
+    get_approved_translations_with_hashes(service_type_hash)?
+      .into_iter()
+      .map(|(_, translation)| translation)
+      .collect(),
+  )
+}
+
+/// The normalized names and aliases a service type can be found by
+pub(crate) fn service_type_terms(
+  name: &str,
+  translations: &[ServiceTypeTranslation],
+) -> Vec<String> {
+  let mut terms: Vec<String> = Vec::new();
+  let names = std::iter::once(name).chain(translations.iter().flat_map(|translation| {
+    std::iter::once(translation.name.as_str()).chain(translation.aliases.iter().map(String::as_str))
+  }));
+  for name in names {
+    let term = normalize_tag(name);
+    // The name index separates names from proofs with a NUL byte
+    if !term.is_empty() && !term.contains('\0') && !terms.contains(&term) {
+      terms.push(term);
+    }
+  }
+  terms
+}
+
+fn get_name_index_links(prefix: &str) -> ExternResult<Vec<Link>> {
+  let link_type_filter = LinkTypes::ServiceTypeNames
+    .try_into_filter()
+    .map_err(|e| wasm_error!(WasmErrorInner::Guest(e.to_string())))?;
+  get_links(
+    LinkQuery::new(
+      Path::from(SERVICE_TYPE_NAMES_PATH).path_entry_hash()?,
+      link_type_filter,
+    )
+    .tag_prefix(LinkTag::new(prefix.as_bytes().to_vec())),
+    GetStrategy::Network,
+  )
+}
+
+/// Moves a service type in the name index from `previous_terms` to `terms`
+pub(crate) fn update_service_type_name_index(
+  service_type_hash: &ActionHash,
+  previous_terms: &[String],
+  terms: &[String],
+) -> ExternResult<()> {
+  for term in previous_terms.iter().filter(|term| !terms.contains(term)) {
+    for link in get_name_index_links(term)? {
+      if service_type_name_from_tag(&link.tag).as_ref() == Some(term)
+        && link.target.clone().into_action_hash().as_ref() == Some(service_type_hash)
+      {
+        delete_link(link.create_link_hash, GetOptions::default())?;
+      }
+    }
+  }
+
+  let added_terms: Vec<&String> = terms
+    .iter()
+    .filter(|term| !previous_terms.contains(term))
+    .collect();
+  if added_terms.is_empty() {
+    return Ok(());
+  }
+  let proof = administrator_proof()?;
+  for term in added_terms {
+    create_link(
+      Path::from(SERVICE_TYPE_NAMES_PATH).path_entry_hash()?,
+      service_type_hash.clone(),
+      LinkTypes::ServiceTypeNames,
+      service_type_name_tag(term, &proof)?,
+    )?;
+  }
+
+  Ok(())
+}
+
+/// Adds the names of a service type that are missing from the name index, once it is
+/// approved
+pub(crate) fn index_service_type_names(service_type_hash: &ActionHash) -> ExternResult<()> {
+  let (_, service_type) = get_latest_service_type(service_type_hash.clone())?;
+  let terms = service_type_terms(
+    &service_type.name,
+    &get_approved_translations(service_type_hash)?,
+  );
+
+  let mut indexed_terms: Vec<String> = Vec::new();
+  for term in &terms {
+    let is_indexed = get_name_index_links(term)?.into_iter().any(|link| {
+      service_type_name_from_tag(&link.tag).as_ref() == Some(term)
+        && link.target.into_action_hash().as_ref() == Some(service_type_hash)
+    });
+    if is_indexed {
+      indexed_terms.push(term.clone());
+    }
+  }
+
+  update_service_type_name_index(service_type_hash, &indexed_terms, &terms)
+}
+
+/// The proof of the calling agent's `AgentAdministrators` link to "network.administrators"
+pub(crate) fn administrator_proof() -> ExternResult<RoleProof> {
+  let administrators_path_hash: AnyLinkableHash = Path::from("network.administrators")
+    .path_entry_hash()?
+    .into();
+  let administrator_link = get_agent_administrator_links(agent_info()?.agent_initial_pubkey)?
+    .into_iter()
+    .find(|link| link.target == administrators_path_hash)
+    .ok_or(AdministrationError::Unauthorized)?;
+  make_role_proof(&administrator_link)
+}
+
+/// The tag of the links created on the strength of the administrator role, see
+/// [`administrator_proof`]
+pub(crate) fn administrator_proof_tag() -> ExternResult<LinkTag> {
+  role_proof_tag(&administrator_proof()?)
+}
+
+/// Gives a translation a status, keeping the name index in sync with the approved
+/// translations
+fn set_translation_status(translation_hash: ActionHash, status_path: &str) -> ExternResult<()> {
+  let translation = get_translation(translation_hash.clone())?;
+  let service_type_hash = translation.service_type_hash.clone();
+  let (_, service_type) = get_latest_service_type(service_type_hash.clone())?;
+
+  let approved = get_approved_translations_with_hashes(&service_type_hash)?;
+  let previous_translations: Vec<ServiceTypeTranslation> = approved
+    .iter()
+    .map(|(_, translation)| translation.clone())
+    .collect();
+
+  let mut translations: Vec<ServiceTypeTranslation> = Vec::new();
+  for (hash, approved_translation) in approved {
+    if hash == translation_hash {
+      continue;
+    }
+    // An approved translation replaces the one approved before it for the same locale
+    if status_path == APPROVED_TRANSLATIONS_PATH
+      && approved_translation.locale == translation.locale
+    {
+      remove_translation_from_status_paths(&hash)?;
+      continue;
+    }
+    translations.push(approved_translation);
+  }
+  if status_path == APPROVED_TRANSLATIONS_PATH {
+    translations.push(translation);
+  }
+
+  remove_translation_from_status_paths(&translation_hash)?;
+  create_link(
+    get_status_path_hash(status_path)?,
+    translation_hash,
+    LinkTypes::ServiceTypeTranslationStatus,
+    administrator_proof_tag()?,
+  )?;
+
+  update_service_type_name_index(
+    &service_type_hash,
+    &service_type_terms(&service_type.name, &previous_translations),
+    &service_type_terms(&service_type.name, &translations),
+  )
+}
+
+/// Suggest a translation of a service type (accepted users OR administrators, pending
+/// approval)
+#[hdk_extern]
+pub fn suggest_service_type_translation(
+  input: ServiceTypeTranslationInput,
+) -> ExternResult<Record> {
+  check_if_agent_can_suggest()?;
+
+  let mut translation = input.translation;
+  get_latest_service_type(translation.service_type_hash.clone())?;
+  translation.locale = normalize_locale(&translation.locale);
+  translation.name = translation.name.trim().to_string();
+  translation.description = translation.description.trim().to_string();
+  let mut aliases: Vec<String> = Vec::new();
+  for alias in &translation.aliases {
+    let alias = alias.trim().to_string();
+    if !alias.is_empty()
+      && !aliases
+        .iter()
+        .any(|existing| normalize_tag(existing) == normalize_tag(&alias))
+    {
+      aliases.push(alias);
+    }
+  }
+  translation.aliases = aliases;
+
+  let translation_hash = create_entry(EntryTypes::ServiceTypeTranslation(translation.clone()))?;
+  let record = get(translation_hash.clone(), GetOptions::default())?.ok_or(
+    CommonError::EntryNotFound("Could not find the newly created translation".to_string()),
+  )?;
+
+  create_link(
+    translation.service_type_hash,
+    translation_hash.clone(),
+    LinkTypes::ServiceTypeToTranslation,
+    LinkTag::new(translation.locale.as_bytes().to_vec()),
+  )?;
+  create_link(
+    get_status_path_hash(PENDING_TRANSLATIONS_PATH)?,
+    translation_hash,
+    LinkTypes::ServiceTypeTranslationStatus,
+    (),
+  )?;
+
+  Ok(record)
+}
+
+/// Approve a translation (admin only). It replaces the translation approved before it
+/// for the same locale, if any.
+#[hdk_extern]
+pub fn approve_service_type_translation(translation_hash: ActionHash) -> ExternResult<()> {
+  let is_admin = check_if_agent_is_administrator(agent_info()?.agent_initial_pubkey)?;
+  if !is_admin {
+    return Err(AdministrationError::Unauthorized.into());
+  }
+
+  set_translation_status(translation_hash, APPROVED_TRANSLATIONS_PATH)
+}
+
+/// Reject a pending or approved translation (admin only)
+#[hdk_extern]
+pub fn reject_service_type_translation(translation_hash: ActionHash) -> ExternResult<()> {
+  let is_admin = check_if_agent_is_administrator(agent_info()?.agent_initial_pubkey)?;
+  if !is_admin {
+    return Err(AdministrationError::Unauthorized.into());
+  }
+
+  let is_rejected =
+    get_translation_hashes_with_status(REJECTED_TRANSLATIONS_PATH)?.contains(&translation_hash);
+  if is_rejected {
+    return Err(CommonError::InvalidData("Translation is already rejected".to_string()).into());
+  }
+
+  set_translation_status(translation_hash, REJECTED_TRANSLATIONS_PATH)
+}
+
+/// Get all pending translations (admin only)
+#[hdk_extern]
+pub fn get_pending_service_type_translations(_: ()) -> ExternResult<Vec<Record>> {
+  let is_admin = check_if_agent_is_administrator(agent_info()?.agent_initial_pubkey)?;
+  if !is_admin {
+    return Err(AdministrationError::Unauthorized.into());
+  }
+
+  let mut records = Vec::new();
+  for translation_hash in get_translation_hashes_with_status(PENDING_TRANSLATIONS_PATH)? {
+    if let Some(record) = get(translation_hash, GetOptions::default())? {
+      records.push(record);
+    }
+  }
+  Ok(records)
+}
+
+/// Get the approved translations of a service type
+#[hdk_extern]
+pub fn get_service_type_translations(service_type_hash: ActionHash) -> ExternResult<Vec<Record>> {
+  let mut records = Vec::new();
+  for (translation_hash, _) in get_approved_translations_with_hashes(&service_type_hash)? {
+    if let Some(record) = get(translation_hash, GetOptions::default())? {
+      records.push(record);
+    }
+  }
+  Ok(records)
+}
+
+/// The language of a locale, such as "es" for "es-mx"
+fn language(locale: &str) -> &str {
+  locale.split('-').next().unwrap_or(locale)
+}
+
+/// Picks the approved translation for a locale: the exact locale first, then any
+/// translation in the same language
+fn find_translation<'a>(
+  translations: &'a [ServiceTypeTranslation],
+  locale: &str,
+) -> Option<&'a ServiceTypeTranslation> {
+  translations
+    .iter()
+    .find(|translation| translation.locale == locale)
+    .or_else(|| {
+      translations
+        .iter()
+        .find(|translation| language(&translation.locale) == language(locale))
+    })
+}
+
+fn localize_service_type(
+  service_type_hash: ActionHash,
+  locale: &str,
+) -> ExternResult<Option<LocalizedServiceType>> {
+  let Some(record) = get_latest_service_type_record(service_type_hash.clone())? else {
+    return Ok(None);
+  };
+  let Some(service_type) = record
+    .entry()
+    .to_app_option::<ServiceType>()
+    .map_err(CommonError::Serialize)?
+  else {
+    return Ok(None);
+  };
+
+  let translations = get_approved_translations(&service_type_hash)?;
+  let localized = match find_translation(&translations, &normalize_locale(locale)) {
+    Some(translation) => LocalizedServiceType {
+      service_type_hash,
+      record,
+      locale: Some(translation.locale.clone()),
+      name: translation.name.clone(),
+      description: translation.description.clone(),
+      aliases: translation.aliases.clone(),
+    },
+    None => LocalizedServiceType {
+      service_type_hash,
+      record,
+      locale: None,
+      name: service_type.name,
+      description: service_type.description,
+      aliases: Vec::new(),
+    },
+  };
+  Ok(Some(localized))
+}
+
+/// Get a service type with its name, description and aliases in a locale, falling back
+/// to its own name and description when it has no approved translation for it
+#[hdk_extern]
+pub fn get_localized_service_type(
+  input: LocalizedServiceTypeInput,
+) -> ExternResult<Option<LocalizedServiceType>> {
+  localize_service_type(input.service_type_hash, &input.locale)
+}
+
+/// Localizes the approved service types of the given name index links, sorted by name
+fn localize_matching_service_types(
+  links: Vec<Link>,
+  locale: &str,
+  limit: Option<usize>,
+) -> ExternResult<Vec<LocalizedServiceType>> {
+  let mut service_type_hashes: Vec<ActionHash> = Vec::new();
+  for link in links {
+    if let Some(service_type_hash) = link.target.into_action_hash() {
+      if !service_type_hashes.contains(&service_type_hash) {
+        service_type_hashes.push(service_type_hash);
+      }
+    }
+  }
+
+  let mut results = Vec::new();
+  for service_type_hash in service_type_hashes {
+    if !is_service_type_approved(service_type_hash.clone())? {
+      continue;
+    }
+    if let Some(localized) = localize_service_type(service_type_hash, locale)? {
+      results.push(localized);
+    }
+  }
+  results.sort_by_key(|localized| localized.name.to_lowercase());
+  if let Some(limit) = limit {
+    results.truncate(limit);
+  }
+
+  Ok(results)
+}
+
+/// Search the approved service types whose name, approved translations or aliases
+/// contain the query, returned in the requested locale
+#[hdk_extern]
+pub fn search_service_types(
+  input: SearchServiceTypesInput,
+) -> ExternResult<Vec<LocalizedServiceType>> {
+  let query = normalize_tag(&input.query);
+  if query.is_empty() {
+    return Ok(Vec::new());
+  }
+
+  let links: Vec<Link> = get_name_index_links("")?
+    .into_iter()
+    .filter(|link| service_type_name_from_tag(&link.tag).is_some_and(|term| term.contains(&query)))
+    .collect();
+  localize_matching_service_types(links, &input.locale, None)
+}
+
+/// Autocompletes a service type: the approved service types with a name, translation or
+/// alias starting with the query, returned in the requested locale
+#[hdk_extern]
+pub fn autocomplete_service_types(
+  input: SearchServiceTypesInput,
+) -> ExternResult<Vec<LocalizedServiceType>> {
+  let prefix = normalize_tag(&input.query);
+  if prefix.is_empty() {
+    return Ok(Vec::new());
+  }
+
+  localize_matching_service_types(
+    get_name_index_links(&prefix)?,
+    &input.locale,
+    Some(MAX_AUTOCOMPLETE_RESULTS),
+  )
+}
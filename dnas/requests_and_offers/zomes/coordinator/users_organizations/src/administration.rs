@@ -1,6 +1,7 @@
 use hdk::prelude::*;
 use users_organizations_integrity::LinkTypes;
 use utils::errors::AdministrationError;
+use utils::{paginate_links, Page, PaginationInput};
 
 use crate::external_calls::{check_if_agent_is_administrator, get_accepted_entities};
 
@@ -20,6 +21,13 @@ pub fn get_all_users(_: ()) -> ExternResult<Vec<Link>> {
   )
 }
 
+/// Get a page of user links (admin only), ordered by creation time
+#[hdk_extern]
+pub fn get_all_users_paginated(input: PaginationInput) -> ExternResult<Page<Link>> {
+  let (items, next_cursor) = paginate_links(get_all_users(())?, &input)?;
+  Ok(Page { items, next_cursor })
+}
+
 #[hdk_extern]
 pub fn get_user_status_link(user_original_action_hash: ActionHash) -> ExternResult<Option<Link>> {
   let link_type_filter = LinkTypes::UserStatus
@@ -54,6 +62,15 @@ pub fn get_all_organizations_links(_: ()) -> ExternResult<Vec<Link>> {
   }
 }
 
+/// Get a page of organization links, ordered by creation time.
+///
+/// Follows the same visibility rules as `get_all_organizations_links`.
+#[hdk_extern]
+pub fn get_all_organizations_links_paginated(input: PaginationInput) -> ExternResult<Page<Link>> {
+  let (items, next_cursor) = paginate_links(get_all_organizations_links(())?, &input)?;
+  Ok(Page { items, next_cursor })
+}
+
 #[hdk_extern]
 pub fn get_organization_status_link(
   organization_original_action_hash: ActionHash,
@@ -1,6 +1,6 @@
 use hdk::prelude::*;
 use utils::{
-  external_local_call, EntityActionHash, EntityAgent, OriginalActionHash,
+  external_local_call, EntityActionHash, EntityAgent, OriginalActionHash, SearchIndexInput,
   UpdateServiceTypeLinksInput,
 };
 
@@ -95,3 +95,11 @@ pub fn check_if_entity_is_accepted(original_action_hash: EntityActionHash) -> Ex
 pub fn update_service_type_links(input: UpdateServiceTypeLinksInput) -> ExternResult<()> {
   external_local_call("update_service_type_links", "service_types", input)
 }
+
+pub fn index_entity(input: SearchIndexInput) -> ExternResult<()> {
+  external_local_call("index_entity", "search", input)
+}
+
+pub fn remove_entity_from_index(original_action_hash: ActionHash) -> ExternResult<()> {
+  external_local_call("remove_entity_from_index", "search", original_action_hash)
+}
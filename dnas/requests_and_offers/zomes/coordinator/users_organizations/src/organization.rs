@@ -2,18 +2,32 @@ use hdk::prelude::*;
 use users_organizations_integrity::*;
 use utils::{
   errors::{AdministrationError, CommonError, OrganizationsError, UsersError},
-  EntityActionHash, OriginalActionHash, OrganizationContactInput, OrganizationUserInput,
-  PreviousActionHash,
+  role_links::{make_role_proof, role_proof_tag},
+  EntityActionHash, OrganizationContactInput, OrganizationUserInput, OriginalActionHash,
+  PreviousActionHash, SearchIndexInput,
 };
 
 use crate::{
   administration::get_organization_status_link,
   external_calls::{
-    check_if_entity_is_accepted, create_status, delete_status, get_accepted_entities,
+    check_if_entity_is_accepted, create_status, delete_status, get_accepted_entities, index_entity,
+    remove_entity_from_index,
   },
   user::{get_agent_user, get_latest_user},
 };
 
+/// Indexes the name and description of an organization for full-text search
+fn index_organization(
+  original_action_hash: ActionHash,
+  organization: &Organization,
+) -> ExternResult<()> {
+  index_entity(SearchIndexInput {
+    original_action_hash: OriginalActionHash(original_action_hash),
+    entity: "organization".to_string(),
+    fields: vec![organization.name.clone(), organization.description.clone()],
+  })
+}
+
 #[hdk_extern]
 pub fn create_organization(organization: Organization) -> ExternResult<Record> {
   let user_links = get_agent_user(agent_info()?.agent_initial_pubkey)?;
@@ -57,12 +71,14 @@ pub fn create_organization(organization: Organization) -> ExternResult<Record> {
   )?;
 
   create_link(
-    organization_hash,
+    organization_hash.clone(),
     user_links[0].target.clone(),
     LinkTypes::OrganizationCoordinators,
     (),
   )?;
 
+  index_organization(organization_hash, &organization)?;
+
   Ok(record)
 }
 
@@ -104,9 +120,29 @@ pub fn get_latest_organization(original_action_hash: ActionHash) -> ExternResult
   Ok(latest_organization)
 }
 
+/// The tag of the role links created by the calling agent in an organization: the proof of
+/// their own `OrganizationCoordinators` link, which integrity requires from anyone but the
+/// organization's creator
+fn coordinator_proof_tag(organization_original_action_hash: ActionHash) -> ExternResult<LinkTag> {
+  let agent_user_hash = get_agent_user(agent_info()?.agent_initial_pubkey)?
+    .first()
+    .and_then(|link| link.target.clone().into_action_hash());
+  let proof_link = get_organization_coordinators_links(organization_original_action_hash)?
+    .into_iter()
+    .find(|link| {
+      agent_user_hash.is_some() && link.target.clone().into_action_hash() == agent_user_hash
+    });
+
+  Ok(match proof_link {
+    Some(link) => role_proof_tag(&make_role_proof(&link)?)?,
+    None => LinkTag::new(Vec::new()),
+  })
+}
+
 #[hdk_extern]
 pub fn add_member_to_organization(input: OrganizationUserInput) -> ExternResult<bool> {
-  if !check_if_agent_is_organization_coordinator(input.organization_original_action_hash.0.clone())? {
+  if !check_if_agent_is_organization_coordinator(input.organization_original_action_hash.0.clone())?
+  {
     return Err(OrganizationsError::NotCoordinator.into());
   }
 
@@ -122,7 +158,7 @@ pub fn add_member_to_organization(input: OrganizationUserInput) -> ExternResult<
     input.organization_original_action_hash.0.clone(),
     input.user_original_action_hash.0.clone(),
     LinkTypes::OrganizationMembers,
-    (),
+    coordinator_proof_tag(input.organization_original_action_hash.0.clone())?,
   )?;
 
   create_link(
@@ -189,7 +225,8 @@ pub fn is_organization_member(input: OrganizationUserInput) -> ExternResult<bool
   let user_org_links = get_user_organizations_links(input.user_original_action_hash.0.clone())?;
 
   let is_user_org = user_org_links.iter().any(|link| {
-    link.target.clone().into_action_hash() == Some(input.organization_original_action_hash.0.clone())
+    link.target.clone().into_action_hash()
+      == Some(input.organization_original_action_hash.0.clone())
   });
 
   // Both links must exist for valid membership
@@ -233,7 +270,8 @@ pub fn get_user_organizations(
 
 #[hdk_extern]
 pub fn add_coordinator_to_organization(input: OrganizationUserInput) -> ExternResult<bool> {
-  if !check_if_agent_is_organization_coordinator(input.organization_original_action_hash.0.clone())? {
+  if !check_if_agent_is_organization_coordinator(input.organization_original_action_hash.0.clone())?
+  {
     return Err(OrganizationsError::NotCoordinator.into());
   }
 
@@ -269,11 +307,12 @@ pub fn add_coordinator_to_organization(input: OrganizationUserInput) -> ExternRe
     return Err(OrganizationsError::AlreadyCoordinator.into());
   }
 
+  let tag = coordinator_proof_tag(input.organization_original_action_hash.0.clone())?;
   create_link(
     input.organization_original_action_hash.0,
     input.user_original_action_hash.0,
     LinkTypes::OrganizationCoordinators,
-    (),
+    tag,
   )?;
 
   Ok(true)
@@ -321,7 +360,8 @@ pub fn get_organization_coordinators(
 
 #[hdk_extern]
 pub fn is_organization_coordinator(input: OrganizationUserInput) -> ExternResult<bool> {
-  let links = get_organization_coordinators_links(input.organization_original_action_hash.0.clone())?;
+  let links =
+    get_organization_coordinators_links(input.organization_original_action_hash.0.clone())?;
 
   let is_coordinator = links.into_iter().any(|link| {
     link.target.clone().into_action_hash() == Some(input.user_original_action_hash.0.clone())
@@ -372,7 +412,8 @@ pub fn get_organization_contact(
 
 #[hdk_extern]
 pub fn set_organization_contact(input: OrganizationContactInput) -> ExternResult<bool> {
-  if !check_if_agent_is_organization_coordinator(input.organization_original_action_hash.0.clone())? {
+  if !check_if_agent_is_organization_coordinator(input.organization_original_action_hash.0.clone())?
+  {
     return Err(OrganizationsError::NotCoordinator.into());
   }
 
@@ -536,7 +577,8 @@ pub fn leave_organization(original_action_hash: ActionHash) -> ExternResult<bool
 
 #[hdk_extern]
 pub fn remove_organization_member(input: OrganizationUserInput) -> ExternResult<ActionHash> {
-  if !check_if_agent_is_organization_coordinator(input.organization_original_action_hash.0.clone())? {
+  if !check_if_agent_is_organization_coordinator(input.organization_original_action_hash.0.clone())?
+  {
     return Err(OrganizationsError::NotCoordinator.into());
   }
 
@@ -579,7 +621,8 @@ pub fn remove_organization_member(input: OrganizationUserInput) -> ExternResult<
   let user_organizations_links =
     get_user_organizations_links(input.user_original_action_hash.0.clone())?;
   let this_user_organizations_link = user_organizations_links.into_iter().find(|link| {
-    link.target.clone().into_action_hash() == Some(input.organization_original_action_hash.0.clone())
+    link.target.clone().into_action_hash()
+      == Some(input.organization_original_action_hash.0.clone())
   });
 
   if this_user_organizations_link.is_none() {
@@ -594,7 +637,8 @@ pub fn remove_organization_member(input: OrganizationUserInput) -> ExternResult<
 
 #[hdk_extern]
 pub fn remove_organization_coordinator(input: OrganizationUserInput) -> ExternResult<bool> {
-  if !check_if_agent_is_organization_coordinator(input.organization_original_action_hash.0.clone())? {
+  if !check_if_agent_is_organization_coordinator(input.organization_original_action_hash.0.clone())?
+  {
     return Err(OrganizationsError::NotCoordinator.into());
   }
 
@@ -612,12 +656,13 @@ pub fn remove_organization_coordinator(input: OrganizationUserInput) -> ExternRe
     return Err(OrganizationsError::LastCoordinator.into());
   }
 
-  let link = get_organization_coordinators_links(input.organization_original_action_hash.0.clone())?
-    .into_iter()
-    .find(|link| {
-      link.target.clone().into_action_hash() == Some(input.user_original_action_hash.0.clone())
-    })
-    .ok_or(CommonError::LinkNotFound("coordinator".to_string()))?;
+  let link =
+    get_organization_coordinators_links(input.organization_original_action_hash.0.clone())?
+      .into_iter()
+      .find(|link| {
+        link.target.clone().into_action_hash() == Some(input.user_original_action_hash.0.clone())
+      })
+      .ok_or(CommonError::LinkNotFound("coordinator".to_string()))?;
 
   delete_link(link.create_link_hash, GetOptions::default())?;
 
@@ -653,12 +698,14 @@ pub fn update_organization(input: UpdateOrganizationInput) -> ExternResult<Recor
   )?;
 
   create_link(
-    input.original_action_hash.0,
+    input.original_action_hash.0.clone(),
     updated_organization_hash.clone(),
     LinkTypes::OrganizationUpdates,
     (),
   )?;
 
+  index_organization(input.original_action_hash.0, &input.updated_organization)?;
+
   let record = get(updated_organization_hash.clone(), GetOptions::default())?
     .ok_or(CommonError::EntryNotFound("organization".to_string()))?;
 
@@ -705,8 +752,7 @@ pub fn delete_organization(
   }
 
   // Delete contact links
-  let contact_links =
-    get_organization_contacts_links(organization_original_action_hash.clone())?;
+  let contact_links = get_organization_contacts_links(organization_original_action_hash.clone())?;
   for link in contact_links {
     delete_link(link.create_link_hash, GetOptions::default())?;
   }
@@ -756,6 +802,9 @@ pub fn delete_organization(
     entity: String::from("organizations"),
   })?;
 
+  // Remove the organization from the search index
+  remove_entity_from_index(organization_original_action_hash.clone())?;
+
   // Finally delete the organization entry
   delete_entry(organization_original_action_hash)
 }
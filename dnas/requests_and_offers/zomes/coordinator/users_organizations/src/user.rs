@@ -1,9 +1,25 @@
 use hdk::prelude::*;
 use users_organizations_integrity::*;
 use utils::errors::{CommonError, UsersError};
-use utils::{check_if_progenitor, external_local_call, DnaProperties, EntityActionHashAgents, OriginalActionHash, PreviousActionHash};
-
-use crate::external_calls::create_status;
+use utils::{
+  check_if_progenitor, external_local_call, DnaProperties, EntityActionHashAgents,
+  OriginalActionHash, PreviousActionHash, SearchIndexInput,
+};
+
+use crate::external_calls::{create_status, index_entity};
+
+/// Indexes the name, nickname and location of a user for full-text search
+fn index_user(original_action_hash: ActionHash, user: &User) -> ExternResult<()> {
+  index_entity(SearchIndexInput {
+    original_action_hash: OriginalActionHash(original_action_hash),
+    entity: "user".to_string(),
+    fields: vec![
+      user.name.clone(),
+      user.nickname.clone(),
+      user.location.clone(),
+    ],
+  })
+}
 
 #[hdk_extern]
 pub fn create_user(input: User) -> ExternResult<Record> {
@@ -80,6 +96,8 @@ pub fn create_user(input: User) -> ExternResult<Record> {
     )?;
   }
 
+  index_user(user_hash, &input)?;
+
   Ok(record)
 }
 
@@ -168,12 +186,14 @@ pub fn update_user(input: UpdateUserInput) -> ExternResult<Record> {
   let updated_user_hash = update_entry(input.previous_action_hash.into(), &input.updated_user)?;
 
   create_link(
-    input.original_action_hash,
+    input.original_action_hash.clone(),
     updated_user_hash.clone(),
     LinkTypes::UserUpdates,
     (),
   )?;
 
+  index_user(input.original_action_hash.0, &input.updated_user)?;
+
   // Users don't have service type links - this was removed
   // update_service_type_links would be called here if needed for other entities
 
@@ -0,0 +1,276 @@
+use std::collections::BTreeSet;
+
+use hdk::prelude::*;
+use utils::{
+  errors::CommonError, GetMediumOfExchangeForEntityInput, GetServiceTypeForEntityInput,
+  ListingStatus, OriginalActionHash, PaginationCursor, PaginationInput, Valuation,
+};
+
+use crate::external_calls::{
+  get_active_offers_paginated, get_active_requests_paginated, get_fulfilled_offers_paginated,
+  get_fulfilled_requests_paginated, get_latest_medium_of_exchange_record, get_latest_organization,
+  get_latest_service_type_record, get_latest_user, get_mediums_of_exchange_for_entity,
+  get_offer_creator, get_offer_organization, get_request_creator, get_request_organization,
+  get_service_types_for_entity,
+};
+
+use crate::json_ld::{
+  valueflows_json_ld, ValueFlowsExport, VfAgent, VfAgentType, VfListing, VfMediumOfExchange,
+  VfServiceType,
+};
+
+/// The index of listings a page of the export is taken from
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq)]
+pub enum ValueFlowsListings {
+  ActiveRequests,
+  FulfilledRequests,
+  ActiveOffers,
+  FulfilledOffers,
+}
+
+/// Input for exporting a page of listings to ValueFlows
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct ValueFlowsExportInput {
+  pub listings: ValueFlowsListings,
+  pub pagination: PaginationInput,
+}
+
+/// A page of the export. `next_cursor` is `None` on the last page.
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct ValueFlowsExportPage {
+  /// The JSON-LD document of the listings of the page, with the agents, service types
+  /// and mediums of exchange they reference
+  pub document: String,
+  pub next_cursor: Option<PaginationCursor>,
+}
+
+/// The fields of a `Request` or `Offer` entry that are exported.
+/// Unknown fields are ignored when deserializing.
+#[derive(Serialize, Deserialize, SerializedBytes, Debug, Clone)]
+pub struct ListingFields {
+  pub title: String,
+  pub description: String,
+  #[serde(default)]
+  pub valuation: Option<Valuation>,
+  #[serde(default)]
+  pub status: ListingStatus,
+}
+
+/// The name of a `User` or `Organization` entry
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct NamedEntry {
+  pub name: String,
+}
+
+/// The fields of a `ServiceType` entry that are exported
+#[derive(Serialize, Deserialize, SerializedBytes, Debug, Clone)]
+pub struct ServiceTypeFields {
+  pub name: String,
+  pub description: String,
+}
+
+/// The fields of a `MediumOfExchange` entry that are exported
+#[derive(Serialize, Deserialize, SerializedBytes, Debug, Clone)]
+pub struct MediumOfExchangeFields {
+  pub code: String,
+  pub name: String,
+  #[serde(default)]
+  pub description: Option<String>,
+  #[serde(default)]
+  pub resource_spec_hrea_id: Option<String>,
+}
+
+fn entry_from_record<T>(record: &Record, entry_name: &str) -> ExternResult<T>
+where
+  T: TryFrom<SerializedBytes, Error = SerializedBytesError>,
+{
+  Ok(
+    record
+      .entry()
+      .to_app_option()
+      .map_err(CommonError::Serialize)?
+      .ok_or(CommonError::EntryNotFound(format!(
+        "Could not deserialize {} entry",
+        entry_name
+      )))?,
+  )
+}
+
+/// The original action hash of a listing from its latest record
+fn original_action_hash(record: &Record) -> ActionHash {
+  match record.action() {
+    Action::Update(update) => update.original_action_address.clone(),
+    _ => record.action_address().clone(),
+  }
+}
+
+/// The agents, service types and mediums of exchange referenced by the exported listings
+#[derive(Default)]
+struct References {
+  users: BTreeSet<ActionHash>,
+  organizations: BTreeSet<ActionHash>,
+  service_types: BTreeSet<ActionHash>,
+  mediums_of_exchange: BTreeSet<ActionHash>,
+}
+
+/// Maps listings of one kind, `request` or `offer`
+fn export_listings(
+  records: Vec<Record>,
+  entity: &str,
+  references: &mut References,
+) -> ExternResult<Vec<VfListing>> {
+  let mut listings = Vec::new();
+  for record in records {
+    let fields: ListingFields = entry_from_record(&record, entity)?;
+    let id = original_action_hash(&record);
+
+    let (creator, organization) = if entity == "request" {
+      (
+        get_request_creator(id.clone())?,
+        get_request_organization(id.clone())?,
+      )
+    } else {
+      (
+        get_offer_creator(id.clone())?,
+        get_offer_organization(id.clone())?,
+      )
+    };
+    let agent_id = match (organization, creator) {
+      (Some(organization), _) => {
+        references.organizations.insert(organization.clone());
+        organization
+      }
+      (None, Some(creator)) => {
+        references.users.insert(creator.clone());
+        creator
+      }
+      // Listings whose author cannot be found cannot be attributed to an agent
+      (None, None) => continue,
+    };
+
+    let service_type_ids = get_service_types_for_entity(GetServiceTypeForEntityInput {
+      original_action_hash: OriginalActionHash(id.clone()),
+      entity: entity.to_string(),
+    })?;
+    references
+      .service_types
+      .extend(service_type_ids.iter().cloned());
+    references
+      .mediums_of_exchange
+      .extend(get_mediums_of_exchange_for_entity(
+        GetMediumOfExchangeForEntityInput {
+          original_action_hash: OriginalActionHash(id.clone()),
+          entity: entity.to_string(),
+        },
+      )?);
+    if let Some(valuation) = &fields.valuation {
+      references
+        .mediums_of_exchange
+        .insert(valuation.medium_of_exchange_hash.clone());
+    }
+
+    listings.push(VfListing {
+      id,
+      title: fields.title,
+      description: fields.description,
+      agent_id,
+      service_type_ids,
+      valuation: fields.valuation,
+      finished: fields.status == ListingStatus::Fulfilled,
+    });
+  }
+  Ok(listings)
+}
+
+/// Gathers a page of listings, with the agents, service types and mediums of exchange
+/// they reference, and the cursor of the next page
+pub fn collect_valueflows_export(
+  input: &ValueFlowsExportInput,
+) -> ExternResult<(ValueFlowsExport, Option<PaginationCursor>)> {
+  let mut references = References::default();
+
+  let pagination = input.pagination.clone();
+  let (page, entity) = match input.listings {
+    ValueFlowsListings::ActiveRequests => (get_active_requests_paginated(pagination)?, "request"),
+    ValueFlowsListings::FulfilledRequests => {
+      (get_fulfilled_requests_paginated(pagination)?, "request")
+    }
+    ValueFlowsListings::ActiveOffers => (get_active_offers_paginated(pagination)?, "offer"),
+    ValueFlowsListings::FulfilledOffers => (get_fulfilled_offers_paginated(pagination)?, "offer"),
+  };
+  let listings = export_listings(page.items, entity, &mut references)?;
+  let (requests, offers) = if entity == "request" {
+    (listings, Vec::new())
+  } else {
+    (Vec::new(), listings)
+  };
+
+  let mut agents = Vec::new();
+  for id in references.users {
+    let user = get_latest_user(id.clone())?;
+    agents.push(VfAgent {
+      id,
+      agent_type: VfAgentType::Person,
+      name: user.name,
+    });
+  }
+  for id in references.organizations {
+    let organization = get_latest_organization(id.clone())?;
+    agents.push(VfAgent {
+      id,
+      agent_type: VfAgentType::Organization,
+      name: organization.name,
+    });
+  }
+
+  let mut service_types = Vec::new();
+  for id in references.service_types {
+    let Some(record) = get_latest_service_type_record(id.clone())? else {
+      continue;
+    };
+    let service_type: ServiceTypeFields = entry_from_record(&record, "service type")?;
+    service_types.push(VfServiceType {
+      id,
+      name: service_type.name,
+      description: Some(service_type.description).filter(|description| !description.is_empty()),
+    });
+  }
+
+  let mut mediums_of_exchange = Vec::new();
+  for id in references.mediums_of_exchange {
+    let Some(record) = get_latest_medium_of_exchange_record(id.clone())? else {
+      continue;
+    };
+    let medium: MediumOfExchangeFields = entry_from_record(&record, "medium of exchange")?;
+    mediums_of_exchange.push(VfMediumOfExchange {
+      id,
+      code: medium.code,
+      name: medium.name,
+      description: medium.description,
+      resource_spec_hrea_id: medium.resource_spec_hrea_id,
+    });
+  }
+
+  Ok((
+    ValueFlowsExport {
+      agents,
+      service_types,
+      mediums_of_exchange,
+      requests,
+      offers,
+    },
+    page.next_cursor,
+  ))
+}
+
+/// Exports a page of active or fulfilled requests or offers as a ValueFlows JSON-LD
+/// document, for hREA and other ValueFlows tools. Requests are intents received by their
+/// organization or author, offers are proposals of intents they provide.
+#[hdk_extern]
+pub fn export_valueflows(input: ValueFlowsExportInput) -> ExternResult<ValueFlowsExportPage> {
+  let (export, next_cursor) = collect_valueflows_export(&input)?;
+  Ok(ValueFlowsExportPage {
+    document: valueflows_json_ld(&export).to_string(),
+    next_cursor,
+  })
+}
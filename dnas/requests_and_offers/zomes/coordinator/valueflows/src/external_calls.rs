@@ -0,0 +1,91 @@
+use hdk::prelude::*;
+use utils::{
+  external_local_call, GetMediumOfExchangeForEntityInput, GetServiceTypeForEntityInput, Page,
+  PaginationInput,
+};
+
+use crate::export::NamedEntry;
+
+pub fn get_active_requests_paginated(input: PaginationInput) -> ExternResult<Page<Record>> {
+  external_local_call("get_active_requests_paginated", "requests", input)
+}
+
+pub fn get_fulfilled_requests_paginated(input: PaginationInput) -> ExternResult<Page<Record>> {
+  external_local_call("get_fulfilled_requests_paginated", "requests", input)
+}
+
+pub fn get_active_offers_paginated(input: PaginationInput) -> ExternResult<Page<Record>> {
+  external_local_call("get_active_offers_paginated", "offers", input)
+}
+
+pub fn get_fulfilled_offers_paginated(input: PaginationInput) -> ExternResult<Page<Record>> {
+  external_local_call("get_fulfilled_offers_paginated", "offers", input)
+}
+
+pub fn get_request_creator(request_hash: ActionHash) -> ExternResult<Option<ActionHash>> {
+  external_local_call("get_request_creator", "requests", request_hash)
+}
+
+pub fn get_request_organization(request_hash: ActionHash) -> ExternResult<Option<ActionHash>> {
+  external_local_call("get_request_organization", "requests", request_hash)
+}
+
+pub fn get_offer_creator(offer_hash: ActionHash) -> ExternResult<Option<ActionHash>> {
+  external_local_call("get_offer_creator", "offers", offer_hash)
+}
+
+pub fn get_offer_organization(offer_hash: ActionHash) -> ExternResult<Option<ActionHash>> {
+  external_local_call("get_offer_organization", "offers", offer_hash)
+}
+
+pub fn get_service_types_for_entity(
+  input: GetServiceTypeForEntityInput,
+) -> ExternResult<Vec<ActionHash>> {
+  external_local_call("get_service_types_for_entity", "service_types", input)
+}
+
+pub fn get_mediums_of_exchange_for_entity(
+  input: GetMediumOfExchangeForEntityInput,
+) -> ExternResult<Vec<ActionHash>> {
+  external_local_call(
+    "get_mediums_of_exchange_for_entity",
+    "mediums_of_exchange",
+    input,
+  )
+}
+
+pub fn get_latest_service_type_record(
+  original_action_hash: ActionHash,
+) -> ExternResult<Option<Record>> {
+  external_local_call(
+    "get_latest_service_type_record",
+    "service_types",
+    original_action_hash,
+  )
+}
+
+pub fn get_latest_medium_of_exchange_record(
+  original_action_hash: ActionHash,
+) -> ExternResult<Option<Record>> {
+  external_local_call(
+    "get_latest_medium_of_exchange_record",
+    "mediums_of_exchange",
+    original_action_hash,
+  )
+}
+
+pub fn get_latest_user(original_action_hash: ActionHash) -> ExternResult<NamedEntry> {
+  external_local_call(
+    "get_latest_user",
+    "users_organizations",
+    original_action_hash,
+  )
+}
+
+pub fn get_latest_organization(original_action_hash: ActionHash) -> ExternResult<NamedEntry> {
+  external_local_call(
+    "get_latest_organization",
+    "users_organizations",
+    original_action_hash,
+  )
+}
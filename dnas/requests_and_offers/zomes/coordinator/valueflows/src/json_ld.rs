@@ -0,0 +1,405 @@
+//! Export of listings to ValueFlows, the vocabulary hREA and accounting tools exchange
+//! economic data in.
+//!
+//! Requests become intents received by their author, offers become proposals of intents
+//! provided by theirs, service types and mediums of exchange become resource
+//! specifications and users and organizations become agents. A valuation adds the
+//! payment as a reciprocal `transfer` intent in its medium of exchange.
+//!
+//! [`valueflows_json_ld`] only maps data, so it works outside of a conductor as well.
+
+use hdk::prelude::*;
+use serde_json::{json, Map, Value};
+
+use utils::{valuation::MINOR_UNITS_PER_UNIT, Valuation, ValuationUnit};
+
+/// Prefix of the identifiers of exported nodes
+const ID_PREFIX: &str = "urn:requests-and-offers";
+
+/// Whether an agent is a user or an organization
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq)]
+pub enum VfAgentType {
+  Person,
+  Organization,
+}
+
+/// A user or organization, by its original action hash
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct VfAgent {
+  pub id: ActionHash,
+  pub agent_type: VfAgentType,
+  pub name: String,
+}
+
+/// A service type, by its original action hash
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct VfServiceType {
+  pub id: ActionHash,
+  pub name: String,
+  pub description: Option<String>,
+}
+
+/// A medium of exchange, by its original action hash
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct VfMediumOfExchange {
+  pub id: ActionHash,
+  pub code: String,
+  pub name: String,
+  pub description: Option<String>,
+  /// Exported as the identifier of the medium when set, so that it matches hREA's
+  pub resource_spec_hrea_id: Option<String>,
+}
+
+/// A request or an offer, by its original action hash
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct VfListing {
+  pub id: ActionHash,
+  pub title: String,
+  pub description: String,
+  /// The organization the listing is made for, or else its author
+  pub agent_id: ActionHash,
+  pub service_type_ids: Vec<ActionHash>,
+  pub valuation: Option<Valuation>,
+  /// Whether the listing is fulfilled
+  pub finished: bool,
+}
+
+/// Everything exported to ValueFlows
+#[derive(Serialize, Deserialize, Debug, Clone, Default, PartialEq)]
+pub struct ValueFlowsExport {
+  pub agents: Vec<VfAgent>,
+  pub service_types: Vec<VfServiceType>,
+  pub mediums_of_exchange: Vec<VfMediumOfExchange>,
+  pub requests: Vec<VfListing>,
+  pub offers: Vec<VfListing>,
+}
+
+fn node_id(kind: &str, hash: &ActionHash) -> String {
+  format!("{}:{}:{}", ID_PREFIX, kind, hash)
+}
+
+fn reference(id: String) -> Value {
+  json!({ "@id": id })
+}
+
+/// One reference, or an array of them when there are several
+fn references(ids: Vec<String>) -> Value {
+  let mut references: Vec<Value> = ids.into_iter().map(reference).collect();
+  if references.len() == 1 {
+    references.remove(0)
+  } else {
+    Value::Array(references)
+  }
+}
+
+fn measure(amount: f64, unit: &str) -> Value {
+  json!({
+    "@type": "om2:Measure",
+    "om2:hasNumericalValue": amount,
+    "om2:hasUnit": { "@id": unit },
+  })
+}
+
+fn medium_of_exchange_id(export: &ValueFlowsExport, hash: &ActionHash) -> String {
+  export
+    .mediums_of_exchange
+    .iter()
+    .find(|medium| medium.id == *hash)
+    .and_then(|medium| medium.resource_spec_hrea_id.clone())
+    .unwrap_or_else(|| node_id("medium-of-exchange", hash))
+}
+
+/// Adds the optional fields of a node, skipping empty values
+fn with_optional(mut node: Value, fields: Vec<(&str, Option<Value>)>) -> Value {
+  if let Value::Object(map) = &mut node {
+    for (key, value) in fields {
+      if let Some(value) = value {
+        map.insert(key.to_string(), value);
+      }
+    }
+  }
+  node
+}
+
+/// Which side of its intents the author of a listing is on
+#[derive(Clone, Copy)]
+enum Purpose {
+  Request,
+  Offer,
+}
+
+impl Purpose {
+  fn name(self) -> &'static str {
+    match self {
+      Purpose::Request => "request",
+      Purpose::Offer => "offer",
+    }
+  }
+
+  /// The side of the author in the service: receiver of requests, provider of offers
+  fn service_side(self) -> &'static str {
+    match self {
+      Purpose::Request => "vf:receiver",
+      Purpose::Offer => "vf:provider",
+    }
+  }
+
+  /// The side of the author in the payment, the other one
+  fn payment_side(self) -> &'static str {
+    match self {
+      Purpose::Request => "vf:provider",
+      Purpose::Offer => "vf:receiver",
+    }
+  }
+}
+
+/// The intents of a listing, and its proposal when it is an offer or has a valuation
+fn listing_nodes(export: &ValueFlowsExport, listing: &VfListing, purpose: Purpose) -> Vec<Value> {
+  let agent = reference(node_id("agent", &listing.agent_id));
+  let intent_id = node_id("intent", &listing.id);
+
+  let mut intent = Map::new();
+  intent.insert("@id".to_string(), json!(intent_id));
+  intent.insert("@type".to_string(), json!("vf:Intent"));
+  intent.insert(
+    "vf:action".to_string(),
+    reference("vf:deliverService".to_string()),
+  );
+  intent.insert(purpose.service_side().to_string(), agent.clone());
+  intent.insert("vf:name".to_string(), json!(listing.title));
+  intent.insert("vf:note".to_string(), json!(listing.description));
+  intent.insert("vf:finished".to_string(), json!(listing.finished));
+  if !listing.service_type_ids.is_empty() {
+    intent.insert(
+      "vf:resourceConformsTo".to_string(),
+      references(
+        listing
+          .service_type_ids
+          .iter()
+          .map(|hash| node_id("service-type", hash))
+          .collect(),
+      ),
+    );
+  }
+
+  let mut nodes = Vec::new();
+  let mut reciprocal = None;
+  if let Some(valuation) = &listing.valuation {
+    // Unit based proposals pay the reciprocal quantity per unit of service
+    match valuation.unit {
+      ValuationUnit::PerHour => {
+        intent.insert("vf:resourceQuantity".to_string(), measure(1.0, "om2:hour"));
+      }
+      ValuationUnit::PerItem => {
+        intent.insert("vf:resourceQuantity".to_string(), measure(1.0, "om2:one"));
+      }
+      ValuationUnit::Fixed => {}
+    }
+
+    let reciprocal_id = format!("{}:reciprocal", intent_id);
+    let medium_id = medium_of_exchange_id(export, &valuation.medium_of_exchange_hash);
+    nodes.push(json!({
+      "@id": reciprocal_id,
+      "@type": "vf:Intent",
+      "vf:action": reference("vf:transfer".to_string()),
+      purpose.payment_side(): agent,
+      "vf:resourceConformsTo": reference(medium_id),
+      "vf:resourceQuantity": measure(
+        valuation.amount as f64 / MINOR_UNITS_PER_UNIT as f64,
+        "om2:one"
+      ),
+      "vf:finished": listing.finished,
+    }));
+    reciprocal = Some(reciprocal_id);
+  }
+  nodes.insert(0, Value::Object(intent));
+
+  if matches!(purpose, Purpose::Offer) || reciprocal.is_some() {
+    let unit_based = listing
+      .valuation
+      .as_ref()
+      .is_some_and(|valuation| valuation.unit != ValuationUnit::Fixed);
+    nodes.push(with_optional(
+      json!({
+        "@id": node_id("proposal", &listing.id),
+        "@type": "vf:Proposal",
+        "vf:name": listing.title,
+        "vf:purpose": purpose.name(),
+        "vf:unitBased": unit_based,
+        "vf:publishes": reference(intent_id),
+      }),
+      vec![("vf:reciprocal", reciprocal.map(reference))],
+    ));
+  }
+
+  nodes
+}
+
+/// Maps listings, service types, mediums of exchange and agents to a ValueFlows JSON-LD
+/// document
+pub fn valueflows_json_ld(export: &ValueFlowsExport) -> Value {
+  let mut graph: Vec<Value> = Vec::new();
+
+  for agent in &export.agents {
+    let agent_type = match agent.agent_type {
+      VfAgentType::Person => "vf:Person",
+      VfAgentType::Organization => "vf:Organization",
+    };
+    graph.push(json!({
+      "@id": node_id("agent", &agent.id),
+      "@type": agent_type,
+      "vf:name": agent.name,
+    }));
+  }
+
+  for service_type in &export.service_types {
+    graph.push(with_optional(
+      json!({
+        "@id": node_id("service-type", &service_type.id),
+        "@type": "vf:ResourceSpecification",
+        "vf:name": service_type.name,
+      }),
+      vec![("vf:note", service_type.description.clone().map(Value::from))],
+    ));
+  }
+
+  for medium in &export.mediums_of_exchange {
+    graph.push(with_optional(
+      json!({
+        "@id": medium_of_exchange_id(export, &medium.id),
+        "@type": "vf:ResourceSpecification",
+        "vf:name": medium.name,
+        "skos:notation": medium.code,
+      }),
+      vec![("vf:note", medium.description.clone().map(Value::from))],
+    ));
+  }
+
+  for request in &export.requests {
+    graph.extend(listing_nodes(export, request, Purpose::Request));
+  }
+  for offer in &export.offers {
+    graph.extend(listing_nodes(export, offer, Purpose::Offer));
+  }
+
+  json!({
+    "@context": {
+      "vf": "https://w3id.org/valueflows/ont/vf#",
+      "om2": "http://www.ontology-of-units-of-measure.org/resource/om-2/",
+      "skos": "http://www.w3.org/2004/02/skos/core#",
+    },
+    "@graph": graph,
+  })
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  fn hash(byte: u8) -> ActionHash {
+    ActionHash::from_raw_36(vec![byte; 36])
+  }
+
+  fn listing(id: u8, valuation: Option<Valuation>) -> VfListing {
+    VfListing {
+      id: hash(id),
+      title: "Garden help".to_string(),
+      description: "Weeding and planting".to_string(),
+      agent_id: hash(1),
+      service_type_ids: vec![hash(2)],
+      valuation,
+      finished: false,
+    }
+  }
+
+  fn node<'a>(document: &'a Value, id: &str) -> &'a Value {
+    document["@graph"]
+      .as_array()
+      .unwrap()
+      .iter()
+      .find(|node| node["@id"] == id)
+      .unwrap_or_else(|| panic!("no node {}", id))
+  }
+
+  #[test]
+  fn test_requests_are_intents_received_by_their_author() {
+    let export = ValueFlowsExport {
+      requests: vec![listing(5, None)],
+      ..Default::default()
+    };
+    let document = valueflows_json_ld(&export);
+
+    let intent = node(&document, &node_id("intent", &hash(5)));
+    assert_eq!(intent["@type"], "vf:Intent");
+    assert_eq!(intent["vf:receiver"]["@id"], node_id("agent", &hash(1)));
+    assert_eq!(
+      intent["vf:resourceConformsTo"]["@id"],
+      node_id("service-type", &hash(2))
+    );
+    assert!(intent.get("vf:provider").is_none());
+    // Unvalued requests have nothing to propose
+    assert_eq!(document["@graph"].as_array().unwrap().len(), 1);
+  }
+
+  #[test]
+  fn test_valued_offers_propose_a_reciprocal_payment() {
+    let export = ValueFlowsExport {
+      mediums_of_exchange: vec![VfMediumOfExchange {
+        id: hash(3),
+        code: "TIME".to_string(),
+        name: "Time Banking".to_string(),
+        description: None,
+        resource_spec_hrea_id: Some("hrea:time".to_string()),
+      }],
+      offers: vec![listing(
+        6,
+        Some(Valuation {
+          amount: 200,
+          unit: ValuationUnit::PerHour,
+          medium_of_exchange_hash: hash(3),
+        }),
+      )],
+      ..Default::default()
+    };
+    let document = valueflows_json_ld(&export);
+
+    let intent_id = node_id("intent", &hash(6));
+    let intent = node(&document, &intent_id);
+    assert_eq!(intent["vf:provider"]["@id"], node_id("agent", &hash(1)));
+    assert_eq!(
+      intent["vf:resourceQuantity"]["om2:hasUnit"]["@id"],
+      "om2:hour"
+    );
+
+    let reciprocal = node(&document, &format!("{}:reciprocal", intent_id));
+    assert_eq!(reciprocal["vf:action"]["@id"], "vf:transfer");
+    assert_eq!(reciprocal["vf:receiver"]["@id"], node_id("agent", &hash(1)));
+    assert_eq!(reciprocal["vf:resourceConformsTo"]["@id"], "hrea:time");
+    assert_eq!(
+      reciprocal["vf:resourceQuantity"]["om2:hasNumericalValue"],
+      2.0
+    );
+
+    let proposal = node(&document, &node_id("proposal", &hash(6)));
+    assert_eq!(proposal["vf:purpose"], "offer");
+    assert_eq!(proposal["vf:unitBased"], true);
+    assert_eq!(proposal["vf:publishes"]["@id"], intent_id);
+
+    let medium = node(&document, "hrea:time");
+    assert_eq!(medium["@type"], "vf:ResourceSpecification");
+    assert_eq!(medium["skos:notation"], "TIME");
+  }
+
+  #[test]
+  fn test_unvalued_offers_are_proposals_without_reciprocal() {
+    let export = ValueFlowsExport {
+      offers: vec![listing(7, None)],
+      ..Default::default()
+    };
+    let document = valueflows_json_ld(&export);
+
+    let proposal = node(&document, &node_id("proposal", &hash(7)));
+    assert_eq!(proposal["vf:unitBased"], false);
+    assert!(proposal.get("vf:reciprocal").is_none());
+  }
+}
@@ -0,0 +1,3 @@
+pub mod export;
+mod external_calls;
+pub mod json_ld;
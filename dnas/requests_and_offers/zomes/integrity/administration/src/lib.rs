@@ -1,5 +1,9 @@
 use hdi::prelude::*;
 use status::*;
+use utils::{
+  role_links::{check_administrator_proof, role_proof_from_tag},
+  DnaProperties,
+};
 
 pub mod status;
 mod tests;
@@ -61,27 +65,62 @@ pub fn validate_agent_joining(
   Ok(ValidateCallbackResult::Valid)
 }
 
+// ============================================================================
+// ADMINISTRATOR LINK AUTHORSHIP
+// ============================================================================
+
+/// Checks that an administrator link for the `administrators_path` of an entity is
+/// created by the progenitor, or by an administrator of that entity.
+///
+/// `get_links` is not available in validation, so an administrator proves their role
+/// with their own `AgentAdministrators` link to the same path, carried in the tag (see
+/// `utils::role_links`). Without a configured progenitor (dev mode) the network has no
+/// root of trust: any agent may bootstrap the first administrator, but integrity accepts
+/// no administrator proof.
+fn validate_administrator_link_author(
+  action: &CreateLink,
+  administrators_path: &AnyLinkableHash,
+) -> ExternResult<ValidateCallbackResult> {
+  match DnaProperties::get_progenitor_pubkey()? {
+    None => return Ok(ValidateCallbackResult::Valid),
+    Some(progenitor) if progenitor == action.author => return Ok(ValidateCallbackResult::Valid),
+    Some(_) => {}
+  }
+
+  let Some(proof) = role_proof_from_tag(&action.tag) else {
+    return Ok(ValidateCallbackResult::Invalid(
+      "Only the progenitor or an administrator can add administrators".to_string(),
+    ));
+  };
+  if let Err(error) = check_administrator_proof(
+    &proof,
+    administrators_path,
+    &action.author,
+    &action.prev_action,
+    action.timestamp,
+    &dna_info()?.zome_names,
+  )? {
+    return Ok(ValidateCallbackResult::Invalid(error));
+  }
+
+  Ok(ValidateCallbackResult::Valid)
+}
+
 // ============================================================================
 // AllAdministrators LINK VALIDATION
 // ============================================================================
 
 /// Validates `AllAdministrators` link creation.
 ///
-/// Authorization is enforced by the coordinator layer (`add_administrator` →
-/// `check_if_agent_is_administrator`). Integrity defaults to `Valid` unconditionally
-/// because `get_links` is not available in HDI 0.7.0 validation callbacks —
-/// dynamic admin-membership checks require DHT reads which cannot be performed here.
-///
-/// NOTE: `is_progenitor` is intentionally not called here. It would return `Valid`
-/// in both branches, making it dead code. If HDI ever exposes `get_links`, this
-/// function is the right place to add real membership enforcement.
+/// The link must start from the `"{entity}.administrators"` path that the author
+/// administers, see [`validate_administrator_link_author`].
 fn validate_create_link_all_administrators(
-  _action: CreateLink,
-  _base_address: AnyLinkableHash,
+  action: CreateLink,
+  base_address: AnyLinkableHash,
   _target_address: AnyLinkableHash,
   _tag: LinkTag,
 ) -> ExternResult<ValidateCallbackResult> {
-  Ok(ValidateCallbackResult::Valid)
+  validate_administrator_link_author(&action, &base_address)
 }
 
 /// Validates `AllAdministrators` link deletion.
@@ -106,23 +145,23 @@ fn validate_delete_link_all_administrators(
 
 /// Validates `AgentAdministrators` link creation.
 ///
-/// Same default-allow policy as [`validate_create_link_all_administrators`]:
-/// authorization is enforced by the coordinator layer. See that function's doc
-/// for the HDI 0.7.0 rationale.
+/// The link must point to the `"{entity}.administrators"` path that the author
+/// administers, see [`validate_administrator_link_author`]. Listings zomes accept these
+/// links as proof of an administrator's right to update someone else's listing.
 fn validate_create_link_agent_administrators(
-  _action: CreateLink,
+  action: CreateLink,
   _base_address: AnyLinkableHash,
-  _target_address: AnyLinkableHash,
+  target_address: AnyLinkableHash,
   _tag: LinkTag,
 ) -> ExternResult<ValidateCallbackResult> {
-  Ok(ValidateCallbackResult::Valid)
+  validate_administrator_link_author(&action, &target_address)
 }
 
 /// Validates `AgentAdministrators` link deletion.
 ///
-/// Authorization is enforced by the coordinator layer. Integrity returns `Valid`
-/// unconditionally because `get_links` is not available in HDI 0.7.0 validation
-/// callbacks.
+/// Any administrator can remove another one, which integrity cannot check because
+/// `get_links` is not available in HDI 0.7.0 validation callbacks: authorization is
+/// enforced by the coordinator layer. Integrity returns `Valid` unconditionally.
 fn validate_delete_link_agent_administrators(
   _action: DeleteLink,
   _original_action: CreateLink,
@@ -1 +1,2 @@
 mod status;
+mod zome_types;
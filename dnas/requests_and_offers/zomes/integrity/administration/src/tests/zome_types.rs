@@ -0,0 +1,13 @@
+#[cfg(test)]
+mod zome_types_tests {
+  use utils::role_links::AGENT_ADMINISTRATORS;
+
+  use crate::LinkTypes;
+
+  /// Other zomes reference the link types of this zome by index
+  #[test]
+  fn test_referenced_link_type_indices_match() {
+    assert_eq!(AGENT_ADMINISTRATORS.0, env!("CARGO_PKG_NAME"));
+    assert_eq!(LinkTypes::AgentAdministrators as u8, AGENT_ADMINISTRATORS.1);
+  }
+}
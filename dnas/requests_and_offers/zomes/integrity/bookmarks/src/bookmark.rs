@@ -0,0 +1,62 @@
+use hdi::prelude::*;
+
+/// What a user bookmarks
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub enum BookmarkTarget {
+  /// The original action hash of a request
+  Request(ActionHash),
+  /// The original action hash of an offer
+  Offer(ActionHash),
+  /// The original action hash of a user
+  User(ActionHash),
+  /// The original action hash of an organization
+  Organization(ActionHash),
+}
+
+impl BookmarkTarget {
+  pub fn hash(&self) -> &ActionHash {
+    match self {
+      BookmarkTarget::Request(hash)
+      | BookmarkTarget::Offer(hash)
+      | BookmarkTarget::User(hash)
+      | BookmarkTarget::Organization(hash) => hash,
+    }
+  }
+}
+
+/// Represents a Bookmark Entry: a listing, user or organization kept by an agent on
+/// their own source chain
+#[hdk_entry_helper]
+#[derive(Clone, PartialEq)]
+pub struct Bookmark {
+  pub target: BookmarkTarget,
+  /// Private notes about the target
+  pub notes: Option<String>,
+  /// The folder the bookmark is filed under, if any
+  pub folder: Option<String>,
+}
+
+pub fn validate_bookmark(bookmark: Bookmark) -> ExternResult<ValidateCallbackResult> {
+  if let Some(notes) = &bookmark.notes {
+    if notes.len() > 1000 {
+      return Ok(ValidateCallbackResult::Invalid(
+        "Bookmark notes cannot exceed 1000 characters".to_string(),
+      ));
+    }
+  }
+
+  if let Some(folder) = &bookmark.folder {
+    if folder.trim().is_empty() {
+      return Ok(ValidateCallbackResult::Invalid(
+        "Bookmark folder cannot be empty".to_string(),
+      ));
+    }
+    if folder.len() > 100 {
+      return Ok(ValidateCallbackResult::Invalid(
+        "Bookmark folder cannot exceed 100 characters".to_string(),
+      ));
+    }
+  }
+
+  Ok(ValidateCallbackResult::Valid)
+}
@@ -0,0 +1,46 @@
+use hdi::prelude::*;
+
+mod bookmark;
+pub use bookmark::*;
+
+#[derive(Serialize, Deserialize)]
+#[serde(tag = "type")]
+#[hdk_entry_types]
+#[unit_enum(UnitEntryTypes)]
+pub enum EntryTypes {
+  #[entry_type(visibility = "private")]
+  Bookmark(Bookmark),
+}
+
+#[hdk_extern]
+pub fn genesis_self_check(_data: GenesisSelfCheckData) -> ExternResult<ValidateCallbackResult> {
+  Ok(ValidateCallbackResult::Valid)
+}
+
+#[hdk_extern]
+pub fn validate(op: Op) -> ExternResult<ValidateCallbackResult> {
+  match op.flattened::<EntryTypes, ()>()? {
+    FlatOp::StoreEntry(OpEntry::CreateEntry { app_entry, .. })
+    | FlatOp::StoreRecord(OpRecord::CreateEntry { app_entry, .. }) => match app_entry {
+      EntryTypes::Bookmark(bookmark) => validate_bookmark(bookmark),
+    },
+    FlatOp::StoreEntry(OpEntry::UpdateEntry { app_entry, .. })
+    | FlatOp::StoreRecord(OpRecord::UpdateEntry { app_entry, .. }) => match app_entry {
+      EntryTypes::Bookmark(bookmark) => validate_bookmark(bookmark),
+    },
+    FlatOp::StoreRecord(OpRecord::DeleteEntry {
+      original_action_hash,
+      action,
+      ..
+    }) => {
+      let original_record = must_get_valid_record(original_action_hash)?;
+      if *original_record.action().author() != action.author {
+        return Ok(ValidateCallbackResult::Invalid(
+          "Only the owner can delete a bookmark".to_string(),
+        ));
+      }
+      Ok(ValidateCallbackResult::Valid)
+    }
+    _ => Ok(ValidateCallbackResult::Valid),
+  }
+}
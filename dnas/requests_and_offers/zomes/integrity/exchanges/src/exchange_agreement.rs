@@ -0,0 +1,192 @@
+use hdi::prelude::*;
+use utils::role_links::{must_get_create_of, USER_ENTRY};
+
+use crate::UnitEntryTypes;
+
+/// One side of an exchange
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct ExchangeParty {
+  /// The agent who signs the agreement
+  pub agent: AgentPubKey,
+  /// The original action hash of the agent's user profile
+  pub user_hash: ActionHash,
+  /// The original action hash of the organization the agent acts for, if any
+  pub organization_hash: Option<ActionHash>,
+}
+
+/// What the parties agreed to exchange
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub enum AgreedAmount {
+  /// A number of hours of service
+  Hours(f64),
+  /// A quantity of the medium of exchange
+  Quantity(f64),
+}
+
+/// Represents an ExchangeAgreement Entry: the record of a request being answered by an
+/// offer, countersigned by both parties
+#[hdk_entry_helper]
+#[derive(Clone, PartialEq)]
+pub struct ExchangeAgreement {
+  /// The original action hash of the request
+  pub request_hash: ActionHash,
+  /// The original action hash of the offer
+  pub offer_hash: ActionHash,
+  /// The author of the request, who receives the service
+  pub requester: ExchangeParty,
+  /// The author of the offer, who provides the service
+  pub provider: ExchangeParty,
+  /// The original action hash of the service type exchanged
+  pub service_type_hash: ActionHash,
+  /// The original action hash of the medium of exchange used
+  pub medium_of_exchange_hash: ActionHash,
+  /// The agreed quantity or hours
+  pub amount: AgreedAmount,
+}
+
+/// Validates an exchange agreement entry
+pub fn validate_exchange_agreement(
+  agreement: &ExchangeAgreement,
+) -> ExternResult<ValidateCallbackResult> {
+  if agreement.requester.agent == agreement.provider.agent {
+    return Ok(ValidateCallbackResult::Invalid(
+      "The requester and the provider must be different agents".to_string(),
+    ));
+  }
+
+  let (AgreedAmount::Hours(amount) | AgreedAmount::Quantity(amount)) = agreement.amount;
+  if !amount.is_finite() || amount <= 0.0 {
+    return Ok(ValidateCallbackResult::Invalid(
+      "The agreed amount must be greater than zero".to_string(),
+    ));
+  }
+
+  let zome_names = dna_info()?.zome_names;
+  for party in [&agreement.requester, &agreement.provider] {
+    if let Err(error) = validate_party_user(party, &zome_names)? {
+      return Ok(ValidateCallbackResult::Invalid(error));
+    }
+  }
+
+  Ok(ValidateCallbackResult::Valid)
+}
+
+/// Checks that the user profile of a party belongs to its agent. A profile's `UserAgents`
+/// link can only point to the profile's author, so the agent must be that author.
+fn validate_party_user(
+  party: &ExchangeParty,
+  zome_names: &[ZomeName],
+) -> ExternResult<Result<(), String>> {
+  let user = match must_get_create_of(&party.user_hash.clone().into(), USER_ENTRY, zome_names)? {
+    Ok(user) => user,
+    Err(error) => return Ok(Err(error)),
+  };
+  if user.author != party.agent {
+    return Ok(Err(
+      "The user profile of a party must belong to its agent".to_string(),
+    ));
+  }
+
+  Ok(Ok(()))
+}
+
+/// Validates that an exchange agreement is committed in a countersigning session
+/// signed by exactly its two parties
+pub fn validate_countersigned_exchange_agreement(
+  agreement: &ExchangeAgreement,
+  session_data: Option<&CounterSigningSessionData>,
+) -> ExternResult<ValidateCallbackResult> {
+  let Some(session_data) = session_data else {
+    return Ok(ValidateCallbackResult::Invalid(
+      "An exchange agreement must be countersigned".to_string(),
+    ));
+  };
+
+  let signing_agents: Vec<&AgentPubKey> = session_data
+    .preflight_request()
+    .signing_agents
+    .iter()
+    .map(|(agent, _)| agent)
+    .collect();
+  let signed_by_parties = signing_agents.len() == 2
+    && signing_agents.contains(&&agreement.requester.agent)
+    && signing_agents.contains(&&agreement.provider.agent);
+  if !signed_by_parties {
+    return Ok(ValidateCallbackResult::Invalid(
+      "An exchange agreement must be signed by the requester and the provider only".to_string(),
+    ));
+  }
+
+  validate_exchange_agreement(agreement)
+}
+
+/// Reads an exchange agreement out of an entry, which is normally countersigned
+pub fn exchange_agreement_from_entry(entry: &Entry) -> ExternResult<Option<ExchangeAgreement>> {
+  match entry {
+    Entry::CounterSign(_, app_entry_bytes) | Entry::App(app_entry_bytes) => {
+      Ok(ExchangeAgreement::try_from(app_entry_bytes.clone().into_sb()).ok())
+    }
+    _ => Ok(None),
+  }
+}
+
+/// Fetches the exchange agreement committed by `author` in `agreement_action_hash`, with
+/// entry hash `exchange_hash`, or returns why it cannot be used
+pub fn must_get_own_agreement(
+  agreement_action_hash: &ActionHash,
+  exchange_hash: &EntryHash,
+  author: &AgentPubKey,
+) -> ExternResult<Result<ExchangeAgreement, String>> {
+  let agreement_record = must_get_valid_record(agreement_action_hash.clone())?;
+  let agreement_type: EntryType = UnitEntryTypes::ExchangeAgreement.try_into()?;
+  if agreement_record.action().entry_type() != Some(&agreement_type)
+    || agreement_record.action().entry_hash() != Some(exchange_hash)
+  {
+    return Ok(Err("The exchange agreement could not be found".to_string()));
+  }
+  if agreement_record.action().author() != author {
+    return Ok(Err(
+      "The exchange agreement must be the one on the author's own chain".to_string(),
+    ));
+  }
+
+  Ok(
+    agreement_record
+      .entry()
+      .as_option()
+      .map(exchange_agreement_from_entry)
+      .transpose()?
+      .flatten()
+      .ok_or("The exchange agreement could not be found".to_string()),
+  )
+}
+
+/// The entries of type `entry_type` on the chain of the author of `action`, from the
+/// agreement committed in `agreement_action_hash` up to `action`
+pub(crate) fn entries_since_agreement<T>(
+  action: &Create,
+  agreement_action_hash: &ActionHash,
+  entry_type: &EntryType,
+) -> ExternResult<Vec<T>>
+where
+  T: TryFrom<SerializedBytes, Error = SerializedBytesError>,
+{
+  let chain_filter =
+    ChainFilter::new(action.prev_action.clone()).until_hash(agreement_action_hash.clone());
+
+  let mut entries = Vec::new();
+  for activity in must_get_agent_activity(action.author.clone(), chain_filter)? {
+    if activity.action.hashed.content.entry_type() != Some(entry_type) {
+      continue;
+    }
+    if let Some(entry) = must_get_valid_record(activity.action.hashed.hash)?
+      .entry()
+      .to_app_option::<T>()
+      .map_err(|e| wasm_error!(e))?
+    {
+      entries.push(entry);
+    }
+  }
+
+  Ok(entries)
+}
@@ -0,0 +1,88 @@
+use hdi::prelude::*;
+
+use crate::{entries_since_agreement, must_get_own_agreement, UnitEntryTypes};
+
+/// Represents an ExchangeCompletion Entry: a party's confirmation that an agreed exchange
+/// took place. The parties can review each other once both have confirmed it.
+#[hdk_entry_helper]
+#[derive(Clone, PartialEq)]
+pub struct ExchangeCompletion {
+  /// The entry hash of the exchange agreement
+  pub exchange_hash: EntryHash,
+  /// The action committing the exchange agreement on the confirming party's chain
+  pub agreement_action_hash: ActionHash,
+}
+
+/// Reads the exchange completion out of a record, if it holds one
+pub fn exchange_completion_from_record(
+  record: &Record,
+) -> ExternResult<Option<ExchangeCompletion>> {
+  let completion_type: EntryType = UnitEntryTypes::ExchangeCompletion.try_into()?;
+  if record.action().entry_type() != Some(&completion_type) {
+    return Ok(None);
+  }
+  record.entry().to_app_option().map_err(|e| wasm_error!(e))
+}
+
+/// Validates a new exchange completion.
+///
+/// The completion must reference the exchange agreement as committed on the confirming
+/// party's own chain, and each party can confirm an exchange once.
+pub fn validate_create_exchange_completion(
+  action: &Create,
+  completion: ExchangeCompletion,
+) -> ExternResult<ValidateCallbackResult> {
+  if let Err(error) = must_get_own_agreement(
+    &completion.agreement_action_hash,
+    &completion.exchange_hash,
+    &action.author,
+  )? {
+    return Ok(ValidateCallbackResult::Invalid(error));
+  }
+
+  let completions: Vec<ExchangeCompletion> = entries_since_agreement(
+    action,
+    &completion.agreement_action_hash,
+    &UnitEntryTypes::ExchangeCompletion.try_into()?,
+  )?;
+  if completions
+    .iter()
+    .any(|earlier| earlier.exchange_hash == completion.exchange_hash)
+  {
+    return Ok(ValidateCallbackResult::Invalid(
+      "Each party can confirm an exchange only once".to_string(),
+    ));
+  }
+
+  Ok(ValidateCallbackResult::Valid)
+}
+
+/// Validates a link from an exchange agreement to a completion of it. Only the party who
+/// confirmed the exchange can link their completion.
+pub fn validate_create_link_exchange_completion(
+  action: &CreateLink,
+) -> ExternResult<ValidateCallbackResult> {
+  let Some(completion_hash) = action.target_address.clone().into_action_hash() else {
+    return Ok(ValidateCallbackResult::Invalid(
+      "A completion link must point to an exchange completion".to_string(),
+    ));
+  };
+  let completion_record = must_get_valid_record(completion_hash)?;
+  let Some(completion) = exchange_completion_from_record(&completion_record)? else {
+    return Ok(ValidateCallbackResult::Invalid(
+      "A completion link must point to an exchange completion".to_string(),
+    ));
+  };
+  if *completion_record.action().author() != action.author {
+    return Ok(ValidateCallbackResult::Invalid(
+      "Only the confirming party can link an exchange completion".to_string(),
+    ));
+  }
+  if action.base_address != completion.exchange_hash.into() {
+    return Ok(ValidateCallbackResult::Invalid(
+      "A completion link must start from the exchange it confirms".to_string(),
+    ));
+  }
+
+  Ok(ValidateCallbackResult::Valid)
+}
@@ -0,0 +1,115 @@
+use hdi::prelude::*;
+
+mod exchange_agreement;
+mod exchange_completion;
+mod review;
+pub use exchange_agreement::*;
+pub use exchange_completion::*;
+pub use review::*;
+mod tests;
+
+#[derive(Serialize, Deserialize)]
+#[serde(tag = "type")]
+#[hdk_entry_types]
+#[unit_enum(UnitEntryTypes)]
+pub enum EntryTypes {
+  ExchangeAgreement(ExchangeAgreement),
+  Review(Review),
+  ExchangeCompletion(ExchangeCompletion),
+}
+
+/// Agreement index links target the entry hash of an agreement, which is the same on
+/// both parties' chains
+#[derive(Serialize, Deserialize)]
+#[hdk_link_types]
+pub enum LinkTypes {
+  RequestAgreements,
+  OfferAgreements,
+  UserAgreements,
+  OrganizationAgreements,
+  AgreementReviews,
+  UserReviews,
+  AgreementCompletions,
+}
+
+#[hdk_extern]
+pub fn genesis_self_check(_data: GenesisSelfCheckData) -> ExternResult<ValidateCallbackResult> {
+  Ok(ValidateCallbackResult::Valid)
+}
+
+/// The countersigning session an op's entry was committed in, if any
+fn countersigning_session_data(op: &Op) -> Option<&CounterSigningSessionData> {
+  let entry = match op {
+    Op::StoreRecord(StoreRecord { record }) => record.entry().as_option(),
+    Op::StoreEntry(StoreEntry { entry, .. }) => Some(entry),
+    _ => None,
+  };
+  match entry {
+    Some(Entry::CounterSign(session_data, _)) => Some(session_data),
+    _ => None,
+  }
+}
+
+#[hdk_extern]
+pub fn validate(op: Op) -> ExternResult<ValidateCallbackResult> {
+  let session_data = countersigning_session_data(&op);
+
+  match op.flattened::<EntryTypes, LinkTypes>()? {
+    FlatOp::StoreEntry(OpEntry::CreateEntry { app_entry, action })
+    | FlatOp::StoreRecord(OpRecord::CreateEntry { app_entry, action }) => match app_entry {
+      EntryTypes::ExchangeAgreement(agreement) => {
+        validate_countersigned_exchange_agreement(&agreement, session_data)
+      }
+      EntryTypes::Review(review) => validate_create_review(&action, review),
+      EntryTypes::ExchangeCompletion(completion) => {
+        validate_create_exchange_completion(&action, completion)
+      }
+    },
+    FlatOp::StoreEntry(OpEntry::UpdateEntry { app_entry, .. })
+    | FlatOp::StoreRecord(OpRecord::UpdateEntry { app_entry, .. }) => match app_entry {
+      EntryTypes::ExchangeAgreement(_) => Ok(ValidateCallbackResult::Invalid(
+        "Exchange agreements cannot be updated".to_string(),
+      )),
+      EntryTypes::Review(_) => Ok(ValidateCallbackResult::Invalid(
+        "Reviews cannot be updated".to_string(),
+      )),
+      EntryTypes::ExchangeCompletion(_) => Ok(ValidateCallbackResult::Invalid(
+        "Exchange completions cannot be updated".to_string(),
+      )),
+    },
+    FlatOp::RegisterCreateLink {
+      link_type, action, ..
+    } => match link_type {
+      LinkTypes::AgreementReviews => {
+        validate_create_link_review(&action, |review| review.exchange_hash.clone().into())
+      }
+      LinkTypes::UserReviews => {
+        validate_create_link_review(&action, |review| review.reviewee_hash.clone().into())
+      }
+      LinkTypes::AgreementCompletions => validate_create_link_exchange_completion(&action),
+      _ => Ok(ValidateCallbackResult::Valid),
+    },
+    FlatOp::StoreRecord(OpRecord::DeleteEntry {
+      original_action_hash,
+      ..
+    }) => {
+      let original_record = must_get_valid_record(original_action_hash)?;
+      let agreement_type: EntryType = UnitEntryTypes::ExchangeAgreement.try_into()?;
+      let review_type: EntryType = UnitEntryTypes::Review.try_into()?;
+      let completion_type: EntryType = UnitEntryTypes::ExchangeCompletion.try_into()?;
+      match original_record.action().entry_type() {
+        Some(entry_type) if *entry_type == agreement_type => Ok(ValidateCallbackResult::Invalid(
+          "Exchange agreements cannot be deleted".to_string(),
+        )),
+        Some(entry_type) if *entry_type == review_type => Ok(ValidateCallbackResult::Invalid(
+          "Reviews cannot be deleted".to_string(),
+        )),
+        Some(entry_type) if *entry_type == completion_type => Ok(ValidateCallbackResult::Invalid(
+          "Exchange completions cannot be deleted".to_string(),
+        )),
+        _ => Ok(ValidateCallbackResult::Valid),
+      }
+    }
+    _ => Ok(ValidateCallbackResult::Valid),
+  }
+}
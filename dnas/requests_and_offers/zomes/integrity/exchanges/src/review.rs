@@ -0,0 +1,172 @@
+use hdi::prelude::*;
+
+use crate::{
+  entries_since_agreement, exchange_completion_from_record, must_get_own_agreement,
+  ExchangeAgreement, ExchangeCompletion, ExchangeParty, UnitEntryTypes,
+};
+
+pub const MIN_RATING: u8 = 1;
+pub const MAX_RATING: u8 = 5;
+
+/// Represents a Review Entry: one party's assessment of the other after an exchange
+#[hdk_entry_helper]
+#[derive(Clone, PartialEq)]
+pub struct Review {
+  /// The entry hash of the exchange agreement being reviewed
+  pub exchange_hash: EntryHash,
+  /// The action committing the exchange agreement on the reviewer's chain
+  pub agreement_action_hash: ActionHash,
+  /// The original action hash of the reviewee's user profile
+  pub reviewee_hash: ActionHash,
+  /// The reviewee's confirmation that the exchange is complete
+  pub counterpart_completion_hash: ActionHash,
+  /// The rating, from 1 to 5
+  pub rating: u8,
+  /// The review text
+  pub comment: String,
+}
+
+/// Validates a review entry
+pub fn validate_review(review: &Review) -> ExternResult<ValidateCallbackResult> {
+  if !(MIN_RATING..=MAX_RATING).contains(&review.rating) {
+    return Ok(ValidateCallbackResult::Invalid(format!(
+      "The rating must be between {} and {}",
+      MIN_RATING, MAX_RATING
+    )));
+  }
+
+  // Validate comment length (1000 character limit)
+  if review.comment.len() > 1000 {
+    return Ok(ValidateCallbackResult::Invalid(
+      "Review comment cannot exceed 1000 characters".to_string(),
+    ));
+  }
+
+  Ok(ValidateCallbackResult::Valid)
+}
+
+/// Validates a new review.
+///
+/// The review must reference the exchange agreement as committed on the reviewer's own
+/// chain, which makes the reviewer one of its parties, and the reviewee must be the
+/// other party. The exchange must be complete: the reviewer's chain since the agreement
+/// must hold their confirmation, and the review must reference the reviewee's. Each
+/// party can review an exchange once: the reviewer's chain since the agreement must not
+/// hold another review of it.
+pub fn validate_create_review(
+  action: &Create,
+  review: Review,
+) -> ExternResult<ValidateCallbackResult> {
+  let agreement = match must_get_own_agreement(
+    &review.agreement_action_hash,
+    &review.exchange_hash,
+    &action.author,
+  )? {
+    Ok(agreement) => agreement,
+    Err(error) => return Ok(ValidateCallbackResult::Invalid(error)),
+  };
+
+  let reviewee = match reviewee_of(&agreement, &action.author) {
+    None => {
+      return Ok(ValidateCallbackResult::Invalid(
+        "Only a party to the exchange can review it".to_string(),
+      ))
+    }
+    Some(reviewee) if reviewee.user_hash != review.reviewee_hash => {
+      return Ok(ValidateCallbackResult::Invalid(
+        "The reviewee must be the other party to the exchange".to_string(),
+      ))
+    }
+    Some(reviewee) => reviewee,
+  };
+
+  let completions: Vec<ExchangeCompletion> = entries_since_agreement(
+    action,
+    &review.agreement_action_hash,
+    &UnitEntryTypes::ExchangeCompletion.try_into()?,
+  )?;
+  if !completions
+    .iter()
+    .any(|completion| completion.exchange_hash == review.exchange_hash)
+  {
+    return Ok(ValidateCallbackResult::Invalid(
+      "The reviewer must confirm the exchange is complete before reviewing it".to_string(),
+    ));
+  }
+
+  let counterpart_completion_record =
+    must_get_valid_record(review.counterpart_completion_hash.clone())?;
+  let counterpart_completed = *counterpart_completion_record.action().author() == reviewee.agent
+    && exchange_completion_from_record(&counterpart_completion_record)?
+      .is_some_and(|completion| completion.exchange_hash == review.exchange_hash);
+  if !counterpart_completed {
+    return Ok(ValidateCallbackResult::Invalid(
+      "The reviewee must confirm the exchange is complete before it can be reviewed".to_string(),
+    ));
+  }
+
+  let reviews: Vec<Review> = entries_since_agreement(
+    action,
+    &review.agreement_action_hash,
+    &UnitEntryTypes::Review.try_into()?,
+  )?;
+  if reviews
+    .iter()
+    .any(|earlier_review| earlier_review.exchange_hash == review.exchange_hash)
+  {
+    return Ok(ValidateCallbackResult::Invalid(
+      "Each party can review an exchange only once".to_string(),
+    ));
+  }
+
+  validate_review(&review)
+}
+
+/// Validates a link from an exchange agreement, or from the reviewee's user profile, to a
+/// review. Only the reviewer can link their review, from the base it names.
+pub fn validate_create_link_review(
+  action: &CreateLink,
+  expected_base: fn(&Review) -> AnyLinkableHash,
+) -> ExternResult<ValidateCallbackResult> {
+  let Some(review_hash) = action.target_address.clone().into_action_hash() else {
+    return Ok(ValidateCallbackResult::Invalid(
+      "A review link must point to a review".to_string(),
+    ));
+  };
+  let review_record = must_get_valid_record(review_hash)?;
+  let Some(review) = review_record
+    .entry()
+    .to_app_option::<Review>()
+    .map_err(|e| wasm_error!(e))?
+  else {
+    return Ok(ValidateCallbackResult::Invalid(
+      "A review link must point to a review".to_string(),
+    ));
+  };
+  if *review_record.action().author() != action.author {
+    return Ok(ValidateCallbackResult::Invalid(
+      "Only the reviewer can link a review".to_string(),
+    ));
+  }
+  if action.base_address != expected_base(&review) {
+    return Ok(ValidateCallbackResult::Invalid(
+      "A review link must start from the exchange or the reviewee of the review".to_string(),
+    ));
+  }
+
+  Ok(ValidateCallbackResult::Valid)
+}
+
+/// The party reviewed by `reviewer`, if the reviewer is a party to the agreement
+fn reviewee_of<'a>(
+  agreement: &'a ExchangeAgreement,
+  reviewer: &AgentPubKey,
+) -> Option<&'a ExchangeParty> {
+  if *reviewer == agreement.requester.agent {
+    Some(&agreement.provider)
+  } else if *reviewer == agreement.provider.agent {
+    Some(&agreement.requester)
+  } else {
+    None
+  }
+}
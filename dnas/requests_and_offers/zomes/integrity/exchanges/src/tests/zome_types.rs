@@ -0,0 +1,16 @@
+#[cfg(test)]
+mod zome_types_tests {
+  use utils::role_links::EXCHANGE_AGREEMENT_ENTRY;
+
+  use crate::UnitEntryTypes;
+
+  /// Other zomes reference the entry types of this zome by index
+  #[test]
+  fn test_referenced_type_indices_match() {
+    assert_eq!(EXCHANGE_AGREEMENT_ENTRY.0, env!("CARGO_PKG_NAME"));
+    assert_eq!(
+      UnitEntryTypes::ExchangeAgreement as u8,
+      EXCHANGE_AGREEMENT_ENTRY.1
+    );
+  }
+}
@@ -0,0 +1,102 @@
+use hdi::prelude::*;
+use utils::role_links::{is_entry_type_of, EXCHANGE_AGREEMENT_ENTRY, MEDIUM_OF_EXCHANGE_ENTRY};
+
+use crate::to_minor_units;
+
+/// One side of an `ExchangeAgreement` from `exchanges_integrity`
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct AgreementParty {
+  pub agent: AgentPubKey,
+  pub user_hash: ActionHash,
+}
+
+/// The amount of an `ExchangeAgreement` from `exchanges_integrity`
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub enum AgreedAmount {
+  Hours(f64),
+  Quantity(f64),
+}
+
+impl AgreedAmount {
+  /// The amount in minor units of the medium of exchange: hours are the units of time
+  /// banks. None when the amount cannot be represented.
+  pub fn minor_units(&self) -> Option<i64> {
+    let (AgreedAmount::Hours(value) | AgreedAmount::Quantity(value)) = self;
+    to_minor_units(*value)
+  }
+}
+
+/// The fields of an `ExchangeAgreement` from `exchanges_integrity` that the ledger settles.
+/// The ledger cannot depend on the exchanges zome, so they are read from the agreement
+/// entry directly.
+#[derive(Serialize, Deserialize, SerializedBytes, Debug, Clone, PartialEq)]
+pub struct AgreementTerms {
+  /// The author of the request, who receives the service and pays for it
+  pub requester: AgreementParty,
+  /// The author of the offer, who provides the service and is paid for it
+  pub provider: AgreementParty,
+  pub medium_of_exchange_hash: ActionHash,
+  pub amount: AgreedAmount,
+}
+
+/// Reads a party's record of a countersigned exchange agreement, with the terms of the
+/// agreement, or None when the action does not commit one
+pub fn must_get_agreement_terms(
+  agreement_action_hash: &ActionHash,
+) -> ExternResult<Option<(Create, AgreementTerms)>> {
+  let record = must_get_valid_record(agreement_action_hash.clone())?;
+  let Action::Create(create) = record.action().clone() else {
+    return Ok(None);
+  };
+  if !is_entry_type_of(
+    &create.entry_type,
+    EXCHANGE_AGREEMENT_ENTRY,
+    &dna_info()?.zome_names,
+  ) {
+    return Ok(None);
+  }
+
+  match record.entry().as_option() {
+    Some(Entry::CounterSign(_, app_entry_bytes)) => Ok(
+      AgreementTerms::try_from(app_entry_bytes.clone().into_sb())
+        .ok()
+        .map(|terms| (create, terms)),
+    ),
+    _ => Ok(None),
+  }
+}
+
+/// The fields of a `MediumOfExchange` from `mediums_of_exchange_integrity` that the
+/// ledger needs
+#[derive(Serialize, Deserialize, SerializedBytes, Debug, Clone, PartialEq)]
+pub struct LedgerMedium {
+  pub code: String,
+  /// Only "base" mediums of exchange such as TIME have ledger balances
+  pub exchange_type: String,
+}
+
+/// Reads the original version of a medium of exchange, or None when the action does not
+/// create one
+pub fn must_get_ledger_medium(
+  medium_of_exchange_hash: &ActionHash,
+) -> ExternResult<Option<LedgerMedium>> {
+  let record = must_get_valid_record(medium_of_exchange_hash.clone())?;
+  let is_medium_of_exchange = match record.action() {
+    Action::Create(create) => is_entry_type_of(
+      &create.entry_type,
+      MEDIUM_OF_EXCHANGE_ENTRY,
+      &dna_info()?.zome_names,
+    ),
+    _ => false,
+  };
+  if !is_medium_of_exchange {
+    return Ok(None);
+  }
+
+  match record.entry().as_option() {
+    Some(Entry::App(app_entry_bytes)) => {
+      Ok(LedgerMedium::try_from(app_entry_bytes.clone().into_sb()).ok())
+    }
+    _ => Ok(None),
+  }
+}
@@ -0,0 +1,383 @@
+use hdi::prelude::*;
+pub use utils::valuation::MINOR_UNITS_PER_UNIT;
+use utils::DnaProperties;
+
+use crate::{must_get_agreement_terms, must_get_ledger_medium, UnitEntryTypes};
+
+/// How far below zero a balance can go in a medium of exchange without a credit limit in
+/// the DNA properties, in minor units
+pub const DEFAULT_CREDIT_LIMIT: i64 = 20 * MINOR_UNITS_PER_UNIT;
+
+/// Converts an amount in units of a medium of exchange to minor units, rounded to the
+/// nearest one. None when the amount cannot be represented.
+pub fn to_minor_units(value: f64) -> Option<i64> {
+  let minor_units = (value * MINOR_UNITS_PER_UNIT as f64).round();
+  (minor_units.is_finite() && minor_units.abs() < i64::MAX as f64).then_some(minor_units as i64)
+}
+
+/// One side of a ledger transaction
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct LedgerParty {
+  /// The agent who signs the transaction
+  pub agent: AgentPubKey,
+  /// The original action hash of the agent's user profile
+  pub user_hash: ActionHash,
+}
+
+/// Represents a LedgerTransaction Entry: the settlement of an exchange agreement in a
+/// mutual-credit medium of exchange, countersigned by both parties. It is a debit on the
+/// payer's chain and the matching credit on the payee's.
+#[hdk_entry_helper]
+#[derive(Clone, PartialEq)]
+pub struct LedgerTransaction {
+  /// The entry hash of the exchange agreement settled
+  pub agreement_hash: EntryHash,
+  /// A party's countersigned record of the agreement, which confirms the exchange
+  pub agreement_action_hash: ActionHash,
+  /// The original action hash of the medium of exchange
+  pub medium_of_exchange_hash: ActionHash,
+  /// The requester of the agreement, debited
+  pub payer: LedgerParty,
+  /// The provider of the agreement, credited
+  pub payee: LedgerParty,
+  /// In minor units of the medium of exchange
+  pub amount: i64,
+}
+
+impl LedgerTransaction {
+  /// How the transaction changes an agent's balance in a medium of exchange
+  pub fn balance_change(&self, agent: &AgentPubKey, medium_of_exchange_hash: &ActionHash) -> i64 {
+    if self.medium_of_exchange_hash != *medium_of_exchange_hash {
+      0
+    } else if self.payee.agent == *agent {
+      self.amount
+    } else if self.payer.agent == *agent {
+      -self.amount
+    } else {
+      0
+    }
+  }
+}
+
+/// The balance of an agent in a medium of exchange after some transactions
+pub fn balance_of(
+  agent: &AgentPubKey,
+  medium_of_exchange_hash: &ActionHash,
+  transactions: &[LedgerTransaction],
+) -> i64 {
+  transactions
+    .iter()
+    .map(|transaction| transaction.balance_change(agent, medium_of_exchange_hash))
+    .sum()
+}
+
+/// The credit limit of a medium of exchange in minor units, from the DNA properties
+pub fn credit_limit(medium_of_exchange_code: &str) -> ExternResult<i64> {
+  let properties = DnaProperties::get()?;
+  match properties
+    .credit_limits
+    .get(medium_of_exchange_code)
+    .copied()
+    .or(properties.default_credit_limit)
+  {
+    Some(limit) => to_minor_units(limit).ok_or(wasm_error!(WasmErrorInner::Guest(format!(
+      "Invalid credit limit for {}",
+      medium_of_exchange_code
+    )))),
+    None => Ok(DEFAULT_CREDIT_LIMIT),
+  }
+}
+
+/// Reads a ledger transaction out of an entry, which is normally countersigned
+pub fn ledger_transaction_from_entry(entry: &Entry) -> ExternResult<Option<LedgerTransaction>> {
+  match entry {
+    Entry::CounterSign(_, app_entry_bytes) | Entry::App(app_entry_bytes) => {
+      Ok(LedgerTransaction::try_from(app_entry_bytes.clone().into_sb()).ok())
+    }
+    _ => Ok(None),
+  }
+}
+
+/// Validates that a transaction settles an exchange agreement exactly, in a base medium
+/// of exchange
+pub fn validate_ledger_transaction(
+  transaction: &LedgerTransaction,
+) -> ExternResult<ValidateCallbackResult> {
+  if transaction.payer.agent == transaction.payee.agent {
+    return Ok(ValidateCallbackResult::Invalid(
+      "The payer and the payee must be different agents".to_string(),
+    ));
+  }
+  if transaction.amount <= 0 {
+    return Ok(ValidateCallbackResult::Invalid(
+      "The transaction amount must be greater than zero".to_string(),
+    ));
+  }
+
+  let Some((agreement, terms)) = must_get_agreement_terms(&transaction.agreement_action_hash)?
+  else {
+    return Ok(ValidateCallbackResult::Invalid(
+      "A ledger transaction must settle a countersigned exchange agreement".to_string(),
+    ));
+  };
+  if agreement.entry_hash != transaction.agreement_hash {
+    return Ok(ValidateCallbackResult::Invalid(
+      "A ledger transaction must reference the record of the agreement it settles".to_string(),
+    ));
+  }
+  let settles_agreement = terms.requester.agent == transaction.payer.agent
+    && terms.requester.user_hash == transaction.payer.user_hash
+    && terms.provider.agent == transaction.payee.agent
+    && terms.provider.user_hash == transaction.payee.user_hash
+    && terms.medium_of_exchange_hash == transaction.medium_of_exchange_hash
+    && terms.amount.minor_units() == Some(transaction.amount);
+  if !settles_agreement {
+    return Ok(ValidateCallbackResult::Invalid(
+      "A ledger transaction must have the parties, medium and amount of its agreement".to_string(),
+    ));
+  }
+
+  match must_get_ledger_medium(&transaction.medium_of_exchange_hash)? {
+    Some(medium) if medium.exchange_type == "base" => Ok(ValidateCallbackResult::Valid),
+    Some(_) => Ok(ValidateCallbackResult::Invalid(
+      "Only base mediums of exchange have a ledger".to_string(),
+    )),
+    None => Ok(ValidateCallbackResult::Invalid(
+      "A ledger transaction must be in the original action of a medium of exchange".to_string(),
+    )),
+  }
+}
+
+/// Validates that a transaction is committed in a countersigning session signed by
+/// exactly its two parties
+pub fn validate_countersigned_ledger_transaction(
+  transaction: &LedgerTransaction,
+  session_data: Option<&CounterSigningSessionData>,
+) -> ExternResult<ValidateCallbackResult> {
+  let Some(session_data) = session_data else {
+    return Ok(ValidateCallbackResult::Invalid(
+      "A ledger transaction must be countersigned".to_string(),
+    ));
+  };
+
+  let signing_agents: Vec<&AgentPubKey> = session_data
+    .preflight_request()
+    .signing_agents
+    .iter()
+    .map(|(agent, _)| agent)
+    .collect();
+  let signed_by_parties = signing_agents.len() == 2
+    && signing_agents.contains(&&transaction.payer.agent)
+    && signing_agents.contains(&&transaction.payee.agent);
+  if !signed_by_parties {
+    return Ok(ValidateCallbackResult::Invalid(
+      "A ledger transaction must be signed by the payer and the payee only".to_string(),
+    ));
+  }
+
+  validate_ledger_transaction(transaction)
+}
+
+/// Checks a transaction against the transactions before it on the payer's chain: the
+/// agreement must not be settled already, and the payer's balance must stay within the
+/// credit limit of the medium of exchange
+pub fn check_payer_history(
+  transaction: &LedgerTransaction,
+  previous_transactions: &[LedgerTransaction],
+) -> ExternResult<ValidateCallbackResult> {
+  if previous_transactions
+    .iter()
+    .any(|previous| previous.agreement_hash == transaction.agreement_hash)
+  {
+    return Ok(ValidateCallbackResult::Invalid(
+      "This exchange agreement is already settled in the ledger".to_string(),
+    ));
+  }
+
+  let Some(medium) = must_get_ledger_medium(&transaction.medium_of_exchange_hash)? else {
+    return Ok(ValidateCallbackResult::Invalid(
+      "A ledger transaction must be in the original action of a medium of exchange".to_string(),
+    ));
+  };
+  let credit_limit = credit_limit(&medium.code)?;
+  let balance = balance_of(
+    &transaction.payer.agent,
+    &transaction.medium_of_exchange_hash,
+    previous_transactions,
+  );
+  let within_limit = balance
+    .checked_sub(transaction.amount)
+    .is_some_and(|new_balance| new_balance >= -credit_limit);
+  if !within_limit {
+    return Ok(ValidateCallbackResult::Invalid(format!(
+      "The transaction would take the payer's {} balance below the credit limit of {}",
+      medium.code,
+      credit_limit as f64 / MINOR_UNITS_PER_UNIT as f64
+    )));
+  }
+
+  Ok(ValidateCallbackResult::Valid)
+}
+
+/// Validates the payer's side of a transaction by walking their source chain back from
+/// the transaction. The payee's side, a credit, needs no check.
+pub fn validate_ledger_transaction_history(
+  action: &Create,
+  transaction: &LedgerTransaction,
+) -> ExternResult<ValidateCallbackResult> {
+  if action.author != transaction.payer.agent {
+    return Ok(ValidateCallbackResult::Valid);
+  }
+
+  let transaction_type: EntryType = UnitEntryTypes::LedgerTransaction.try_into()?;
+  let mut previous_transactions = Vec::new();
+  for activity in must_get_agent_activity(
+    action.author.clone(),
+    ChainFilter::new(action.prev_action.clone()),
+  )? {
+    let Action::Create(create) = activity.action.hashed.content else {
+      continue;
+    };
+    if create.entry_type != transaction_type {
+      continue;
+    }
+    let entry = must_get_entry(create.entry_hash)?;
+    if let Some(previous) = ledger_transaction_from_entry(entry.as_content())? {
+      previous_transactions.push(previous);
+    }
+  }
+
+  check_payer_history(transaction, &previous_transactions)
+}
+
+/// Validates a link indexing a transaction from a user profile: the transaction must be
+/// a valid countersigned one, the profile one of its parties' and the author a party
+pub fn validate_create_link_user_ledger_transaction(
+  action: &CreateLink,
+) -> ExternResult<ValidateCallbackResult> {
+  let Some(entry_hash) = action.target_address.clone().into_entry_hash() else {
+    return Ok(ValidateCallbackResult::Invalid(
+      "A transaction link must point to the entry of a transaction".to_string(),
+    ));
+  };
+  let entry = must_get_entry(entry_hash)?;
+  let Entry::CounterSign(session_data, app_entry_bytes) = entry.as_content() else {
+    return Ok(ValidateCallbackResult::Invalid(
+      "A transaction link must point to a countersigned transaction".to_string(),
+    ));
+  };
+  let Ok(transaction) = LedgerTransaction::try_from(app_entry_bytes.clone().into_sb()) else {
+    return Ok(ValidateCallbackResult::Invalid(
+      "A transaction link must point to a countersigned transaction".to_string(),
+    ));
+  };
+  let result = validate_countersigned_ledger_transaction(&transaction, Some(&**session_data))?;
+  if result != ValidateCallbackResult::Valid {
+    return Ok(result);
+  }
+
+  let parties = [&transaction.payer, &transaction.payee];
+  if !parties.iter().any(|party| party.agent == action.author) {
+    return Ok(ValidateCallbackResult::Invalid(
+      "Only a party to a transaction can index it".to_string(),
+    ));
+  }
+  let base_hash = action.base_address.clone().into_action_hash();
+  if !parties
+    .iter()
+    .any(|party| base_hash.as_ref() == Some(&party.user_hash))
+  {
+    return Ok(ValidateCallbackResult::Invalid(
+      "A transaction can only be indexed from the user profiles of its parties".to_string(),
+    ));
+  }
+
+  Ok(ValidateCallbackResult::Valid)
+}
+
+/// Validates the removal of a transaction from a user's index by the party who added it
+pub fn validate_delete_link_user_ledger_transaction(
+  action: DeleteLink,
+  original_action: CreateLink,
+) -> ValidateCallbackResult {
+  if action.author != original_action.author {
+    return ValidateCallbackResult::Invalid(
+      "Only the party who indexed a transaction can remove it from the index".to_string(),
+    );
+  }
+  ValidateCallbackResult::Valid
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  fn transaction(payer: u8, payee: u8, medium: u8, amount: i64) -> LedgerTransaction {
+    LedgerTransaction {
+      agreement_hash: EntryHash::from_raw_36(vec![amount as u8; 36]),
+      agreement_action_hash: ActionHash::from_raw_36(vec![amount as u8; 36]),
+      medium_of_exchange_hash: ActionHash::from_raw_36(vec![medium; 36]),
+      payer: LedgerParty {
+        agent: AgentPubKey::from_raw_36(vec![payer; 36]),
+        user_hash: ActionHash::from_raw_36(vec![payer; 36]),
+      },
+      payee: LedgerParty {
+        agent: AgentPubKey::from_raw_36(vec![payee; 36]),
+        user_hash: ActionHash::from_raw_36(vec![payee; 36]),
+      },
+      amount,
+    }
+  }
+
+  #[test]
+  fn test_payer_is_debited_and_payee_credited() {
+    let transactions = vec![transaction(1, 2, 9, 300), transaction(2, 1, 9, 150)];
+    let medium = ActionHash::from_raw_36(vec![9; 36]);
+    assert_eq!(
+      balance_of(
+        &AgentPubKey::from_raw_36(vec![1; 36]),
+        &medium,
+        &transactions
+      ),
+      -150
+    );
+    assert_eq!(
+      balance_of(
+        &AgentPubKey::from_raw_36(vec![2; 36]),
+        &medium,
+        &transactions
+      ),
+      150
+    );
+    assert_eq!(
+      balance_of(
+        &AgentPubKey::from_raw_36(vec![3; 36]),
+        &medium,
+        &transactions
+      ),
+      0
+    );
+  }
+
+  #[test]
+  fn test_balances_are_per_medium_of_exchange() {
+    let transactions = vec![transaction(1, 2, 8, 400), transaction(1, 2, 9, 200)];
+    let payer = AgentPubKey::from_raw_36(vec![1; 36]);
+    assert_eq!(
+      balance_of(&payer, &ActionHash::from_raw_36(vec![8; 36]), &transactions),
+      -400
+    );
+    assert_eq!(
+      balance_of(&payer, &ActionHash::from_raw_36(vec![9; 36]), &transactions),
+      -200
+    );
+  }
+
+  #[test]
+  fn test_amounts_are_converted_to_minor_units() {
+    assert_eq!(to_minor_units(1.5), Some(150));
+    assert_eq!(to_minor_units(0.1 + 0.2), Some(30));
+    assert_eq!(to_minor_units(f64::NAN), None);
+    assert_eq!(to_minor_units(f64::INFINITY), None);
+  }
+}
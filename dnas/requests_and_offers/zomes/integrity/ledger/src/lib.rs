@@ -0,0 +1,99 @@
+use hdi::prelude::*;
+
+mod agreement;
+mod ledger_transaction;
+pub use agreement::*;
+pub use ledger_transaction::*;
+
+#[derive(Serialize, Deserialize)]
+#[serde(tag = "type")]
+#[hdk_entry_types]
+#[unit_enum(UnitEntryTypes)]
+pub enum EntryTypes {
+  LedgerTransaction(LedgerTransaction),
+}
+
+/// Transaction links target the entry hash of a transaction, which is the same on both
+/// parties' chains
+#[derive(Serialize, Deserialize)]
+#[hdk_link_types]
+pub enum LinkTypes {
+  UserLedgerTransactions,
+}
+
+#[hdk_extern]
+pub fn genesis_self_check(_data: GenesisSelfCheckData) -> ExternResult<ValidateCallbackResult> {
+  Ok(ValidateCallbackResult::Valid)
+}
+
+/// The countersigning session an op's entry was committed in, if any
+fn countersigning_session_data(op: &Op) -> Option<&CounterSigningSessionData> {
+  let entry = match op {
+    Op::StoreRecord(StoreRecord { record }) => record.entry().as_option(),
+    Op::StoreEntry(StoreEntry { entry, .. }) => Some(entry),
+    _ => None,
+  };
+  match entry {
+    Some(Entry::CounterSign(session_data, _)) => Some(session_data),
+    _ => None,
+  }
+}
+
+#[hdk_extern]
+pub fn validate(op: Op) -> ExternResult<ValidateCallbackResult> {
+  let session_data = countersigning_session_data(&op);
+
+  match op.flattened::<EntryTypes, LinkTypes>()? {
+    FlatOp::StoreEntry(OpEntry::CreateEntry { app_entry, .. }) => match app_entry {
+      EntryTypes::LedgerTransaction(transaction) => {
+        validate_countersigned_ledger_transaction(&transaction, session_data)
+      }
+    },
+    // The record of each party's action also checks the payer's chain
+    FlatOp::StoreRecord(OpRecord::CreateEntry { app_entry, action }) => match app_entry {
+      EntryTypes::LedgerTransaction(transaction) => {
+        let result = validate_countersigned_ledger_transaction(&transaction, session_data)?;
+        if result != ValidateCallbackResult::Valid {
+          return Ok(result);
+        }
+        validate_ledger_transaction_history(&action, &transaction)
+      }
+    },
+    FlatOp::StoreEntry(OpEntry::UpdateEntry { app_entry, .. })
+    | FlatOp::StoreRecord(OpRecord::UpdateEntry { app_entry, .. }) => match app_entry {
+      EntryTypes::LedgerTransaction(_) => Ok(ValidateCallbackResult::Invalid(
+        "Ledger transactions cannot be updated".to_string(),
+      )),
+    },
+    FlatOp::RegisterCreateLink {
+      link_type, action, ..
+    } => match link_type {
+      LinkTypes::UserLedgerTransactions => validate_create_link_user_ledger_transaction(&action),
+    },
+    FlatOp::RegisterDeleteLink {
+      link_type,
+      original_action,
+      action,
+      ..
+    } => match link_type {
+      LinkTypes::UserLedgerTransactions => Ok(validate_delete_link_user_ledger_transaction(
+        action,
+        original_action,
+      )),
+    },
+    FlatOp::StoreRecord(OpRecord::DeleteEntry {
+      original_action_hash,
+      ..
+    }) => {
+      let original_record = must_get_valid_record(original_action_hash)?;
+      let transaction_type: EntryType = UnitEntryTypes::LedgerTransaction.try_into()?;
+      match original_record.action().entry_type() {
+        Some(entry_type) if *entry_type == transaction_type => Ok(ValidateCallbackResult::Invalid(
+          "Ledger transactions cannot be deleted".to_string(),
+        )),
+        _ => Ok(ValidateCallbackResult::Valid),
+      }
+    }
+    _ => Ok(ValidateCallbackResult::Valid),
+  }
+}
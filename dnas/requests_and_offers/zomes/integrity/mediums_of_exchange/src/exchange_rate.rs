@@ -0,0 +1,187 @@
+use hdi::prelude::*;
+use utils::role_links::{
+  check_administrator_proof, validate_network_administrator_link, RoleProof,
+};
+
+use crate::{LinkTypes, UnitEntryTypes};
+
+/// ExchangeRate Entry Definition
+///
+/// How much one medium of exchange is worth in another from a given time, until the
+/// next rate between the same two mediums takes effect. The rate is kept as a fraction so
+/// that every agent reads and converts with the same value.
+#[hdk_entry_helper]
+#[derive(Clone, PartialEq)]
+pub struct ExchangeRate {
+  /// Original action hash of the medium of exchange converted from
+  pub from_medium_hash: ActionHash,
+  /// Original action hash of the medium of exchange converted to
+  pub to_medium_hash: ActionHash,
+  /// Units of the `to` medium worth `rate_denominator` units of the `from` medium: 15 for
+  /// 1 TIME = 15 LOCAL
+  pub rate_numerator: u64,
+  /// Units of the `from` medium worth `rate_numerator` units of the `to` medium
+  pub rate_denominator: u64,
+  /// When the rate takes effect
+  pub effective_at: Timestamp,
+  /// Proof that the author administers the network
+  pub administrator_proof: RoleProof,
+}
+
+/// Validation for a new ExchangeRate: a positive rate between the original actions of two
+/// different mediums of exchange, set by a network administrator
+pub fn validate_create_exchange_rate(
+  action: &Create,
+  exchange_rate: &ExchangeRate,
+) -> ExternResult<ValidateCallbackResult> {
+  if exchange_rate.rate_numerator == 0 || exchange_rate.rate_denominator == 0 {
+    return Ok(ValidateCallbackResult::Invalid(
+      "ExchangeRate rate must be greater than zero".to_string(),
+    ));
+  }
+  if exchange_rate.from_medium_hash == exchange_rate.to_medium_hash {
+    return Ok(ValidateCallbackResult::Invalid(
+      "ExchangeRate must be between two different mediums of exchange".to_string(),
+    ));
+  }
+
+  let medium_of_exchange_type: EntryType = UnitEntryTypes::MediumOfExchange.try_into()?;
+  for medium_hash in [
+    &exchange_rate.from_medium_hash,
+    &exchange_rate.to_medium_hash,
+  ] {
+    let is_medium_of_exchange = match must_get_valid_record(medium_hash.clone())?.action() {
+      Action::Create(create) => create.entry_type == medium_of_exchange_type,
+      _ => false,
+    };
+    if !is_medium_of_exchange {
+      return Ok(ValidateCallbackResult::Invalid(
+        "ExchangeRate must point to the original actions of mediums of exchange".to_string(),
+      ));
+    }
+  }
+
+  if let Err(error) = check_administrator_proof(
+    &exchange_rate.administrator_proof,
+    &Path::from("network.administrators")
+      .path_entry_hash()?
+      .into(),
+    &action.author,
+    &action.prev_action,
+    action.timestamp,
+    &dna_info()?.zome_names,
+  )? {
+    return Ok(ValidateCallbackResult::Invalid(error));
+  }
+
+  Ok(ValidateCallbackResult::Valid)
+}
+
+/// Validates a link from one of the mediums of an exchange rate to the rate. Links are
+/// created by the administrator who set the rate, with the other medium in the tag.
+pub fn validate_create_link_medium_of_exchange_to_exchange_rate(
+  action: CreateLink,
+) -> ExternResult<ValidateCallbackResult> {
+  let Some(exchange_rate_hash) = action.target_address.clone().into_action_hash() else {
+    return Ok(ValidateCallbackResult::Invalid(
+      "An exchange rate link must point to an exchange rate".to_string(),
+    ));
+  };
+  let record = must_get_valid_record(exchange_rate_hash)?;
+  let Some(exchange_rate) = record
+    .entry()
+    .to_app_option::<ExchangeRate>()
+    .map_err(|e| wasm_error!(e))?
+  else {
+    return Ok(ValidateCallbackResult::Invalid(
+      "An exchange rate link must point to an exchange rate".to_string(),
+    ));
+  };
+  if *record.action().author() != action.author {
+    return Ok(ValidateCallbackResult::Invalid(
+      "Only the administrator who set an exchange rate can link it".to_string(),
+    ));
+  }
+
+  let base_hash = action.base_address.into_action_hash();
+  let other_medium_hash = if base_hash.as_ref() == Some(&exchange_rate.from_medium_hash) {
+    &exchange_rate.to_medium_hash
+  } else if base_hash.as_ref() == Some(&exchange_rate.to_medium_hash) {
+    &exchange_rate.from_medium_hash
+  } else {
+    return Ok(ValidateCallbackResult::Invalid(
+      "An exchange rate must be linked from one of its mediums of exchange".to_string(),
+    ));
+  };
+  if action.tag.0 != other_medium_hash.get_raw_39() {
+    return Ok(ValidateCallbackResult::Invalid(
+      "The tag of an exchange rate link must hold the other medium of exchange".to_string(),
+    ));
+  }
+
+  Ok(ValidateCallbackResult::Valid)
+}
+
+/// Validates a link marking the deletion of an exchange rate: from the rate to itself,
+/// with the proof of the deleting administrator's role in the tag
+pub fn validate_create_link_exchange_rate_deletion(
+  action: CreateLink,
+) -> ExternResult<ValidateCallbackResult> {
+  let Some(exchange_rate_hash) = action.base_address.clone().into_action_hash() else {
+    return Ok(ValidateCallbackResult::Invalid(
+      "An exchange rate deletion link must start from an exchange rate".to_string(),
+    ));
+  };
+  if action.target_address != action.base_address {
+    return Ok(ValidateCallbackResult::Invalid(
+      "An exchange rate deletion link must point to the rate it starts from".to_string(),
+    ));
+  }
+  let exchange_rate_type: EntryType = UnitEntryTypes::ExchangeRate.try_into()?;
+  let is_exchange_rate = match must_get_valid_record(exchange_rate_hash)?.action() {
+    Action::Create(create) => create.entry_type == exchange_rate_type,
+    _ => false,
+  };
+  if !is_exchange_rate {
+    return Ok(ValidateCallbackResult::Invalid(
+      "An exchange rate deletion link must start from an exchange rate".to_string(),
+    ));
+  }
+
+  validate_network_administrator_link(&action)
+}
+
+/// Checks that an exchange rate, or one of its links, is deleted by a network
+/// administrator. Deletions cannot carry the proof of their author's role, so the author
+/// must have linked the rate to itself with an `ExchangeRateDeletions` link carrying it,
+/// earlier on their chain.
+pub fn validate_exchange_rate_deletion(
+  author: &AgentPubKey,
+  prev_action: &ActionHash,
+  exchange_rate_hash: &ActionHash,
+) -> ExternResult<ValidateCallbackResult> {
+  let deletion_link_type: ScopedLinkType = LinkTypes::ExchangeRateDeletions.try_into()?;
+  let exchange_rate_set_at = must_get_action(exchange_rate_hash.clone())?
+    .action()
+    .timestamp();
+  let activity = must_get_agent_activity(
+    author.clone(),
+    ChainFilter::new(prev_action.clone()).until_timestamp(exchange_rate_set_at),
+  )?;
+  for activity in activity {
+    let Action::CreateLink(create_link) = activity.action.hashed.content else {
+      continue;
+    };
+    if create_link.zome_index == deletion_link_type.zome_index
+      && create_link.link_type == deletion_link_type.zome_type
+      && create_link.base_address == exchange_rate_hash.clone().into()
+      && validate_create_link_exchange_rate_deletion(create_link)? == ValidateCallbackResult::Valid
+    {
+      return Ok(ValidateCallbackResult::Valid);
+    }
+  }
+
+  Ok(ValidateCallbackResult::Invalid(
+    "Only network administrators can delete exchange rates".to_string(),
+  ))
+}
@@ -1,7 +1,10 @@
 use hdi::prelude::*;
 
+mod exchange_rate;
+pub use exchange_rate::*;
 mod medium_of_exchange;
 pub use medium_of_exchange::*;
+mod tests;
 
 #[derive(Serialize, Deserialize)]
 #[serde(tag = "type")]
@@ -9,6 +12,7 @@ pub use medium_of_exchange::*;
 #[unit_enum(UnitEntryTypes)]
 pub enum EntryTypes {
   MediumOfExchange(MediumOfExchange),
+  ExchangeRate(ExchangeRate),
 }
 
 #[derive(Serialize, Deserialize)]
@@ -23,6 +27,15 @@ pub enum LinkTypes {
 
   MediumOfExchangeToOffer,
   OfferToMediumOfExchange,
+
+  // Exchange rates
+  /// From the original action hash of each of the two mediums of an exchange rate to the
+  /// rate. The link tag holds the raw hash of the other medium.
+  MediumOfExchangeToExchangeRate,
+  /// From an exchange rate to itself, created by the administrator deleting it. The link
+  /// tag holds the proof of their role, which the deletion of the rate and of its links
+  /// cannot carry.
+  ExchangeRateDeletions,
 }
 
 #[hdk_extern]
@@ -35,12 +48,62 @@ pub fn genesis_self_check(_data: GenesisSelfCheckData) -> ExternResult<ValidateC
 pub fn validate(op: Op) -> ExternResult<ValidateCallbackResult> {
   if let FlatOp::StoreEntry(store_entry) = op.flattened::<EntryTypes, LinkTypes>()? {
     match store_entry {
-      OpEntry::CreateEntry { app_entry, .. } | OpEntry::UpdateEntry { app_entry, .. } => {
-        match app_entry {
-          EntryTypes::MediumOfExchange(medium_of_exchange) => {
-            return validate_medium_of_exchange(medium_of_exchange);
-          }
+      OpEntry::CreateEntry { app_entry, action } => match app_entry {
+        EntryTypes::MediumOfExchange(medium_of_exchange) => {
+          return validate_medium_of_exchange(medium_of_exchange);
+        }
+        EntryTypes::ExchangeRate(exchange_rate) => {
+          return validate_create_exchange_rate(&action, &exchange_rate);
+        }
+      },
+      OpEntry::UpdateEntry { app_entry, .. } => match app_entry {
+        EntryTypes::MediumOfExchange(medium_of_exchange) => {
+          return validate_medium_of_exchange(medium_of_exchange);
+        }
+        EntryTypes::ExchangeRate(_) => {
+          return Ok(ValidateCallbackResult::Invalid(
+            "Exchange rates cannot be updated: set a new rate instead".to_string(),
+          ));
         }
+      },
+      _ => (),
+    }
+  }
+
+  if let FlatOp::RegisterCreateLink {
+    link_type, action, ..
+  } = op.flattened::<EntryTypes, LinkTypes>()?
+  {
+    match link_type {
+      LinkTypes::MediumOfExchangeToExchangeRate => {
+        return validate_create_link_medium_of_exchange_to_exchange_rate(action);
+      }
+      LinkTypes::ExchangeRateDeletions => {
+        return validate_create_link_exchange_rate_deletion(action);
+      }
+      _ => (),
+    }
+  }
+
+  if let FlatOp::RegisterDeleteLink {
+    link_type,
+    original_action,
+    action,
+    ..
+  } = op.flattened::<EntryTypes, LinkTypes>()?
+  {
+    match link_type {
+      LinkTypes::MediumOfExchangeToExchangeRate => {
+        let Some(exchange_rate_hash) = original_action.target_address.into_action_hash() else {
+          return Ok(ValidateCallbackResult::Invalid(
+            "An exchange rate link must point to an exchange rate".to_string(),
+          ));
+        };
+        return validate_exchange_rate_deletion(
+          &action.author,
+          &action.prev_action,
+          &exchange_rate_hash,
+        );
       }
       _ => (),
     }
@@ -50,9 +113,10 @@ pub fn validate(op: Op) -> ExternResult<ValidateCallbackResult> {
     match store_record {
       OpRecord::DeleteEntry {
         original_action_hash,
+        action,
         ..
       } => {
-        let original_record = must_get_valid_record(original_action_hash)?;
+        let original_record = must_get_valid_record(original_action_hash.clone())?;
         let original_action = original_record.action().clone();
         let original_action = match original_action {
           Action::Create(create) => EntryCreationAction::Create(create),
@@ -97,6 +161,13 @@ pub fn validate(op: Op) -> ExternResult<ValidateCallbackResult> {
           EntryTypes::MediumOfExchange(_original_medium_of_exchange) => {
             // You can add specific delete validation for mediums of exchange here if needed
           }
+          EntryTypes::ExchangeRate(_original_exchange_rate) => {
+            return validate_exchange_rate_deletion(
+              &action.author,
+              &action.prev_action,
+              &original_action_hash,
+            );
+          }
         }
       }
       _ => (),
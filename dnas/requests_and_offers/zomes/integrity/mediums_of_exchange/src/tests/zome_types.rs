@@ -0,0 +1,13 @@
+#[cfg(test)]
+mod zome_types_tests {
+  use utils::role_links::MEDIUM_OF_EXCHANGE_ENTRY;
+
+  use crate::UnitEntryTypes;
+
+  /// Other zomes reference the entry types of this zome by index
+  #[test]
+  fn test_referenced_type_indices_match() {
+    assert_eq!(MEDIUM_OF_EXCHANGE_ENTRY.0, env!("CARGO_PKG_NAME"));
+    assert_eq!(UnitEntryTypes::MediumOfExchange as u8, MEDIUM_OF_EXCHANGE_ENTRY.1);
+  }
+}
@@ -0,0 +1,77 @@
+use hdi::prelude::*;
+
+mod message;
+pub use message::*;
+
+#[derive(Serialize, Deserialize)]
+#[serde(tag = "type")]
+#[hdk_entry_types]
+#[unit_enum(UnitEntryTypes)]
+pub enum EntryTypes {
+  #[entry_type(visibility = "private")]
+  Message(Message),
+  MailboxMessage(MailboxMessage),
+}
+
+#[derive(Serialize, Deserialize)]
+#[hdk_link_types]
+pub enum LinkTypes {
+  AgentMailbox,
+}
+
+#[hdk_extern]
+pub fn genesis_self_check(_data: GenesisSelfCheckData) -> ExternResult<ValidateCallbackResult> {
+  Ok(ValidateCallbackResult::Valid)
+}
+
+#[hdk_extern]
+pub fn validate(op: Op) -> ExternResult<ValidateCallbackResult> {
+  match op.flattened::<EntryTypes, LinkTypes>()? {
+    FlatOp::StoreEntry(OpEntry::CreateEntry { app_entry, action })
+    | FlatOp::StoreRecord(OpRecord::CreateEntry { app_entry, action }) => match app_entry {
+      EntryTypes::Message(message) => validate_message(message),
+      EntryTypes::MailboxMessage(mailbox_message) => {
+        validate_create_mailbox_message(&action, mailbox_message)
+      }
+    },
+    FlatOp::RegisterCreateLink {
+      link_type, action, ..
+    } => match link_type {
+      LinkTypes::AgentMailbox => validate_create_link_agent_mailbox(&action),
+    },
+    FlatOp::RegisterDeleteLink {
+      link_type,
+      original_action,
+      action,
+      ..
+    } => match link_type {
+      LinkTypes::AgentMailbox => validate_delete_link_agent_mailbox(&action, &original_action),
+    },
+    FlatOp::StoreRecord(OpRecord::DeleteEntry {
+      original_action_hash,
+      action,
+      ..
+    }) => {
+      let original_record = must_get_valid_record(original_action_hash)?;
+      let mailbox_type: EntryType = UnitEntryTypes::MailboxMessage.try_into()?;
+      if original_record.action().entry_type() != Some(&mailbox_type) {
+        return Ok(ValidateCallbackResult::Valid);
+      }
+      match original_record
+        .entry()
+        .to_app_option::<MailboxMessage>()
+        .map_err(|e| wasm_error!(e))?
+      {
+        Some(mailbox_message) => validate_delete_mailbox_message(&action, &mailbox_message),
+        None => Ok(ValidateCallbackResult::Invalid(
+          "Original record for a delete must be a mailbox message".to_string(),
+        )),
+      }
+    }
+    FlatOp::StoreEntry(OpEntry::UpdateEntry { .. })
+    | FlatOp::StoreRecord(OpRecord::UpdateEntry { .. }) => Ok(ValidateCallbackResult::Invalid(
+      "Messages cannot be edited".to_string(),
+    )),
+    _ => Ok(ValidateCallbackResult::Valid),
+  }
+}
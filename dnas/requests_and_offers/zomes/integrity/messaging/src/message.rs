@@ -0,0 +1,131 @@
+use hdi::prelude::*;
+
+/// Represents a Message Entry. Messages are private: the sender keeps a copy on their
+/// chain and each recipient agent commits its own copy when the message is delivered.
+#[hdk_entry_helper]
+#[derive(Clone, PartialEq)]
+pub struct Message {
+  /// The original action hash of the sender's user profile
+  pub sender_hash: ActionHash,
+  /// The original action hash of the recipient's user profile
+  pub recipient_hash: ActionHash,
+  /// The message text
+  pub content: String,
+  /// When the sender wrote the message
+  pub sent_at: Timestamp,
+  /// For a received copy, the mailbox message it was delivered through
+  pub mailbox_hash: Option<ActionHash>,
+}
+
+/// Represents a MailboxMessage Entry: a message encrypted for one recipient agent, kept on
+/// the DHT until that agent collects it
+#[hdk_entry_helper]
+#[derive(Clone, PartialEq)]
+pub struct MailboxMessage {
+  /// The agent the message is encrypted for
+  pub recipient: AgentPubKey,
+  /// The serialized message, encrypted from the author's key to the recipient's
+  pub encrypted_message: XSalsa20Poly1305EncryptedData,
+}
+
+/// Validates a message entry
+pub fn validate_message(message: Message) -> ExternResult<ValidateCallbackResult> {
+  if message.content.trim().is_empty() {
+    return Ok(ValidateCallbackResult::Invalid(
+      "Message content cannot be empty".to_string(),
+    ));
+  }
+
+  // Validate content length (2000 character limit)
+  if message.content.len() > 2000 {
+    return Ok(ValidateCallbackResult::Invalid(
+      "Message content cannot exceed 2000 characters".to_string(),
+    ));
+  }
+
+  if message.sender_hash == message.recipient_hash {
+    return Ok(ValidateCallbackResult::Invalid(
+      "Cannot send a message to yourself".to_string(),
+    ));
+  }
+
+  Ok(ValidateCallbackResult::Valid)
+}
+
+/// Validates a mailbox message entry
+pub fn validate_create_mailbox_message(
+  action: &Create,
+  mailbox_message: MailboxMessage,
+) -> ExternResult<ValidateCallbackResult> {
+  if mailbox_message.recipient == action.author {
+    return Ok(ValidateCallbackResult::Invalid(
+      "Cannot send a message to yourself".to_string(),
+    ));
+  }
+
+  Ok(ValidateCallbackResult::Valid)
+}
+
+/// Validates a link from an agent to a mailbox message for them. Only the sender of the
+/// message can leave it in the recipient's mailbox.
+pub fn validate_create_link_agent_mailbox(
+  action: &CreateLink,
+) -> ExternResult<ValidateCallbackResult> {
+  let Some(mailbox_hash) = action.target_address.clone().into_action_hash() else {
+    return Ok(ValidateCallbackResult::Invalid(
+      "A mailbox link must point to a mailbox message".to_string(),
+    ));
+  };
+  let mailbox_record = must_get_valid_record(mailbox_hash)?;
+  let Some(mailbox_message) = mailbox_record
+    .entry()
+    .to_app_option::<MailboxMessage>()
+    .map_err(|e| wasm_error!(e))?
+  else {
+    return Ok(ValidateCallbackResult::Invalid(
+      "A mailbox link must point to a mailbox message".to_string(),
+    ));
+  };
+  if *mailbox_record.action().author() != action.author {
+    return Ok(ValidateCallbackResult::Invalid(
+      "Only the sender of a message can leave it in a mailbox".to_string(),
+    ));
+  }
+  if action.base_address != mailbox_message.recipient.into() {
+    return Ok(ValidateCallbackResult::Invalid(
+      "A message can only be left in its recipient's mailbox".to_string(),
+    ));
+  }
+
+  Ok(ValidateCallbackResult::Valid)
+}
+
+/// Validates the deletion of a link to a mailbox message, which only the recipient can
+/// delete once they have collected it
+pub fn validate_delete_link_agent_mailbox(
+  action: &DeleteLink,
+  original_action: &CreateLink,
+) -> ExternResult<ValidateCallbackResult> {
+  if original_action.base_address != action.author.clone().into() {
+    return Ok(ValidateCallbackResult::Invalid(
+      "Only the recipient can delete a message from their mailbox".to_string(),
+    ));
+  }
+
+  Ok(ValidateCallbackResult::Valid)
+}
+
+/// Validates the deletion of a mailbox message, which only its recipient can delete once
+/// they have collected it
+pub fn validate_delete_mailbox_message(
+  action: &Delete,
+  mailbox_message: &MailboxMessage,
+) -> ExternResult<ValidateCallbackResult> {
+  if mailbox_message.recipient != action.author {
+    return Ok(ValidateCallbackResult::Invalid(
+      "Only the recipient can delete a mailbox message".to_string(),
+    ));
+  }
+
+  Ok(ValidateCallbackResult::Valid)
+}
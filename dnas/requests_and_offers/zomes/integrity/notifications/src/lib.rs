@@ -0,0 +1,65 @@
+use hdi::prelude::*;
+
+mod subscription;
+pub use subscription::*;
+
+#[derive(Serialize, Deserialize)]
+#[serde(tag = "type")]
+#[hdk_entry_types]
+#[unit_enum(UnitEntryTypes)]
+pub enum EntryTypes {
+  Subscription(Subscription),
+}
+
+#[derive(Serialize, Deserialize)]
+#[hdk_link_types]
+pub enum LinkTypes {
+  /// From a service type or medium of exchange to a subscribed agent
+  TargetSubscribers,
+  /// From an agent to their subscriptions
+  AgentSubscriptions,
+}
+
+#[hdk_extern]
+pub fn genesis_self_check(_data: GenesisSelfCheckData) -> ExternResult<ValidateCallbackResult> {
+  Ok(ValidateCallbackResult::Valid)
+}
+
+#[hdk_extern]
+pub fn validate(op: Op) -> ExternResult<ValidateCallbackResult> {
+  match op.flattened::<EntryTypes, LinkTypes>()? {
+    FlatOp::StoreEntry(OpEntry::CreateEntry { app_entry, .. })
+    | FlatOp::StoreRecord(OpRecord::CreateEntry { app_entry, .. }) => match app_entry {
+      EntryTypes::Subscription(subscription) => validate_create_subscription(subscription),
+    },
+    FlatOp::StoreEntry(OpEntry::UpdateEntry { .. })
+    | FlatOp::StoreRecord(OpRecord::UpdateEntry { .. }) => Ok(ValidateCallbackResult::Invalid(
+      "Subscriptions cannot be updated".to_string(),
+    )),
+    FlatOp::StoreRecord(OpRecord::DeleteEntry {
+      original_action_hash,
+      action,
+      ..
+    }) => {
+      let original_record = must_get_valid_record(original_action_hash)?;
+      if *original_record.action().author() != action.author {
+        return Ok(ValidateCallbackResult::Invalid(
+          "Only the subscriber can delete a subscription".to_string(),
+        ));
+      }
+      Ok(ValidateCallbackResult::Valid)
+    }
+    FlatOp::RegisterCreateLink {
+      link_type, action, ..
+    } => match link_type {
+      LinkTypes::TargetSubscribers => validate_create_link_target_subscribers(&action),
+      LinkTypes::AgentSubscriptions => validate_create_link_agent_subscriptions(&action),
+    },
+    FlatOp::RegisterDeleteLink {
+      original_action,
+      action,
+      ..
+    } => validate_delete_link_subscription(&action, &original_action),
+    _ => Ok(ValidateCallbackResult::Valid),
+  }
+}
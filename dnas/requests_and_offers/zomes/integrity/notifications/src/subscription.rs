@@ -0,0 +1,147 @@
+use hdi::prelude::*;
+use utils::role_links::{must_get_create_of, MEDIUM_OF_EXCHANGE_ENTRY, SERVICE_TYPE_ENTRY};
+
+use crate::UnitEntryTypes;
+
+/// What a user subscribes to
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub enum SubscriptionTarget {
+  /// The original action hash of a service type
+  ServiceType(ActionHash),
+  /// The original action hash of a medium of exchange
+  MediumOfExchange(ActionHash),
+}
+
+impl SubscriptionTarget {
+  pub fn hash(&self) -> &ActionHash {
+    match self {
+      SubscriptionTarget::ServiceType(hash) | SubscriptionTarget::MediumOfExchange(hash) => hash,
+    }
+  }
+}
+
+/// Represents a Subscription Entry: an agent asking to be told about new listings for a
+/// service type or medium of exchange
+#[hdk_entry_helper]
+#[derive(Clone, PartialEq)]
+pub struct Subscription {
+  pub target: SubscriptionTarget,
+}
+
+/// Validates a new subscription, whose target must be a service type or a medium of
+/// exchange, as the subscription says
+pub fn validate_create_subscription(
+  subscription: Subscription,
+) -> ExternResult<ValidateCallbackResult> {
+  let entry_type = match subscription.target {
+    SubscriptionTarget::ServiceType(_) => SERVICE_TYPE_ENTRY,
+    SubscriptionTarget::MediumOfExchange(_) => MEDIUM_OF_EXCHANGE_ENTRY,
+  };
+  if let Err(error) = must_get_create_of(
+    &subscription.target.hash().clone().into(),
+    entry_type,
+    &dna_info()?.zome_names,
+  )? {
+    return Ok(ValidateCallbackResult::Invalid(error));
+  }
+
+  Ok(ValidateCallbackResult::Valid)
+}
+
+/// Fetches a subscription created by `author`, or returns why it cannot be used
+fn must_get_own_subscription(
+  hash: &AnyLinkableHash,
+  author: &AgentPubKey,
+) -> ExternResult<Result<Subscription, String>> {
+  let Some(subscription_hash) = hash.clone().into_action_hash() else {
+    return Ok(Err(
+      "A subscription link must point to a subscription".to_string(),
+    ));
+  };
+  let record = must_get_valid_record(subscription_hash)?;
+  let subscription_type: EntryType = UnitEntryTypes::Subscription.try_into()?;
+  if record.action().entry_type() != Some(&subscription_type) {
+    return Ok(Err(
+      "A subscription link must point to a subscription".to_string(),
+    ));
+  }
+  if record.action().author() != author {
+    return Ok(Err(
+      "Only the subscriber can link a subscription".to_string(),
+    ));
+  }
+  let Some(subscription) = record
+    .entry()
+    .to_app_option::<Subscription>()
+    .map_err(|e| wasm_error!(e))?
+  else {
+    return Ok(Err(
+      "A subscription link must point to a subscription".to_string(),
+    ));
+  };
+
+  Ok(Ok(subscription))
+}
+
+/// Validates a link from a service type or medium of exchange to a subscribed agent. Only
+/// the subscriber can link themselves, and the target must be a service type or a medium
+/// of exchange.
+pub fn validate_create_link_target_subscribers(
+  action: &CreateLink,
+) -> ExternResult<ValidateCallbackResult> {
+  if action.target_address != action.author.clone().into() {
+    return Ok(ValidateCallbackResult::Invalid(
+      "Agents can only subscribe themselves".to_string(),
+    ));
+  }
+  let Some(target_hash) = action.base_address.clone().into_action_hash() else {
+    return Ok(ValidateCallbackResult::Invalid(
+      "A subscriber link must start from a service type or a medium of exchange".to_string(),
+    ));
+  };
+
+  let zome_names = dna_info()?.zome_names;
+  let is_target = [SERVICE_TYPE_ENTRY, MEDIUM_OF_EXCHANGE_ENTRY]
+    .into_iter()
+    .map(|entry_type| must_get_create_of(&target_hash.clone().into(), entry_type, &zome_names))
+    .collect::<ExternResult<Vec<_>>>()?
+    .iter()
+    .any(Result::is_ok);
+  if !is_target {
+    return Ok(ValidateCallbackResult::Invalid(
+      "A subscriber link must start from a service type or a medium of exchange".to_string(),
+    ));
+  }
+
+  Ok(ValidateCallbackResult::Valid)
+}
+
+/// Validates a link from an agent to one of their subscriptions
+pub fn validate_create_link_agent_subscriptions(
+  action: &CreateLink,
+) -> ExternResult<ValidateCallbackResult> {
+  if action.base_address != action.author.clone().into() {
+    return Ok(ValidateCallbackResult::Invalid(
+      "Agents can only link their own subscriptions".to_string(),
+    ));
+  }
+  if let Err(error) = must_get_own_subscription(&action.target_address, &action.author)? {
+    return Ok(ValidateCallbackResult::Invalid(error));
+  }
+
+  Ok(ValidateCallbackResult::Valid)
+}
+
+/// Validates the deletion of a subscription link, which only the subscriber can delete
+pub fn validate_delete_link_subscription(
+  action: &DeleteLink,
+  original_action: &CreateLink,
+) -> ExternResult<ValidateCallbackResult> {
+  if action.author != original_action.author {
+    return Ok(ValidateCallbackResult::Invalid(
+      "Only the subscriber can delete a subscription link".to_string(),
+    ));
+  }
+
+  Ok(ValidateCallbackResult::Valid)
+}
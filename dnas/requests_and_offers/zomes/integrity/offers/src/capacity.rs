@@ -0,0 +1,105 @@
+use hdi::prelude::*;
+use utils::role_links::{is_entry_type_of, must_get_create_of, OFFER_ENTRY, RESPONSE_ENTRY};
+
+/// The part of a response entry needed to claim a slot of the offer it proposes.
+/// Other fields are ignored when deserializing.
+#[derive(Serialize, Deserialize, SerializedBytes, Debug)]
+pub struct ResponseClaim {
+  pub offer_hash: Option<ActionHash>,
+  pub status: String,
+}
+
+/// The tag of an `OfferTakers` or `OfferWaitlist` link: the update accepting the
+/// response it points to
+pub fn slot_claim_tag(acceptance_hash: &ActionHash) -> LinkTag {
+  LinkTag::new(acceptance_hash.get_raw_39().to_vec())
+}
+
+/// Validates a link claiming a slot of an offer, or a place on its waitlist, for an
+/// accepted response. Only the agent who accepted the response can claim it, and only
+/// for an offer of the responder.
+pub fn validate_create_link_offer_slot(action: CreateLink) -> ExternResult<ValidateCallbackResult> {
+  let Ok(acceptance_hash) = ActionHash::try_from_raw_39(action.tag.0.clone()) else {
+    return Ok(ValidateCallbackResult::Invalid(
+      "An offer slot link must carry the acceptance of its response".to_string(),
+    ));
+  };
+  let acceptance = must_get_valid_record(acceptance_hash)?;
+  let Action::Update(update) = acceptance.action().clone() else {
+    return Ok(ValidateCallbackResult::Invalid(
+      "The acceptance of a response must be an update".to_string(),
+    ));
+  };
+  if !is_entry_type_of(&update.entry_type, RESPONSE_ENTRY, &dna_info()?.zome_names) {
+    return Ok(ValidateCallbackResult::Invalid(
+      "The acceptance of a response must update a response".to_string(),
+    ));
+  }
+  if update.author != action.author {
+    return Ok(ValidateCallbackResult::Invalid(
+      "Only the agent who accepted a response can claim an offer slot for it".to_string(),
+    ));
+  }
+  if action.target_address.into_action_hash() != Some(update.original_action_address.clone()) {
+    return Ok(ValidateCallbackResult::Invalid(
+      "An offer slot link must point to the accepted response".to_string(),
+    ));
+  }
+
+  let Some(response) = acceptance
+    .entry()
+    .to_app_option::<ResponseClaim>()
+    .map_err(|e| wasm_error!(e))?
+  else {
+    return Ok(ValidateCallbackResult::Invalid(
+      "The acceptance of a response must contain the response".to_string(),
+    ));
+  };
+  if response.status != "Accepted" {
+    return Ok(ValidateCallbackResult::Invalid(
+      "Only accepted responses can take up an offer".to_string(),
+    ));
+  }
+  if response.offer_hash != action.base_address.clone().into_action_hash() {
+    return Ok(ValidateCallbackResult::Invalid(
+      "The response does not propose this offer".to_string(),
+    ));
+  }
+
+  let offer = match must_get_create_of(&action.base_address, OFFER_ENTRY, &dna_info()?.zome_names)?
+  {
+    Ok(offer) => offer,
+    Err(error) => return Ok(ValidateCallbackResult::Invalid(error)),
+  };
+  let responder = must_get_action(update.original_action_address)?
+    .action()
+    .author()
+    .clone();
+  if offer.author != responder {
+    return Ok(ValidateCallbackResult::Invalid(
+      "A response can only take up an offer of its responder".to_string(),
+    ));
+  }
+
+  Ok(ValidateCallbackResult::Valid)
+}
+
+/// Validates the release of an offer slot, or of a place on its waitlist, by the
+/// agent who claimed it or the author of the offer
+pub fn validate_delete_link_offer_slot(
+  action: DeleteLink,
+  original_action: CreateLink,
+) -> ExternResult<ValidateCallbackResult> {
+  if action.author == original_action.author {
+    return Ok(ValidateCallbackResult::Valid);
+  }
+  if let Some(offer_hash) = original_action.base_address.into_action_hash() {
+    if *must_get_action(offer_hash)?.action().author() == action.author {
+      return Ok(ValidateCallbackResult::Valid);
+    }
+  }
+  Ok(ValidateCallbackResult::Invalid(
+    "Only the agent who claimed an offer slot or the author of the offer can release it"
+      .to_string(),
+  ))
+}
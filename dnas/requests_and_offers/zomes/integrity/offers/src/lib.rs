@@ -1,7 +1,14 @@
 use hdi::prelude::*;
+use utils::listing_links::{
+  validate_delete_listing_link, validate_listing_index_link, validate_listing_organization_link,
+  validate_listing_user_link,
+};
 
+mod capacity;
 mod offer;
+pub use capacity::*;
 pub use offer::*;
+mod tests;
 
 #[derive(Serialize, Deserialize)]
 #[serde(tag = "type")]
@@ -22,6 +29,9 @@ pub enum LinkTypes {
   OrganizationOffers,
   OfferCreator,
   OfferOrganization,
+  FulfilledOffers,
+  OfferTakers,
+  OfferWaitlist,
 }
 
 #[hdk_extern]
@@ -34,10 +44,39 @@ pub fn genesis_self_check(_data: GenesisSelfCheckData) -> ExternResult<ValidateC
 pub fn validate(op: Op) -> ExternResult<ValidateCallbackResult> {
   if let FlatOp::StoreEntry(store_entry) = op.flattened::<EntryTypes, LinkTypes>()? {
     match store_entry {
-      OpEntry::CreateEntry { app_entry, .. } | OpEntry::UpdateEntry { app_entry, .. } => {
+      OpEntry::CreateEntry { app_entry, .. } => match app_entry {
+        EntryTypes::Offer(offer) => {
+          return validate_create_offer(offer);
+        }
+      },
+      OpEntry::UpdateEntry {
+        app_entry,
+        action,
+        original_action_hash,
+        ..
+      } => {
+        let original_record = must_get_valid_record(original_action_hash)?;
+        let original_action = match original_record.action().clone() {
+          Action::Create(create) => EntryCreationAction::Create(create),
+          Action::Update(update) => EntryCreationAction::Update(update),
+          _ => {
+            return Ok(ValidateCallbackResult::Invalid(
+              "Original action for an update must be a Create or Update action".to_string(),
+            ));
+          }
+        };
+        let Some(original_offer) = original_record
+          .entry()
+          .to_app_option::<Offer>()
+          .map_err(|e| wasm_error!(e))?
+        else {
+          return Ok(ValidateCallbackResult::Invalid(
+            "Original record for an update must be a offer".to_string(),
+          ));
+        };
         match app_entry {
           EntryTypes::Offer(offer) => {
-            return validate_offer(offer);
+            return validate_update_offer(action, offer, original_action, original_offer);
           }
         }
       }
@@ -100,5 +139,84 @@ pub fn validate(op: Op) -> ExternResult<ValidateCallbackResult> {
       _ => (),
     }
   }
+  if let FlatOp::RegisterCreateLink {
+    link_type,
+    base_address,
+    target_address,
+    tag,
+    action,
+  } = op.flattened::<EntryTypes, LinkTypes>()?
+  {
+    let listing_entry_type: EntryType = UnitEntryTypes::Offer.try_into()?;
+    return match link_type {
+      LinkTypes::OfferUpdates => {
+        validate_create_link_offer_updates(action, base_address, target_address, tag)
+      }
+      LinkTypes::ActiveOffers => {
+        validate_listing_index_link(&action, "offers.active", &listing_entry_type)
+      }
+      LinkTypes::ArchivedOffers => {
+        validate_listing_index_link(&action, "offers.archived", &listing_entry_type)
+      }
+      LinkTypes::FulfilledOffers => {
+        validate_listing_index_link(&action, "offers.fulfilled", &listing_entry_type)
+      }
+      LinkTypes::UserOffers => validate_listing_user_link(
+        &action,
+        &action.target_address,
+        &action.base_address,
+        &listing_entry_type,
+      ),
+      LinkTypes::OfferCreator => validate_listing_user_link(
+        &action,
+        &action.base_address,
+        &action.target_address,
+        &listing_entry_type,
+      ),
+      LinkTypes::OrganizationOffers => validate_listing_organization_link(
+        &action,
+        &action.target_address,
+        &action.base_address,
+        &listing_entry_type,
+      ),
+      LinkTypes::OfferOrganization => validate_listing_organization_link(
+        &action,
+        &action.base_address,
+        &action.target_address,
+        &listing_entry_type,
+      ),
+      LinkTypes::OfferTakers | LinkTypes::OfferWaitlist => validate_create_link_offer_slot(action),
+      _ => Ok(ValidateCallbackResult::Valid),
+    };
+  }
+  if let FlatOp::RegisterDeleteLink {
+    link_type,
+    original_action,
+    action,
+    ..
+  } = op.flattened::<EntryTypes, LinkTypes>()?
+  {
+    let listing_entry_type: EntryType = UnitEntryTypes::Offer.try_into()?;
+    let listing_hash = match link_type {
+      LinkTypes::OfferTakers | LinkTypes::OfferWaitlist => {
+        return validate_delete_link_offer_slot(action, original_action);
+      }
+      LinkTypes::OfferUpdates | LinkTypes::OfferCreator | LinkTypes::OfferOrganization => {
+        original_action.base_address.clone()
+      }
+      LinkTypes::AllOffers
+      | LinkTypes::ActiveOffers
+      | LinkTypes::ArchivedOffers
+      | LinkTypes::FulfilledOffers
+      | LinkTypes::UserOffers
+      | LinkTypes::OrganizationOffers => original_action.target_address.clone(),
+    };
+    return validate_delete_listing_link(
+      &action,
+      &original_action,
+      &listing_hash,
+      &listing_entry_type,
+    );
+  }
   Ok(ValidateCallbackResult::Valid)
 }
@@ -1,13 +1,12 @@
 use hdi::prelude::*;
-use utils::{InteractionType, TimePreference, TimeZone};
-
-/// Represents the status of a listing
-#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
-pub enum ListingStatus {
-  Active,
-  Archived,
-  Deleted, // Soft delete
-}
+pub use utils::ListingStatus;
+use utils::{
+  update_authorization::{validate_listing_update_link, validate_update_authorization},
+  valuation::validate_valuation,
+  GeoLocation, InteractionType, TimePreference, TimeZone, UpdateAuthorization, Valuation,
+};
+
+use crate::{LinkTypes, UnitEntryTypes};
 
 /// Represents an Offer Entry with various attributes
 #[hdk_entry_helper]
@@ -28,6 +27,22 @@ pub struct Offer {
   /// The status of the offer (defaults to Active for new offers)
   #[serde(default = "default_listing_status")]
   pub status: ListingStatus,
+  /// What was done, recorded when the offer is fulfilled
+  #[serde(default)]
+  pub fulfilment_notes: Option<String>,
+  /// The approximate location of the offer, for in-person exchanges
+  #[serde(default)]
+  pub location: Option<GeoLocation>,
+  /// How many times the offer can be taken up (None means unlimited)
+  #[serde(default)]
+  pub capacity: Option<u32>,
+  /// How much the offerer asks for the offer
+  #[serde(default)]
+  pub valuation: Option<Valuation>,
+  /// Proof of the role of an administrator or organization coordinator updating someone
+  /// else's offer, set by the coordinator on each update
+  #[serde(default)]
+  pub update_authorization: Option<UpdateAuthorization>,
 }
 
 /// Default function for listing status
@@ -58,29 +73,89 @@ pub fn validate_offer(offer: Offer) -> ExternResult<ValidateCallbackResult> {
     ));
   }
 
+  // Validate location if present
+  if let Some(location) = &offer.location {
+    if let Err(error) = location.validate() {
+      return Ok(ValidateCallbackResult::Invalid(error));
+    }
+  }
+
+  // Validate capacity if present
+  if offer.capacity == Some(0) {
+    return Ok(ValidateCallbackResult::Invalid(
+      "Offer capacity must be at least 1".to_string(),
+    ));
+  }
+
+  // Validate valuation if present
+  if let Some(valuation) = &offer.valuation {
+    let result = validate_valuation(valuation)?;
+    if result != ValidateCallbackResult::Valid {
+      return Ok(result);
+    }
+  }
+
   Ok(ValidateCallbackResult::Valid)
 }
 
-/// Validates an update to an offer
+/// Validates a new offer, which must start out active and without fulfilment notes
+pub fn validate_create_offer(offer: Offer) -> ExternResult<ValidateCallbackResult> {
+  if offer.status != ListingStatus::Active {
+    return Ok(ValidateCallbackResult::Invalid(
+      "New offers must be active".to_string(),
+    ));
+  }
+
+  if offer.fulfilment_notes.is_some() {
+    return Ok(ValidateCallbackResult::Invalid(
+      "New offers cannot have fulfilment notes".to_string(),
+    ));
+  }
+
+  validate_offer(offer)
+}
+
+/// Validates an update to an offer, including its status transition
 pub fn validate_update_offer(
-  _action: Update,
-  _offer: Offer,
-  _original_action: EntryCreationAction,
-  _original_offer: Offer,
+  action: Update,
+  offer: Offer,
+  original_action: EntryCreationAction,
+  original_offer: Offer,
 ) -> ExternResult<ValidateCallbackResult> {
-  // Add specific update validation logic if needed
-  Ok(ValidateCallbackResult::Valid)
+  let authorization = validate_update_authorization(
+    &action,
+    &original_action,
+    LinkTypes::OfferOrganization.try_into()?,
+    offer.update_authorization.as_ref(),
+  )?;
+  if authorization != ValidateCallbackResult::Valid {
+    return Ok(authorization);
+  }
+
+  if !original_offer.status.can_transition_to(&offer.status) {
+    return Ok(ValidateCallbackResult::Invalid(format!(
+      "Offer status cannot change from {:?} to {:?}",
+      original_offer.status, offer.status
+    )));
+  }
+
+  if offer.fulfilment_notes.is_some() && offer.status != ListingStatus::Fulfilled {
+    return Ok(ValidateCallbackResult::Invalid(
+      "Only fulfilled offers can have fulfilment notes".to_string(),
+    ));
+  }
+
+  validate_offer(offer)
 }
 
 /// Validates an offer link creation
 pub fn validate_create_link_offer_updates(
-  _action: CreateLink,
+  action: CreateLink,
   _base_address: AnyLinkableHash,
   _target_address: AnyLinkableHash,
   _tag: LinkTag,
 ) -> ExternResult<ValidateCallbackResult> {
-  // Add specific link creation validation logic if needed
-  Ok(ValidateCallbackResult::Valid)
+  validate_listing_update_link(&action, &UnitEntryTypes::Offer.try_into()?)
 }
 
 /// Validates an offer link deletion
@@ -1,7 +1,12 @@
 use hdi::prelude::*;
+use utils::listing_links::{
+  validate_delete_listing_link, validate_listing_index_link, validate_listing_organization_link,
+  validate_listing_user_link,
+};
 
 mod request;
 pub use request::*;
+mod tests;
 
 #[derive(Serialize, Deserialize)]
 #[serde(tag = "type")]
@@ -22,6 +27,8 @@ pub enum LinkTypes {
   OrganizationRequests,
   RequestCreator,
   RequestOrganization,
+  ExpiredRequests,
+  FulfilledRequests,
 }
 
 #[hdk_extern]
@@ -34,10 +41,39 @@ pub fn genesis_self_check(_data: GenesisSelfCheckData) -> ExternResult<ValidateC
 pub fn validate(op: Op) -> ExternResult<ValidateCallbackResult> {
   if let FlatOp::StoreEntry(store_entry) = op.flattened::<EntryTypes, LinkTypes>()? {
     match store_entry {
-      OpEntry::CreateEntry { app_entry, .. } | OpEntry::UpdateEntry { app_entry, .. } => {
+      OpEntry::CreateEntry { app_entry, .. } => match app_entry {
+        EntryTypes::Request(request) => {
+          return validate_create_request(request);
+        }
+      },
+      OpEntry::UpdateEntry {
+        app_entry,
+        action,
+        original_action_hash,
+        ..
+      } => {
+        let original_record = must_get_valid_record(original_action_hash)?;
+        let original_action = match original_record.action().clone() {
+          Action::Create(create) => EntryCreationAction::Create(create),
+          Action::Update(update) => EntryCreationAction::Update(update),
+          _ => {
+            return Ok(ValidateCallbackResult::Invalid(
+              "Original action for an update must be a Create or Update action".to_string(),
+            ));
+          }
+        };
+        let Some(original_request) = original_record
+          .entry()
+          .to_app_option::<Request>()
+          .map_err(|e| wasm_error!(e))?
+        else {
+          return Ok(ValidateCallbackResult::Invalid(
+            "Original record for an update must be a request".to_string(),
+          ));
+        };
         match app_entry {
           EntryTypes::Request(request) => {
-            return validate_request(request);
+            return validate_update_request(action, request, original_action, original_request);
           }
         }
       }
@@ -100,5 +136,84 @@ pub fn validate(op: Op) -> ExternResult<ValidateCallbackResult> {
       _ => (),
     }
   }
+  if let FlatOp::RegisterCreateLink {
+    link_type,
+    base_address,
+    target_address,
+    tag,
+    action,
+  } = op.flattened::<EntryTypes, LinkTypes>()?
+  {
+    let listing_entry_type: EntryType = UnitEntryTypes::Request.try_into()?;
+    return match link_type {
+      LinkTypes::RequestUpdates => {
+        validate_create_link_request_updates(action, base_address, target_address, tag)
+      }
+      LinkTypes::ActiveRequests => {
+        validate_listing_index_link(&action, "requests.active", &listing_entry_type)
+      }
+      LinkTypes::ArchivedRequests => {
+        validate_listing_index_link(&action, "requests.archived", &listing_entry_type)
+      }
+      LinkTypes::ExpiredRequests => {
+        validate_listing_index_link(&action, "requests.expired", &listing_entry_type)
+      }
+      LinkTypes::FulfilledRequests => {
+        validate_listing_index_link(&action, "requests.fulfilled", &listing_entry_type)
+      }
+      LinkTypes::UserRequests => validate_listing_user_link(
+        &action,
+        &action.target_address,
+        &action.base_address,
+        &listing_entry_type,
+      ),
+      LinkTypes::RequestCreator => validate_listing_user_link(
+        &action,
+        &action.base_address,
+        &action.target_address,
+        &listing_entry_type,
+      ),
+      LinkTypes::OrganizationRequests => validate_listing_organization_link(
+        &action,
+        &action.target_address,
+        &action.base_address,
+        &listing_entry_type,
+      ),
+      LinkTypes::RequestOrganization => validate_listing_organization_link(
+        &action,
+        &action.base_address,
+        &action.target_address,
+        &listing_entry_type,
+      ),
+      _ => Ok(ValidateCallbackResult::Valid),
+    };
+  }
+  if let FlatOp::RegisterDeleteLink {
+    link_type,
+    original_action,
+    action,
+    ..
+  } = op.flattened::<EntryTypes, LinkTypes>()?
+  {
+    let listing_entry_type: EntryType = UnitEntryTypes::Request.try_into()?;
+    let listing_hash = match link_type {
+      LinkTypes::RequestUpdates | LinkTypes::RequestCreator | LinkTypes::RequestOrganization => {
+        original_action.base_address.clone()
+      }
+      LinkTypes::AllRequests
+      | LinkTypes::ActiveRequests
+      | LinkTypes::ArchivedRequests
+      | LinkTypes::ExpiredRequests
+      | LinkTypes::FulfilledRequests
+      | LinkTypes::UserRequests
+      | LinkTypes::OrganizationRequests => original_action.target_address.clone(),
+    };
+    return validate_delete_listing_link(
+      &action,
+      &original_action,
+      &listing_hash,
+      &listing_entry_type,
+    );
+  }
   Ok(ValidateCallbackResult::Valid)
 }
@@ -1,14 +1,13 @@
 use hdi::prelude::*;
-use utils::{ContactPreference, DateRange, InteractionType, TimePreference, TimeZone};
-
-/// Represents the status of a listing
-#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Default)]
-pub enum ListingStatus {
-  #[default]
-  Active,
-  Archived,
-  Deleted, // Soft delete
-}
+pub use utils::ListingStatus;
+use utils::{
+  update_authorization::{validate_listing_update_link, validate_update_authorization},
+  valuation::validate_valuation,
+  ContactPreference, DateRange, GeoLocation, InteractionType, TimePreference, TimeZone,
+  UpdateAuthorization, Valuation,
+};
+
+use crate::{LinkTypes, UnitEntryTypes};
 
 /// Represents a Request Entry with various attributes
 #[hdk_entry_helper]
@@ -39,6 +38,19 @@ pub struct Request {
   /// The status of the request (defaults to Active for backward compatibility)
   #[serde(default)]
   pub status: ListingStatus,
+  /// What was done, recorded when the request is fulfilled
+  #[serde(default)]
+  pub fulfilment_notes: Option<String>,
+  /// The approximate location of the request, for in-person exchanges
+  #[serde(default)]
+  pub location: Option<GeoLocation>,
+  /// How much the requester offers for the request
+  #[serde(default)]
+  pub valuation: Option<Valuation>,
+  /// Proof of the role of an administrator or organization coordinator updating someone
+  /// else's request, set by the coordinator on each update
+  #[serde(default)]
+  pub update_authorization: Option<UpdateAuthorization>,
 }
 
 /// Validates a request entry
@@ -73,28 +85,82 @@ pub fn validate_request(request: Request) -> ExternResult<ValidateCallbackResult
     }
   }
 
+  // Validate location if present
+  if let Some(location) = &request.location {
+    if let Err(error) = location.validate() {
+      return Ok(ValidateCallbackResult::Invalid(error));
+    }
+  }
+
+  // Validate valuation if present
+  if let Some(valuation) = &request.valuation {
+    let result = validate_valuation(valuation)?;
+    if result != ValidateCallbackResult::Valid {
+      return Ok(result);
+    }
+  }
+
   Ok(ValidateCallbackResult::Valid)
 }
 
-/// Validates an update to a request
+/// Validates a new request, which must start out active and without fulfilment notes
+pub fn validate_create_request(request: Request) -> ExternResult<ValidateCallbackResult> {
+  if request.status != ListingStatus::Active {
+    return Ok(ValidateCallbackResult::Invalid(
+      "New requests must be active".to_string(),
+    ));
+  }
+
+  if request.fulfilment_notes.is_some() {
+    return Ok(ValidateCallbackResult::Invalid(
+      "New requests cannot have fulfilment notes".to_string(),
+    ));
+  }
+
+  validate_request(request)
+}
+
+/// Validates an update to a request, including its status transition
 pub fn validate_update_request(
-  _action: Update,
+  action: Update,
   request: Request,
-  _original_action: EntryCreationAction,
-  _original_request: Request,
+  original_action: EntryCreationAction,
+  original_request: Request,
 ) -> ExternResult<ValidateCallbackResult> {
+  let authorization = validate_update_authorization(
+    &action,
+    &original_action,
+    LinkTypes::RequestOrganization.try_into()?,
+    request.update_authorization.as_ref(),
+  )?;
+  if authorization != ValidateCallbackResult::Valid {
+    return Ok(authorization);
+  }
+
+  if !original_request.status.can_transition_to(&request.status) {
+    return Ok(ValidateCallbackResult::Invalid(format!(
+      "Request status cannot change from {:?} to {:?}",
+      original_request.status, request.status
+    )));
+  }
+
+  if request.fulfilment_notes.is_some() && request.status != ListingStatus::Fulfilled {
+    return Ok(ValidateCallbackResult::Invalid(
+      "Only fulfilled requests can have fulfilment notes".to_string(),
+    ));
+  }
+
   validate_request(request)
 }
 
 /// Validates a request link creation
 pub fn validate_create_link_request_updates(
-  _action: CreateLink,
+  action: CreateLink,
   _base_address: AnyLinkableHash,
   _target_address: AnyLinkableHash,
   _tag: LinkTag,
 ) -> ExternResult<ValidateCallbackResult> {
-  // Add specific link creation validation logic if needed
-  Ok(ValidateCallbackResult::Valid)
+  validate_listing_update_link(&action, &UnitEntryTypes::Request.try_into()?)
 }
 
 /// Validates a request link deletion
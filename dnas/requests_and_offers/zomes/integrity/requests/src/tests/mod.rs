@@ -0,0 +1 @@
+mod zome_types;
@@ -0,0 +1,124 @@
+use hdi::prelude::*;
+
+mod response;
+pub use response::*;
+mod tests;
+
+#[derive(Serialize, Deserialize)]
+#[serde(tag = "type")]
+#[hdk_entry_types]
+#[unit_enum(UnitEntryTypes)]
+pub enum EntryTypes {
+  Response(Response),
+}
+
+#[derive(Serialize, Deserialize)]
+#[hdk_link_types]
+pub enum LinkTypes {
+  ResponseUpdates,
+  RequestResponses,
+  UserResponses,
+  ResponseResponder,
+}
+
+#[hdk_extern]
+pub fn genesis_self_check(_data: GenesisSelfCheckData) -> ExternResult<ValidateCallbackResult> {
+  Ok(ValidateCallbackResult::Valid)
+}
+
+#[allow(clippy::collapsible_match, clippy::single_match)]
+#[hdk_extern]
+pub fn validate(op: Op) -> ExternResult<ValidateCallbackResult> {
+  if let FlatOp::StoreEntry(store_entry) = op.flattened::<EntryTypes, LinkTypes>()? {
+    match store_entry {
+      OpEntry::CreateEntry { app_entry, action } => match app_entry {
+        EntryTypes::Response(response) => {
+          return validate_create_response(action, response);
+        }
+      },
+      OpEntry::UpdateEntry {
+        app_entry,
+        action,
+        original_action_hash,
+        ..
+      } => {
+        let original_record = must_get_valid_record(original_action_hash)?;
+        let original_action = match original_record.action().clone() {
+          Action::Create(create) => EntryCreationAction::Create(create),
+          Action::Update(update) => EntryCreationAction::Update(update),
+          _ => {
+            return Ok(ValidateCallbackResult::Invalid(
+              "Original action for an update must be a Create or Update action".to_string(),
+            ));
+          }
+        };
+        let Some(original_response) = original_record
+          .entry()
+          .to_app_option::<Response>()
+          .map_err(|e| wasm_error!(e))?
+        else {
+          return Ok(ValidateCallbackResult::Invalid(
+            "Original record for an update must be a response".to_string(),
+          ));
+        };
+        match app_entry {
+          EntryTypes::Response(response) => {
+            return validate_update_response(action, response, original_action, original_response);
+          }
+        }
+      }
+      _ => (),
+    }
+  }
+  if let FlatOp::RegisterCreateLink {
+    link_type, action, ..
+  } = op.flattened::<EntryTypes, LinkTypes>()?
+  {
+    match link_type {
+      LinkTypes::ResponseUpdates => {
+        return validate_create_link_response_updates(action);
+      }
+      LinkTypes::RequestResponses => {
+        return validate_create_link_request_responses(action);
+      }
+      LinkTypes::UserResponses => {
+        return validate_create_link_user_responses(action);
+      }
+      LinkTypes::ResponseResponder => {
+        return validate_create_link_response_responder(action);
+      }
+    }
+  }
+  if let FlatOp::RegisterDeleteLink {
+    link_type,
+    original_action,
+    action,
+    ..
+  } = op.flattened::<EntryTypes, LinkTypes>()?
+  {
+    match link_type {
+      LinkTypes::RequestResponses | LinkTypes::UserResponses | LinkTypes::ResponseResponder => {
+        return validate_delete_link_response(action, original_action);
+      }
+      _ => (),
+    }
+  }
+  if let FlatOp::StoreRecord(store_record) = op.flattened::<EntryTypes, LinkTypes>()? {
+    match store_record {
+      OpRecord::DeleteEntry {
+        original_action_hash,
+        action,
+        ..
+      } => {
+        let original_record = must_get_valid_record(original_action_hash)?;
+        if *original_record.action().author() != action.author {
+          return Ok(ValidateCallbackResult::Invalid(
+            "Only the responder can delete a response".to_string(),
+          ));
+        }
+      }
+      _ => (),
+    }
+  }
+  Ok(ValidateCallbackResult::Valid)
+}
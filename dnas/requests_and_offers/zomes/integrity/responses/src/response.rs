@@ -0,0 +1,268 @@
+use hdi::prelude::*;
+use utils::{
+  role_links::{
+    must_get_create_of, MEDIUM_OF_EXCHANGE_ENTRY, OFFER_ENTRY, REQUEST_ENTRY, USER_ENTRY,
+  },
+  update_authorization::validate_listing_update_link,
+};
+
+use crate::UnitEntryTypes;
+
+/// Decision of the request owner on a response
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Default)]
+pub enum ResponseStatus {
+  #[default]
+  Pending,
+  Accepted,
+  Declined,
+}
+
+/// Represents a Response Entry: a user answering a request, either with one of
+/// their offers or with a free-form proposal
+#[hdk_entry_helper]
+#[derive(Clone, PartialEq)]
+pub struct Response {
+  /// The original action hash of the request being answered
+  pub request_hash: ActionHash,
+  /// The original action hash of one of the responder's offers, if any
+  pub offer_hash: Option<ActionHash>,
+  /// The message to the request owner
+  pub message: String,
+  /// The original action hash of the proposed medium of exchange, if any
+  pub medium_of_exchange_hash: Option<ActionHash>,
+  /// The decision of the request owner (defaults to Pending for new responses)
+  #[serde(default)]
+  pub status: ResponseStatus,
+}
+
+/// Validates a response entry
+pub fn validate_response(response: Response) -> ExternResult<ValidateCallbackResult> {
+  // Validate message
+  if response.message.trim().is_empty() {
+    return Ok(ValidateCallbackResult::Invalid(
+      "Response message cannot be empty".to_string(),
+    ));
+  }
+
+  // Validate message length (1000 character limit)
+  if response.message.len() > 1000 {
+    return Ok(ValidateCallbackResult::Invalid(
+      "Response message cannot exceed 1000 characters".to_string(),
+    ));
+  }
+
+  Ok(ValidateCallbackResult::Valid)
+}
+
+/// Validates a new response, which must be pending and answer someone else's request.
+/// The offer it proposes must be one of the responder's, and its medium of exchange a
+/// medium of exchange.
+pub fn validate_create_response(
+  action: Create,
+  response: Response,
+) -> ExternResult<ValidateCallbackResult> {
+  if response.status != ResponseStatus::Pending {
+    return Ok(ValidateCallbackResult::Invalid(
+      "A new response must be pending".to_string(),
+    ));
+  }
+
+  let zome_names = dna_info()?.zome_names;
+  let request = match must_get_create_of(
+    &response.request_hash.clone().into(),
+    REQUEST_ENTRY,
+    &zome_names,
+  )? {
+    Ok(request) => request,
+    Err(_) => {
+      return Ok(ValidateCallbackResult::Invalid(
+        "A response must answer the original action of a request".to_string(),
+      ))
+    }
+  };
+  if request.author == action.author {
+    return Ok(ValidateCallbackResult::Invalid(
+      "A response cannot answer the responder's own request".to_string(),
+    ));
+  }
+
+  if let Some(offer_hash) = &response.offer_hash {
+    let offer = match must_get_create_of(&offer_hash.clone().into(), OFFER_ENTRY, &zome_names)? {
+      Ok(offer) => offer,
+      Err(_) => {
+        return Ok(ValidateCallbackResult::Invalid(
+          "A response can only propose the original action of an offer".to_string(),
+        ))
+      }
+    };
+    if offer.author != action.author {
+      return Ok(ValidateCallbackResult::Invalid(
+        "A response can only propose one of the responder's offers".to_string(),
+      ));
+    }
+  }
+
+  if let Some(medium_of_exchange_hash) = &response.medium_of_exchange_hash {
+    if must_get_create_of(
+      &medium_of_exchange_hash.clone().into(),
+      MEDIUM_OF_EXCHANGE_ENTRY,
+      &zome_names,
+    )?
+    .is_err()
+    {
+      return Ok(ValidateCallbackResult::Invalid(
+        "A response can only propose a medium of exchange".to_string(),
+      ));
+    }
+  }
+
+  validate_response(response)
+}
+
+/// Validates an update to a response.
+///
+/// Updates only record the decision of the request owner: a pending response can be
+/// accepted or declined once, by the author of the request, without changing anything else.
+pub fn validate_update_response(
+  action: Update,
+  response: Response,
+  _original_action: EntryCreationAction,
+  original_response: Response,
+) -> ExternResult<ValidateCallbackResult> {
+  if original_response.status != ResponseStatus::Pending
+    || response.status == ResponseStatus::Pending
+  {
+    return Ok(ValidateCallbackResult::Invalid(
+      "Only a pending response can be accepted or declined".to_string(),
+    ));
+  }
+
+  let unchanged = Response {
+    status: response.status.clone(),
+    ..original_response
+  };
+  if response != unchanged {
+    return Ok(ValidateCallbackResult::Invalid(
+      "Only the status of a response can be updated".to_string(),
+    ));
+  }
+
+  let request_action = must_get_action(response.request_hash)?;
+  if *request_action.action().author() != action.author {
+    return Ok(ValidateCallbackResult::Invalid(
+      "Only the author of the request can accept or decline a response".to_string(),
+    ));
+  }
+
+  Ok(ValidateCallbackResult::Valid)
+}
+
+/// Validates a link from the original action of a response to one of its updates
+pub fn validate_create_link_response_updates(
+  action: CreateLink,
+) -> ExternResult<ValidateCallbackResult> {
+  validate_listing_update_link(&action, &UnitEntryTypes::Response.try_into()?)
+}
+
+/// Fetches a response written by `author`, or returns why it cannot be used
+fn must_get_own_response(
+  hash: &AnyLinkableHash,
+  author: &AgentPubKey,
+) -> ExternResult<Result<Response, String>> {
+  let Some(action_hash) = hash.clone().into_action_hash() else {
+    return Ok(Err("A response link must point to a response".to_string()));
+  };
+  let record = must_get_valid_record(action_hash)?;
+  let response_entry_type: EntryType = UnitEntryTypes::Response.try_into()?;
+  if !matches!(record.action(), Action::Create(_))
+    || record.action().entry_type() != Some(&response_entry_type)
+  {
+    return Ok(Err(
+      "A response link must point to the original action of a response".to_string(),
+    ));
+  }
+  if record.action().author() != author {
+    return Ok(Err(
+      "Only the responder can link their response".to_string(),
+    ));
+  }
+  let Some(response) = record
+    .entry()
+    .to_app_option::<Response>()
+    .map_err(|e| wasm_error!(e))?
+  else {
+    return Ok(Err("A response link must point to a response".to_string()));
+  };
+
+  Ok(Ok(response))
+}
+
+/// Checks that a link points to the user profile of `author`
+fn validate_own_user(
+  hash: &AnyLinkableHash,
+  author: &AgentPubKey,
+) -> ExternResult<ValidateCallbackResult> {
+  let user = match must_get_create_of(hash, USER_ENTRY, &dna_info()?.zome_names)? {
+    Ok(user) => user,
+    Err(error) => return Ok(ValidateCallbackResult::Invalid(error)),
+  };
+  if user.author != *author {
+    return Ok(ValidateCallbackResult::Invalid(
+      "A response can only be linked to its responder's user profile".to_string(),
+    ));
+  }
+
+  Ok(ValidateCallbackResult::Valid)
+}
+
+/// Validates a link from a request to a response answering it
+pub fn validate_create_link_request_responses(
+  action: CreateLink,
+) -> ExternResult<ValidateCallbackResult> {
+  let response = match must_get_own_response(&action.target_address, &action.author)? {
+    Ok(response) => response,
+    Err(error) => return Ok(ValidateCallbackResult::Invalid(error)),
+  };
+  if action.base_address != response.request_hash.into() {
+    return Ok(ValidateCallbackResult::Invalid(
+      "A response can only be linked from the request it answers".to_string(),
+    ));
+  }
+
+  Ok(ValidateCallbackResult::Valid)
+}
+
+/// Validates a link from the responder's user profile to their response
+pub fn validate_create_link_user_responses(
+  action: CreateLink,
+) -> ExternResult<ValidateCallbackResult> {
+  if let Err(error) = must_get_own_response(&action.target_address, &action.author)? {
+    return Ok(ValidateCallbackResult::Invalid(error));
+  }
+  validate_own_user(&action.base_address, &action.author)
+}
+
+/// Validates a link from a response to its responder's user profile
+pub fn validate_create_link_response_responder(
+  action: CreateLink,
+) -> ExternResult<ValidateCallbackResult> {
+  if let Err(error) = must_get_own_response(&action.base_address, &action.author)? {
+    return Ok(ValidateCallbackResult::Invalid(error));
+  }
+  validate_own_user(&action.target_address, &action.author)
+}
+
+/// Validates the deletion of a link between a response, its request and its responder,
+/// which only the responder can delete
+pub fn validate_delete_link_response(
+  action: DeleteLink,
+  original_action: CreateLink,
+) -> ExternResult<ValidateCallbackResult> {
+  if action.author != original_action.author {
+    return Ok(ValidateCallbackResult::Invalid(
+      "Only the responder can delete the links of a response".to_string(),
+    ));
+  }
+
+  Ok(ValidateCallbackResult::Valid)
+}
@@ -0,0 +1,164 @@
+use hdi::prelude::*;
+use utils::GeoLocation;
+
+mod saved_search;
+pub use saved_search::*;
+
+/// Number of leading characters of a token used to build its index anchor.
+/// Tokens shorter than this are not indexed.
+pub const SEARCH_PREFIX_LENGTH: usize = 3;
+
+/// Maximum number of characters stored for a single token
+pub const MAX_SEARCH_TOKEN_LENGTH: usize = 32;
+
+/// Registry of all entry types defined in this integrity zome.
+#[derive(Serialize, Deserialize)]
+#[serde(tag = "type")]
+#[hdk_entry_types]
+#[unit_enum(UnitEntryTypes)]
+pub enum EntryTypes {
+  #[entry_type(visibility = "private")]
+  SavedSearch(SavedSearch),
+  #[entry_type(visibility = "private")]
+  SavedSearchRun(SavedSearchRun),
+}
+
+/// Registry of all link types defined in this integrity zome.
+#[derive(Serialize, Deserialize)]
+#[hdk_link_types]
+pub enum LinkTypes {
+  /// Index link from a `"search.{entity}.{prefix}"` path entry hash to the original action
+  /// hash of an indexed entity. The link tag holds the full normalized token.
+  SearchIndex,
+
+  /// Reverse link from an indexed entity's original action hash to each anchor it was
+  /// indexed under, so that the entity can be re-indexed or removed from the index.
+  /// The link tag holds the same token as the matching `SearchIndex` link.
+  EntitySearchAnchors,
+
+  /// Index link from a `"geo.{entity}.{geohash}"` path entry hash to the original action
+  /// hash of a listing located in that cell. The link tag holds the listing's serialized
+  /// `GeoLocation`.
+  GeoIndex,
+
+  /// Reverse link from a located listing's original action hash to each geohash anchor
+  /// it was indexed under. The link tag holds the same location as the `GeoIndex` link.
+  EntityGeoAnchors,
+}
+
+#[hdk_extern]
+pub fn genesis_self_check(_data: GenesisSelfCheckData) -> ExternResult<ValidateCallbackResult> {
+  Ok(ValidateCallbackResult::Valid)
+}
+
+/// Checks that a link tag holds a token as produced by the search coordinator:
+/// lowercase alphanumeric characters, between [`SEARCH_PREFIX_LENGTH`] and
+/// [`MAX_SEARCH_TOKEN_LENGTH`] characters long.
+fn validate_token_tag(tag: &LinkTag) -> ExternResult<ValidateCallbackResult> {
+  let Ok(token) = String::from_utf8(tag.0.clone()) else {
+    return Ok(ValidateCallbackResult::Invalid(
+      "Search token must be valid UTF-8".to_string(),
+    ));
+  };
+
+  let length = token.chars().count();
+  if !(SEARCH_PREFIX_LENGTH..=MAX_SEARCH_TOKEN_LENGTH).contains(&length) {
+    return Ok(ValidateCallbackResult::Invalid(format!(
+      "Search token must be between {} and {} characters",
+      SEARCH_PREFIX_LENGTH, MAX_SEARCH_TOKEN_LENGTH
+    )));
+  }
+
+  if token
+    .chars()
+    .any(|c| !c.is_alphanumeric() || c.to_lowercase().ne(std::iter::once(c)))
+  {
+    return Ok(ValidateCallbackResult::Invalid(
+      "Search token must be lowercase alphanumeric".to_string(),
+    ));
+  }
+
+  Ok(ValidateCallbackResult::Valid)
+}
+
+/// Checks that a link tag holds a valid serialized `GeoLocation`
+fn validate_location_tag(tag: &LinkTag) -> ExternResult<ValidateCallbackResult> {
+  let Ok(location) = GeoLocation::from_link_tag(tag) else {
+    return Ok(ValidateCallbackResult::Invalid(
+      "Geo index tag must hold a location".to_string(),
+    ));
+  };
+
+  match location.validate() {
+    Ok(()) => Ok(ValidateCallbackResult::Valid),
+    Err(error) => Ok(ValidateCallbackResult::Invalid(error)),
+  }
+}
+
+/// Checks that an index link is created by the author of the entity it indexes
+fn validate_index_link_author(
+  author: &AgentPubKey,
+  entity_hash: &AnyLinkableHash,
+) -> ExternResult<ValidateCallbackResult> {
+  let Some(action_hash) = entity_hash.clone().into_action_hash() else {
+    return Ok(ValidateCallbackResult::Invalid(
+      "An index link must point to an entity".to_string(),
+    ));
+  };
+  if must_get_valid_record(action_hash)?.action().author() != author {
+    return Ok(ValidateCallbackResult::Invalid(
+      "Only the author of an entity can index it".to_string(),
+    ));
+  }
+
+  Ok(ValidateCallbackResult::Valid)
+}
+
+/// HDK integrity validation callback.
+///
+/// Saved searches are checked on their author's chain. Index links are checked for their
+/// tag and can only be created by the author of the indexed entity, and removed by the
+/// author of the link.
+#[hdk_extern]
+pub fn validate(op: Op) -> ExternResult<ValidateCallbackResult> {
+  match op.flattened::<EntryTypes, LinkTypes>()? {
+    FlatOp::StoreEntry(OpEntry::CreateEntry { app_entry, .. })
+    | FlatOp::StoreEntry(OpEntry::UpdateEntry { app_entry, .. })
+    | FlatOp::StoreRecord(OpRecord::CreateEntry { app_entry, .. })
+    | FlatOp::StoreRecord(OpRecord::UpdateEntry { app_entry, .. }) => match app_entry {
+      EntryTypes::SavedSearch(saved_search) => validate_saved_search(saved_search),
+      EntryTypes::SavedSearchRun(_) => Ok(ValidateCallbackResult::Valid),
+    },
+    FlatOp::RegisterCreateLink {
+      link_type,
+      base_address,
+      target_address,
+      tag,
+      action,
+    } => {
+      let (tag_validation, entity_hash) = match link_type {
+        LinkTypes::SearchIndex => (validate_token_tag(&tag)?, target_address),
+        LinkTypes::EntitySearchAnchors => (validate_token_tag(&tag)?, base_address),
+        LinkTypes::GeoIndex => (validate_location_tag(&tag)?, target_address),
+        LinkTypes::EntityGeoAnchors => (validate_location_tag(&tag)?, base_address),
+      };
+      if tag_validation != ValidateCallbackResult::Valid {
+        return Ok(tag_validation);
+      }
+      validate_index_link_author(&action.author, &entity_hash)
+    }
+    FlatOp::RegisterDeleteLink {
+      action,
+      original_action,
+      ..
+    } => {
+      if action.author != original_action.author {
+        return Ok(ValidateCallbackResult::Invalid(
+          "Only the author of an index link can remove it".to_string(),
+        ));
+      }
+      Ok(ValidateCallbackResult::Valid)
+    }
+    _ => Ok(ValidateCallbackResult::Valid),
+  }
+}
@@ -0,0 +1,59 @@
+use hdi::prelude::*;
+use utils::{DateRange, InteractionType, TimePreference, TimeZone};
+
+/// Represents a SavedSearch Entry: filter criteria a user runs again and again.
+/// Saved searches are private to the agent who made them.
+#[hdk_entry_helper]
+#[derive(Clone, PartialEq)]
+pub struct SavedSearch {
+  /// A name to recognise the search by
+  pub name: String,
+  /// Whether to search requests or offers ("request" or "offer")
+  pub entity: String,
+  /// Listings must have at least one of these service types, if any are given
+  pub service_type_hashes: Vec<ActionHash>,
+  /// Listings must accept at least one of these mediums of exchange, if any are given
+  pub medium_of_exchange_hashes: Vec<ActionHash>,
+  pub interaction_type: Option<InteractionType>,
+  pub time_preference: Option<TimePreference>,
+  pub time_zone: Option<TimeZone>,
+  /// Listings with a date range must overlap this window
+  pub date_range: Option<DateRange>,
+}
+
+/// Represents a SavedSearchRun Entry: the listings a saved search found when it was last
+/// run, used to tell which results are new on the next run
+#[hdk_entry_helper]
+#[derive(Clone, PartialEq)]
+pub struct SavedSearchRun {
+  /// The action hash of the saved search
+  pub saved_search_hash: ActionHash,
+  /// The original action hashes of the listings found
+  pub result_hashes: Vec<ActionHash>,
+}
+
+/// Validates a saved search entry
+pub fn validate_saved_search(saved_search: SavedSearch) -> ExternResult<ValidateCallbackResult> {
+  if saved_search.name.trim().is_empty() {
+    return Ok(ValidateCallbackResult::Invalid(
+      "Saved search name cannot be empty".to_string(),
+    ));
+  }
+
+  if saved_search.entity != "request" && saved_search.entity != "offer" {
+    return Ok(ValidateCallbackResult::Invalid(
+      "A saved search must be for requests or offers".to_string(),
+    ));
+  }
+
+  // Results are looked up from the service type and medium of exchange indexes
+  if saved_search.service_type_hashes.is_empty()
+    && saved_search.medium_of_exchange_hashes.is_empty()
+  {
+    return Ok(ValidateCallbackResult::Invalid(
+      "A saved search needs at least one service type or medium of exchange".to_string(),
+    ));
+  }
+
+  Ok(ValidateCallbackResult::Valid)
+}
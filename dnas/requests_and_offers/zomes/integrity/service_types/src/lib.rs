@@ -2,6 +2,9 @@ use hdi::prelude::*;
 
 mod service_type;
 pub use service_type::*;
+mod translation;
+pub use translation::*;
+mod tests;
 
 #[derive(Serialize, Deserialize)]
 #[serde(tag = "type")]
@@ -9,6 +12,7 @@ pub use service_type::*;
 #[unit_enum(UnitEntryTypes)]
 pub enum EntryTypes {
   ServiceType(ServiceType),
+  ServiceTypeTranslation(ServiceTypeTranslation),
 }
 
 #[derive(Serialize, Deserialize)]
@@ -28,6 +32,30 @@ pub enum LinkTypes {
   // Users
   ServiceTypeToUser,
   UserToServiceType,
+
+  // Tags
+  /// From the `["service_types", "tags", tag]` path to the original action hash of each
+  /// service type carrying the tag
+  TagToServiceType,
+  /// From the "service_types.tags" path to the path of each tag in use. The link tag
+  /// holds the tag itself.
+  AllServiceTypeTags,
+
+  // Hierarchy
+  /// From the original action hash of a service type to the original action hash of
+  /// each service type directly under it
+  ServiceTypeChildren,
+
+  // Translations
+  /// From the original action hash of a service type to each of its translations. The
+  /// link tag holds the locale of the translation.
+  ServiceTypeToTranslation,
+  /// From the "service_types.translations.pending", ".approved" and ".rejected" paths to
+  /// the translations with that status
+  ServiceTypeTranslationStatus,
+  /// From the "service_types.names" path to the original action hash of a service type.
+  /// The link tag holds one of its normalized names or aliases, in any approved locale.
+  ServiceTypeNames,
 }
 
 #[hdk_extern]
@@ -40,19 +68,81 @@ pub fn genesis_self_check(_data: GenesisSelfCheckData) -> ExternResult<ValidateC
 pub fn validate(op: Op) -> ExternResult<ValidateCallbackResult> {
   if let FlatOp::StoreEntry(store_entry) = op.flattened::<EntryTypes, LinkTypes>()? {
     match store_entry {
-      OpEntry::CreateEntry { app_entry, .. } | OpEntry::UpdateEntry { app_entry, .. } => {
-        match app_entry {
-          EntryTypes::ServiceType(service_type) => {
-            return validate_service_type(service_type);
+      OpEntry::CreateEntry { app_entry, .. } => match app_entry {
+        EntryTypes::ServiceType(service_type) => {
+          let result = validate_service_type_parent(service_type.parent_hash.as_ref())?;
+          if result != ValidateCallbackResult::Valid {
+            return Ok(result);
           }
+          let result = validate_service_type_merge(None, &service_type)?;
+          if result != ValidateCallbackResult::Valid {
+            return Ok(result);
+          }
+          return validate_service_type(service_type);
+        }
+        EntryTypes::ServiceTypeTranslation(translation) => {
+          return validate_service_type_translation(&translation);
+        }
+      },
+      OpEntry::UpdateEntry {
+        action,
+        app_entry,
+        original_action_hash,
+        ..
+      } => match app_entry {
+        EntryTypes::ServiceType(service_type) => {
+          let result = validate_service_type_parent_unchanged(
+            original_action_hash.clone(),
+            service_type.parent_hash.as_ref(),
+          )?;
+          if result != ValidateCallbackResult::Valid {
+            return Ok(result);
+          }
+          let result = validate_service_type_merge(Some(&action), &service_type)?;
+          if result != ValidateCallbackResult::Valid {
+            return Ok(result);
+          }
+          return validate_service_type(service_type);
+        }
+        EntryTypes::ServiceTypeTranslation(translation) => {
+          return validate_service_type_translation(&translation);
         }
+      },
+      _ => (),
+    }
+  }
+  if let FlatOp::RegisterCreateLink {
+    link_type, action, ..
+  } = op.flattened::<EntryTypes, LinkTypes>()?
+  {
+    match link_type {
+      LinkTypes::ServiceTypeChildren => {
+        return validate_create_link_service_type_child(action);
+      }
+      LinkTypes::ServiceTypeTranslationStatus => {
+        return validate_create_link_translation_status(action);
+      }
+      LinkTypes::ServiceTypeNames => {
+        return validate_create_link_service_type_name(action);
+      }
+      _ => (),
+    }
+  }
+
+  if let FlatOp::RegisterDeleteLink {
+    link_type,
+    original_action,
+    action,
+    ..
+  } = op.flattened::<EntryTypes, LinkTypes>()?
+  {
+    match link_type {
+      LinkTypes::ServiceTypeNames => {
+        return validate_delete_link_service_type_name(action, original_action);
       }
       _ => (),
     }
   }
-  // TODO: Implement link validation for ServiceType links
-  // This will be completed in a future iteration after resolving HDI syntax
-  // Current focus is on frontend implementation since backend is fully functional
 
   if let FlatOp::StoreRecord(store_record) = op.flattened::<EntryTypes, LinkTypes>()? {
     match store_record {
@@ -105,6 +195,7 @@ pub fn validate(op: Op) -> ExternResult<ValidateCallbackResult> {
           EntryTypes::ServiceType(_original_service_type) => {
             // You can add specific delete validation for service types here if needed
           }
+          EntryTypes::ServiceTypeTranslation(_original_translation) => {}
         }
       }
       _ => (),
@@ -125,5 +216,5 @@ pub fn validate_service_type(service_type: ServiceType) -> ExternResult<Validate
     ));
   }
 
-  Ok(ValidateCallbackResult::Valid)
+  validate_service_type_tags(&service_type.tags)
 }
@@ -1,4 +1,18 @@
 use hdi::prelude::*;
+use utils::role_links::{
+  check_administrator_proof, validate_network_administrator_link, RoleProof,
+};
+
+use crate::UnitEntryTypes;
+
+/// Maximum number of tags on a service type
+pub const MAX_SERVICE_TYPE_TAGS: usize = 10;
+
+/// Maximum number of characters in a tag
+pub const MAX_TAG_LENGTH: usize = 50;
+
+/// Maximum number of levels in the service type hierarchy
+pub const MAX_SERVICE_TYPE_DEPTH: usize = 10;
 
 // ServiceType Entry Definition
 #[hdk_entry_helper]
@@ -7,6 +21,283 @@ pub struct ServiceType {
   pub name: String,
   pub description: String,
   pub technical: bool, // true for technical services, false for non-technical
+  /// Normalized tags, see [`normalize_tag`]
+  #[serde(default)]
+  pub tags: Vec<String>,
+  /// The original action hash of the broader service type this one belongs under, if any
+  #[serde(default)]
+  pub parent_hash: Option<ActionHash>,
+  /// The original action hash of the service type this one was merged into, if any
+  #[serde(default)]
+  pub merged_into: Option<ActionHash>,
+  /// Set by the update merging the service type, see [`validate_service_type_merge`]
+  #[serde(default)]
+  pub merge_authorization: Option<MergeAuthorization>,
+}
+
+/// Proof that the update merging a service type is made by a network administrator,
+/// into a service type that was not merged itself
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct MergeAuthorization {
+  /// Proof of the `AgentAdministrators` link from the merging agent to
+  /// "network.administrators"
+  pub administrator_proof: RoleProof,
+  /// The latest version of the service type merged into, when it was merged into
+  pub target_version: ActionHash,
+}
+
+/// Normalizes a tag: lowercase, trimmed, with inner whitespace collapsed to single spaces
+pub fn normalize_tag(tag: &str) -> String {
+  tag
+    .split_whitespace()
+    .collect::<Vec<&str>>()
+    .join(" ")
+    .to_lowercase()
+}
+
+/// Checks that the tags of a service type are normalized, unique and within the limits
+pub fn validate_service_type_tags(tags: &[String]) -> ExternResult<ValidateCallbackResult> {
+  if tags.len() > MAX_SERVICE_TYPE_TAGS {
+    return Ok(ValidateCallbackResult::Invalid(format!(
+      "A service type cannot have more than {} tags",
+      MAX_SERVICE_TYPE_TAGS
+    )));
+  }
+
+  for (index, tag) in tags.iter().enumerate() {
+    if tag.is_empty() {
+      return Ok(ValidateCallbackResult::Invalid(
+        "Service type tags cannot be empty".to_string(),
+      ));
+    }
+    if tag.chars().count() > MAX_TAG_LENGTH {
+      return Ok(ValidateCallbackResult::Invalid(format!(
+        "Service type tags cannot exceed {} characters",
+        MAX_TAG_LENGTH
+      )));
+    }
+    if *tag != normalize_tag(tag) {
+      return Ok(ValidateCallbackResult::Invalid(format!(
+        "Service type tag \"{}\" is not normalized",
+        tag
+      )));
+    }
+    if tags[..index].contains(tag) {
+      return Ok(ValidateCallbackResult::Invalid(format!(
+        "Service type tag \"{}\" is repeated",
+        tag
+      )));
+    }
+  }
+
+  Ok(ValidateCallbackResult::Valid)
+}
+
+/// Follows an update chain back to the original action hash of a service type
+pub fn get_original_action_hash(action_hash: ActionHash) -> ExternResult<ActionHash> {
+  let mut action_hash = action_hash;
+  loop {
+    match must_get_action(action_hash.clone())?.action() {
+      Action::Update(update) => action_hash = update.original_action_address.clone(),
+      _ => return Ok(action_hash),
+    }
+  }
+}
+
+/// Fetches the service type created by an action
+fn must_get_service_type(action_hash: ActionHash) -> ExternResult<ServiceType> {
+  must_get_valid_record(action_hash)?
+    .entry()
+    .to_app_option::<ServiceType>()
+    .map_err(|e| wasm_error!(e))?
+    .ok_or(wasm_error!(WasmErrorInner::Guest(
+      "The action must create a service type".to_string()
+    )))
+}
+
+/// Checks that the parent of a new service type is the original action of another
+/// service type, and that following the parents up from it does not go deeper than
+/// [`MAX_SERVICE_TYPE_DEPTH`].
+///
+/// Parents cannot change after creation (see [`validate_service_type_parent_unchanged`]),
+/// so every ancestor is read as it was created and no cycle can form.
+pub fn validate_service_type_parent(
+  parent_hash: Option<&ActionHash>,
+) -> ExternResult<ValidateCallbackResult> {
+  let mut ancestor_hash = parent_hash.cloned();
+  let mut depth = 1;
+  while let Some(hash) = ancestor_hash {
+    depth += 1;
+    if depth > MAX_SERVICE_TYPE_DEPTH {
+      return Ok(ValidateCallbackResult::Invalid(format!(
+        "The service type hierarchy cannot be deeper than {} levels",
+        MAX_SERVICE_TYPE_DEPTH
+      )));
+    }
+
+    let record = must_get_valid_record(hash)?;
+    if !matches!(record.action(), Action::Create(_)) {
+      return Ok(ValidateCallbackResult::Invalid(
+        "The parent of a service type must be its original action hash".to_string(),
+      ));
+    }
+    let Some(ancestor) = record
+      .entry()
+      .to_app_option::<ServiceType>()
+      .map_err(|e| wasm_error!(e))?
+    else {
+      return Ok(ValidateCallbackResult::Invalid(
+        "The parent of a service type must be a service type".to_string(),
+      ));
+    };
+    ancestor_hash = ancestor.parent_hash;
+  }
+
+  Ok(ValidateCallbackResult::Valid)
+}
+
+/// Checks that an update keeps the parent of the version it updates
+pub fn validate_service_type_parent_unchanged(
+  previous_action_hash: ActionHash,
+  parent_hash: Option<&ActionHash>,
+) -> ExternResult<ValidateCallbackResult> {
+  let Some(previous) = must_get_valid_record(previous_action_hash)?
+    .entry()
+    .to_app_option::<ServiceType>()
+    .map_err(|e| wasm_error!(e))?
+  else {
+    return Ok(ValidateCallbackResult::Invalid(
+      "A service type update must update a service type".to_string(),
+    ));
+  };
+  if previous.parent_hash.as_ref() != parent_hash {
+    return Ok(ValidateCallbackResult::Invalid(
+      "The parent of a service type cannot be changed".to_string(),
+    ));
+  }
+
+  Ok(ValidateCallbackResult::Valid)
+}
+
+/// Checks that a service type is only merged when updated, and into the original action
+/// of another service type. A merged service type stays merged into the same one.
+///
+/// The update merging a service type must carry a [`MergeAuthorization`]: the proof of
+/// its author's administrator role, and a version of the target that is not merged.
+pub fn validate_service_type_merge(
+  update: Option<&Update>,
+  service_type: &ServiceType,
+) -> ExternResult<ValidateCallbackResult> {
+  let Some(update) = update else {
+    if service_type.merged_into.is_some() {
+      return Ok(ValidateCallbackResult::Invalid(
+        "A new service type cannot be merged".to_string(),
+      ));
+    }
+    return Ok(ValidateCallbackResult::Valid);
+  };
+
+  let previous = must_get_service_type(update.original_action_address.clone())?;
+  if previous.merged_into.is_some() {
+    if service_type.merged_into != previous.merged_into {
+      return Ok(ValidateCallbackResult::Invalid(
+        "A merged service type cannot be unmerged or merged again".to_string(),
+      ));
+    }
+    return Ok(ValidateCallbackResult::Valid);
+  }
+  let Some(merged_into) = &service_type.merged_into else {
+    return Ok(ValidateCallbackResult::Valid);
+  };
+
+  let original_action_hash = get_original_action_hash(update.original_action_address.clone())?;
+  if *merged_into == original_action_hash {
+    return Ok(ValidateCallbackResult::Invalid(
+      "A service type cannot be merged into itself".to_string(),
+    ));
+  }
+  let service_type_entry_type: EntryType = UnitEntryTypes::ServiceType.try_into()?;
+  let is_service_type = match must_get_valid_record(merged_into.clone())?.action() {
+    Action::Create(create) => create.entry_type == service_type_entry_type,
+    _ => false,
+  };
+  if !is_service_type {
+    return Ok(ValidateCallbackResult::Invalid(
+      "A service type must be merged into the original action of another service type".to_string(),
+    ));
+  }
+
+  let Some(authorization) = &service_type.merge_authorization else {
+    return Ok(ValidateCallbackResult::Invalid(
+      "Only network administrators can merge service types".to_string(),
+    ));
+  };
+  if let Err(error) = check_administrator_proof(
+    &authorization.administrator_proof,
+    &Path::from("network.administrators")
+      .path_entry_hash()?
+      .into(),
+    &update.author,
+    &update.prev_action,
+    update.timestamp,
+    &dna_info()?.zome_names,
+  )? {
+    return Ok(ValidateCallbackResult::Invalid(error));
+  }
+
+  if get_original_action_hash(authorization.target_version.clone())? != *merged_into {
+    return Ok(ValidateCallbackResult::Invalid(
+      "The merge must reference a version of the service type merged into".to_string(),
+    ));
+  }
+  if must_get_service_type(authorization.target_version.clone())?
+    .merged_into
+    .is_some()
+  {
+    return Ok(ValidateCallbackResult::Invalid(
+      "A service type cannot be merged into a merged service type".to_string(),
+    ));
+  }
+
+  Ok(ValidateCallbackResult::Valid)
+}
+
+/// Checks a link from a service type to a service type directly under it. The child
+/// must have been created under the base, unless the link is written by a network
+/// administrator, with the proof of their role in the tag, moving the children of a
+/// merged service type under the one it was merged into.
+pub fn validate_create_link_service_type_child(
+  action: CreateLink,
+) -> ExternResult<ValidateCallbackResult> {
+  let Some(child_hash) = action.target_address.clone().into_action_hash() else {
+    return Ok(ValidateCallbackResult::Invalid(
+      "A service type child link must point to a service type".to_string(),
+    ));
+  };
+  let service_type_entry_type: EntryType = UnitEntryTypes::ServiceType.try_into()?;
+  let record = must_get_valid_record(child_hash)?;
+  let is_service_type = match record.action() {
+    Action::Create(create) => create.entry_type == service_type_entry_type,
+    _ => false,
+  };
+  if !is_service_type {
+    return Ok(ValidateCallbackResult::Invalid(
+      "A service type child link must point to the original action of a service type".to_string(),
+    ));
+  }
+
+  let parent_hash = record
+    .entry()
+    .to_app_option::<ServiceType>()
+    .map_err(|e| wasm_error!(e))?
+    .and_then(|child| child.parent_hash);
+  if parent_hash
+    .is_some_and(|parent_hash| AnyLinkableHash::from(parent_hash) == action.base_address)
+  {
+    return Ok(ValidateCallbackResult::Valid);
+  }
+
+  validate_network_administrator_link(&action)
 }
 
 // Validation for creating a ServiceType
@@ -0,0 +1,13 @@
+#[cfg(test)]
+mod zome_types_tests {
+  use utils::role_links::SERVICE_TYPE_ENTRY;
+
+  use crate::UnitEntryTypes;
+
+  /// Other zomes reference the entry types of this zome by index
+  #[test]
+  fn test_referenced_type_indices_match() {
+    assert_eq!(SERVICE_TYPE_ENTRY.0, env!("CARGO_PKG_NAME"));
+    assert_eq!(UnitEntryTypes::ServiceType as u8, SERVICE_TYPE_ENTRY.1);
+  }
+}
@@ -0,0 +1,322 @@
+use hdi::prelude::*;
+use utils::role_links::{
+  check_administrator_proof, role_proof_from_tag, role_proof_tag,
+  validate_network_administrator_link, RoleProof,
+};
+
+use crate::{get_original_action_hash, normalize_tag, LinkTypes, UnitEntryTypes};
+
+/// Path linking to the translations waiting for approval
+pub const PENDING_TRANSLATIONS_PATH: &str = "service_types.translations.pending";
+
+/// Path indexing service types by their names and aliases in every approved locale
+pub const SERVICE_TYPE_NAMES_PATH: &str = "service_types.names";
+
+/// Separates the name from the proof in the tag of a name index link
+const NAME_TAG_SEPARATOR: u8 = 0;
+
+/// Maximum number of aliases in a service type translation
+pub const MAX_TRANSLATION_ALIASES: usize = 10;
+
+/// Maximum number of characters in an alias
+pub const MAX_ALIAS_LENGTH: usize = 100;
+
+/// The name, description and aliases of a service type in one locale. Translations are
+/// suggested by users and only shown once approved by an administrator.
+#[hdk_entry_helper]
+#[derive(Clone, PartialEq)]
+pub struct ServiceTypeTranslation {
+  /// The original action hash of the translated service type
+  pub service_type_hash: ActionHash,
+  /// A lowercase language tag such as "fr" or "es-mx", see [`normalize_locale`]
+  pub locale: String,
+  pub name: String,
+  pub description: String,
+  /// Other names the service type is known by in this locale
+  #[serde(default)]
+  pub aliases: Vec<String>,
+}
+
+/// Normalizes a language tag: trimmed, lowercase and with "-" as separator
+pub fn normalize_locale(locale: &str) -> String {
+  locale.trim().replace('_', "-").to_lowercase()
+}
+
+/// Whether a locale is a normalized language tag: a 2 or 3 letter language, optionally
+/// followed by "-" and a 2 to 8 character region or variant
+pub fn is_valid_locale(locale: &str) -> bool {
+  let mut subtags = locale.split('-');
+  let language_is_valid = subtags.next().is_some_and(|language| {
+    (2..=3).contains(&language.len()) && language.chars().all(|c| c.is_ascii_lowercase())
+  });
+  let region_is_valid = match subtags.next() {
+    Some(region) => {
+      (2..=8).contains(&region.len())
+        && region
+          .chars()
+          .all(|c| c.is_ascii_lowercase() || c.is_ascii_digit())
+    }
+    None => true,
+  };
+  language_is_valid && region_is_valid && subtags.next().is_none()
+}
+
+/// Checks the fields of a translation and that it translates the original action of a
+/// service type
+pub fn validate_service_type_translation(
+  translation: &ServiceTypeTranslation,
+) -> ExternResult<ValidateCallbackResult> {
+  if !is_valid_locale(&translation.locale) {
+    return Ok(ValidateCallbackResult::Invalid(format!(
+      "\"{}\" is not a valid locale",
+      translation.locale
+    )));
+  }
+  if translation.name.trim().is_empty() {
+    return Ok(ValidateCallbackResult::Invalid(
+      "Translated name cannot be empty".to_string(),
+    ));
+  }
+  if translation.description.trim().is_empty() {
+    return Ok(ValidateCallbackResult::Invalid(
+      "Translated description cannot be empty".to_string(),
+    ));
+  }
+
+  if translation.aliases.len() > MAX_TRANSLATION_ALIASES {
+    return Ok(ValidateCallbackResult::Invalid(format!(
+      "A translation cannot have more than {} aliases",
+      MAX_TRANSLATION_ALIASES
+    )));
+  }
+  for alias in &translation.aliases {
+    if alias.trim().is_empty() {
+      return Ok(ValidateCallbackResult::Invalid(
+        "Aliases cannot be empty".to_string(),
+      ));
+    }
+    if alias.chars().count() > MAX_ALIAS_LENGTH {
+      return Ok(ValidateCallbackResult::Invalid(format!(
+        "Aliases cannot exceed {} characters",
+        MAX_ALIAS_LENGTH
+      )));
+    }
+  }
+
+  let service_type_entry_type: EntryType = UnitEntryTypes::ServiceType.try_into()?;
+  let record = must_get_valid_record(translation.service_type_hash.clone())?;
+  let is_service_type = match record.action() {
+    Action::Create(create) => create.entry_type == service_type_entry_type,
+    _ => false,
+  };
+  if !is_service_type {
+    return Ok(ValidateCallbackResult::Invalid(
+      "A translation must point to the original action of a service type".to_string(),
+    ));
+  }
+
+  Ok(ValidateCallbackResult::Valid)
+}
+
+/// Validates a link giving a translation a status. A translation is submitted for approval
+/// by its author, and only network administrators approve or reject it.
+pub fn validate_create_link_translation_status(
+  action: CreateLink,
+) -> ExternResult<ValidateCallbackResult> {
+  let Some(translation_hash) = action.target_address.clone().into_action_hash() else {
+    return Ok(ValidateCallbackResult::Invalid(
+      "A translation status link must point to a translation".to_string(),
+    ));
+  };
+  let translation_entry_type: EntryType = UnitEntryTypes::ServiceTypeTranslation.try_into()?;
+  let record = must_get_valid_record(translation_hash)?;
+  let is_translation = match record.action() {
+    Action::Create(create) => create.entry_type == translation_entry_type,
+    _ => false,
+  };
+  if !is_translation {
+    return Ok(ValidateCallbackResult::Invalid(
+      "A translation status link must point to the original action of a translation".to_string(),
+    ));
+  }
+
+  let pending_path: AnyLinkableHash = Path::from(PENDING_TRANSLATIONS_PATH)
+    .path_entry_hash()?
+    .into();
+  if action.base_address == pending_path {
+    if *record.action().author() != action.author {
+      return Ok(ValidateCallbackResult::Invalid(
+        "Only the author of a translation can submit it for approval".to_string(),
+      ));
+    }
+    return Ok(ValidateCallbackResult::Valid);
+  }
+
+  validate_network_administrator_link(&action)
+}
+
+/// The tag of a link from the name index to a service type: the normalized name, so the
+/// index can be searched by prefix, then the proof of the indexing administrator's role
+pub fn service_type_name_tag(name: &str, proof: &RoleProof) -> ExternResult<LinkTag> {
+  let mut bytes = name.as_bytes().to_vec();
+  bytes.push(NAME_TAG_SEPARATOR);
+  bytes.extend(role_proof_tag(proof)?.0);
+  Ok(LinkTag::new(bytes))
+}
+
+/// The name and the proof held by a tag made by [`service_type_name_tag`]
+fn split_service_type_name_tag(tag: &LinkTag) -> Option<(String, Option<RoleProof>)> {
+  let separator = tag.0.iter().position(|byte| *byte == NAME_TAG_SEPARATOR)?;
+  let name = String::from_utf8(tag.0[..separator].to_vec()).ok()?;
+  let proof = role_proof_from_tag(&LinkTag::new(tag.0[separator + 1..].to_vec()));
+  Some((name, proof))
+}
+
+/// The name held by a tag made by [`service_type_name_tag`]
+pub fn service_type_name_from_tag(tag: &LinkTag) -> Option<String> {
+  split_service_type_name_tag(tag).map(|(name, _)| name)
+}
+
+/// Validates a link from the name index to a service type. Only network administrators
+/// index names, with the proof of their role after the name in the tag.
+pub fn validate_create_link_service_type_name(
+  action: CreateLink,
+) -> ExternResult<ValidateCallbackResult> {
+  let names_path: AnyLinkableHash = Path::from(SERVICE_TYPE_NAMES_PATH)
+    .path_entry_hash()?
+    .into();
+  if action.base_address != names_path {
+    return Ok(ValidateCallbackResult::Invalid(format!(
+      "A service type name link must start from the \"{}\" path",
+      SERVICE_TYPE_NAMES_PATH
+    )));
+  }
+
+  let Some(service_type_hash) = action.target_address.clone().into_action_hash() else {
+    return Ok(ValidateCallbackResult::Invalid(
+      "A service type name link must point to a service type".to_string(),
+    ));
+  };
+  let service_type_entry_type: EntryType = UnitEntryTypes::ServiceType.try_into()?;
+  let is_service_type = match must_get_valid_record(service_type_hash)?.action() {
+    Action::Create(create) => create.entry_type == service_type_entry_type,
+    _ => false,
+  };
+  if !is_service_type {
+    return Ok(ValidateCallbackResult::Invalid(
+      "A service type name link must point to the original action of a service type".to_string(),
+    ));
+  }
+
+  let Some((name, proof)) = split_service_type_name_tag(&action.tag) else {
+    return Ok(ValidateCallbackResult::Invalid(
+      "A service type name link must be tagged with a name".to_string(),
+    ));
+  };
+  if name.is_empty() || name != normalize_tag(&name) {
+    return Ok(ValidateCallbackResult::Invalid(format!(
+      "Service type name \"{}\" is not normalized",
+      name
+    )));
+  }
+  let Some(proof) = proof else {
+    return Ok(ValidateCallbackResult::Invalid(
+      "Only network administrators can index service type names".to_string(),
+    ));
+  };
+  if let Err(error) = check_administrator_proof(
+    &proof,
+    &Path::from("network.administrators")
+      .path_entry_hash()?
+      .into(),
+    &action.author,
+    &action.prev_action,
+    action.timestamp,
+    &dna_info()?.zome_names,
+  )? {
+    return Ok(ValidateCallbackResult::Invalid(error));
+  }
+
+  Ok(ValidateCallbackResult::Valid)
+}
+
+/// Whether a link an agent wrote gives a status to a translation of a service type on
+/// the strength of their administrator role
+fn approves_or_rejects_translation_of(
+  create_link: &CreateLink,
+  service_type_hash: &ActionHash,
+) -> ExternResult<bool> {
+  let status_link_type: ScopedLinkType = LinkTypes::ServiceTypeTranslationStatus.try_into()?;
+  let pending_path: AnyLinkableHash = Path::from(PENDING_TRANSLATIONS_PATH)
+    .path_entry_hash()?
+    .into();
+  if create_link.zome_index != status_link_type.zome_index
+    || create_link.link_type != status_link_type.zome_type
+    || create_link.base_address == pending_path
+  {
+    return Ok(false);
+  }
+  let Some(translation_hash) = create_link.target_address.clone().into_action_hash() else {
+    return Ok(false);
+  };
+
+  let translation_entry_type: EntryType = UnitEntryTypes::ServiceTypeTranslation.try_into()?;
+  let record = must_get_valid_record(translation_hash)?;
+  if record.action().entry_type() != Some(&translation_entry_type) {
+    return Ok(false);
+  }
+  let translates_service_type = record
+    .entry()
+    .to_app_option::<ServiceTypeTranslation>()
+    .map_err(|e| wasm_error!(e))?
+    .is_some_and(|translation| translation.service_type_hash == *service_type_hash);
+
+  Ok(
+    translates_service_type
+      && validate_network_administrator_link(create_link)? == ValidateCallbackResult::Valid,
+  )
+}
+
+/// Validates the removal of a link from the name index. A `DeleteLink` cannot carry the
+/// proof of the deleting agent's role, so the link must be removed by the agent who
+/// indexed the name, or by one who, since, updated or deleted the service type, or
+/// approved or rejected one of its translations.
+pub fn validate_delete_link_service_type_name(
+  action: DeleteLink,
+  original_action: CreateLink,
+) -> ExternResult<ValidateCallbackResult> {
+  if action.author == original_action.author {
+    return Ok(ValidateCallbackResult::Valid);
+  }
+  let Some(service_type_hash) = original_action.target_address.into_action_hash() else {
+    return Ok(ValidateCallbackResult::Invalid(
+      "A service type name link must point to a service type".to_string(),
+    ));
+  };
+
+  let service_type_entry_type: EntryType = UnitEntryTypes::ServiceType.try_into()?;
+  let activity = must_get_agent_activity(
+    action.author.clone(),
+    ChainFilter::new(action.prev_action.clone()).until_timestamp(original_action.timestamp),
+  )?;
+  for activity in activity {
+    let is_authorized = match activity.action.hashed.content {
+      Action::Update(update) => {
+        update.entry_type == service_type_entry_type
+          && get_original_action_hash(update.original_action_address)? == service_type_hash
+      }
+      Action::Delete(delete) => delete.deletes_address == service_type_hash,
+      Action::CreateLink(create_link) => {
+        approves_or_rejects_translation_of(&create_link, &service_type_hash)?
+      }
+      _ => false,
+    };
+    if is_authorized {
+      return Ok(ValidateCallbackResult::Valid);
+    }
+  }
+
+  Ok(ValidateCallbackResult::Invalid(
+    "Only the agent who indexed a name, or one who since updated the service type or decided on its translations, can remove it".to_string(),
+  ))
+}
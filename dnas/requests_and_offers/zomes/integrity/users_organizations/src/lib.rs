@@ -68,6 +68,20 @@ pub fn validate(op: Op) -> ExternResult<ValidateCallbackResult> {
       _ => (),
     }
   }
+  if let FlatOp::RegisterCreateLink {
+    link_type, action, ..
+  } = op.flattened::<EntryTypes, LinkTypes>()?
+  {
+    match link_type {
+      LinkTypes::UserAgents => {
+        return validate_create_link_user_agents(&action);
+      }
+      LinkTypes::OrganizationCoordinators | LinkTypes::OrganizationMembers => {
+        return validate_create_link_organization_role(&action);
+      }
+      _ => (),
+    }
+  }
   if let FlatOp::StoreRecord(store_record) = op.flattened::<EntryTypes, LinkTypes>()? {
     match store_record {
       OpRecord::DeleteEntry {
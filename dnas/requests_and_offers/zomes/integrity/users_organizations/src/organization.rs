@@ -1,6 +1,12 @@
 use email_address::EmailAddress;
 use hdi::prelude::*;
-use utils::{errors::CommonError, is_image};
+use utils::{
+  errors::CommonError,
+  is_image,
+  role_links::{
+    must_get_create_of, validate_organization_role_author, ORGANIZATION_ENTRY, USER_ENTRY,
+  },
+};
 
 #[hdk_entry_helper]
 #[derive(Clone, PartialEq)]
@@ -98,3 +104,22 @@ pub fn validate_delete_link_organization_updates(
     "OrganizationUpdates links cannot be deleted",
   )))
 }
+
+/// Validates the creation of an `OrganizationCoordinators` or `OrganizationMembers` link,
+/// from an organization to a user profile. Listings zomes accept these links as proof of
+/// a role in the organization, so only its creator or its coordinators can create them.
+pub fn validate_create_link_organization_role(
+  action: &CreateLink,
+) -> ExternResult<ValidateCallbackResult> {
+  let zome_names = dna_info()?.zome_names;
+  let organization =
+    match must_get_create_of(&action.base_address, ORGANIZATION_ENTRY, &zome_names)? {
+      Ok(organization) => organization,
+      Err(error) => return Ok(ValidateCallbackResult::Invalid(error)),
+    };
+  if let Err(error) = must_get_create_of(&action.target_address, USER_ENTRY, &zome_names)? {
+    return Ok(ValidateCallbackResult::Invalid(error));
+  }
+
+  validate_organization_role_author(action, &organization.author, &zome_names)
+}
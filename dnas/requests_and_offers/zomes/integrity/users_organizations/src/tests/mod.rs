@@ -1 +1,2 @@
 mod user_test;
+mod zome_types;
@@ -0,0 +1,28 @@
+#[cfg(test)]
+mod zome_types_tests {
+  use utils::role_links::{
+    ORGANIZATION_COORDINATORS, ORGANIZATION_ENTRY, ORGANIZATION_MEMBERS, USER_ENTRY,
+  };
+
+  use crate::{LinkTypes, UnitEntryTypes};
+
+  /// Other zomes reference the entry and link types of this zome by index
+  #[test]
+  fn test_referenced_type_indices_match() {
+    for (zome_name, _) in [
+      USER_ENTRY,
+      ORGANIZATION_ENTRY,
+      ORGANIZATION_COORDINATORS,
+      ORGANIZATION_MEMBERS,
+    ] {
+      assert_eq!(zome_name, env!("CARGO_PKG_NAME"));
+    }
+    assert_eq!(UnitEntryTypes::User as u8, USER_ENTRY.1);
+    assert_eq!(UnitEntryTypes::Organization as u8, ORGANIZATION_ENTRY.1);
+    assert_eq!(
+      LinkTypes::OrganizationCoordinators as u8,
+      ORGANIZATION_COORDINATORS.1
+    );
+    assert_eq!(LinkTypes::OrganizationMembers as u8, ORGANIZATION_MEMBERS.1);
+  }
+}
@@ -133,6 +133,33 @@ pub fn validate_create_link_user_updates(
   Ok(ValidateCallbackResult::Valid)
 }
 
+/// Checks a link from a user profile to an agent acting for it. Only the author of the
+/// profile can link it, and only to themselves.
+pub fn validate_create_link_user_agents(
+  action: &CreateLink,
+) -> ExternResult<ValidateCallbackResult> {
+  let user_hash = action
+    .base_address
+    .clone()
+    .into_action_hash()
+    .ok_or(CommonError::ActionHashNotFound("user".to_string()))?;
+  let record = must_get_valid_record(user_hash)?;
+  let _user: User = record
+    .entry()
+    .to_app_option()
+    .map_err(CommonError::Serialize)?
+    .ok_or(CommonError::EntryNotFound("user".to_string()))?;
+  if *record.action().author() != action.author
+    || action.target_address != action.author.clone().into()
+  {
+    return Ok(ValidateCallbackResult::Invalid(String::from(
+      "A user profile can only be linked to its author",
+    )));
+  }
+
+  Ok(ValidateCallbackResult::Valid)
+}
+
 pub fn validate_delete_link_user_updates(
   _action: DeleteLink,
   _original_action: CreateLink,
@@ -6,6 +6,8 @@
 use holochain::prelude::*;
 use serde::{Deserialize, Serialize};
 
+use super::mirrors::{DateRange, GeoLocation, PaginationCursor, ServiceTypeTranslation, Valuation};
+
 // ── User fixtures ─────────────────────────────────────────────
 
 /// Input for `create_user` / `update_user` zome calls.
@@ -157,13 +159,16 @@ pub const ENTITY_NETWORK: &str = "network";
 
 /// Mirrors `ServiceType` from `service_types_integrity` for use as coordinator input.
 ///
-/// Fields: name (String), description (String), technical (bool).
+/// Fields: name (String), description (String), technical (bool), tags (Vec<String>),
+/// parent_hash (Option<ActionHash>).
 /// This is used both as create/update input and as the inner type for `ServiceTypeInput`.
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct ServiceTypeEntry {
     pub name: String,
     pub description: String,
     pub technical: bool,
+    pub tags: Vec<String>,
+    pub parent_hash: Option<ActionHash>,
 }
 
 /// Input for `create_service_type` zome call.
@@ -179,6 +184,32 @@ pub fn sample_service_type(name: impl Into<String>) -> ServiceTypeInput {
             name: name.into(),
             description: "A sample service type for testing.".to_string(),
             technical: false,
+            tags: Vec::new(),
+            parent_hash: None,
+        },
+    }
+}
+
+/// Input for `suggest_service_type_translation` zome call.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ServiceTypeTranslationInput {
+    pub translation: ServiceTypeTranslation,
+}
+
+/// Create a sample translation of a service type.
+pub fn sample_translation(
+    service_type_hash: ActionHash,
+    locale: impl Into<String>,
+    name: impl Into<String>,
+    aliases: &[&str],
+) -> ServiceTypeTranslationInput {
+    ServiceTypeTranslationInput {
+        translation: ServiceTypeTranslation {
+            service_type_hash,
+            locale: locale.into(),
+            name: name.into(),
+            description: "A sample translation for testing.".to_string(),
+            aliases: aliases.iter().map(|alias| alias.to_string()).collect(),
         },
     }
 }
@@ -236,13 +267,15 @@ pub struct RequestData {
     pub title: String,
     pub description: String,
     pub contact_preference: String,
-    pub date_range: Option<()>,
+    pub date_range: Option<DateRange>,
     pub time_estimate_hours: Option<f64>,
     pub time_preference: String,
     pub time_zone: Option<String>,
     pub interaction_type: String,
     pub links: Vec<String>,
     pub status: String,
+    pub location: Option<GeoLocation>,
+    pub valuation: Option<Valuation>,
 }
 
 /// Create a sample request input.
@@ -259,6 +292,8 @@ pub fn sample_request(title: impl Into<String>) -> CreateRequestInput {
             interaction_type: "Virtual".to_string(),
             links: vec!["https://example.com/resource".to_string()],
             status: "Active".to_string(),
+            location: None,
+            valuation: None,
         },
         organization: None,
         service_type_hashes: vec![],
@@ -286,6 +321,8 @@ pub struct OfferData {
     pub interaction_type: String,
     pub links: Vec<String>,
     pub status: String,
+    pub capacity: Option<u32>,
+    pub valuation: Option<Valuation>,
 }
 
 /// Create a sample offer input.
@@ -299,6 +336,8 @@ pub fn sample_offer(title: impl Into<String>) -> CreateOfferInput {
             interaction_type: "Virtual".to_string(),
             links: vec!["https://example.com/resource".to_string()],
             status: "Active".to_string(),
+            capacity: None,
+            valuation: None,
         },
         organization: None,
         service_type_hashes: vec![],
@@ -336,3 +375,17 @@ pub fn sample_medium_of_exchange(
         },
     }
 }
+
+// ── Pagination fixtures ───────────────────────────────────────
+
+/// Input for the `*_paginated` zome calls.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PaginationInput {
+    pub cursor: Option<PaginationCursor>,
+    pub limit: usize,
+}
+
+/// Build a pagination input for the page following `cursor`.
+pub fn page_input(cursor: Option<PaginationCursor>, limit: usize) -> PaginationInput {
+    PaginationInput { cursor, limit }
+}
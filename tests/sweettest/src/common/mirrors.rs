@@ -63,9 +63,53 @@ pub struct ServiceType {
     pub name: String,
     pub description: String,
     pub technical: bool,
+    #[serde(default)]
+    pub tags: Vec<String>,
+    #[serde(default)]
+    pub parent_hash: Option<ActionHash>,
+    #[serde(default)]
+    pub merged_into: Option<ActionHash>,
 }
 holochain_serialized_bytes::holochain_serial!(ServiceType);
 
+/// Mirror of `ServiceTypeNode` from the `service_types` coordinator.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ServiceTypeNode {
+    pub service_type_hash: ActionHash,
+    pub record: Record,
+    pub children: Vec<ServiceTypeNode>,
+}
+
+/// Mirror of `TagStatistic` from the `service_types` coordinator.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct TagStatistic {
+    pub tag: String,
+    pub service_type_count: u32,
+}
+
+/// Mirror of `ServiceTypeTranslation` from `service_types_integrity`.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct ServiceTypeTranslation {
+    pub service_type_hash: ActionHash,
+    pub locale: String,
+    pub name: String,
+    pub description: String,
+    #[serde(default)]
+    pub aliases: Vec<String>,
+}
+holochain_serialized_bytes::holochain_serial!(ServiceTypeTranslation);
+
+/// Mirror of `LocalizedServiceType` from the `service_types` coordinator.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct LocalizedServiceType {
+    pub service_type_hash: ActionHash,
+    pub record: Record,
+    pub locale: Option<String>,
+    pub name: String,
+    pub description: String,
+    pub aliases: Vec<String>,
+}
+
 // ── Request mirrors ───────────────────────────────────────────
 
 /// Mirror of `Request` from `requests_integrity`.
@@ -81,6 +125,8 @@ pub struct Request {
     pub interaction_type: String,
     pub links: Vec<String>,
     pub status: String,
+    #[serde(default)]
+    pub fulfilment_notes: Option<String>,
 }
 holochain_serialized_bytes::holochain_serial!(Request);
 
@@ -103,9 +149,22 @@ pub struct Offer {
     pub interaction_type: String,
     pub links: Vec<String>,
     pub status: String,
+    #[serde(default)]
+    pub fulfilment_notes: Option<String>,
+    #[serde(default)]
+    pub capacity: Option<u32>,
 }
 holochain_serialized_bytes::holochain_serial!(Offer);
 
+/// Mirror of `OfferCapacity` returned by the `offers` coordinator.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct OfferCapacity {
+    pub capacity: Option<u32>,
+    pub taken: Vec<ActionHash>,
+    pub remaining: Option<u32>,
+    pub waitlist: Vec<ActionHash>,
+}
+
 // ── Medium of Exchange mirrors ────────────────────────────────
 
 /// Mirror of `MediumOfExchange` from `mediums_of_exchange_integrity`.
@@ -117,3 +176,207 @@ pub struct MediumOfExchange {
     pub resource_spec_hrea_id: Option<String>,
 }
 holochain_serialized_bytes::holochain_serial!(MediumOfExchange);
+
+/// Mirror of `ExchangeRate` from `mediums_of_exchange_integrity`.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct ExchangeRate {
+    pub from_medium_hash: ActionHash,
+    pub to_medium_hash: ActionHash,
+    pub rate_numerator: u64,
+    pub rate_denominator: u64,
+    pub effective_at: Timestamp,
+}
+holochain_serialized_bytes::holochain_serial!(ExchangeRate);
+
+/// Mirror of `Valuation` from `utils::valuation`.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct Valuation {
+    /// In hundredths of a unit of the medium of exchange
+    pub amount: i64,
+    /// "PerHour", "Fixed" or "PerItem"
+    pub unit: String,
+    pub medium_of_exchange_hash: ActionHash,
+}
+
+// ── Matching mirrors ──────────────────────────────────────────
+
+/// Mirror of `ListingMatch` from the `matching` coordinator.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct ListingMatch {
+    pub original_action_hash: ActionHash,
+    pub score: u32,
+    pub shared_service_types: Vec<ActionHash>,
+    pub shared_mediums_of_exchange: Vec<ActionHash>,
+}
+
+// ── Search mirrors ────────────────────────────────────────────
+
+/// Mirror of `SearchResult` from the `search` coordinator.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct SearchResult {
+    pub original_action_hash: ActionHash,
+    pub entity: String,
+    pub score: u32,
+}
+
+/// Mirror of `SavedSearchResults` from the `search` coordinator.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct SavedSearchResults {
+    pub result_hashes: Vec<ActionHash>,
+    pub new_result_hashes: Vec<ActionHash>,
+}
+
+/// Mirror of `GeoPoint` from `utils::geo`.
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq)]
+pub struct GeoPoint {
+    pub latitude: f64,
+    pub longitude: f64,
+}
+
+/// Mirror of `GeoLocation` from `utils::geo`.
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq)]
+pub struct GeoLocation {
+    pub point: GeoPoint,
+    pub privacy_radius_km: f64,
+}
+
+/// Mirror of `NearbyListing` from the `search` coordinator.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct NearbyListing {
+    pub original_action_hash: ActionHash,
+    pub entity: String,
+    pub location: GeoLocation,
+    pub distance_km: f64,
+}
+
+// ── Pagination mirrors ────────────────────────────────────────
+
+/// Mirror of `PaginationCursor` from `utils::pagination`.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct PaginationCursor {
+    pub timestamp: Timestamp,
+    pub create_link_hash: ActionHash,
+}
+
+/// Mirror of `Page<T>` from `utils::pagination`.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Page<T> {
+    pub items: Vec<T>,
+    pub next_cursor: Option<PaginationCursor>,
+}
+
+// ── Response mirrors ──────────────────────────────────────────
+
+/// Mirror of `Response` from `responses_integrity`.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct Response {
+    pub request_hash: ActionHash,
+    pub offer_hash: Option<ActionHash>,
+    pub message: String,
+    pub medium_of_exchange_hash: Option<ActionHash>,
+    pub status: String,
+}
+holochain_serialized_bytes::holochain_serial!(Response);
+
+// ── Exchange mirrors ──────────────────────────────────────────
+
+/// Mirror of `ExchangeParty` from `exchanges_integrity`.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct ExchangeParty {
+    pub agent: AgentPubKey,
+    pub user_hash: ActionHash,
+    pub organization_hash: Option<ActionHash>,
+}
+
+/// Mirror of `ExchangeAgreement` from `exchanges_integrity`.
+/// `amount` is kept as raw JSON-like value, e.g. `{"Hours": 2.0}`.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct ExchangeAgreement {
+    pub request_hash: ActionHash,
+    pub offer_hash: ActionHash,
+    pub requester: ExchangeParty,
+    pub provider: ExchangeParty,
+    pub service_type_hash: ActionHash,
+    pub medium_of_exchange_hash: ActionHash,
+    pub amount: std::collections::BTreeMap<String, f64>,
+}
+holochain_serialized_bytes::holochain_serial!(ExchangeAgreement);
+
+/// Mirror of `ReputationSummary` returned by the `exchanges` coordinator.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct ReputationSummary {
+    pub review_count: u32,
+    pub average_rating: Option<f64>,
+    pub rating_counts: Vec<u32>,
+}
+
+// ── Ledger mirrors ────────────────────────────────────────────
+
+/// Mirror of `LedgerBalance` returned by the `ledger` coordinator.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct LedgerBalance {
+    pub medium_of_exchange_hash: ActionHash,
+    pub balance: i64,
+    pub credit_limit: i64,
+}
+
+/// Mirror of `LedgerStatementLine` returned by the `ledger` coordinator.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct LedgerStatementLine {
+    pub transaction_hash: EntryHash,
+    pub agreement_hash: EntryHash,
+    pub counterpart_user_hash: ActionHash,
+    pub amount: i64,
+    pub balance: i64,
+    pub timestamp: Timestamp,
+}
+
+// ── ValueFlows mirrors ────────────────────────────────────────
+
+/// Mirror of `ValueFlowsExportPage` returned by the `valueflows` coordinator.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ValueFlowsExportPage {
+    pub document: String,
+    pub next_cursor: Option<PaginationCursor>,
+}
+
+// ── Messaging mirrors ─────────────────────────────────────────
+
+/// Mirror of `Message` from `messaging_integrity`.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct Message {
+    pub sender_hash: ActionHash,
+    pub recipient_hash: ActionHash,
+    pub content: String,
+    pub sent_at: Timestamp,
+    pub mailbox_hash: Option<ActionHash>,
+}
+holochain_serialized_bytes::holochain_serial!(Message);
+
+// ── Bookmark mirrors ──────────────────────────────────────────
+
+/// Mirror of `BookmarkTarget` from `bookmarks_integrity`.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub enum BookmarkTarget {
+    Request(ActionHash),
+    Offer(ActionHash),
+    User(ActionHash),
+    Organization(ActionHash),
+}
+
+/// Mirror of `Bookmark` from `bookmarks_integrity`.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct Bookmark {
+    pub target: BookmarkTarget,
+    pub notes: Option<String>,
+    pub folder: Option<String>,
+}
+holochain_serialized_bytes::holochain_serial!(Bookmark);
+
+/// Mirror of `ResolvedBookmark` returned by the `bookmarks` coordinator.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ResolvedBookmark {
+    pub bookmark_hash: ActionHash,
+    pub bookmark: Bookmark,
+    pub target_record: Option<Record>,
+}
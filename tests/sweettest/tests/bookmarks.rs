@@ -0,0 +1,139 @@
+//! Bookmarks zome tests.
+
+use holochain::prelude::*;
+use holochain::sweettest::*;
+use requests_and_offers_sweettest::common::*;
+
+#[tokio::test(flavor = "multi_thread")]
+async fn agents_bookmark_listings_privately() {
+    let (conductors, alice, bob) = setup_two_agents_with_alice_as_progenitor().await;
+
+    conductors[0]
+        .call::<_, Record>(&alice.zome("users_organizations"), "create_user", sample_user("Alice"))
+        .await;
+
+    await_consistency(15, [&alice, &bob]).await.unwrap();
+
+    let links: Vec<Link> = conductors[0]
+        .call(&alice.zome("users_organizations"), "get_agent_user", alice.agent_pubkey().clone())
+        .await;
+    let alice_user_hash = links[0].target.clone().into_action_hash().unwrap();
+    accept_entity(&conductors[0], &alice, ENTITY_USERS, alice_user_hash.clone()).await;
+
+    let request: Record = conductors[0]
+        .call(&alice.zome("requests"), "create_request", sample_request("Need a ladder"))
+        .await;
+    let request_hash = request.signed_action.hashed.hash.clone();
+
+    await_consistency(15, [&alice, &bob]).await.unwrap();
+
+    // Bob bookmarks the request and its author; a second bookmark of the request is rejected.
+    let request_bookmark = Bookmark {
+        target: BookmarkTarget::Request(request_hash.clone()),
+        notes: Some("Ask about the weekend".to_string()),
+        folder: Some("Neighbours".to_string()),
+    };
+    let created: Record = conductors[1]
+        .call(&bob.zome("bookmarks"), "create_bookmark", request_bookmark.clone())
+        .await;
+    let again: Result<Record, _> = conductors[1]
+        .call_fallible(&bob.zome("bookmarks"), "create_bookmark", request_bookmark.clone())
+        .await;
+    assert!(again.is_err());
+    let _: Record = conductors[1]
+        .call(
+            &bob.zome("bookmarks"),
+            "create_bookmark",
+            Bookmark {
+                target: BookmarkTarget::User(alice_user_hash),
+                notes: None,
+                folder: None,
+            },
+        )
+        .await;
+
+    // Alice updates her request; the bookmark resolves to the latest version.
+    let mut updated_request = sample_request("Need a long ladder").request;
+    updated_request.description = "At least four metres".to_string();
+    let _: Record = conductors[0]
+        .call(
+            &alice.zome("requests"),
+            "update_request",
+            serde_json::json!({
+                "original_action_hash": request_hash,
+                "previous_action_hash": request_hash,
+                "updated_request": updated_request,
+                "service_type_hashes": [],
+                "medium_of_exchange_hashes": []
+            }),
+        )
+        .await;
+
+    await_consistency(15, [&alice, &bob]).await.unwrap();
+
+    let bookmarks: Vec<ResolvedBookmark> = conductors[1]
+        .call(&bob.zome("bookmarks"), "get_my_bookmarks", ())
+        .await;
+    assert_eq!(bookmarks.len(), 2);
+    let in_folder: Vec<ResolvedBookmark> = conductors[1]
+        .call(&bob.zome("bookmarks"), "get_my_bookmarks_in_folder", "Neighbours".to_string())
+        .await;
+    assert_eq!(in_folder.len(), 1);
+    let latest: Request = in_folder[0]
+        .target_record
+        .as_ref()
+        .expect("target record")
+        .entry()
+        .to_app_option()
+        .unwrap()
+        .expect("entry");
+    assert_eq!(latest.title, "Need a long ladder");
+
+    // Bookmarks are kept on Bob's chain only.
+    let alice_bookmarks: Vec<ResolvedBookmark> = conductors[0]
+        .call(&alice.zome("bookmarks"), "get_my_bookmarks", ())
+        .await;
+    assert!(alice_bookmarks.is_empty());
+
+    // The notes and folder can change, the target cannot.
+    let moved: Record = conductors[1]
+        .call(
+            &bob.zome("bookmarks"),
+            "update_bookmark",
+            serde_json::json!({
+                "previous_bookmark_hash": created.signed_action.hashed.hash,
+                "updated_bookmark": Bookmark {
+                    folder: Some("Tools".to_string()),
+                    ..request_bookmark.clone()
+                }
+            }),
+        )
+        .await;
+    let retargeted: Result<Record, _> = conductors[1]
+        .call_fallible(
+            &bob.zome("bookmarks"),
+            "update_bookmark",
+            serde_json::json!({
+                "previous_bookmark_hash": moved.signed_action.hashed.hash,
+                "updated_bookmark": Bookmark {
+                    target: BookmarkTarget::Offer(request_hash.clone()),
+                    ..request_bookmark.clone()
+                }
+            }),
+        )
+        .await;
+    assert!(retargeted.is_err());
+
+    let folders: Vec<String> = conductors[1]
+        .call(&bob.zome("bookmarks"), "get_my_bookmark_folders", ())
+        .await;
+    assert_eq!(folders, vec!["Tools".to_string()]);
+
+    let _: ActionHash = conductors[1]
+        .call(&bob.zome("bookmarks"), "delete_bookmark", moved.signed_action.hashed.hash.clone())
+        .await;
+    let bookmarks: Vec<ResolvedBookmark> = conductors[1]
+        .call(&bob.zome("bookmarks"), "get_my_bookmarks", ())
+        .await;
+    assert_eq!(bookmarks.len(), 1);
+}
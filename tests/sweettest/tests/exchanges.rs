@@ -0,0 +1,335 @@
+//! Exchanges zome tests.
+
+use holochain::prelude::*;
+use holochain::sweettest::*;
+use requests_and_offers_sweettest::common::*;
+
+/// Reads the agreement out of a countersigned record.
+fn agreement_from_record(record: &Record) -> ExchangeAgreement {
+    match record.entry().as_option() {
+        Some(Entry::CounterSign(_, bytes)) => {
+            ExchangeAgreement::try_from(bytes.clone().into_sb()).unwrap()
+        }
+        other => panic!("Expected a countersigned entry, got {:?}", other),
+    }
+}
+
+/// An exchange between Alice (requester) and Bob (provider).
+struct AgreedExchange {
+    conductors: SweetConductorBatch,
+    alice: SweetCell,
+    bob: SweetCell,
+    user_hashes: Vec<ActionHash>,
+    request_hash: ActionHash,
+    offer_hash: ActionHash,
+    agreement_input: serde_json::Value,
+}
+
+/// Sets up accepted users, a service type, a medium of exchange and matching
+/// listings for Alice and Bob to agree on.
+async fn prepare_exchange() -> AgreedExchange {
+    let (conductors, alice, bob) = setup_two_agents_with_alice_as_progenitor().await;
+
+    // Create users. Alice (progenitor) is auto-registered as admin via init callback.
+    conductors[0]
+        .call::<_, Record>(&alice.zome("users_organizations"), "create_user", sample_user("Alice"))
+        .await;
+    conductors[1]
+        .call::<_, Record>(&bob.zome("users_organizations"), "create_user", sample_user("Bob"))
+        .await;
+
+    await_consistency(15, [&alice, &bob]).await.unwrap();
+
+    let mut user_hashes = Vec::new();
+    for (conductor, cell) in [(&conductors[0], &alice), (&conductors[1], &bob)] {
+        let links: Vec<Link> = conductor
+            .call(&cell.zome("users_organizations"), "get_agent_user", cell.agent_pubkey().clone())
+            .await;
+        let user_hash = links[0].target.clone().into_action_hash().unwrap();
+        accept_entity(&conductors[0], &alice, ENTITY_USERS, user_hash.clone()).await;
+        user_hashes.push(user_hash);
+    }
+
+    // Alice sets up a service type and a medium of exchange.
+    let gardening: Record = conductors[0]
+        .call(&alice.zome("service_types"), "create_service_type", sample_service_type("Gardening"))
+        .await;
+    let gardening_hash = gardening.signed_action.hashed.hash.clone();
+    let moe: Record = conductors[0]
+        .call(
+            &alice.zome("mediums_of_exchange"),
+            "suggest_medium_of_exchange",
+            sample_medium_of_exchange("HOUR", "Time bank hour"),
+        )
+        .await;
+    let moe_hash = moe.signed_action.hashed.hash.clone();
+    let _: () = conductors[0]
+        .call(&alice.zome("mediums_of_exchange"), "approve_medium_of_exchange", moe_hash.clone())
+        .await;
+
+    await_consistency(15, [&alice, &bob]).await.unwrap();
+
+    // Alice requests gardening help, Bob offers it.
+    let mut request_input = sample_request("Help with gardening");
+    request_input.service_type_hashes = vec![gardening_hash.clone()];
+    let request: Record = conductors[0]
+        .call(&alice.zome("requests"), "create_request", request_input)
+        .await;
+    let request_hash = request.signed_action.hashed.hash.clone();
+
+    let mut offer_input = sample_offer("Gardening on weekends");
+    offer_input.service_type_hashes = vec![gardening_hash.clone()];
+    let offer: Record = conductors[1]
+        .call(&bob.zome("offers"), "create_offer", offer_input)
+        .await;
+    let offer_hash = offer.signed_action.hashed.hash.clone();
+
+    await_consistency(15, [&alice, &bob]).await.unwrap();
+
+    let agreement_input = serde_json::json!({
+        "request_hash": request_hash,
+        "offer_hash": offer_hash,
+        "service_type_hash": gardening_hash,
+        "medium_of_exchange_hash": moe_hash,
+        "amount": { "Hours": 3.0 }
+    });
+
+    AgreedExchange {
+        conductors,
+        alice,
+        bob,
+        user_hashes,
+        request_hash,
+        offer_hash,
+        agreement_input,
+    }
+}
+
+/// Has Alice and Bob countersign an agreement: Bob approves it, then Alice starts it and
+/// Bob's cell joins the session remotely.
+async fn agree_exchange() -> AgreedExchange {
+    let exchange = prepare_exchange().await;
+    let AgreedExchange {
+        conductors,
+        alice,
+        bob,
+        agreement_input,
+        ..
+    } = &exchange;
+
+    let _: () = conductors[1]
+        .call(&bob.zome("exchanges"), "approve_exchange_agreement", agreement_input.clone())
+        .await;
+    let _: ActionHash = conductors[0]
+        .call(&alice.zome("exchanges"), "create_exchange_agreement", agreement_input.clone())
+        .await;
+
+    await_consistency(30, [alice, bob]).await.unwrap();
+
+    exchange
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn agreements_need_the_counterparts_approval() {
+    let AgreedExchange {
+        conductors,
+        alice,
+        bob,
+        agreement_input,
+        ..
+    } = prepare_exchange().await;
+
+    // Bob never approves the agreement, so his cell refuses to join the session.
+    let unapproved: Result<ActionHash, _> = conductors[0]
+        .call_fallible(&alice.zome("exchanges"), "create_exchange_agreement", agreement_input)
+        .await;
+    assert!(unapproved.is_err());
+
+    await_consistency(15, [&alice, &bob]).await.unwrap();
+
+    for (conductor, cell) in [(&conductors[0], &alice), (&conductors[1], &bob)] {
+        let agreements: Vec<Record> = conductor
+            .call(&cell.zome("exchanges"), "get_my_exchange_agreements", ())
+            .await;
+        assert!(agreements.is_empty());
+    }
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn exchange_agreement_is_countersigned_by_both_parties() {
+    let AgreedExchange {
+        conductors,
+        alice,
+        bob,
+        user_hashes,
+        request_hash,
+        offer_hash,
+        ..
+    } = agree_exchange().await;
+
+    // Both chains hold the same countersigned entry.
+    let alice_agreements: Vec<Record> = conductors[0]
+        .call(&alice.zome("exchanges"), "get_my_exchange_agreements", ())
+        .await;
+    let bob_agreements: Vec<Record> = conductors[1]
+        .call(&bob.zome("exchanges"), "get_my_exchange_agreements", ())
+        .await;
+    assert_eq!(alice_agreements.len(), 1);
+    assert_eq!(bob_agreements.len(), 1);
+    let entry_hash = alice_agreements[0].action().entry_hash().unwrap().clone();
+    assert_eq!(bob_agreements[0].action().entry_hash(), Some(&entry_hash));
+
+    let agreement = agreement_from_record(&alice_agreements[0]);
+    assert_eq!(&agreement.requester.agent, alice.agent_pubkey());
+    assert_eq!(&agreement.provider.agent, bob.agent_pubkey());
+    assert_eq!(agreement.requester.user_hash, user_hashes[0]);
+    assert_eq!(agreement.provider.user_hash, user_hashes[1]);
+    assert_eq!(agreement.amount.get("Hours"), Some(&3.0));
+
+    // Bob publishes the index links; doing it twice is harmless.
+    for _ in 0..2 {
+        let _: () = conductors[1]
+            .call(&bob.zome("exchanges"), "index_exchange_agreement", entry_hash.clone())
+            .await;
+    }
+
+    await_consistency(15, [&alice, &bob]).await.unwrap();
+
+    let for_request: Vec<Record> = conductors[0]
+        .call(&alice.zome("exchanges"), "get_agreements_for_request", request_hash)
+        .await;
+    assert_eq!(for_request.len(), 1);
+    let for_offer: Vec<Record> = conductors[0]
+        .call(&alice.zome("exchanges"), "get_agreements_for_offer", offer_hash)
+        .await;
+    assert_eq!(for_offer.len(), 1);
+    let for_alice: Vec<Record> = conductors[1]
+        .call(&bob.zome("exchanges"), "get_user_agreements", user_hashes[0].clone())
+        .await;
+    assert_eq!(for_alice.len(), 1);
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn parties_review_each_other_after_an_exchange() {
+    let AgreedExchange {
+        conductors,
+        alice,
+        bob,
+        user_hashes,
+        ..
+    } = agree_exchange().await;
+
+    let alice_agreements: Vec<Record> = conductors[0]
+        .call(&alice.zome("exchanges"), "get_my_exchange_agreements", ())
+        .await;
+    let exchange_hash = alice_agreements[0].action().entry_hash().unwrap().clone();
+
+    await_consistency(15, [&alice, &bob]).await.unwrap();
+
+    // The exchange cannot be reviewed before both parties confirm it is complete.
+    let _: Record = conductors[0]
+        .call(&alice.zome("exchanges"), "confirm_exchange_completion", exchange_hash.clone())
+        .await;
+
+    await_consistency(15, [&alice, &bob]).await.unwrap();
+
+    let early_review: Result<Record, _> = conductors[0]
+        .call_fallible(
+            &alice.zome("exchanges"),
+            "create_review",
+            serde_json::json!({
+                "exchange_hash": exchange_hash,
+                "reviewee_hash": user_hashes[1],
+                "rating": 5,
+                "comment": "Looking forward to it"
+            }),
+        )
+        .await;
+    assert!(early_review.is_err());
+
+    let _: Record = conductors[1]
+        .call(&bob.zome("exchanges"), "confirm_exchange_completion", exchange_hash.clone())
+        .await;
+
+    await_consistency(15, [&alice, &bob]).await.unwrap();
+
+    let completions: Vec<Record> = conductors[0]
+        .call(&alice.zome("exchanges"), "get_exchange_completions", exchange_hash.clone())
+        .await;
+    assert_eq!(completions.len(), 2);
+
+    // Alice reviews Bob, Bob reviews Alice.
+    let _: Record = conductors[0]
+        .call(
+            &alice.zome("exchanges"),
+            "create_review",
+            serde_json::json!({
+                "exchange_hash": exchange_hash,
+                "reviewee_hash": user_hashes[1],
+                "rating": 5,
+                "comment": "Lovely garden work"
+            }),
+        )
+        .await;
+    let _: Record = conductors[1]
+        .call(
+            &bob.zome("exchanges"),
+            "create_review",
+            serde_json::json!({
+                "exchange_hash": exchange_hash,
+                "reviewee_hash": user_hashes[0],
+                "rating": 4,
+                "comment": "Clear instructions"
+            }),
+        )
+        .await;
+
+    // A party cannot review the same exchange twice.
+    let second_review: Result<Record, _> = conductors[0]
+        .call_fallible(
+            &alice.zome("exchanges"),
+            "create_review",
+            serde_json::json!({
+                "exchange_hash": exchange_hash,
+                "reviewee_hash": user_hashes[1],
+                "rating": 1,
+                "comment": "Changed my mind"
+            }),
+        )
+        .await;
+    assert!(second_review.is_err());
+
+    // A party cannot review themselves.
+    let self_review: Result<Record, _> = conductors[1]
+        .call_fallible(
+            &bob.zome("exchanges"),
+            "create_review",
+            serde_json::json!({
+                "exchange_hash": exchange_hash,
+                "reviewee_hash": user_hashes[1],
+                "rating": 5,
+                "comment": "Great provider"
+            }),
+        )
+        .await;
+    assert!(self_review.is_err());
+
+    await_consistency(15, [&alice, &bob]).await.unwrap();
+
+    let reviews: Vec<Record> = conductors[0]
+        .call(&alice.zome("exchanges"), "get_reviews_for_agreement", exchange_hash)
+        .await;
+    assert_eq!(reviews.len(), 2);
+
+    let bob_reputation: ReputationSummary = conductors[0]
+        .call(&alice.zome("exchanges"), "get_user_reputation", user_hashes[1].clone())
+        .await;
+    assert_eq!(
+        bob_reputation,
+        ReputationSummary {
+            review_count: 1,
+            average_rating: Some(5.0),
+            rating_counts: vec![0, 0, 0, 0, 1],
+        }
+    );
+}
@@ -0,0 +1,324 @@
+//! Ledger zome tests.
+
+use holochain::prelude::*;
+use holochain::sweettest::*;
+use requests_and_offers_sweettest::common::*;
+
+/// Alice's request and Bob's offer for the same service, with a TIME medium of exchange.
+struct TimeBank {
+    conductors: SweetConductorBatch,
+    alice: SweetCell,
+    bob: SweetCell,
+    user_hashes: Vec<ActionHash>,
+    request_hash: ActionHash,
+    offer_hash: ActionHash,
+    service_type_hash: ActionHash,
+    time_hash: ActionHash,
+}
+
+/// Sets up accepted users, a service type, a base TIME medium of exchange and matching
+/// listings.
+async fn setup_time_bank() -> TimeBank {
+    let (conductors, alice, bob) = setup_two_agents_with_alice_as_progenitor().await;
+
+    // Create users. Alice (progenitor) is auto-registered as admin via init callback.
+    conductors[0]
+        .call::<_, Record>(&alice.zome("users_organizations"), "create_user", sample_user("Alice"))
+        .await;
+    conductors[1]
+        .call::<_, Record>(&bob.zome("users_organizations"), "create_user", sample_user("Bob"))
+        .await;
+
+    await_consistency(15, [&alice, &bob]).await.unwrap();
+
+    let mut user_hashes = Vec::new();
+    for (conductor, cell) in [(&conductors[0], &alice), (&conductors[1], &bob)] {
+        let links: Vec<Link> = conductor
+            .call(&cell.zome("users_organizations"), "get_agent_user", cell.agent_pubkey().clone())
+            .await;
+        let user_hash = links[0].target.clone().into_action_hash().unwrap();
+        accept_entity(&conductors[0], &alice, ENTITY_USERS, user_hash.clone()).await;
+        user_hashes.push(user_hash);
+    }
+
+    let gardening: Record = conductors[0]
+        .call(&alice.zome("service_types"), "create_service_type", sample_service_type("Gardening"))
+        .await;
+    let service_type_hash = gardening.signed_action.hashed.hash.clone();
+    let mut time_input = sample_medium_of_exchange("TIME", "Time bank hour");
+    time_input.medium_of_exchange.exchange_type = "base".to_string();
+    let time: Record = conductors[0]
+        .call(&alice.zome("mediums_of_exchange"), "create_medium_of_exchange", time_input)
+        .await;
+    let time_hash = time.signed_action.hashed.hash.clone();
+
+    await_consistency(15, [&alice, &bob]).await.unwrap();
+
+    let mut request_input = sample_request("Help with gardening");
+    request_input.service_type_hashes = vec![service_type_hash.clone()];
+    let request: Record = conductors[0]
+        .call(&alice.zome("requests"), "create_request", request_input)
+        .await;
+    let request_hash = request.signed_action.hashed.hash.clone();
+
+    let mut offer_input = sample_offer("Gardening on weekends");
+    offer_input.service_type_hashes = vec![service_type_hash.clone()];
+    let offer: Record = conductors[1]
+        .call(&bob.zome("offers"), "create_offer", offer_input)
+        .await;
+    let offer_hash = offer.signed_action.hashed.hash.clone();
+
+    await_consistency(15, [&alice, &bob]).await.unwrap();
+
+    TimeBank {
+        conductors,
+        alice,
+        bob,
+        user_hashes,
+        request_hash,
+        offer_hash,
+        service_type_hash,
+        time_hash,
+    }
+}
+
+impl TimeBank {
+    /// Has Alice and Bob countersign an agreement for some hours, and returns Alice's record
+    /// of it and its entry hash.
+    async fn agree(&self, hours: f64) -> (ActionHash, EntryHash) {
+        let agreement_input = serde_json::json!({
+            "request_hash": self.request_hash,
+            "offer_hash": self.offer_hash,
+            "service_type_hash": self.service_type_hash,
+            "medium_of_exchange_hash": self.time_hash,
+            "amount": { "Hours": hours }
+        });
+        let _: () = self.conductors[1]
+            .call(
+                &self.bob.zome("exchanges"),
+                "approve_exchange_agreement",
+                agreement_input.clone(),
+            )
+            .await;
+        let action_hash: ActionHash = self.conductors[0]
+            .call(&self.alice.zome("exchanges"), "create_exchange_agreement", agreement_input)
+            .await;
+
+        await_consistency(30, [&self.alice, &self.bob]).await.unwrap();
+
+        let agreements: Vec<Record> = self.conductors[0]
+            .call(&self.alice.zome("exchanges"), "get_my_exchange_agreements", ())
+            .await;
+        let agreement_hash = agreements
+            .iter()
+            .find(|record| record.action_address() == &action_hash)
+            .and_then(|record| record.action().entry_hash().cloned())
+            .expect("agreement on Alice's chain");
+        (action_hash, agreement_hash)
+    }
+
+    /// Has Alice, the requester, approve paying for an agreement.
+    async fn approve(&self, agreement_action_hash: &ActionHash) {
+        let _: () = self.conductors[0]
+            .call(
+                &self.alice.zome("ledger"),
+                "approve_ledger_transaction",
+                agreement_action_hash.clone(),
+            )
+            .await;
+    }
+
+    /// Waits for the transactions recorded by Bob to be indexed once their session is over.
+    async fn await_indexed(&self, user_hash: &ActionHash, count: usize) -> Vec<Record> {
+        for _ in 0..30 {
+            let transactions: Vec<Record> = self.conductors[0]
+                .call(&self.alice.zome("ledger"), "get_user_ledger_transactions", user_hash.clone())
+                .await;
+            if transactions.len() >= count {
+                return transactions;
+            }
+            tokio::time::sleep(std::time::Duration::from_secs(3)).await;
+        }
+        panic!("transactions were not indexed");
+    }
+}
+
+/// A confirmed exchange becomes a debit for the requester and a credit for the provider.
+#[tokio::test(flavor = "multi_thread")]
+async fn exchanges_are_settled_in_the_ledger() {
+    let bank = setup_time_bank().await;
+    let TimeBank {
+        conductors,
+        alice,
+        bob,
+        user_hashes,
+        time_hash,
+        ..
+    } = &bank;
+
+    let (agreement_action_hash, agreement_hash) = bank.agree(3.0).await;
+
+    // Alice, the requester, must approve paying before Bob can record the transaction.
+    let unapproved: Result<ActionHash, _> = conductors[1]
+        .call_fallible(
+            &bob.zome("ledger"),
+            "record_exchange_transaction",
+            agreement_action_hash.clone(),
+        )
+        .await;
+    assert!(unapproved.is_err(), "The payer must approve a transaction");
+
+    // Bob, the provider, records the transaction; Alice's cell countersigns it.
+    bank.approve(&agreement_action_hash).await;
+    let _: ActionHash = conductors[1]
+        .call(
+            &bob.zome("ledger"),
+            "record_exchange_transaction",
+            agreement_action_hash.clone(),
+        )
+        .await;
+
+    await_consistency(30, [alice, bob]).await.unwrap();
+
+    let alice_transactions: Vec<Record> = conductors[0]
+        .call(&alice.zome("ledger"), "get_my_ledger_transactions", ())
+        .await;
+    let bob_transactions: Vec<Record> = conductors[1]
+        .call(&bob.zome("ledger"), "get_my_ledger_transactions", ())
+        .await;
+    assert_eq!(alice_transactions.len(), 1);
+    assert_eq!(bob_transactions.len(), 1);
+    let transaction_hash = alice_transactions[0].action().entry_hash().unwrap().clone();
+    assert_eq!(bob_transactions[0].action().entry_hash(), Some(&transaction_hash));
+
+    // Bob's cell indexes the transaction for both users once the session is over.
+    let indexed = bank.await_indexed(&user_hashes[0], 1).await;
+    assert_eq!(indexed.len(), 1);
+    bank.await_indexed(&user_hashes[1], 1).await;
+
+    let alice_balances: Vec<LedgerBalance> = conductors[1]
+        .call(&bob.zome("ledger"), "get_user_balances", user_hashes[0].clone())
+        .await;
+    assert_eq!(
+        alice_balances,
+        vec![LedgerBalance {
+            medium_of_exchange_hash: time_hash.clone(),
+            balance: -300,
+            credit_limit: 2000,
+        }]
+    );
+    let bob_balances: Vec<LedgerBalance> = conductors[0]
+        .call(&alice.zome("ledger"), "get_user_balances", user_hashes[1].clone())
+        .await;
+    assert_eq!(bob_balances[0].balance, 300);
+
+    let statement: Vec<LedgerStatementLine> = conductors[0]
+        .call(
+            &alice.zome("ledger"),
+            "get_user_statement",
+            serde_json::json!({
+                "user_hash": user_hashes[1],
+                "medium_of_exchange_hash": time_hash,
+            }),
+        )
+        .await;
+    assert_eq!(statement.len(), 1);
+    assert_eq!(statement[0].transaction_hash, transaction_hash);
+    assert_eq!(statement[0].agreement_hash, agreement_hash);
+    assert_eq!(statement[0].counterpart_user_hash, user_hashes[0]);
+    assert_eq!(statement[0].amount, 300);
+    assert_eq!(statement[0].balance, 300);
+}
+
+/// A second transaction settling the same agreement is refused, even once re-approved.
+#[tokio::test(flavor = "multi_thread")]
+async fn agreements_are_settled_once() {
+    let bank = setup_time_bank().await;
+    let TimeBank {
+        conductors,
+        alice,
+        bob,
+        ..
+    } = &bank;
+
+    let (agreement_action_hash, _) = bank.agree(2.0).await;
+
+    bank.approve(&agreement_action_hash).await;
+    let _: ActionHash = conductors[1]
+        .call(
+            &bob.zome("ledger"),
+            "record_exchange_transaction",
+            agreement_action_hash.clone(),
+        )
+        .await;
+
+    await_consistency(30, [alice, bob]).await.unwrap();
+
+    // Bob cannot record it again: Alice's approval is used up.
+    let again: Result<ActionHash, _> = conductors[1]
+        .call_fallible(
+            &bob.zome("ledger"),
+            "record_exchange_transaction",
+            agreement_action_hash.clone(),
+        )
+        .await;
+    assert!(again.is_err(), "An agreement cannot be settled twice");
+
+    // Alice cannot approve paying for it again either.
+    let approval: Result<(), _> = conductors[0]
+        .call_fallible(
+            &alice.zome("ledger"),
+            "approve_ledger_transaction",
+            agreement_action_hash.clone(),
+        )
+        .await;
+    assert!(approval.is_err(), "A settled agreement cannot be approved again");
+
+    let alice_transactions: Vec<Record> = conductors[0]
+        .call(&alice.zome("ledger"), "get_my_ledger_transactions", ())
+        .await;
+    let bob_transactions: Vec<Record> = conductors[1]
+        .call(&bob.zome("ledger"), "get_my_ledger_transactions", ())
+        .await;
+    assert_eq!(alice_transactions.len(), 1);
+    assert_eq!(bob_transactions.len(), 1);
+}
+
+/// The requester cannot go further below zero than the credit limit of the medium.
+#[tokio::test(flavor = "multi_thread")]
+async fn transactions_beyond_the_credit_limit_are_rejected() {
+    let bank = setup_time_bank().await;
+    let TimeBank {
+        conductors,
+        alice,
+        bob,
+        ..
+    } = &bank;
+
+    let (within_limit, _) = bank.agree(15.0).await;
+    let (beyond_limit, _) = bank.agree(6.0).await;
+
+    bank.approve(&within_limit).await;
+    let _: ActionHash = conductors[1]
+        .call(&bob.zome("ledger"), "record_exchange_transaction", within_limit)
+        .await;
+
+    await_consistency(30, [alice, bob]).await.unwrap();
+
+    // Alice cannot even approve it.
+    let approval: Result<(), _> = conductors[0]
+        .call_fallible(&alice.zome("ledger"), "approve_ledger_transaction", beyond_limit.clone())
+        .await;
+    assert!(approval.is_err(), "Alice cannot approve going below the credit limit");
+
+    // 15 + 6 hours would take Alice to -21 TIME, below the default limit of 20.
+    let result: Result<ActionHash, _> = conductors[1]
+        .call_fallible(&bob.zome("ledger"), "record_exchange_transaction", beyond_limit)
+        .await;
+    assert!(result.is_err(), "Alice's balance cannot go below the credit limit");
+
+    let alice_transactions: Vec<Record> = conductors[0]
+        .call(&alice.zome("ledger"), "get_my_ledger_transactions", ())
+        .await;
+    assert_eq!(alice_transactions.len(), 1);
+}
@@ -0,0 +1,89 @@
+//! Matching zome tests.
+
+use holochain::prelude::*;
+use holochain::sweettest::*;
+use requests_and_offers_sweettest::common::*;
+
+#[tokio::test(flavor = "multi_thread")]
+async fn requests_and_offers_are_matched_by_service_type() {
+    let (conductors, alice, bob) = setup_two_agents_with_alice_as_progenitor().await;
+
+    // Create users. Alice (progenitor) is auto-registered as admin via init callback.
+    conductors[0]
+        .call::<_, Record>(&alice.zome("users_organizations"), "create_user", sample_user("Alice"))
+        .await;
+    conductors[1]
+        .call::<_, Record>(&bob.zome("users_organizations"), "create_user", sample_user("Bob"))
+        .await;
+
+    await_consistency(15, [&alice, &bob]).await.unwrap();
+
+    // Accept both profiles so they can create listings.
+    for (conductor, cell) in [(&conductors[0], &alice), (&conductors[1], &bob)] {
+        let links: Vec<Link> = conductor
+            .call(&cell.zome("users_organizations"), "get_agent_user", cell.agent_pubkey().clone())
+            .await;
+        let user_hash = links[0].target.clone().into_action_hash().unwrap();
+        accept_entity(&conductors[0], &alice, ENTITY_USERS, user_hash).await;
+    }
+
+    // Alice creates two service types (admin-only; auto-approved).
+    let gardening: Record = conductors[0]
+        .call(&alice.zome("service_types"), "create_service_type", sample_service_type("Gardening"))
+        .await;
+    let gardening_hash = gardening.signed_action.hashed.hash.clone();
+    let tutoring: Record = conductors[0]
+        .call(&alice.zome("service_types"), "create_service_type", sample_service_type("Tutoring"))
+        .await;
+    let tutoring_hash = tutoring.signed_action.hashed.hash.clone();
+
+    await_consistency(15, [&alice, &bob]).await.unwrap();
+
+    // Alice requests gardening help.
+    let mut request_input = sample_request("Help with gardening");
+    request_input.service_type_hashes = vec![gardening_hash.clone()];
+    let request_record: Record = conductors[0]
+        .call(&alice.zome("requests"), "create_request", request_input)
+        .await;
+    let request_hash = request_record.signed_action.hashed.hash.clone();
+
+    // Bob offers gardening, and separately tutoring.
+    let mut gardening_offer_input = sample_offer("Gardening on weekends");
+    gardening_offer_input.service_type_hashes = vec![gardening_hash.clone()];
+    let gardening_offer: Record = conductors[1]
+        .call(&bob.zome("offers"), "create_offer", gardening_offer_input)
+        .await;
+    let gardening_offer_hash = gardening_offer.signed_action.hashed.hash.clone();
+
+    let mut tutoring_offer_input = sample_offer("Maths tutoring");
+    tutoring_offer_input.service_type_hashes = vec![tutoring_hash];
+    let _: Record = conductors[1]
+        .call(&bob.zome("offers"), "create_offer", tutoring_offer_input)
+        .await;
+
+    await_consistency(15, [&alice, &bob]).await.unwrap();
+
+    // Only the gardening offer matches the request.
+    let offer_matches: Vec<ListingMatch> = conductors[0]
+        .call(
+            &alice.zome("matching"),
+            "get_matching_offers_for_request",
+            request_hash.clone(),
+        )
+        .await;
+    assert_eq!(offer_matches.len(), 1);
+    assert_eq!(offer_matches[0].original_action_hash, gardening_offer_hash);
+    assert_eq!(offer_matches[0].shared_service_types, vec![gardening_hash]);
+    assert!(offer_matches[0].score > 0);
+
+    // The reverse lookup finds the request from the offer.
+    let request_matches: Vec<ListingMatch> = conductors[1]
+        .call(
+            &bob.zome("matching"),
+            "get_matching_requests_for_offer",
+            gardening_offer_hash,
+        )
+        .await;
+    assert_eq!(request_matches.len(), 1);
+    assert_eq!(request_matches[0].original_action_hash, request_hash);
+}
@@ -114,3 +114,112 @@ async fn basic_medium_of_exchange_suggestion_and_approval_workflow() {
         .await;
     assert!(!rejected.is_empty(), "Should have a rejected MoE");
 }
+
+/// Admins keep a dated history of exchange rates, which `convert` applies as of a time
+/// and in either direction.
+#[tokio::test(flavor = "multi_thread")]
+async fn exchange_rates_convert_between_mediums() {
+    let (conductors, alice, bob) = setup_two_agents_with_alice_as_progenitor().await;
+
+    conductors[0]
+        .call::<_, Record>(&alice.zome("users_organizations"), "create_user", sample_user("Alice"))
+        .await;
+
+    await_consistency(15, [&alice, &bob]).await.unwrap();
+
+    // Mediums created by the admin are approved right away.
+    let time: Record = conductors[0]
+        .call(
+            &alice.zome("mediums_of_exchange"),
+            "create_medium_of_exchange",
+            sample_medium_of_exchange("TIME", "Time Banking"),
+        )
+        .await;
+    let time_hash = time.signed_action.hashed.hash.clone();
+    let local: Record = conductors[0]
+        .call(
+            &alice.zome("mediums_of_exchange"),
+            "create_medium_of_exchange",
+            sample_medium_of_exchange("LOCAL", "Local Currency"),
+        )
+        .await;
+    let local_hash = local.signed_action.hashed.hash.clone();
+
+    let now = Timestamp::now().as_micros();
+    let rate = |rate_numerator: u64, effective_at: i64| {
+        serde_json::json!({
+            "from_medium_hash": time_hash,
+            "to_medium_hash": local_hash,
+            "rate_numerator": rate_numerator,
+            "rate_denominator": 1,
+            "effective_at": Timestamp::from_micros(effective_at),
+        })
+    };
+    let hour_ago = now - 3_600_000_000;
+    let minute_ago = now - 60_000_000;
+    conductors[0]
+        .call::<_, Record>(&alice.zome("mediums_of_exchange"), "create_exchange_rate", rate(15, hour_ago))
+        .await;
+    conductors[0]
+        .call::<_, Record>(&alice.zome("mediums_of_exchange"), "create_exchange_rate", rate(20, minute_ago))
+        .await;
+
+    await_consistency(15, [&alice, &bob]).await.unwrap();
+
+    // Only administrators maintain rates.
+    let result = conductors[1]
+        .call_fallible::<_, Record>(&bob.zome("mediums_of_exchange"), "create_exchange_rate", rate(10, now))
+        .await;
+    assert!(result.is_err(), "Non-admin should not set exchange rates");
+
+    let history: Vec<Record> = conductors[1]
+        .call(
+            &bob.zome("mediums_of_exchange"),
+            "get_exchange_rate_history",
+            serde_json::json!({ "from_medium_hash": local_hash, "to_medium_hash": time_hash }),
+        )
+        .await;
+    let rates: Vec<(u64, u64)> = history
+        .iter()
+        .map(|record| {
+            let exchange_rate: ExchangeRate = record.entry().to_app_option().unwrap().expect("entry");
+            (exchange_rate.rate_numerator, exchange_rate.rate_denominator)
+        })
+        .collect();
+    assert_eq!(rates, vec![(15, 1), (20, 1)]);
+
+    let convert = |amount: f64, from: &ActionHash, to: &ActionHash, at: Option<i64>| {
+        serde_json::json!({
+            "amount": amount,
+            "from_medium_hash": from,
+            "to_medium_hash": to,
+            "at": at.map(Timestamp::from_micros),
+        })
+    };
+    let latest: f64 = conductors[1]
+        .call(&bob.zome("mediums_of_exchange"), "convert", convert(2.0, &time_hash, &local_hash, None))
+        .await;
+    assert_eq!(latest, 40.0);
+    let earlier: f64 = conductors[1]
+        .call(
+            &bob.zome("mediums_of_exchange"),
+            "convert",
+            convert(2.0, &time_hash, &local_hash, Some(hour_ago + 1_000_000)),
+        )
+        .await;
+    assert_eq!(earlier, 30.0);
+    let inverted: f64 = conductors[1]
+        .call(&bob.zome("mediums_of_exchange"), "convert", convert(30.0, &local_hash, &time_hash, None))
+        .await;
+    assert_eq!(inverted, 1.5);
+
+    // Before the first rate there is nothing to convert with.
+    let result = conductors[1]
+        .call_fallible::<_, f64>(
+            &bob.zome("mediums_of_exchange"),
+            "convert",
+            convert(1.0, &time_hash, &local_hash, Some(hour_ago - 1_000_000)),
+        )
+        .await;
+    assert!(result.is_err());
+}
@@ -0,0 +1,97 @@
+//! Messaging zome tests.
+
+use holochain::prelude::*;
+use holochain::sweettest::*;
+use requests_and_offers_sweettest::common::*;
+
+fn message_from_record(record: &Record) -> Message {
+    record
+        .entry()
+        .to_app_option::<Message>()
+        .unwrap()
+        .expect("Expected a message entry")
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn users_exchange_private_messages() {
+    let (conductors, alice, bob) = setup_two_agents_with_alice_as_progenitor().await;
+
+    conductors[0]
+        .call::<_, Record>(&alice.zome("users_organizations"), "create_user", sample_user("Alice"))
+        .await;
+    conductors[1]
+        .call::<_, Record>(&bob.zome("users_organizations"), "create_user", sample_user("Bob"))
+        .await;
+
+    await_consistency(15, [&alice, &bob]).await.unwrap();
+
+    let mut user_hashes = Vec::new();
+    for (conductor, cell) in [(&conductors[0], &alice), (&conductors[1], &bob)] {
+        let links: Vec<Link> = conductor
+            .call(&cell.zome("users_organizations"), "get_agent_user", cell.agent_pubkey().clone())
+            .await;
+        user_hashes.push(links[0].target.clone().into_action_hash().unwrap());
+    }
+
+    // Alice writes to Bob's user profile.
+    let sent: Record = conductors[0]
+        .call(
+            &alice.zome("messaging"),
+            "send_message",
+            serde_json::json!({
+                "recipient_hash": user_hashes[1],
+                "content": "Is the bike still available?"
+            }),
+        )
+        .await;
+    let sent_message = message_from_record(&sent);
+    assert_eq!(sent_message.sender_hash, user_hashes[0]);
+    assert_eq!(sent_message.mailbox_hash, None);
+
+    await_consistency(15, [&alice, &bob]).await.unwrap();
+
+    // Whether the live signal or the mailbox delivered it, Bob stores the message once,
+    // and collecting removes it from his mailbox.
+    let _: Vec<Record> = conductors[1]
+        .call(&bob.zome("messaging"), "collect_messages", ())
+        .await;
+    await_consistency(15, [&alice, &bob]).await.unwrap();
+    let collected_again: Vec<Record> = conductors[1]
+        .call(&bob.zome("messaging"), "collect_messages", ())
+        .await;
+    assert!(collected_again.is_empty());
+    let bob_conversation: Vec<Record> = conductors[1]
+        .call(&bob.zome("messaging"), "get_conversation", user_hashes[0].clone())
+        .await;
+    assert_eq!(bob_conversation.len(), 1);
+    let received = message_from_record(&bob_conversation[0]);
+    assert_eq!(received.content, "Is the bike still available?");
+    assert_eq!(received.sender_hash, user_hashes[0]);
+    assert!(received.mailbox_hash.is_some());
+
+    // Bob replies; Alice sees both messages in order.
+    let _: Record = conductors[1]
+        .call(
+            &bob.zome("messaging"),
+            "send_message",
+            serde_json::json!({
+                "recipient_hash": user_hashes[0],
+                "content": "Yes, come by tomorrow"
+            }),
+        )
+        .await;
+
+    await_consistency(15, [&alice, &bob]).await.unwrap();
+
+    let _: Vec<Record> = conductors[0]
+        .call(&alice.zome("messaging"), "collect_messages", ())
+        .await;
+    let alice_conversation: Vec<Record> = conductors[0]
+        .call(&alice.zome("messaging"), "get_conversation", user_hashes[1].clone())
+        .await;
+    let contents: Vec<String> = alice_conversation
+        .iter()
+        .map(|record| message_from_record(record).content)
+        .collect();
+    assert_eq!(contents, vec!["Is the bike still available?", "Yes, come by tomorrow"]);
+}
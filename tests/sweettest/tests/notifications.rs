@@ -0,0 +1,67 @@
+//! Notifications zome tests.
+
+use holochain::prelude::*;
+use holochain::sweettest::*;
+use requests_and_offers_sweettest::common::*;
+
+#[tokio::test(flavor = "multi_thread")]
+async fn agents_subscribe_to_service_types() {
+    let (conductors, alice, bob) = setup_two_agents_with_alice_as_progenitor().await;
+
+    conductors[0]
+        .call::<_, Record>(&alice.zome("users_organizations"), "create_user", sample_user("Alice"))
+        .await;
+
+    await_consistency(15, [&alice, &bob]).await.unwrap();
+
+    let links: Vec<Link> = conductors[0]
+        .call(&alice.zome("users_organizations"), "get_agent_user", alice.agent_pubkey().clone())
+        .await;
+    let alice_user_hash = links[0].target.clone().into_action_hash().unwrap();
+    accept_entity(&conductors[0], &alice, ENTITY_USERS, alice_user_hash).await;
+
+    let gardening: Record = conductors[0]
+        .call(&alice.zome("service_types"), "create_service_type", sample_service_type("Gardening"))
+        .await;
+    let gardening_hash = gardening.signed_action.hashed.hash.clone();
+    let target = serde_json::json!({ "ServiceType": gardening_hash });
+
+    // Bob subscribes once; a second subscription is rejected.
+    let _: Record = conductors[1]
+        .call(&bob.zome("notifications"), "subscribe", target.clone())
+        .await;
+    let again: Result<Record, _> = conductors[1]
+        .call_fallible(&bob.zome("notifications"), "subscribe", target.clone())
+        .await;
+    assert!(again.is_err());
+
+    await_consistency(15, [&alice, &bob]).await.unwrap();
+
+    let subscribers: Vec<AgentPubKey> = conductors[0]
+        .call(&alice.zome("notifications"), "get_subscribers", target.clone())
+        .await;
+    assert_eq!(subscribers, vec![bob.agent_pubkey().clone()]);
+
+    // Publishing a listing for the service type notifies Bob without failing the listing.
+    let mut request_input = sample_request("Help with gardening");
+    request_input.service_type_hashes = vec![gardening_hash.clone()];
+    let _: Record = conductors[0]
+        .call(&alice.zome("requests"), "create_request", request_input)
+        .await;
+
+    let unsubscribed: bool = conductors[1]
+        .call(&bob.zome("notifications"), "unsubscribe", target.clone())
+        .await;
+    assert!(unsubscribed);
+
+    await_consistency(15, [&alice, &bob]).await.unwrap();
+
+    let subscribers: Vec<AgentPubKey> = conductors[0]
+        .call(&alice.zome("notifications"), "get_subscribers", target.clone())
+        .await;
+    assert!(subscribers.is_empty());
+    let my_subscriptions: Vec<Record> = conductors[1]
+        .call(&bob.zome("notifications"), "get_my_subscriptions", ())
+        .await;
+    assert!(my_subscriptions.is_empty());
+}
@@ -142,3 +142,175 @@ async fn offer_archive_and_delete() {
         .await;
     assert!(after_delete.is_none(), "Deleted offer should not be found");
 }
+
+#[tokio::test(flavor = "multi_thread")]
+async fn archived_offer_can_be_reactivated() {
+    let (conductors, alice, bob) = setup_two_agents_with_alice_as_progenitor().await;
+
+    conductors[0]
+        .call::<_, Record>(&alice.zome("users_organizations"), "create_user", sample_user("Alice"))
+        .await;
+
+    await_consistency(15, [&alice, &bob]).await.unwrap();
+
+    let alice_links: Vec<Link> = conductors[0]
+        .call(&alice.zome("users_organizations"), "get_agent_user", alice.agent_pubkey().clone())
+        .await;
+    let alice_user_hash = alice_links[0].target.clone().into_action_hash().unwrap();
+    accept_entity(&conductors[0], &alice, ENTITY_USERS, alice_user_hash).await;
+
+    let tutoring: Record = conductors[0]
+        .call(&alice.zome("service_types"), "create_service_type", sample_service_type("Tutoring"))
+        .await;
+    let tutoring_hash = tutoring.signed_action.hashed.hash.clone();
+
+    let mut offer_input = sample_offer("Maths tutoring");
+    offer_input.service_type_hashes = vec![tutoring_hash.clone()];
+    let offer_record: Record = conductors[0]
+        .call(&alice.zome("offers"), "create_offer", offer_input)
+        .await;
+    let offer_hash = offer_record.signed_action.hashed.hash.clone();
+
+    // Only archived offers can be reactivated.
+    let not_archived: Result<Record, _> = conductors[0]
+        .call_fallible(&alice.zome("offers"), "reactivate_offer", offer_hash.clone())
+        .await;
+    assert!(not_archived.is_err());
+
+    let archived: bool = conductors[0]
+        .call(&alice.zome("offers"), "archive_offer", offer_hash.clone())
+        .await;
+    assert!(archived);
+
+    await_consistency(15, [&alice, &bob]).await.unwrap();
+
+    let archived_offers: Vec<Record> = conductors[1]
+        .call(&bob.zome("offers"), "get_archived_offers", ())
+        .await;
+    assert_eq!(archived_offers.len(), 1);
+
+    // Bob cannot reactivate Alice's offer.
+    let by_bob: Result<Record, _> = conductors[1]
+        .call_fallible(&bob.zome("offers"), "reactivate_offer", offer_hash.clone())
+        .await;
+    assert!(by_bob.is_err());
+
+    let reactivated: Record = conductors[0]
+        .call(&alice.zome("offers"), "reactivate_offer", offer_hash.clone())
+        .await;
+    let reactivated_offer: Offer = reactivated.entry().to_app_option().unwrap().expect("entry");
+    assert_eq!(reactivated_offer.status, "Active");
+
+    await_consistency(15, [&alice, &bob]).await.unwrap();
+
+    let archived_offers: Vec<Record> = conductors[1]
+        .call(&bob.zome("offers"), "get_archived_offers", ())
+        .await;
+    assert!(archived_offers.is_empty());
+    let active_offers: Vec<Record> = conductors[1]
+        .call(&bob.zome("offers"), "get_active_offers", ())
+        .await;
+    assert_eq!(active_offers.len(), 1);
+
+    // The offer keeps its original action hash and service type link.
+    let for_service_type: Vec<Record> = conductors[1]
+        .call(&bob.zome("service_types"), "get_offers_for_service_type", tutoring_hash)
+        .await;
+    assert_eq!(for_service_type.len(), 1);
+    assert_eq!(*for_service_type[0].action_address(), offer_hash);
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn administrators_and_organization_coordinators_can_update_offers() {
+    let (conductors, alice, bob) = setup_two_agents_with_alice_as_progenitor().await;
+
+    // Alice (progenitor) is auto-registered as admin; Bob is a regular user.
+    conductors[0]
+        .call::<_, Record>(&alice.zome("users_organizations"), "create_user", sample_user("Alice"))
+        .await;
+    conductors[1]
+        .call::<_, Record>(&bob.zome("users_organizations"), "create_user", sample_user("Bob"))
+        .await;
+
+    await_consistency(15, [&alice, &bob]).await.unwrap();
+
+    let mut user_hashes = Vec::new();
+    for (conductor, cell) in [(&conductors[0], &alice), (&conductors[1], &bob)] {
+        let links: Vec<Link> = conductor
+            .call(&cell.zome("users_organizations"), "get_agent_user", cell.agent_pubkey().clone())
+            .await;
+        let user_hash = links[0].target.clone().into_action_hash().unwrap();
+        accept_entity(&conductors[0], &alice, ENTITY_USERS, user_hash.clone()).await;
+        user_hashes.push(user_hash);
+    }
+    let bob_user_hash = user_hashes[1].clone();
+
+    // Alice creates an organization and makes Bob one of its coordinators.
+    let org_record: Record = conductors[0]
+        .call(
+            &alice.zome("users_organizations"),
+            "create_organization",
+            sample_organization("Repair Cafe"),
+        )
+        .await;
+    let org_hash = org_record.signed_action.hashed.hash.clone();
+
+    await_consistency(15, [&alice, &bob]).await.unwrap();
+    accept_entity(&conductors[0], &alice, ENTITY_ORGANIZATIONS, org_hash.clone()).await;
+    await_consistency(15, [&alice, &bob]).await.unwrap();
+
+    let _: bool = conductors[0]
+        .call(
+            &alice.zome("users_organizations"),
+            "add_coordinator_to_organization",
+            serde_json::json!({
+                "organization_original_action_hash": org_hash,
+                "user_original_action_hash": bob_user_hash
+            }),
+        )
+        .await;
+
+    // Alice offers help personally and on behalf of the organization.
+    let personal_offer: Record = conductors[0]
+        .call(&alice.zome("offers"), "create_offer", sample_offer("Personal offer"))
+        .await;
+    let mut org_offer_input = sample_offer("Organization offer");
+    org_offer_input.organization = Some(org_hash.clone());
+    let org_offer: Record = conductors[0]
+        .call(&alice.zome("offers"), "create_offer", org_offer_input)
+        .await;
+    let bob_offer: Record = conductors[1]
+        .call(&bob.zome("offers"), "create_offer", sample_offer("Bob's offer"))
+        .await;
+
+    await_consistency(15, [&alice, &bob]).await.unwrap();
+
+    // Bob is neither the author nor an administrator of Alice's personal offer.
+    let not_allowed: Result<bool, _> = conductors[1]
+        .call_fallible(
+            &bob.zome("offers"),
+            "archive_offer",
+            personal_offer.signed_action.hashed.hash.clone(),
+        )
+        .await;
+    assert!(not_allowed.is_err());
+
+    // As a coordinator of its organization, Bob can archive the organization's offer.
+    let archived_by_coordinator: bool = conductors[1]
+        .call(&bob.zome("offers"), "archive_offer", org_offer.signed_action.hashed.hash.clone())
+        .await;
+    assert!(archived_by_coordinator);
+
+    // As an administrator, Alice can archive Bob's offer.
+    let archived_by_admin: bool = conductors[0]
+        .call(&alice.zome("offers"), "archive_offer", bob_offer.signed_action.hashed.hash.clone())
+        .await;
+    assert!(archived_by_admin);
+
+    await_consistency(15, [&alice, &bob]).await.unwrap();
+
+    let archived_offers: Vec<Record> = conductors[1]
+        .call(&bob.zome("offers"), "get_archived_offers", ())
+        .await;
+    assert_eq!(archived_offers.len(), 2);
+}
@@ -148,3 +148,513 @@ async fn request_archive_and_delete() {
         .await;
     assert!(after_delete.is_none(), "Deleted request should not be found");
 }
+
+#[tokio::test(flavor = "multi_thread")]
+async fn administrators_delete_other_users_requests() {
+    let (conductors, alice, bob) = setup_two_agents_with_alice_as_progenitor().await;
+
+    conductors[1]
+        .call::<_, Record>(&bob.zome("users_organizations"), "create_user", sample_user("Bob"))
+        .await;
+
+    await_consistency(15, [&alice, &bob]).await.unwrap();
+
+    let bob_links: Vec<Link> = conductors[1]
+        .call(&bob.zome("users_organizations"), "get_agent_user", bob.agent_pubkey().clone())
+        .await;
+    let bob_user_hash = bob_links[0].target.clone().into_action_hash().unwrap();
+    accept_entity(&conductors[0], &alice, ENTITY_USERS, bob_user_hash.clone()).await;
+
+    await_consistency(15, [&alice, &bob]).await.unwrap();
+
+    let req_record: Record = conductors[1]
+        .call(&bob.zome("requests"), "create_request", sample_request("Spam"))
+        .await;
+    let req_hash = req_record.signed_action.hashed.hash.clone();
+
+    await_consistency(15, [&alice, &bob]).await.unwrap();
+
+    // Alice, an administrator, removes Bob's request and its index links.
+    let deleted: bool = conductors[0]
+        .call(&alice.zome("requests"), "delete_request", req_hash.clone())
+        .await;
+    assert!(deleted);
+
+    await_consistency(15, [&alice, &bob]).await.unwrap();
+
+    let active: Vec<Record> = conductors[1]
+        .call(&bob.zome("requests"), "get_active_requests", ())
+        .await;
+    assert!(active.is_empty());
+    let bob_requests: Vec<Record> = conductors[1]
+        .call(&bob.zome("requests"), "get_user_requests", bob_user_hash)
+        .await;
+    assert!(bob_requests.is_empty());
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn active_requests_are_paginated() {
+    let (conductors, alice, bob) = setup_two_agents_with_alice_as_progenitor().await;
+
+    conductors[0]
+        .call::<_, Record>(&alice.zome("users_organizations"), "create_user", sample_user("Alice"))
+        .await;
+
+    await_consistency(15, [&alice, &bob]).await.unwrap();
+
+    let alice_links: Vec<Link> = conductors[0]
+        .call(&alice.zome("users_organizations"), "get_agent_user", alice.agent_pubkey().clone())
+        .await;
+    let alice_user_hash = alice_links[0].target.clone().into_action_hash().unwrap();
+    accept_entity(&conductors[0], &alice, ENTITY_USERS, alice_user_hash).await;
+
+    // Alice creates three requests.
+    let mut created = Vec::new();
+    for title in ["First request", "Second request", "Third request"] {
+        let record: Record = conductors[0]
+            .call(&alice.zome("requests"), "create_request", sample_request(title))
+            .await;
+        created.push(record.signed_action.hashed.hash.clone());
+    }
+
+    await_consistency(15, [&alice, &bob]).await.unwrap();
+
+    // First page holds the two oldest requests.
+    let first_page: Page<Record> = conductors[1]
+        .call(&bob.zome("requests"), "get_active_requests_paginated", page_input(None, 2))
+        .await;
+    let first_hashes: Vec<ActionHash> = first_page
+        .items
+        .iter()
+        .map(|record| record.signed_action.hashed.hash.clone())
+        .collect();
+    assert_eq!(first_hashes, created[..2].to_vec());
+    assert!(first_page.next_cursor.is_some());
+
+    // Second page holds the last one and ends the listing.
+    let second_page: Page<Record> = conductors[1]
+        .call(
+            &bob.zome("requests"),
+            "get_active_requests_paginated",
+            page_input(first_page.next_cursor, 2),
+        )
+        .await;
+    assert_eq!(second_page.items.len(), 1);
+    assert_eq!(second_page.items[0].signed_action.hashed.hash, created[2]);
+    assert!(second_page.next_cursor.is_none());
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn ended_requests_expire_and_can_be_renewed() {
+    let (conductors, alice, bob) = setup_two_agents_with_alice_as_progenitor().await;
+
+    conductors[0]
+        .call::<_, Record>(&alice.zome("users_organizations"), "create_user", sample_user("Alice"))
+        .await;
+
+    await_consistency(15, [&alice, &bob]).await.unwrap();
+
+    let alice_links: Vec<Link> = conductors[0]
+        .call(&alice.zome("users_organizations"), "get_agent_user", alice.agent_pubkey().clone())
+        .await;
+    let alice_user_hash = alice_links[0].target.clone().into_action_hash().unwrap();
+    accept_entity(&conductors[0], &alice, ENTITY_USERS, alice_user_hash).await;
+
+    // Alice creates a request whose date range has already ended.
+    let now = Timestamp::now().as_micros();
+    let mut input = sample_request("Stale request");
+    input.request.date_range = Some(DateRange {
+        start: Some(now - 7_200_000_000),
+        end: Some(now - 3_600_000_000),
+    });
+    let req_record: Record = conductors[0]
+        .call(&alice.zome("requests"), "create_request", input)
+        .await;
+    let req_hash = req_record.signed_action.hashed.hash.clone();
+
+    // Run the expiry sweep instead of waiting for the scheduler.
+    let _: Option<Schedule> = conductors[0]
+        .call(&alice.zome("requests"), "expire_requests", None::<Schedule>)
+        .await;
+
+    await_consistency(15, [&alice, &bob]).await.unwrap();
+
+    let active: Vec<Record> = conductors[1]
+        .call(&bob.zome("requests"), "get_active_requests", ())
+        .await;
+    assert!(active.is_empty(), "Expired request should leave the active index");
+
+    let expired: Vec<Record> = conductors[1]
+        .call(&bob.zome("requests"), "get_expired_requests", ())
+        .await;
+    assert_eq!(expired.len(), 1);
+    let expired_req: Request = expired[0].entry().to_app_option().unwrap().expect("entry");
+    assert_eq!(expired_req.status, "Expired");
+
+    // Renewing with a range that has already ended is rejected.
+    let stale_renewal: Result<Record, _> = conductors[0]
+        .call_fallible(
+            &alice.zome("requests"),
+            "renew_request",
+            serde_json::json!({
+                "original_action_hash": req_hash,
+                "date_range": { "start": null, "end": now - 1_000_000 }
+            }),
+        )
+        .await;
+    assert!(stale_renewal.is_err());
+
+    // Alice renews the request with a range ending tomorrow.
+    let renewed: Record = conductors[0]
+        .call(
+            &alice.zome("requests"),
+            "renew_request",
+            serde_json::json!({
+                "original_action_hash": req_hash,
+                "date_range": { "start": now, "end": now + 86_400_000_000i64 }
+            }),
+        )
+        .await;
+    let renewed_req: Request = renewed.entry().to_app_option().unwrap().expect("entry");
+    assert_eq!(renewed_req.status, "Active");
+
+    await_consistency(15, [&alice, &bob]).await.unwrap();
+
+    let active: Vec<Record> = conductors[1]
+        .call(&bob.zome("requests"), "get_active_requests", ())
+        .await;
+    assert_eq!(active.len(), 1);
+    let expired: Vec<Record> = conductors[1]
+        .call(&bob.zome("requests"), "get_expired_requests", ())
+        .await;
+    assert!(expired.is_empty());
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn request_fulfilment_lifecycle() {
+    let (conductors, alice, bob) = setup_two_agents_with_alice_as_progenitor().await;
+
+    conductors[0]
+        .call::<_, Record>(&alice.zome("users_organizations"), "create_user", sample_user("Alice"))
+        .await;
+
+    await_consistency(15, [&alice, &bob]).await.unwrap();
+
+    let alice_links: Vec<Link> = conductors[0]
+        .call(&alice.zome("users_organizations"), "get_agent_user", alice.agent_pubkey().clone())
+        .await;
+    let alice_user_hash = alice_links[0].target.clone().into_action_hash().unwrap();
+    accept_entity(&conductors[0], &alice, ENTITY_USERS, alice_user_hash).await;
+
+    let req_record: Record = conductors[0]
+        .call(&alice.zome("requests"), "create_request", sample_request("Fix my bike"))
+        .await;
+    let req_hash = req_record.signed_action.hashed.hash.clone();
+
+    // In-progress requests stay in the active index.
+    let in_progress: Record = conductors[0]
+        .call(&alice.zome("requests"), "mark_request_in_progress", req_hash.clone())
+        .await;
+    let in_progress_req: Request = in_progress.entry().to_app_option().unwrap().expect("entry");
+    assert_eq!(in_progress_req.status, "InProgress");
+
+    await_consistency(15, [&alice, &bob]).await.unwrap();
+
+    let active: Vec<Record> = conductors[1]
+        .call(&bob.zome("requests"), "get_active_requests", ())
+        .await;
+    assert_eq!(active.len(), 1);
+
+    // Fulfilling moves the request to the fulfilled index.
+    let fulfilled: Record = conductors[0]
+        .call(
+            &alice.zome("requests"),
+            "fulfil_request",
+            serde_json::json!({
+                "original_action_hash": req_hash,
+                "fulfilment_notes": "Replaced the chain"
+            }),
+        )
+        .await;
+    let fulfilled_req: Request = fulfilled.entry().to_app_option().unwrap().expect("entry");
+    assert_eq!(fulfilled_req.status, "Fulfilled");
+    assert_eq!(fulfilled_req.fulfilment_notes.as_deref(), Some("Replaced the chain"));
+
+    await_consistency(15, [&alice, &bob]).await.unwrap();
+
+    let active: Vec<Record> = conductors[1]
+        .call(&bob.zome("requests"), "get_active_requests", ())
+        .await;
+    assert!(active.is_empty());
+    let fulfilled_list: Vec<Record> = conductors[1]
+        .call(&bob.zome("requests"), "get_fulfilled_requests", ())
+        .await;
+    assert_eq!(fulfilled_list.len(), 1);
+
+    // A fulfilled request cannot go back to in progress.
+    let reopened: Result<Record, _> = conductors[0]
+        .call_fallible(&alice.zome("requests"), "mark_request_in_progress", req_hash.clone())
+        .await;
+    assert!(reopened.is_err());
+
+    // Nor can an update bypass the transition rules.
+    let reactivated = RequestData {
+        title: "Fix my bike".to_string(),
+        status: "Active".to_string(),
+        ..sample_request("x").request
+    };
+    let bypass: Result<Record, _> = conductors[0]
+        .call_fallible(
+            &alice.zome("requests"),
+            "update_request",
+            serde_json::json!({
+                "original_action_hash": req_hash,
+                "previous_action_hash": fulfilled.signed_action.hashed.hash,
+                "updated_request": reactivated,
+                "service_type_hashes": [],
+                "medium_of_exchange_hashes": []
+            }),
+        )
+        .await;
+    assert!(bypass.is_err());
+
+    // Archiving a fulfilled request clears its notes and takes it off the fulfilled index.
+    let archived: bool = conductors[0]
+        .call(&alice.zome("requests"), "archive_request", req_hash.clone())
+        .await;
+    assert!(archived);
+    let archived_req: Request = conductors[0]
+        .call(&alice.zome("requests"), "get_latest_request", req_hash.clone())
+        .await;
+    assert_eq!(archived_req.status, "Archived");
+    assert!(archived_req.fulfilment_notes.is_none());
+
+    await_consistency(15, [&alice, &bob]).await.unwrap();
+
+    let fulfilled_list: Vec<Record> = conductors[1]
+        .call(&bob.zome("requests"), "get_fulfilled_requests", ())
+        .await;
+    assert!(fulfilled_list.is_empty());
+    let archived_list: Vec<Record> = conductors[1]
+        .call(&bob.zome("requests"), "get_archived_requests", ())
+        .await;
+    assert_eq!(archived_list.len(), 1);
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn organization_requests_require_membership() {
+    let (conductors, alice, bob) = setup_two_agents_with_alice_as_progenitor().await;
+
+    conductors[0]
+        .call::<_, Record>(&alice.zome("users_organizations"), "create_user", sample_user("Alice"))
+        .await;
+    conductors[1]
+        .call::<_, Record>(&bob.zome("users_organizations"), "create_user", sample_user("Bob"))
+        .await;
+
+    await_consistency(15, [&alice, &bob]).await.unwrap();
+
+    for (conductor, cell) in [(&conductors[0], &alice), (&conductors[1], &bob)] {
+        let links: Vec<Link> = conductor
+            .call(&cell.zome("users_organizations"), "get_agent_user", cell.agent_pubkey().clone())
+            .await;
+        let user_hash = links[0].target.clone().into_action_hash().unwrap();
+        accept_entity(&conductors[0], &alice, ENTITY_USERS, user_hash).await;
+    }
+
+    // Alice creates an organization, which makes her its first member.
+    let org_record: Record = conductors[0]
+        .call(
+            &alice.zome("users_organizations"),
+            "create_organization",
+            sample_organization("Repair Cafe"),
+        )
+        .await;
+    let org_hash = org_record.signed_action.hashed.hash.clone();
+
+    await_consistency(15, [&alice, &bob]).await.unwrap();
+
+    // Bob is not a member, so he cannot file a request under the organization.
+    let mut bob_input = sample_request("Bob's request");
+    bob_input.organization = Some(org_hash.clone());
+    let not_member: Result<Record, _> = conductors[1]
+        .call_fallible(&bob.zome("requests"), "create_request", bob_input)
+        .await;
+    assert!(not_member.is_err());
+
+    let mut alice_input = sample_request("Alice's request");
+    alice_input.organization = Some(org_hash.clone());
+    let req_record: Record = conductors[0]
+        .call(&alice.zome("requests"), "create_request", alice_input)
+        .await;
+    let req_hash = req_record.signed_action.hashed.hash.clone();
+
+    await_consistency(15, [&alice, &bob]).await.unwrap();
+
+    let org_requests: Vec<Record> = conductors[1]
+        .call(&bob.zome("requests"), "get_organization_requests", org_hash.clone())
+        .await;
+    assert_eq!(org_requests.len(), 1);
+    assert_eq!(org_requests[0].signed_action.hashed.hash, req_hash);
+
+    let request_org: Option<ActionHash> = conductors[1]
+        .call(&bob.zome("requests"), "get_request_organization", req_hash)
+        .await;
+    assert_eq!(request_org, Some(org_hash));
+}
+
+/// Requests can be valued in an approved medium of exchange and filtered by amount,
+/// converting other mediums with the current exchange rate.
+#[tokio::test(flavor = "multi_thread")]
+async fn active_requests_can_be_filtered_by_valuation() {
+    let (conductors, alice, bob) = setup_two_agents_with_alice_as_progenitor().await;
+
+    conductors[0]
+        .call::<_, Record>(&alice.zome("users_organizations"), "create_user", sample_user("Alice"))
+        .await;
+    conductors[1]
+        .call::<_, Record>(&bob.zome("users_organizations"), "create_user", sample_user("Bob"))
+        .await;
+
+    await_consistency(15, [&alice, &bob]).await.unwrap();
+
+    let alice_links: Vec<Link> = conductors[0]
+        .call(&alice.zome("users_organizations"), "get_agent_user", alice.agent_pubkey().clone())
+        .await;
+    let bob_links: Vec<Link> = conductors[1]
+        .call(&bob.zome("users_organizations"), "get_agent_user", bob.agent_pubkey().clone())
+        .await;
+    let alice_user_hash = alice_links[0].target.clone().into_action_hash().unwrap();
+    let bob_user_hash = bob_links[0].target.clone().into_action_hash().unwrap();
+    accept_entity(&conductors[0], &alice, ENTITY_USERS, alice_user_hash).await;
+    accept_entity(&conductors[0], &alice, ENTITY_USERS, bob_user_hash).await;
+
+    // Mediums created by the admin are approved right away, Bob's suggestion stays pending.
+    let time: Record = conductors[0]
+        .call(
+            &alice.zome("mediums_of_exchange"),
+            "create_medium_of_exchange",
+            sample_medium_of_exchange("TIME", "Time Banking"),
+        )
+        .await;
+    let time_hash = time.signed_action.hashed.hash.clone();
+    let local: Record = conductors[0]
+        .call(
+            &alice.zome("mediums_of_exchange"),
+            "create_medium_of_exchange",
+            sample_medium_of_exchange("LOCAL", "Local Currency"),
+        )
+        .await;
+    let local_hash = local.signed_action.hashed.hash.clone();
+
+    await_consistency(15, [&alice, &bob]).await.unwrap();
+
+    let pending: Record = conductors[1]
+        .call(
+            &bob.zome("mediums_of_exchange"),
+            "suggest_medium_of_exchange",
+            sample_medium_of_exchange("EUR", "Euro"),
+        )
+        .await;
+    let pending_hash = pending.signed_action.hashed.hash.clone();
+
+    // One hour of TIME is worth 15 LOCAL.
+    conductors[0]
+        .call::<_, Record>(
+            &alice.zome("mediums_of_exchange"),
+            "create_exchange_rate",
+            serde_json::json!({
+                "exchange_rate": ExchangeRate {
+                    from_medium_hash: time_hash.clone(),
+                    to_medium_hash: local_hash.clone(),
+                    rate: 15.0,
+                    effective_at: Timestamp::from_micros(Timestamp::now().as_micros() - 60_000_000),
+                }
+            }),
+        )
+        .await;
+
+    await_consistency(15, [&alice, &bob]).await.unwrap();
+
+    let valued = |title: &str, amount: i64, unit: &str, medium_hash: &ActionHash| {
+        let mut input = sample_request(title);
+        input.request.valuation = Some(Valuation {
+            amount,
+            unit: unit.to_string(),
+            medium_of_exchange_hash: medium_hash.clone(),
+        });
+        input
+    };
+
+    let unapproved: Result<Record, _> = conductors[0]
+        .call_fallible(
+            &alice.zome("requests"),
+            "create_request",
+            valued("Paid in euros", 1000, "Fixed", &pending_hash),
+        )
+        .await;
+    assert!(unapproved.is_err(), "Unapproved mediums cannot value a request");
+
+    let mut hashes = Vec::new();
+    for input in [
+        valued("Garden help", 1000, "PerHour", &local_hash),
+        valued("Bike repair", 200, "Fixed", &time_hash),
+        valued("Moving boxes", 5000, "Fixed", &local_hash),
+        sample_request("Unpriced"),
+    ] {
+        let record: Record = conductors[0]
+            .call(&alice.zome("requests"), "create_request", input)
+            .await;
+        hashes.push(record.signed_action.hashed.hash.clone());
+    }
+
+    await_consistency(15, [&alice, &bob]).await.unwrap();
+
+    let filter = |medium_hash: &ActionHash, min: Option<i64>, max: Option<i64>, unit: Option<&str>| {
+        serde_json::json!({
+            "medium_of_exchange_hash": medium_hash,
+            "min_amount": min,
+            "max_amount": max,
+            "unit": unit,
+        })
+    };
+    let sorted_hashes = |records: Vec<Record>| {
+        let mut hashes: Vec<ActionHash> = records
+            .into_iter()
+            .map(|record| record.signed_action.hashed.hash.clone())
+            .collect();
+        hashes.sort();
+        hashes
+    };
+
+    // 2 TIME converts to 30 LOCAL.
+    let between: Vec<Record> = conductors[1]
+        .call(
+            &bob.zome("requests"),
+            "get_active_requests_by_valuation",
+            filter(&local_hash, Some(2000), Some(4000), None),
+        )
+        .await;
+    assert_eq!(sorted_hashes(between), vec![hashes[1].clone()]);
+
+    let fixed: Vec<Record> = conductors[1]
+        .call(
+            &bob.zome("requests"),
+            "get_active_requests_by_valuation",
+            filter(&local_hash, None, Some(6000), Some("Fixed")),
+        )
+        .await;
+    let mut expected = vec![hashes[1].clone(), hashes[2].clone()];
+    expected.sort();
+    assert_eq!(sorted_hashes(fixed), expected);
+
+    // 10 LOCAL converts to 2/3 of a TIME hour; unpriced requests are never included.
+    let in_time: Vec<Record> = conductors[1]
+        .call(
+            &bob.zome("requests"),
+            "get_active_requests_by_valuation",
+            filter(&time_hash, None, Some(100), None),
+        )
+        .await;
+    assert_eq!(sorted_hashes(in_time), vec![hashes[0].clone()]);
+}
@@ -0,0 +1,265 @@
+//! Responses zome tests.
+
+use holochain::prelude::*;
+use holochain::sweettest::*;
+use requests_and_offers_sweettest::common::*;
+
+#[tokio::test(flavor = "multi_thread")]
+async fn request_owner_accepts_a_response() {
+    let (conductors, alice, bob) = setup_two_agents_with_alice_as_progenitor().await;
+
+    // Create users. Alice (progenitor) is auto-registered as admin via init callback.
+    conductors[0]
+        .call::<_, Record>(&alice.zome("users_organizations"), "create_user", sample_user("Alice"))
+        .await;
+    conductors[1]
+        .call::<_, Record>(&bob.zome("users_organizations"), "create_user", sample_user("Bob"))
+        .await;
+
+    await_consistency(15, [&alice, &bob]).await.unwrap();
+
+    // Accept both profiles so they can create listings and responses.
+    let mut user_hashes = Vec::new();
+    for (conductor, cell) in [(&conductors[0], &alice), (&conductors[1], &bob)] {
+        let links: Vec<Link> = conductor
+            .call(&cell.zome("users_organizations"), "get_agent_user", cell.agent_pubkey().clone())
+            .await;
+        let user_hash = links[0].target.clone().into_action_hash().unwrap();
+        accept_entity(&conductors[0], &alice, ENTITY_USERS, user_hash.clone()).await;
+        user_hashes.push(user_hash);
+    }
+    let bob_user_hash = user_hashes[1].clone();
+
+    await_consistency(15, [&alice, &bob]).await.unwrap();
+
+    // Alice needs help, Bob offers it.
+    let request: Record = conductors[0]
+        .call(&alice.zome("requests"), "create_request", sample_request("Need a website"))
+        .await;
+    let request_hash = request.signed_action.hashed.hash.clone();
+    let offer: Record = conductors[1]
+        .call(&bob.zome("offers"), "create_offer", sample_offer("Web development"))
+        .await;
+    let offer_hash = offer.signed_action.hashed.hash.clone();
+
+    await_consistency(15, [&alice, &bob]).await.unwrap();
+
+    // Alice cannot answer her own request.
+    let own: Result<Record, _> = conductors[0]
+        .call_fallible(
+            &alice.zome("responses"),
+            "create_response",
+            serde_json::json!({
+                "request_hash": request_hash,
+                "offer_hash": null,
+                "message": "I can do it myself",
+                "medium_of_exchange_hash": null
+            }),
+        )
+        .await;
+    assert!(own.is_err());
+
+    // Bob cannot propose someone else's offer.
+    let alice_offer: Record = conductors[0]
+        .call(&alice.zome("offers"), "create_offer", sample_offer("Logo design"))
+        .await;
+    await_consistency(15, [&alice, &bob]).await.unwrap();
+    let borrowed: Result<Record, _> = conductors[1]
+        .call_fallible(
+            &bob.zome("responses"),
+            "create_response",
+            serde_json::json!({
+                "request_hash": request_hash,
+                "offer_hash": alice_offer.signed_action.hashed.hash,
+                "message": "Alice can design it",
+                "medium_of_exchange_hash": null
+            }),
+        )
+        .await;
+    assert!(borrowed.is_err());
+
+    // Bob answers with his offer.
+    let response: Record = conductors[1]
+        .call(
+            &bob.zome("responses"),
+            "create_response",
+            serde_json::json!({
+                "request_hash": request_hash,
+                "offer_hash": offer_hash,
+                "message": "I can build it next week",
+                "medium_of_exchange_hash": null
+            }),
+        )
+        .await;
+    let response_hash = response.signed_action.hashed.hash.clone();
+
+    await_consistency(15, [&alice, &bob]).await.unwrap();
+
+    let for_request: Vec<Record> = conductors[0]
+        .call(&alice.zome("responses"), "get_responses_for_request", request_hash.clone())
+        .await;
+    assert_eq!(for_request.len(), 1);
+    let pending: Response = for_request[0].entry().to_app_option().unwrap().expect("entry");
+    assert_eq!(pending.status, "Pending");
+    assert_eq!(pending.offer_hash, Some(offer_hash));
+
+    let by_bob: Vec<Record> = conductors[0]
+        .call(&alice.zome("responses"), "get_user_responses", bob_user_hash.clone())
+        .await;
+    assert_eq!(by_bob.len(), 1);
+
+    let responder: Option<ActionHash> = conductors[0]
+        .call(&alice.zome("responses"), "get_response_responder", response_hash.clone())
+        .await;
+    assert_eq!(responder, Some(bob_user_hash));
+
+    // Only the request owner can decide.
+    let by_responder: Result<Record, _> = conductors[1]
+        .call_fallible(&bob.zome("responses"), "accept_response", response_hash.clone())
+        .await;
+    assert!(by_responder.is_err());
+
+    let accepted: Record = conductors[0]
+        .call(&alice.zome("responses"), "accept_response", response_hash.clone())
+        .await;
+    let accepted_response: Response = accepted.entry().to_app_option().unwrap().expect("entry");
+    assert_eq!(accepted_response.status, "Accepted");
+
+    await_consistency(15, [&alice, &bob]).await.unwrap();
+
+    // The decision is final.
+    let declined: Result<Record, _> = conductors[0]
+        .call_fallible(&alice.zome("responses"), "decline_response", response_hash.clone())
+        .await;
+    assert!(declined.is_err());
+
+    let latest: Option<Record> = conductors[1]
+        .call(&bob.zome("responses"), "get_latest_response_record", response_hash)
+        .await;
+    let latest_response: Response = latest.unwrap().entry().to_app_option().unwrap().expect("entry");
+    assert_eq!(latest_response.status, "Accepted");
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn accepted_responses_use_up_offer_capacity() {
+    let (conductors, alice, bob) = setup_two_agents_with_alice_as_progenitor().await;
+
+    conductors[0]
+        .call::<_, Record>(&alice.zome("users_organizations"), "create_user", sample_user("Alice"))
+        .await;
+    conductors[1]
+        .call::<_, Record>(&bob.zome("users_organizations"), "create_user", sample_user("Bob"))
+        .await;
+
+    await_consistency(15, [&alice, &bob]).await.unwrap();
+
+    for (conductor, cell) in [(&conductors[0], &alice), (&conductors[1], &bob)] {
+        let links: Vec<Link> = conductor
+            .call(&cell.zome("users_organizations"), "get_agent_user", cell.agent_pubkey().clone())
+            .await;
+        let user_hash = links[0].target.clone().into_action_hash().unwrap();
+        accept_entity(&conductors[0], &alice, ENTITY_USERS, user_hash).await;
+    }
+
+    await_consistency(15, [&alice, &bob]).await.unwrap();
+
+    // Bob can repair one bike this month; Alice has two.
+    let mut offer_input = sample_offer("Bike repair");
+    offer_input.offer.capacity = Some(1);
+    let offer: Record = conductors[1]
+        .call(&bob.zome("offers"), "create_offer", offer_input)
+        .await;
+    let offer_hash = offer.signed_action.hashed.hash.clone();
+
+    let mut response_hashes = Vec::new();
+    for title in ["Fix my road bike", "Fix my city bike"] {
+        let request: Record = conductors[0]
+            .call(&alice.zome("requests"), "create_request", sample_request(title))
+            .await;
+        await_consistency(15, [&alice, &bob]).await.unwrap();
+
+        let response: Record = conductors[1]
+            .call(
+                &bob.zome("responses"),
+                "create_response",
+                serde_json::json!({
+                    "request_hash": request.signed_action.hashed.hash,
+                    "offer_hash": offer_hash,
+                    "message": "I can fix it",
+                    "medium_of_exchange_hash": null
+                }),
+            )
+            .await;
+        response_hashes.push(response.signed_action.hashed.hash.clone());
+    }
+
+    await_consistency(15, [&alice, &bob]).await.unwrap();
+
+    let capacity: OfferCapacity = conductors[0]
+        .call(&alice.zome("offers"), "get_offer_capacity", offer_hash.clone())
+        .await;
+    assert_eq!(capacity.remaining, Some(1));
+
+    // The first acceptance takes the only slot; Bob's cell then archives the full offer.
+    let _: Record = conductors[0]
+        .call(&alice.zome("responses"), "accept_response", response_hashes[0].clone())
+        .await;
+
+    await_consistency(15, [&alice, &bob]).await.unwrap();
+
+    let archived: bool = conductors[1]
+        .call(&bob.zome("offers"), "archive_offer_if_full", offer_hash.clone())
+        .await;
+    assert!(archived);
+
+    await_consistency(15, [&alice, &bob]).await.unwrap();
+
+    let latest: Offer = conductors[0]
+        .call(&alice.zome("offers"), "get_latest_offer", offer_hash.clone())
+        .await;
+    assert_eq!(latest.status, "Archived");
+
+    // The second acceptance goes on the waitlist.
+    let _: Record = conductors[0]
+        .call(&alice.zome("responses"), "accept_response", response_hashes[1].clone())
+        .await;
+
+    await_consistency(15, [&alice, &bob]).await.unwrap();
+
+    let capacity: OfferCapacity = conductors[1]
+        .call(&bob.zome("offers"), "get_offer_capacity", offer_hash.clone())
+        .await;
+    assert_eq!(capacity.capacity, Some(1));
+    assert_eq!(capacity.remaining, Some(0));
+    assert_eq!(capacity.taken, vec![response_hashes[0].clone()]);
+    assert_eq!(capacity.waitlist, vec![response_hashes[1].clone()]);
+
+    // Releasing the first slot hands it to the waitlisted response.
+    let released: bool = conductors[0]
+        .call(&alice.zome("offers"), "release_offer_slot", response_hashes[0].clone())
+        .await;
+    assert!(released);
+
+    await_consistency(15, [&alice, &bob]).await.unwrap();
+
+    let capacity: OfferCapacity = conductors[1]
+        .call(&bob.zome("offers"), "get_offer_capacity", offer_hash)
+        .await;
+    assert_eq!(capacity.remaining, Some(0));
+    assert_eq!(capacity.taken, vec![response_hashes[1].clone()]);
+    assert!(capacity.waitlist.is_empty());
+
+    // Withdrawing the response frees its slot.
+    let deleted: bool = conductors[1]
+        .call(&bob.zome("responses"), "delete_response", response_hashes[1].clone())
+        .await;
+    assert!(deleted);
+
+    await_consistency(15, [&alice, &bob]).await.unwrap();
+
+    let capacity: OfferCapacity = conductors[0]
+        .call(&alice.zome("offers"), "get_offer_capacity", offer_hash)
+        .await;
+    assert_eq!(capacity.remaining, Some(1));
+    assert!(capacity.taken.is_empty());
+}
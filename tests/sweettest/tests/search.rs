@@ -0,0 +1,303 @@
+//! Search zome tests.
+
+use holochain::prelude::*;
+use holochain::sweettest::*;
+use requests_and_offers_sweettest::common::*;
+
+#[tokio::test(flavor = "multi_thread")]
+async fn listings_and_users_are_searchable() {
+    let (conductors, alice, bob) = setup_two_agents_with_alice_as_progenitor().await;
+
+    // Create users. Alice (progenitor) is auto-registered as admin via init callback.
+    conductors[0]
+        .call::<_, Record>(&alice.zome("users_organizations"), "create_user", sample_user("Alice"))
+        .await;
+
+    await_consistency(15, [&alice, &bob]).await.unwrap();
+
+    // Accept Alice's user profile so she can create requests.
+    let alice_links: Vec<Link> = conductors[0]
+        .call(&alice.zome("users_organizations"), "get_agent_user", alice.agent_pubkey().clone())
+        .await;
+    let alice_user_hash = alice_links[0].target.clone().into_action_hash().unwrap();
+    accept_entity(&conductors[0], &alice, ENTITY_USERS, alice_user_hash.clone()).await;
+
+    // Alice creates a request.
+    let req_record: Record = conductors[0]
+        .call(&alice.zome("requests"), "create_request", sample_request("Help with gardening"))
+        .await;
+    let req_hash = req_record.signed_action.hashed.hash.clone();
+
+    await_consistency(15, [&alice, &bob]).await.unwrap();
+
+    // Bob finds the request by a word prefix.
+    let results: Vec<SearchResult> = conductors[1]
+        .call(
+            &bob.zome("search"),
+            "search",
+            serde_json::json!({ "query": "garden", "kinds": ["request"], "limit": null }),
+        )
+        .await;
+    assert_eq!(results.len(), 1);
+    assert_eq!(results[0].original_action_hash, req_hash);
+    assert_eq!(results[0].entity, "request");
+
+    // Every query word must match.
+    let results: Vec<SearchResult> = conductors[1]
+        .call(
+            &bob.zome("search"),
+            "search",
+            serde_json::json!({ "query": "gardening plumbing", "kinds": [], "limit": null }),
+        )
+        .await;
+    assert!(results.is_empty());
+
+    // Bob finds Alice's profile by name.
+    let results: Vec<SearchResult> = conductors[1]
+        .call(
+            &bob.zome("search"),
+            "search",
+            serde_json::json!({ "query": "alice", "kinds": ["user"], "limit": null }),
+        )
+        .await;
+    assert_eq!(results.len(), 1);
+    assert_eq!(results[0].original_action_hash, alice_user_hash);
+
+    // Updating the request re-indexes it.
+    let mut updated_input = sample_request("Help with plumbing");
+    updated_input.request.title = "Help with plumbing".to_string();
+    let _: Record = conductors[0]
+        .call(
+            &alice.zome("requests"),
+            "update_request",
+            serde_json::json!({
+                "original_action_hash": req_hash,
+                "previous_action_hash": req_record.signed_action.hashed.hash,
+                "updated_request": updated_input.request,
+                "service_type_hashes": [],
+                "medium_of_exchange_hashes": []
+            }),
+        )
+        .await;
+
+    await_consistency(15, [&alice, &bob]).await.unwrap();
+
+    let results: Vec<SearchResult> = conductors[1]
+        .call(
+            &bob.zome("search"),
+            "search",
+            serde_json::json!({ "query": "gardening", "kinds": ["request"], "limit": null }),
+        )
+        .await;
+    assert!(results.is_empty(), "Old title should no longer be indexed");
+
+    let results: Vec<SearchResult> = conductors[1]
+        .call(
+            &bob.zome("search"),
+            "search",
+            serde_json::json!({ "query": "plumbing", "kinds": ["request"], "limit": null }),
+        )
+        .await;
+    assert_eq!(results.len(), 1);
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn saved_search_reports_new_listings() {
+    let (conductors, alice, bob) = setup_two_agents_with_alice_as_progenitor().await;
+
+    conductors[0]
+        .call::<_, Record>(&alice.zome("users_organizations"), "create_user", sample_user("Alice"))
+        .await;
+
+    await_consistency(15, [&alice, &bob]).await.unwrap();
+
+    let alice_links: Vec<Link> = conductors[0]
+        .call(&alice.zome("users_organizations"), "get_agent_user", alice.agent_pubkey().clone())
+        .await;
+    let alice_user_hash = alice_links[0].target.clone().into_action_hash().unwrap();
+    accept_entity(&conductors[0], &alice, ENTITY_USERS, alice_user_hash).await;
+
+    let gardening: Record = conductors[0]
+        .call(&alice.zome("service_types"), "create_service_type", sample_service_type("Gardening"))
+        .await;
+    let gardening_hash = gardening.signed_action.hashed.hash.clone();
+
+    let create_gardening_request = |title: &'static str, interaction_type: &'static str| {
+        let mut input = sample_request(title);
+        input.service_type_hashes = vec![gardening_hash.clone()];
+        input.request.interaction_type = interaction_type.to_string();
+        input
+    };
+    let virtual_request: Record = conductors[0]
+        .call(
+            &alice.zome("requests"),
+            "create_request",
+            create_gardening_request("Garden planning call", "Virtual"),
+        )
+        .await;
+    let _: Record = conductors[0]
+        .call(
+            &alice.zome("requests"),
+            "create_request",
+            create_gardening_request("Weeding on site", "InPerson"),
+        )
+        .await;
+
+    await_consistency(15, [&alice, &bob]).await.unwrap();
+
+    // Bob saves a search for virtual gardening requests.
+    let saved_search: Record = conductors[1]
+        .call(
+            &bob.zome("search"),
+            "create_saved_search",
+            serde_json::json!({
+                "name": "Virtual gardening",
+                "entity": "request",
+                "service_type_hashes": [gardening_hash],
+                "medium_of_exchange_hashes": [],
+                "interaction_type": "Virtual",
+                "time_preference": null,
+                "time_zone": null,
+                "date_range": null
+            }),
+        )
+        .await;
+    let saved_search_hash = saved_search.signed_action.hashed.hash.clone();
+
+    let first_run: SavedSearchResults = conductors[1]
+        .call(&bob.zome("search"), "run_saved_search", saved_search_hash.clone())
+        .await;
+    let virtual_request_hash = virtual_request.signed_action.hashed.hash.clone();
+    assert_eq!(first_run.result_hashes, vec![virtual_request_hash.clone()]);
+    assert_eq!(first_run.new_result_hashes, vec![virtual_request_hash.clone()]);
+
+    // A new matching request shows up as new on the next run.
+    let second_request: Record = conductors[0]
+        .call(
+            &alice.zome("requests"),
+            "create_request",
+            create_gardening_request("Balcony garden advice", "Virtual"),
+        )
+        .await;
+
+    await_consistency(15, [&alice, &bob]).await.unwrap();
+
+    let second_run: SavedSearchResults = conductors[1]
+        .call(&bob.zome("search"), "run_saved_search", saved_search_hash.clone())
+        .await;
+    assert_eq!(second_run.result_hashes.len(), 2);
+    assert_eq!(
+        second_run.new_result_hashes,
+        vec![second_request.signed_action.hashed.hash.clone()]
+    );
+
+    // Deleted searches are no longer listed.
+    let _: ActionHash = conductors[1]
+        .call(&bob.zome("search"), "delete_saved_search", saved_search_hash)
+        .await;
+    let saved_searches: Vec<Record> = conductors[1]
+        .call(&bob.zome("search"), "get_my_saved_searches", ())
+        .await;
+    assert!(saved_searches.is_empty());
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn listings_near_a_point_are_found() {
+    let (conductors, alice, bob) = setup_two_agents_with_alice_as_progenitor().await;
+
+    conductors[0]
+        .call::<_, Record>(&alice.zome("users_organizations"), "create_user", sample_user("Alice"))
+        .await;
+
+    await_consistency(15, [&alice, &bob]).await.unwrap();
+
+    let alice_links: Vec<Link> = conductors[0]
+        .call(&alice.zome("users_organizations"), "get_agent_user", alice.agent_pubkey().clone())
+        .await;
+    let alice_user_hash = alice_links[0].target.clone().into_action_hash().unwrap();
+    accept_entity(&conductors[0], &alice, ENTITY_USERS, alice_user_hash).await;
+
+    let located_request = |title: &str, latitude: f64, longitude: f64| {
+        let mut input = sample_request(title);
+        input.request.interaction_type = "InPerson".to_string();
+        input.request.location = Some(GeoLocation {
+            point: GeoPoint {
+                latitude,
+                longitude,
+            },
+            privacy_radius_km: 1.0,
+        });
+        input
+    };
+
+    // One request in central Paris, one in Versailles (~17 km away), one in Lyon (~390 km).
+    let paris: Record = conductors[0]
+        .call(&alice.zome("requests"), "create_request", located_request("Move a sofa", 48.8566, 2.3522))
+        .await;
+    let versailles: Record = conductors[0]
+        .call(&alice.zome("requests"), "create_request", located_request("Prune a hedge", 48.8049, 2.1204))
+        .await;
+    let _: Record = conductors[0]
+        .call(&alice.zome("requests"), "create_request", located_request("Paint a fence", 45.7640, 4.8357))
+        .await;
+
+    await_consistency(15, [&alice, &bob]).await.unwrap();
+
+    let near = |radius_km: f64| {
+        serde_json::json!({
+            "point": { "latitude": 48.8606, "longitude": 2.3376 },
+            "radius_km": radius_km,
+            "kinds": ["request"]
+        })
+    };
+
+    let results: Vec<NearbyListing> = conductors[1]
+        .call(&bob.zome("search"), "get_listings_near", near(5.0))
+        .await;
+    let hashes: Vec<ActionHash> = results.iter().map(|listing| listing.original_action_hash.clone()).collect();
+    assert_eq!(hashes, vec![paris.signed_action.hashed.hash.clone()]);
+
+    // A wider radius also reaches Versailles, closest first.
+    let results: Vec<NearbyListing> = conductors[1]
+        .call(&bob.zome("search"), "get_listings_near", near(25.0))
+        .await;
+    let hashes: Vec<ActionHash> = results.iter().map(|listing| listing.original_action_hash.clone()).collect();
+    assert_eq!(
+        hashes,
+        vec![
+            paris.signed_action.hashed.hash.clone(),
+            versailles.signed_action.hashed.hash.clone(),
+        ]
+    );
+
+    // Published points are snapped to the grid of the privacy radius.
+    let published = results[0].location.point;
+    assert_ne!((published.latitude, published.longitude), (48.8566, 2.3522));
+    assert!((published.latitude - 48.8566).abs() < 0.01);
+    assert!((published.longitude - 2.3522).abs() < 0.02);
+
+    // Archived listings are no longer found.
+    let _: bool = conductors[0]
+        .call(&alice.zome("requests"), "archive_request", versailles.signed_action.hashed.hash.clone())
+        .await;
+
+    await_consistency(15, [&alice, &bob]).await.unwrap();
+
+    let results: Vec<NearbyListing> = conductors[1]
+        .call(&bob.zome("search"), "get_listings_near", near(25.0))
+        .await;
+    let hashes: Vec<ActionHash> = results.iter().map(|listing| listing.original_action_hash.clone()).collect();
+    assert_eq!(hashes, vec![paris.signed_action.hashed.hash.clone()]);
+
+    // Deleted listings leave the geo index.
+    let _: bool = conductors[0]
+        .call(&alice.zome("requests"), "delete_request", paris.signed_action.hashed.hash.clone())
+        .await;
+
+    await_consistency(15, [&alice, &bob]).await.unwrap();
+
+    let results: Vec<NearbyListing> = conductors[1]
+        .call(&bob.zome("search"), "get_listings_near", near(5.0))
+        .await;
+    assert!(results.is_empty());
+}
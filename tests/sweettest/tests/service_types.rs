@@ -2,8 +2,6 @@
 //! Translated from `service-types-tests/service-types.test.ts` and related files.
 //!
 //! Status workflow tests live in `service_types_status.rs`.
-//! Tag tests (tag-functionality, tag-based-discovery) are NOT ported:
-//! the current ServiceType schema has no `tags` field.
 
 use holochain::prelude::*;
 use holochain::sweettest::*;
@@ -241,6 +239,8 @@ async fn service_type_validation_empty_name_fails() {
             name: String::new(),
             description: "Valid description".to_string(),
             technical: false,
+            tags: Vec::new(),
+            parent_hash: None,
         },
     };
 
@@ -267,6 +267,8 @@ async fn service_type_validation_empty_description_fails() {
             name: "Valid Name".to_string(),
             description: String::new(),
             technical: false,
+            tags: Vec::new(),
+            parent_hash: None,
         },
     };
 
@@ -738,3 +740,393 @@ async fn rejected_service_type_cannot_be_linked() {
         .await;
     assert!(result.is_err(), "Rejected service type should not be linkable");
 }
+
+// ── Tags ──────────────────────────────────────────────────────────────────────
+
+/// Tags are normalized, indexed, suggested and used to browse listings, including
+/// after the service type is updated.
+#[tokio::test(flavor = "multi_thread")]
+async fn service_types_can_be_browsed_by_tag() {
+    let (conductors, alice, bob) = setup_two_agents_with_alice_as_progenitor().await;
+
+    conductors[0]
+        .call::<_, Record>(&alice.zome("users_organizations"), "create_user", sample_user("Alice"))
+        .await;
+
+    await_consistency(15, [&alice, &bob]).await.unwrap();
+
+    let alice_links: Vec<Link> = conductors[0]
+        .call(&alice.zome("users_organizations"), "get_agent_user", alice.agent_pubkey().clone())
+        .await;
+    let alice_user_hash = alice_links[0].target.clone().into_action_hash().unwrap();
+    accept_entity(&conductors[0], &alice, ENTITY_USERS, alice_user_hash).await;
+
+    let mut gardening_input = sample_service_type("Gardening");
+    gardening_input.service_type.tags = vec!["  Outdoor ".to_string(), "Green   Spaces".to_string()];
+    let gardening: Record = conductors[0]
+        .call(&alice.zome("service_types"), "create_service_type", gardening_input)
+        .await;
+    let gardening_hash = gardening.signed_action.hashed.hash.clone();
+    let gardening_st: ServiceType = gardening.entry().to_app_option().unwrap().expect("entry");
+    assert_eq!(gardening_st.tags, vec!["outdoor", "green spaces"]);
+
+    let mut hiking_input = sample_service_type("Hiking guide");
+    hiking_input.service_type.tags = vec!["outdoor".to_string()];
+    let _: Record = conductors[0]
+        .call(&alice.zome("service_types"), "create_service_type", hiking_input)
+        .await;
+
+    let mut request_input = sample_request("Help with my garden");
+    request_input.service_type_hashes = vec![gardening_hash.clone()];
+    let request: Record = conductors[0]
+        .call(&alice.zome("requests"), "create_request", request_input)
+        .await;
+
+    await_consistency(15, [&alice, &bob]).await.unwrap();
+
+    let outdoor: Vec<Record> = conductors[1]
+        .call(&bob.zome("service_types"), "get_service_types_by_tag", "OUTDOOR".to_string())
+        .await;
+    assert_eq!(outdoor.len(), 2);
+
+    let statistics: Vec<TagStatistic> = conductors[1]
+        .call(&bob.zome("service_types"), "get_tag_statistics", ())
+        .await;
+    assert_eq!(
+        statistics,
+        vec![
+            TagStatistic { tag: "outdoor".to_string(), service_type_count: 2 },
+            TagStatistic { tag: "green spaces".to_string(), service_type_count: 1 },
+        ]
+    );
+
+    let suggestions: Vec<String> = conductors[1]
+        .call(&bob.zome("service_types"), "get_tag_suggestions", "gr".to_string())
+        .await;
+    assert_eq!(suggestions, vec!["green spaces"]);
+
+    // After an update, listings are still found through the original service type.
+    let update_input = UpdateServiceTypeInput {
+        original_action_hash: gardening_hash.clone(),
+        previous_action_hash: gardening_hash.clone(),
+        updated_service_type: ServiceTypeEntry {
+            name: "Gardening and landscaping".to_string(),
+            tags: vec!["outdoor".to_string(), "landscaping".to_string()],
+            ..sample_service_type("placeholder").service_type
+        },
+    };
+    let _: ActionHash = conductors[0]
+        .call(&alice.zome("service_types"), "update_service_type", update_input)
+        .await;
+
+    await_consistency(15, [&alice, &bob]).await.unwrap();
+
+    let by_tag: Vec<Record> = conductors[1]
+        .call(&bob.zome("requests"), "get_requests_by_tag", "landscaping".to_string())
+        .await;
+    assert_eq!(by_tag.len(), 1);
+    assert_eq!(by_tag[0].signed_action.hashed.hash, request.signed_action.hashed.hash);
+
+    let green_spaces: Vec<Record> = conductors[1]
+        .call(&bob.zome("service_types"), "get_service_types_by_tag", "green spaces".to_string())
+        .await;
+    assert!(green_spaces.is_empty());
+}
+
+// ── Hierarchy ─────────────────────────────────────────────────────────────────
+
+/// Service types can be nested, cannot be placed under their own descendants, and
+/// listings of a whole subtree can be fetched from its root.
+#[tokio::test(flavor = "multi_thread")]
+async fn service_types_form_a_hierarchy() {
+    let (conductors, alice, bob) = setup_two_agents_with_alice_as_progenitor().await;
+
+    conductors[0]
+        .call::<_, Record>(&alice.zome("users_organizations"), "create_user", sample_user("Alice"))
+        .await;
+
+    await_consistency(15, [&alice, &bob]).await.unwrap();
+
+    let alice_links: Vec<Link> = conductors[0]
+        .call(&alice.zome("users_organizations"), "get_agent_user", alice.agent_pubkey().clone())
+        .await;
+    let alice_user_hash = alice_links[0].target.clone().into_action_hash().unwrap();
+    accept_entity(&conductors[0], &alice, ENTITY_USERS, alice_user_hash).await;
+
+    // Software Development → Web Development → Rust
+    let software: Record = conductors[0]
+        .call(
+            &alice.zome("service_types"),
+            "create_service_type",
+            sample_service_type("Software Development"),
+        )
+        .await;
+    let software_hash = software.signed_action.hashed.hash.clone();
+
+    let mut web_input = sample_service_type("Web Development");
+    web_input.service_type.parent_hash = Some(software_hash.clone());
+    let web: Record = conductors[0]
+        .call(&alice.zome("service_types"), "create_service_type", web_input)
+        .await;
+    let web_hash = web.signed_action.hashed.hash.clone();
+
+    let mut rust_input = sample_service_type("Rust");
+    rust_input.service_type.parent_hash = Some(web_hash.clone());
+    let rust: Record = conductors[0]
+        .call(&alice.zome("service_types"), "create_service_type", rust_input)
+        .await;
+    let rust_hash = rust.signed_action.hashed.hash.clone();
+
+    let mut request_input = sample_request("Rust mentoring");
+    request_input.service_type_hashes = vec![rust_hash.clone()];
+    let request: Record = conductors[0]
+        .call(&alice.zome("requests"), "create_request", request_input)
+        .await;
+    let mut offer_input = sample_offer("Website reviews");
+    offer_input.service_type_hashes = vec![web_hash.clone()];
+    let offer: Record = conductors[0]
+        .call(&alice.zome("offers"), "create_offer", offer_input)
+        .await;
+
+    await_consistency(15, [&alice, &bob]).await.unwrap();
+
+    let subtree: Option<ServiceTypeNode> = conductors[1]
+        .call(&bob.zome("service_types"), "get_service_type_subtree", software_hash.clone())
+        .await;
+    let subtree = subtree.expect("subtree");
+    assert_eq!(subtree.children.len(), 1);
+    assert_eq!(subtree.children[0].service_type_hash, web_hash);
+    assert_eq!(subtree.children[0].children[0].service_type_hash, rust_hash);
+
+    let ancestors: Vec<Record> = conductors[1]
+        .call(&bob.zome("service_types"), "get_service_type_ancestors", rust_hash.clone())
+        .await;
+    let ancestor_hashes: Vec<ActionHash> =
+        ancestors.iter().map(|record| record.signed_action.hashed.hash.clone()).collect();
+    assert_eq!(ancestor_hashes, vec![web_hash.clone(), software_hash.clone()]);
+
+    let requests: Vec<Record> = conductors[1]
+        .call(
+            &bob.zome("service_types"),
+            "get_requests_for_service_type_tree",
+            software_hash.clone(),
+        )
+        .await;
+    assert_eq!(requests.len(), 1);
+    assert_eq!(requests[0].signed_action.hashed.hash, request.signed_action.hashed.hash);
+    let offers: Vec<Record> = conductors[1]
+        .call(&bob.zome("service_types"), "get_offers_for_service_type_tree", software_hash.clone())
+        .await;
+    assert_eq!(offers.len(), 1);
+    assert_eq!(offers[0].signed_action.hashed.hash, offer.signed_action.hashed.hash);
+
+    // Parents cannot change, which also keeps Software Development from going under Rust.
+    let cycle = UpdateServiceTypeInput {
+        original_action_hash: software_hash.clone(),
+        previous_action_hash: software_hash.clone(),
+        updated_service_type: ServiceTypeEntry {
+            name: "Software Development".to_string(),
+            parent_hash: Some(rust_hash.clone()),
+            ..sample_service_type("placeholder").service_type
+        },
+    };
+    let result = conductors[0]
+        .call_fallible::<_, ActionHash>(&alice.zome("service_types"), "update_service_type", cycle)
+        .await;
+    assert!(result.is_err(), "The parent of a service type cannot be changed");
+
+    // A service type with children cannot be deleted.
+    let result = conductors[0]
+        .call_fallible::<_, ActionHash>(
+            &alice.zome("service_types"),
+            "delete_service_type",
+            web_hash,
+        )
+        .await;
+    assert!(result.is_err(), "A service type with children cannot be deleted");
+}
+
+// ── Merging ───────────────────────────────────────────────────────────────────
+
+/// Merging a duplicate moves its listings to the kept service type and leaves the
+/// duplicate resolving to it.
+#[tokio::test(flavor = "multi_thread")]
+async fn duplicate_service_types_can_be_merged() {
+    let (conductors, alice, bob) = setup_two_agents_with_alice_as_progenitor().await;
+
+    conductors[0]
+        .call::<_, Record>(&alice.zome("users_organizations"), "create_user", sample_user("Alice"))
+        .await;
+    conductors[1]
+        .call::<_, Record>(&bob.zome("users_organizations"), "create_user", sample_user("Bob"))
+        .await;
+
+    await_consistency(15, [&alice, &bob]).await.unwrap();
+
+    for (conductor, cell) in [(&conductors[0], &alice), (&conductors[1], &bob)] {
+        let links: Vec<Link> = conductor
+            .call(&cell.zome("users_organizations"), "get_agent_user", cell.agent_pubkey().clone())
+            .await;
+        let user_hash = links[0].target.clone().into_action_hash().unwrap();
+        accept_entity(&conductors[0], &alice, ENTITY_USERS, user_hash).await;
+    }
+
+    let kept: Record = conductors[0]
+        .call(&alice.zome("service_types"), "create_service_type", sample_service_type("Web Development"))
+        .await;
+    let kept_hash = kept.signed_action.hashed.hash.clone();
+    let duplicate: Record = conductors[0]
+        .call(&alice.zome("service_types"), "create_service_type", sample_service_type("Web dev"))
+        .await;
+    let duplicate_hash = duplicate.signed_action.hashed.hash.clone();
+
+    await_consistency(15, [&alice, &bob]).await.unwrap();
+
+    let mut request_input = sample_request("Build my website");
+    request_input.service_type_hashes = vec![duplicate_hash.clone()];
+    let request: Record = conductors[1]
+        .call(&bob.zome("requests"), "create_request", request_input)
+        .await;
+    let request_hash = request.signed_action.hashed.hash.clone();
+
+    await_consistency(15, [&alice, &bob]).await.unwrap();
+
+    let merge_input = serde_json::json!({
+        "source_hash": duplicate_hash,
+        "target_hash": kept_hash,
+    });
+
+    // Only administrators can merge.
+    let result = conductors[1]
+        .call_fallible::<_, Record>(&bob.zome("service_types"), "merge_service_types", merge_input.clone())
+        .await;
+    assert!(result.is_err(), "Non-admin should not merge service types");
+
+    let merged: Record = conductors[0]
+        .call(&alice.zome("service_types"), "merge_service_types", merge_input)
+        .await;
+    let merged_st: ServiceType = merged.entry().to_app_option().unwrap().expect("entry");
+    assert_eq!(merged_st.merged_into, Some(kept_hash.clone()));
+
+    await_consistency(15, [&alice, &bob]).await.unwrap();
+
+    let kept_requests: Vec<Record> = conductors[1]
+        .call(&bob.zome("service_types"), "get_requests_for_service_type", kept_hash.clone())
+        .await;
+    assert_eq!(kept_requests.len(), 1);
+    assert_eq!(kept_requests[0].signed_action.hashed.hash, request_hash);
+    let duplicate_requests: Vec<Record> = conductors[1]
+        .call(&bob.zome("service_types"), "get_requests_for_service_type", duplicate_hash.clone())
+        .await;
+    assert!(duplicate_requests.is_empty());
+
+    let status: String = conductors[1]
+        .call(&bob.zome("service_types"), "get_service_type_status", duplicate_hash.clone())
+        .await;
+    assert_eq!(status, "merged");
+    let approved: Vec<Record> = conductors[1]
+        .call(&bob.zome("service_types"), "get_approved_service_types", ())
+        .await;
+    assert_eq!(approved.len(), 1);
+
+    let resolved: Option<Record> = conductors[1]
+        .call(&bob.zome("service_types"), "resolve_service_type", duplicate_hash)
+        .await;
+    let resolved_st: ServiceType = resolved.unwrap().entry().to_app_option().unwrap().expect("entry");
+    assert_eq!(resolved_st.name, "Web Development");
+}
+
+// ── Translations ──────────────────────────────────────────────────────────────
+
+/// Translations are moderated, and once approved their names and aliases are
+/// searchable and returned in the caller's locale.
+#[tokio::test(flavor = "multi_thread")]
+async fn service_types_can_be_translated_and_searched_by_alias() {
+    let (conductors, alice, bob) = setup_two_agents_with_alice_as_progenitor().await;
+
+    conductors[0]
+        .call::<_, Record>(&alice.zome("users_organizations"), "create_user", sample_user("Alice"))
+        .await;
+    conductors[1]
+        .call::<_, Record>(&bob.zome("users_organizations"), "create_user", sample_user("Bob"))
+        .await;
+
+    await_consistency(15, [&alice, &bob]).await.unwrap();
+
+    for (conductor, cell) in [(&conductors[0], &alice), (&conductors[1], &bob)] {
+        let links: Vec<Link> = conductor
+            .call(&cell.zome("users_organizations"), "get_agent_user", cell.agent_pubkey().clone())
+            .await;
+        let user_hash = links[0].target.clone().into_action_hash().unwrap();
+        accept_entity(&conductors[0], &alice, ENTITY_USERS, user_hash).await;
+    }
+
+    let record: Record = conductors[0]
+        .call(&alice.zome("service_types"), "create_service_type", sample_service_type("Web Development"))
+        .await;
+    let service_type_hash = record.signed_action.hashed.hash.clone();
+
+    await_consistency(15, [&alice, &bob]).await.unwrap();
+
+    // Bob suggests a French translation, which stays pending.
+    let translation: Record = conductors[1]
+        .call(
+            &bob.zome("service_types"),
+            "suggest_service_type_translation",
+            sample_translation(service_type_hash.clone(), "FR", "Développement web", &["Site internet"]),
+        )
+        .await;
+    let translation_hash = translation.signed_action.hashed.hash.clone();
+    let entry: ServiceTypeTranslation = translation.entry().to_app_option().unwrap().expect("entry");
+    assert_eq!(entry.locale, "fr");
+
+    await_consistency(15, [&alice, &bob]).await.unwrap();
+
+    let search = |query: &str, locale: &str| {
+        serde_json::json!({ "query": query, "locale": locale })
+    };
+    let results: Vec<LocalizedServiceType> = conductors[1]
+        .call(&bob.zome("service_types"), "search_service_types", search("site", "fr"))
+        .await;
+    assert!(results.is_empty(), "Pending translations should not be searchable");
+
+    // Only administrators approve translations.
+    let result = conductors[1]
+        .call_fallible::<_, ()>(&bob.zome("service_types"), "approve_service_type_translation", translation_hash.clone())
+        .await;
+    assert!(result.is_err(), "Non-admin should not approve translations");
+
+    let pending: Vec<Record> = conductors[0]
+        .call(&alice.zome("service_types"), "get_pending_service_type_translations", ())
+        .await;
+    assert_eq!(pending.len(), 1);
+    conductors[0]
+        .call::<_, ()>(&alice.zome("service_types"), "approve_service_type_translation", translation_hash)
+        .await;
+
+    await_consistency(15, [&alice, &bob]).await.unwrap();
+
+    // An alias matches, and the service type comes back in the requested language.
+    let results: Vec<LocalizedServiceType> = conductors[1]
+        .call(&bob.zome("service_types"), "search_service_types", search("internet", "fr-CA"))
+        .await;
+    assert_eq!(results.len(), 1);
+    assert_eq!(results[0].service_type_hash, service_type_hash);
+    assert_eq!(results[0].locale.as_deref(), Some("fr"));
+    assert_eq!(results[0].name, "Développement web");
+    assert_eq!(results[0].aliases, vec!["Site internet".to_string()]);
+
+    // A translated name matches too, returned in the service type's own name when the
+    // locale has no translation.
+    let results: Vec<LocalizedServiceType> = conductors[1]
+        .call(&bob.zome("service_types"), "autocomplete_service_types", search("dével", "es"))
+        .await;
+    assert_eq!(results.len(), 1);
+    assert_eq!(results[0].locale, None);
+    assert_eq!(results[0].name, "Web Development");
+
+    let translations: Vec<Record> = conductors[1]
+        .call(&bob.zome("service_types"), "get_service_type_translations", service_type_hash)
+        .await;
+    assert_eq!(translations.len(), 1);
+}
@@ -0,0 +1,129 @@
+//! ValueFlows export tests.
+
+use holochain::prelude::*;
+use holochain::sweettest::*;
+use requests_and_offers_sweettest::common::*;
+use serde_json::Value;
+
+/// Finds the node of the exported graph with an id.
+fn node<'a>(document: &'a Value, id: &str) -> &'a Value {
+    document["@graph"]
+        .as_array()
+        .expect("a JSON-LD graph")
+        .iter()
+        .find(|node| node["@id"] == id)
+        .unwrap_or_else(|| panic!("no node {}", id))
+}
+
+/// Exports the first page of one index of listings, which holds all of them here.
+async fn export_page(conductor: &SweetConductor, cell: &SweetCell, listings: &str) -> Value {
+    let page: ValueFlowsExportPage = conductor
+        .call(
+            &cell.zome("valueflows"),
+            "export_valueflows",
+            serde_json::json!({
+                "listings": listings,
+                "pagination": page_input(None, 10),
+            }),
+        )
+        .await;
+    assert!(page.next_cursor.is_none());
+    serde_json::from_str(&page.document).expect("valid JSON")
+}
+
+/// Requests are exported as intents received by their author, and valued offers as
+/// proposals with a reciprocal payment in their medium of exchange.
+#[tokio::test(flavor = "multi_thread")]
+async fn listings_are_exported_as_valueflows_json_ld() {
+    let (conductors, alice, bob) = setup_two_agents_with_alice_as_progenitor().await;
+
+    conductors[0]
+        .call::<_, Record>(&alice.zome("users_organizations"), "create_user", sample_user("Alice"))
+        .await;
+
+    await_consistency(15, [&alice, &bob]).await.unwrap();
+
+    let links: Vec<Link> = conductors[0]
+        .call(&alice.zome("users_organizations"), "get_agent_user", alice.agent_pubkey().clone())
+        .await;
+    let alice_user_hash = links[0].target.clone().into_action_hash().unwrap();
+    accept_entity(&conductors[0], &alice, ENTITY_USERS, alice_user_hash.clone()).await;
+
+    let gardening: Record = conductors[0]
+        .call(&alice.zome("service_types"), "create_service_type", sample_service_type("Gardening"))
+        .await;
+    let service_type_hash = gardening.signed_action.hashed.hash.clone();
+    let time: Record = conductors[0]
+        .call(
+            &alice.zome("mediums_of_exchange"),
+            "create_medium_of_exchange",
+            sample_medium_of_exchange("TIME", "Time bank hour"),
+        )
+        .await;
+    let time_hash = time.signed_action.hashed.hash.clone();
+
+    await_consistency(15, [&alice, &bob]).await.unwrap();
+
+    let mut request_input = sample_request("Help with gardening");
+    request_input.service_type_hashes = vec![service_type_hash.clone()];
+    let request: Record = conductors[0]
+        .call(&alice.zome("requests"), "create_request", request_input)
+        .await;
+    let request_hash = request.signed_action.hashed.hash.clone();
+
+    let mut offer_input = sample_offer("Gardening on weekends");
+    offer_input.service_type_hashes = vec![service_type_hash.clone()];
+    offer_input.offer.valuation = Some(Valuation {
+        amount: 200,
+        unit: "PerHour".to_string(),
+        medium_of_exchange_hash: time_hash.clone(),
+    });
+    let offer: Record = conductors[0]
+        .call(&alice.zome("offers"), "create_offer", offer_input)
+        .await;
+    let offer_hash = offer.signed_action.hashed.hash.clone();
+
+    await_consistency(15, [&alice, &bob]).await.unwrap();
+
+    let requests = export_page(&conductors[1], &bob, "ActiveRequests").await;
+    let offers = export_page(&conductors[1], &bob, "ActiveOffers").await;
+
+    let agent_id = format!("urn:requests-and-offers:agent:{}", alice_user_hash);
+    let service_type_id = format!("urn:requests-and-offers:service-type:{}", service_type_hash);
+    assert_eq!(node(&requests, &agent_id)["@type"], "vf:Person");
+    assert_eq!(node(&requests, &agent_id)["vf:name"], "Alice");
+    assert_eq!(
+        node(&requests, &service_type_id)["@type"],
+        "vf:ResourceSpecification"
+    );
+
+    let request_intent = node(
+        &requests,
+        &format!("urn:requests-and-offers:intent:{}", request_hash),
+    );
+    assert_eq!(request_intent["@type"], "vf:Intent");
+    assert_eq!(request_intent["vf:receiver"]["@id"], agent_id);
+    assert_eq!(request_intent["vf:resourceConformsTo"]["@id"], service_type_id);
+
+    let offer_intent_id = format!("urn:requests-and-offers:intent:{}", offer_hash);
+    assert_eq!(node(&offers, &offer_intent_id)["vf:provider"]["@id"], agent_id);
+    let proposal = node(
+        &offers,
+        &format!("urn:requests-and-offers:proposal:{}", offer_hash),
+    );
+    assert_eq!(proposal["@type"], "vf:Proposal");
+    assert_eq!(proposal["vf:publishes"]["@id"], offer_intent_id);
+    assert_eq!(proposal["vf:unitBased"], true);
+
+    let reciprocal = node(&offers, &format!("{}:reciprocal", offer_intent_id));
+    assert_eq!(reciprocal["vf:action"]["@id"], "vf:transfer");
+    assert_eq!(reciprocal["vf:receiver"]["@id"], agent_id);
+    assert_eq!(reciprocal["vf:resourceQuantity"]["om2:hasNumericalValue"], 2.0);
+    assert_eq!(
+        node(
+            &offers,
+            &format!("urn:requests-and-offers:medium-of-exchange:{}", time_hash)
+        )["skos:notation"],
+        "TIME"
+    );
+}
@@ -33,6 +33,7 @@ export const InteractionTypeSchema = Schema.Union(
 
 // Core ContactPreference schema
 export const ContactPreferenceSchema = Schema.Union(
+  Schema.Literal('AppChat'),
   Schema.Literal('Email'),
   Schema.Literal('Phone'),
   Schema.Struct({ Other: Schema.String })
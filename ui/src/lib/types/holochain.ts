@@ -44,7 +44,7 @@ export enum AdministrationEntity {
   Organizations = 'organizations'
 }
 
-export type ContactPreference = 'Email' | 'Phone' | { Other: string };
+export type ContactPreference = 'AppChat' | 'Email' | 'Phone' | { Other: string };
 
 export type TimePreference =
   | 'Morning'